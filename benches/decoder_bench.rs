@@ -1,11 +1,9 @@
-#![feature(test)]
+extern crate criterion;
 extern crate rosc;
-extern crate test;
 
-use self::test::Bencher;
+use criterion::{criterion_group, criterion_main, Criterion};
 
-#[bench]
-fn bench_decode(b: &mut Bencher) {
+fn bench_decode(c: &mut Criterion) {
     // The message was captured from the `ytterbium` lemur patch looks like this:
     // OSC Bundle: OscBundle { timetag: Time(0, 1), content: [Message(OscMessage { addr: "/OSCILLATORS/OSC2/ADSR/x", args: Some([Float(0.1234567), Float(0.1234567), Float(0.1234567), Float(0.1234567)]) })] }
     let raw_msg: [u8; 72] = [
@@ -13,5 +11,10 @@ fn bench_decode(b: &mut Bencher) {
         73, 76, 76, 65, 84, 79, 82, 83, 47, 79, 83, 67, 50, 47, 65, 68, 83, 82, 47, 122, 0, 0, 0,
         0, 44, 102, 102, 102, 102, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
-    b.iter(|| rosc::decoder::decode(&raw_msg).unwrap());
+    c.bench_function("decode", |b| {
+        b.iter(|| rosc::decoder::decode(&raw_msg).unwrap())
+    });
 }
+
+criterion_group!(benches, bench_decode);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+extern crate criterion;
+extern crate rosc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rosc::{encoder, OscBundle, OscMessage, OscPacket};
+
+fn bench_encode(c: &mut Criterion) {
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: (0, 1).into(),
+        content: (0..100)
+            .map(|i| {
+                OscPacket::Message(OscMessage {
+                    addr: format!("/OSCILLATORS/OSC{}/ADSR/x", i),
+                    args: vec![0.1f32.into(), 0.2f32.into(), "attack".into(), i.into()],
+                })
+            })
+            .collect(),
+    });
+
+    c.bench_function("encode bundle", |b| {
+        b.iter(|| encoder::encode(&bundle).unwrap())
+    });
+
+    let mut buf = Vec::new();
+    c.bench_function("encode bundle into buffer", |b| {
+        b.iter(|| {
+            buf.clear();
+            encoder::encode_into(&bundle, &mut buf).unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, bench_encode);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+extern crate criterion;
+extern crate rosc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rosc::{decoder, encoder, OscMessage, OscMessageSmall, OscPacket, OscPacketSmall};
+
+const ADDR: &str = "/OSCILLATORS/OSC2/ADSR/x";
+const VALUES: [f32; 4] = [0.1, 0.2, 0.3, 0.4];
+
+fn bench_small(c: &mut Criterion) {
+    let raw_msg = encoder::encode(&OscPacket::Message(OscMessage {
+        addr: ADDR.to_string(),
+        args: VALUES.iter().map(|&x| x.into()).collect(),
+    }))
+    .unwrap();
+
+    c.bench_function("decode message", |b| {
+        b.iter(|| decoder::decode(&raw_msg).unwrap())
+    });
+    c.bench_function("decode small message", |b| {
+        b.iter(|| decoder::decode_small(&raw_msg).unwrap())
+    });
+
+    let mut buf = Vec::new();
+    c.bench_function("build and encode message", |b| {
+        b.iter(|| {
+            let packet = OscPacket::Message(OscMessage {
+                addr: ADDR.to_string(),
+                args: VALUES.iter().map(|&x| x.into()).collect(),
+            });
+            buf.clear();
+            encoder::encode_into(&packet, &mut buf).unwrap()
+        })
+    });
+    c.bench_function("build and encode small message", |b| {
+        b.iter(|| {
+            let packet = OscPacketSmall::Message(OscMessageSmall {
+                addr: ADDR.to_string(),
+                args: VALUES.iter().map(|&x| x.into()).collect(),
+            });
+            buf.clear();
+            encoder::encode_small_into(&packet, &mut buf).unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, bench_small);
+criterion_main!(benches);
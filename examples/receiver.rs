@@ -14,7 +14,7 @@ fn main() {
     }
     let addr = match SocketAddrV4::from_str(&args[1]) {
         Ok(addr) => addr,
-        Err(_) => panic!(usage),
+        Err(_) => panic!("{}", usage),
     };
     let sock = UdpSocket::bind(addr).unwrap();
     println!("Listening to {}", addr);
@@ -45,5 +45,8 @@ fn handle_packet(packet: OscPacket) {
         OscPacket::Bundle(bundle) => {
             println!("OSC Bundle: {:?}", bundle);
         }
+        OscPacket::Raw(data) => {
+            println!("Raw OSC packet: {:?}", data);
+        }
     }
 }
@@ -0,0 +1,124 @@
+//! Derive macros for the `ToOsc` and `FromOsc` traits of
+//! [rosc](https://docs.rs/rosc), enabled by its `derive` feature.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Derives `rosc::ToOsc`, see its documentation for details.
+#[proc_macro_derive(ToOsc, attributes(osc))]
+pub fn derive_to_osc(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_osc(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `rosc::FromOsc`, see its documentation for details.
+#[proc_macro_derive(FromOsc, attributes(osc))]
+pub fn derive_from_osc(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_osc(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn to_osc(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let addr = address(input)?;
+    let fields = fields(input, "ToOsc")?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let members = fields.members();
+    Ok(quote! {
+        impl #impl_generics ::rosc::ToOsc for #name #ty_generics #where_clause {
+            fn to_osc(&self) -> ::rosc::OscMessage {
+                let mut msg = ::rosc::OscMessage::from(#addr);
+                #(msg.args.push(::rosc::OscType::from(self.#members.clone()));)*
+                msg
+            }
+        }
+    })
+}
+
+fn from_osc(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let addr = address(input)?;
+    let fields = fields(input, "FromOsc")?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let count = fields.len();
+    let members = fields.members();
+    let indices = 0..count;
+    Ok(quote! {
+        impl #impl_generics ::rosc::FromOsc for #name #ty_generics #where_clause {
+            fn from_osc(msg: &::rosc::OscMessage) -> ::rosc::Result<Self> {
+                ::rosc::__private::check_addr(msg, #addr)?;
+                ::rosc::__private::check_arg_count(&msg.args, #count)?;
+                Ok(Self {
+                    #(#members: ::rosc::__private::parse_arg(&msg.args, #indices)?,)*
+                })
+            }
+        }
+    })
+}
+
+/// Returns the fields of a struct.
+fn fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<&'a Fields> {
+    match input.data {
+        Data::Struct(ref data) => Ok(&data.fields),
+        _ => Err(Error::new_spanned(
+            &input.ident,
+            format!("{} can only be derived for structs", derive),
+        )),
+    }
+}
+
+/// Returns the address given by the `#[osc(addr = "...")]` attribute.
+fn address(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut addr: Option<LitStr> = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("osc"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("addr") {
+                let value: LitStr = meta.value()?.parse()?;
+                check_address(&value)?;
+                addr = Some(value);
+                Ok(())
+            } else {
+                Err(meta.error("unknown osc attribute, expected `addr`"))
+            }
+        })?;
+    }
+    addr.ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "missing address, add an `#[osc(addr = \"/...\")]` attribute",
+        )
+    })
+}
+
+/// Rejects addresses that the encoder would reject as well.
+fn check_address(addr: &LitStr) -> syn::Result<()> {
+    let value = addr.value();
+    if !value.starts_with('/') {
+        return Err(Error::new(addr.span(), "OSC addresses must start with '/'"));
+    }
+    if value
+        .chars()
+        .any(|c| c == ' ' || c == '#' || c.is_control())
+    {
+        return Err(Error::new(
+            addr.span(),
+            "OSC addresses must not contain spaces, '#' or control characters",
+        ));
+    }
+    Ok(())
+}
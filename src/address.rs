@@ -0,0 +1,123 @@
+//! Validation and splitting of OSC addresses.
+//!
+//! An address such as `/synth/1/freq` consists of parts that are each
+//! preceded by a `/`. The address of a message may be a pattern (see the
+//! [`matcher`](crate::matcher) module), while the address of a method
+//! must not contain any of the pattern characters `*,?[]{}`.
+
+use crate::errors::OscError;
+use crate::types::Result;
+
+/// The characters that are not allowed in the names of methods and
+/// containers, besides spaces, `#` and `/`.
+const PATTERN_CHARS: &str = "*,?[]{}";
+
+/// Checks that `addr` is a valid message address or address pattern: it
+/// starts with `/` and contains no spaces, `#` or control characters.
+///
+/// # Example
+///
+/// ```
+/// use rosc::address;
+///
+/// assert!(address::validate("/synth/*/freq").is_ok());
+/// assert!(address::validate("synth/freq").is_err());
+/// assert!(address::validate("/synth freq").is_err());
+/// ```
+pub fn validate(addr: &str) -> Result<()> {
+    if !addr.starts_with('/') {
+        return Err(OscError::BadAddress("Address must start with '/'."));
+    }
+    for c in addr.chars() {
+        match c {
+            ' ' => return Err(OscError::BadAddress("Address must not contain spaces.")),
+            '#' => return Err(OscError::BadAddress("Address must not contain '#'.")),
+            c if c.is_control() => {
+                return Err(OscError::BadAddress(
+                    "Address must not contain control characters.",
+                ))
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether `addr` is a valid message address or address pattern,
+/// like [`validate`] checks. Unlike `validate` it can be evaluated at
+/// compile time.
+///
+/// # Example
+///
+/// ```
+/// use rosc::address;
+///
+/// const VALID: bool = address::is_valid("/synth/freq");
+/// assert!(VALID);
+/// assert!(!address::is_valid("/synth\u{85}freq"));
+/// ```
+pub const fn is_valid(addr: &str) -> bool {
+    let bytes = addr.as_bytes();
+    if bytes.is_empty() || bytes[0] != b'/' {
+        return false;
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' | b'#' | 0x00..=0x1f | 0x7f => return false,
+            // the C1 control characters U+0080 to U+009F
+            0xc2 if i + 1 < bytes.len() && bytes[i + 1] >= 0x80 && bytes[i + 1] <= 0x9f => {
+                return false
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Checks that `addr` is a valid method address: a valid address whose
+/// parts are neither empty nor contain pattern characters.
+///
+/// # Example
+///
+/// ```
+/// use rosc::address;
+///
+/// assert!(address::validate_method("/synth/1/freq").is_ok());
+/// assert!(address::validate_method("/synth/*/freq").is_err());
+/// assert!(address::validate_method("/synth//freq").is_err());
+/// ```
+pub fn validate_method(addr: &str) -> Result<()> {
+    validate(addr)?;
+    if split(addr).any(str::is_empty) || addr == "/" {
+        return Err(OscError::BadAddress("Address contains an empty part."));
+    }
+    if is_pattern(addr) {
+        return Err(OscError::BadAddress(
+            "Method address must not contain pattern characters.",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns whether `addr` contains pattern characters.
+pub fn is_pattern(addr: &str) -> bool {
+    addr.contains(|c| PATTERN_CHARS.contains(c))
+}
+
+/// Returns the parts of `addr`, the address `/` has none.
+///
+/// # Example
+///
+/// ```
+/// use rosc::address;
+///
+/// assert_eq!(vec!["synth", "1", "freq"], address::split("/synth/1/freq").collect::<Vec<_>>());
+/// assert_eq!(0, address::split("/").count());
+/// ```
+pub fn split(addr: &str) -> impl Iterator<Item = &str> {
+    let parts = addr.strip_prefix('/').unwrap_or(addr);
+    let count = if parts.is_empty() { 0 } else { usize::MAX };
+    parts.split('/').take(count)
+}
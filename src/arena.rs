@@ -0,0 +1,159 @@
+use crate::borrowed::{OscPacketRef, OscTypeRef};
+use crate::types::{
+    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType,
+};
+
+use alloc::string::ToString;
+
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+
+/// An OSC packet allocated in a [`Bump`] arena.
+///
+/// Arena packets are produced by
+/// [`decoder::decode_in`](crate::decoder::decode_in), which allocates their
+/// strings, blobs, arguments and bundle content in the arena. Resetting the
+/// arena after a batch of packets frees them all at once, instead of
+/// freeing the allocations of every packet one by one. Use
+/// [`From`]/[`Into`] to turn them into an owned [`OscPacket`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OscPacketArena<'a> {
+    Message(OscMessageArena<'a>),
+    Bundle(OscBundleArena<'a>),
+}
+
+/// An OSC message allocated in an arena.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OscMessageArena<'a> {
+    pub addr: &'a str,
+    pub args: &'a [OscTypeArena<'a>],
+}
+
+/// An OSC bundle allocated in an arena.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OscBundleArena<'a> {
+    pub timetag: OscTime,
+    pub content: &'a [OscPacketArena<'a>],
+}
+
+/// An OSC argument allocated in an arena, see [`OscType`] for the owned
+/// counterpart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OscTypeArena<'a> {
+    Int(i32),
+    Float(f32),
+    String(&'a str),
+    Symbol(&'a str),
+    Blob(&'a [u8]),
+    Time(OscTime),
+    Long(i64),
+    Double(f64),
+    Char(char),
+    Color(OscColor),
+    Midi(OscMidiMessage),
+    Bool(bool),
+    Array(&'a [OscTypeArena<'a>]),
+    Nil,
+    Inf,
+}
+
+impl<'a> OscPacketArena<'a> {
+    /// Copies `packet` into `bump`.
+    pub(crate) fn from_ref(bump: &'a Bump, packet: OscPacketRef) -> OscPacketArena<'a> {
+        match packet {
+            OscPacketRef::Message(msg) => OscPacketArena::Message(OscMessageArena {
+                addr: bump.alloc_str(msg.addr),
+                args: BumpVec::from_iter_in(msg.args().map(|arg| arena_arg(bump, arg)), bump)
+                    .into_bump_slice(),
+            }),
+            OscPacketRef::Bundle(bundle) => OscPacketArena::Bundle(OscBundleArena {
+                timetag: bundle.timetag,
+                content: BumpVec::from_iter_in(
+                    bundle
+                        .content()
+                        .map(|packet| OscPacketArena::from_ref(bump, packet)),
+                    bump,
+                )
+                .into_bump_slice(),
+            }),
+        }
+    }
+}
+
+fn arena_arg<'a>(bump: &'a Bump, arg: OscTypeRef) -> OscTypeArena<'a> {
+    match arg {
+        OscTypeRef::Int(x) => OscTypeArena::Int(x),
+        OscTypeRef::Float(x) => OscTypeArena::Float(x),
+        OscTypeRef::String(x) => OscTypeArena::String(bump.alloc_str(x)),
+        OscTypeRef::Symbol(x) => OscTypeArena::Symbol(bump.alloc_str(x)),
+        OscTypeRef::Blob(x) => OscTypeArena::Blob(bump.alloc_slice_copy(x)),
+        OscTypeRef::Time(x) => OscTypeArena::Time(x),
+        OscTypeRef::Long(x) => OscTypeArena::Long(x),
+        OscTypeRef::Double(x) => OscTypeArena::Double(x),
+        OscTypeRef::Char(x) => OscTypeArena::Char(x),
+        OscTypeRef::Color(x) => OscTypeArena::Color(x),
+        OscTypeRef::Midi(x) => OscTypeArena::Midi(x),
+        OscTypeRef::Bool(x) => OscTypeArena::Bool(x),
+        OscTypeRef::Array(x) => OscTypeArena::Array(
+            BumpVec::from_iter_in(x.iter().map(|arg| arena_arg(bump, arg)), bump).into_bump_slice(),
+        ),
+        OscTypeRef::Nil => OscTypeArena::Nil,
+        OscTypeRef::Inf => OscTypeArena::Inf,
+    }
+}
+
+impl<'a> From<OscPacketArena<'a>> for OscPacket {
+    fn from(packet: OscPacketArena<'a>) -> OscPacket {
+        match packet {
+            OscPacketArena::Message(msg) => OscPacket::Message(msg.into()),
+            OscPacketArena::Bundle(bundle) => OscPacket::Bundle(bundle.into()),
+        }
+    }
+}
+
+impl<'a> From<OscMessageArena<'a>> for OscMessage {
+    fn from(msg: OscMessageArena<'a>) -> OscMessage {
+        OscMessage {
+            addr: msg.addr.to_string(),
+            args: msg.args.iter().copied().map(OscType::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<OscBundleArena<'a>> for OscBundle {
+    fn from(bundle: OscBundleArena<'a>) -> OscBundle {
+        OscBundle {
+            timetag: bundle.timetag,
+            content: bundle
+                .content
+                .iter()
+                .copied()
+                .map(OscPacket::from)
+                .collect(),
+        }
+    }
+}
+
+impl<'a> From<OscTypeArena<'a>> for OscType {
+    fn from(arg: OscTypeArena<'a>) -> OscType {
+        match arg {
+            OscTypeArena::Int(x) => OscType::Int(x),
+            OscTypeArena::Float(x) => OscType::Float(x),
+            OscTypeArena::String(x) => OscType::String(x.to_string()),
+            OscTypeArena::Symbol(x) => OscType::Symbol(x.to_string()),
+            OscTypeArena::Blob(x) => OscType::Blob(x.to_vec()),
+            OscTypeArena::Time(x) => OscType::Time(x),
+            OscTypeArena::Long(x) => OscType::Long(x),
+            OscTypeArena::Double(x) => OscType::Double(x),
+            OscTypeArena::Char(x) => OscType::Char(x),
+            OscTypeArena::Color(x) => OscType::Color(x),
+            OscTypeArena::Midi(x) => OscType::Midi(x),
+            OscTypeArena::Bool(x) => OscType::Bool(x),
+            OscTypeArena::Array(x) => OscType::Array(OscArray {
+                content: x.iter().copied().map(OscType::from).collect(),
+            }),
+            OscTypeArena::Nil => OscType::Nil,
+            OscTypeArena::Inf => OscType::Inf,
+        }
+    }
+}
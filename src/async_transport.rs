@@ -0,0 +1,246 @@
+//! Runtime independent async sending and receiving of OSC packets.
+//!
+//! The [`AsyncOscSink`] and [`AsyncOscStream`] traits work with any
+//! executor, e.g. those of async-std, smol or tokio. The UDP
+//! implementations are driven by the reactor of the
+//! [async-io](https://docs.rs/async-io) crate, which runs on its own
+//! thread when no other async-io based runtime drives it.
+
+use crate::decoder::{self, DecoderOptions};
+use crate::encoder::Encoder;
+use crate::errors::OscError;
+use crate::types::{OscPacket, Result};
+use crate::udp;
+use async_io::Async;
+use std::future::Future;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Asynchronously sends OSC packets.
+pub trait AsyncOscSink {
+    /// Attempts to send `packet`. If the transport isn't ready, the current
+    /// task is woken once it is.
+    fn poll_send(&mut self, cx: &mut Context<'_>, packet: &OscPacket) -> Poll<Result<()>>;
+
+    /// Returns a future that sends `packet`.
+    fn send<'a>(&'a mut self, packet: &'a OscPacket) -> SendFuture<'a, Self>
+    where
+        Self: Sized,
+    {
+        SendFuture { sink: self, packet }
+    }
+}
+
+/// Asynchronously receives OSC packets and their sender.
+pub trait AsyncOscStream {
+    /// Attempts to receive the next packet. If none is available, the
+    /// current task is woken once there is.
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<(OscPacket, SocketAddr)>>;
+
+    /// Returns a future that receives the next packet.
+    fn recv(&mut self) -> RecvFuture<'_, Self>
+    where
+        Self: Sized,
+    {
+        RecvFuture { stream: self }
+    }
+}
+
+/// The future returned by [`AsyncOscSink::send`].
+#[derive(Debug)]
+pub struct SendFuture<'a, S> {
+    sink: &'a mut S,
+    packet: &'a OscPacket,
+}
+
+impl<S: AsyncOscSink> Future for SendFuture<'_, S> {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        this.sink.poll_send(cx, this.packet)
+    }
+}
+
+/// The future returned by [`AsyncOscStream::recv`].
+#[derive(Debug)]
+pub struct RecvFuture<'a, S> {
+    stream: &'a mut S,
+}
+
+impl<S: AsyncOscStream> Future for RecvFuture<'_, S> {
+    type Output = Result<(OscPacket, SocketAddr)>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().stream.poll_recv(cx)
+    }
+}
+
+/// Sends OSC packets to a single UDP target, the async counterpart of
+/// [`OscUdpClient`](crate::udp::OscUdpClient).
+///
+/// # Example
+///
+/// ```no_run
+/// # extern crate futures_lite;
+/// # extern crate rosc;
+/// use rosc::async_transport::{AsyncOscSink, AsyncOscUdpClient};
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let mut client = AsyncOscUdpClient::connect("127.0.0.1:9000").unwrap();
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// futures_lite::future::block_on(client.send(&packet)).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct AsyncOscUdpClient {
+    socket: Async<UdpSocket>,
+    target: SocketAddr,
+    encoder: Encoder,
+}
+
+impl AsyncOscUdpClient {
+    /// Creates a client that sends to `target` from a socket bound to an
+    /// ephemeral port. Returns an `OscError::SocketError` if the target
+    /// can't be resolved or the socket can't be bound.
+    ///
+    /// Note that resolving `target` may block.
+    pub fn connect<A: ToSocketAddrs>(target: A) -> Result<AsyncOscUdpClient> {
+        let target = udp::resolve(target)?;
+        let socket = Async::<UdpSocket>::bind(udp::unspecified_addr(target))
+            .map_err(OscError::SocketError)?;
+        Ok(AsyncOscUdpClient {
+            socket,
+            target,
+            encoder: Encoder::new(),
+        })
+    }
+
+    /// Creates a client that sends to `target` from an existing socket.
+    /// Returns an `OscError::SocketError` if the socket can't be put into
+    /// non-blocking mode.
+    pub fn from_socket(socket: UdpSocket, target: SocketAddr) -> Result<AsyncOscUdpClient> {
+        Ok(AsyncOscUdpClient {
+            socket: Async::new(socket).map_err(OscError::SocketError)?,
+            target,
+            encoder: Encoder::new(),
+        })
+    }
+
+    /// Returns the address packets are sent to.
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    /// Returns the underlying socket.
+    pub fn socket(&self) -> &UdpSocket {
+        self.socket.get_ref()
+    }
+}
+
+impl AsyncOscSink for AsyncOscUdpClient {
+    fn poll_send(&mut self, cx: &mut Context<'_>, packet: &OscPacket) -> Poll<Result<()>> {
+        let bytes = self.encoder.encode(packet)?;
+        loop {
+            match self.socket.get_ref().send_to(bytes, self.target) {
+                Ok(_) => return Poll::Ready(Ok(())),
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    match self.socket.poll_writable(cx) {
+                        Poll::Ready(Ok(())) => continue,
+                        Poll::Ready(Err(err)) => {
+                            return Poll::Ready(Err(OscError::WriteError(err)))
+                        }
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+                Err(err) => return Poll::Ready(Err(OscError::WriteError(err))),
+            }
+        }
+    }
+}
+
+/// Receives OSC packets on a UDP socket, the async counterpart of
+/// [`OscUdpServer::recv`](crate::udp::OscUdpServer::recv).
+///
+/// # Example
+///
+/// ```no_run
+/// # extern crate futures_lite;
+/// # extern crate rosc;
+/// use rosc::async_transport::{AsyncOscStream, AsyncOscUdpServer};
+///
+/// let mut server = AsyncOscUdpServer::bind("0.0.0.0:9000").unwrap();
+/// loop {
+///     match futures_lite::future::block_on(server.recv()) {
+///         Ok((packet, from)) => println!("{}: {:?}", from, packet),
+///         Err(err) => eprintln!("{}", err),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncOscUdpServer {
+    socket: Async<UdpSocket>,
+    buf: Vec<u8>,
+    options: DecoderOptions,
+}
+
+impl AsyncOscUdpServer {
+    /// Creates a server listening on `addr`. Returns an
+    /// `OscError::SocketError` if the socket can't be bound.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<AsyncOscUdpServer> {
+        let socket = UdpSocket::bind(addr).map_err(OscError::SocketError)?;
+        AsyncOscUdpServer::from_socket(socket)
+    }
+
+    /// Creates a server receiving on an existing socket. Returns an
+    /// `OscError::SocketError` if the socket can't be put into non-blocking
+    /// mode.
+    pub fn from_socket(socket: UdpSocket) -> Result<AsyncOscUdpServer> {
+        Ok(AsyncOscUdpServer {
+            socket: Async::new(socket).map_err(OscError::SocketError)?,
+            buf: vec![0; decoder::MAX_DATAGRAM_SIZE],
+            options: DecoderOptions::default(),
+        })
+    }
+
+    /// Decodes received packets using the given `options`.
+    pub fn set_decoder_options(&mut self, options: DecoderOptions) -> &mut AsyncOscUdpServer {
+        self.options = options;
+        self
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.socket
+            .get_ref()
+            .local_addr()
+            .map_err(OscError::SocketError)
+    }
+
+    /// Returns the underlying socket.
+    pub fn socket(&self) -> &UdpSocket {
+        self.socket.get_ref()
+    }
+}
+
+impl AsyncOscStream for AsyncOscUdpServer {
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<(OscPacket, SocketAddr)>> {
+        loop {
+            match self.socket.get_ref().recv_from(&mut self.buf) {
+                Ok((len, from)) => {
+                    let packet = decoder::decode_with_options(&self.buf[..len], &self.options);
+                    return Poll::Ready(packet.map(|packet| (packet, from)));
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    match self.socket.poll_readable(cx) {
+                        Poll::Ready(Ok(())) => continue,
+                        Poll::Ready(Err(err)) => return Poll::Ready(Err(OscError::ReadError(err))),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+                Err(err) => return Poll::Ready(Err(OscError::ReadError(err))),
+            }
+        }
+    }
+}
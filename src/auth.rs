@@ -0,0 +1,133 @@
+//! Authentication of packets by an HMAC-SHA256 signature, so that hosts
+//! that don't know the shared key can't inject packets.
+//!
+//! A signed packet is a bundle, to be executed immediately, with two
+//! elements: the original packet and a message to [`SIGNATURE_ADDR`] whose
+//! only argument is the signature of the packet's encoding as a blob.
+//! Receivers that don't check signatures can still decode it and just see
+//! the additional message.
+//!
+//! Signatures don't hide the content of packets, nor do they keep an
+//! attacker from replaying packets they recorded.
+//!
+//! Set the same key on both ends with
+//! [`OscUdpClient::set_signer`](crate::udp::OscUdpClient::set_signer) and
+//! [`OscUdpServer::set_signer`](crate::udp::OscUdpServer::set_signer), or
+//! sign and verify packets for other transports with an [`HmacSigner`]
+//! directly.
+//!
+//! # Example
+//!
+//! ```
+//! use rosc::auth::HmacSigner;
+//! use rosc::{OscError, OscMessage, OscPacket};
+//!
+//! let signer = HmacSigner::new(b"shared secret");
+//! let packet = OscPacket::Message(OscMessage::from("/cue/go"));
+//! let signed = signer.sign(&packet).unwrap();
+//! assert_eq!(packet, signer.verify_packet(&signed).unwrap());
+//!
+//! let attacker = HmacSigner::new(b"guessed secret");
+//! match signer.verify_packet(&attacker.sign(&packet).unwrap()) {
+//!     Err(OscError::BadSignature) => (),
+//!     other => panic!("forged packet accepted: {:?}", other),
+//! }
+//! ```
+
+use crate::decoder;
+use crate::encoder;
+use crate::errors::OscError;
+use crate::types::{OscPacket, Result};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::fmt;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// The address of the message carrying the signature of a packet.
+pub const SIGNATURE_ADDR: &str = "/hmac";
+
+/// The number of bytes signing adds to an encoded packet.
+pub const SIGNATURE_OVERHEAD: usize = BUNDLE_HEADER.len() + 4 + 4 + SIGNATURE_HEADER.len() + 32;
+
+/// `#bundle` and the time tag for immediate execution.
+const BUNDLE_HEADER: &[u8; 16] = b"#bundle\0\0\0\0\0\0\0\0\x01";
+
+/// The address, type tags and blob size of the signature message.
+const SIGNATURE_HEADER: &[u8; 16] = b"/hmac\0\0\0,b\0\0\0\0\0\x20";
+
+/// Signs packets and verifies their signatures with a shared key.
+#[derive(Clone)]
+pub struct HmacSigner {
+    mac: Hmac<Sha256>,
+}
+
+impl HmacSigner {
+    /// Creates a signer with the shared `key`, which should be at least 32
+    /// random bytes.
+    pub fn new(key: &[u8]) -> HmacSigner {
+        HmacSigner {
+            mac: Hmac::new_from_slice(key).expect("HMAC accepts keys of any length"),
+        }
+    }
+
+    /// Encodes `packet` and returns the signed packet.
+    pub fn sign(&self, packet: &OscPacket) -> Result<Vec<u8>> {
+        encoder::encode(packet).map(|encoded| self.sign_encoded(&encoded))
+    }
+
+    /// Returns the signed packet of the already encoded packet `encoded`.
+    pub fn sign_encoded(&self, encoded: &[u8]) -> Vec<u8> {
+        let mut mac = self.mac.clone();
+        mac.update(encoded);
+        let mut signed = Vec::with_capacity(encoded.len() + SIGNATURE_OVERHEAD);
+        signed.extend_from_slice(BUNDLE_HEADER);
+        signed.extend_from_slice(&(encoded.len() as u32).to_be_bytes());
+        signed.extend_from_slice(encoded);
+        signed.extend_from_slice(&((SIGNATURE_HEADER.len() + 32) as u32).to_be_bytes());
+        signed.extend_from_slice(SIGNATURE_HEADER);
+        signed.extend_from_slice(&mac.finalize().into_bytes());
+        signed
+    }
+
+    /// Checks the signature of the signed packet `signed` and returns the
+    /// encoding of the original packet. Returns an
+    /// `OscError::BadSignature` if `signed` isn't a signed packet or its
+    /// signature doesn't match.
+    pub fn verify<'a>(&self, signed: &'a [u8]) -> Result<&'a [u8]> {
+        let rest = signed
+            .strip_prefix(&BUNDLE_HEADER[..])
+            .filter(|rest| rest.len() >= 4)
+            .ok_or(OscError::BadSignature)?;
+        let (size, rest) = rest.split_at(4);
+        let size = u32::from_be_bytes(size.try_into().unwrap()) as usize;
+        let trailer_len = 4 + SIGNATURE_HEADER.len() + 32;
+        if rest.len().checked_sub(trailer_len) != Some(size) {
+            return Err(OscError::BadSignature);
+        }
+        let (packet, trailer) = rest.split_at(size);
+        let (element_size, trailer) = trailer.split_at(4);
+        let (header, signature) = trailer.split_at(SIGNATURE_HEADER.len());
+        if element_size != ((trailer_len - 4) as u32).to_be_bytes() || header != SIGNATURE_HEADER {
+            return Err(OscError::BadSignature);
+        }
+        let mut mac = self.mac.clone();
+        mac.update(packet);
+        mac.verify_slice(signature)
+            .map_err(|_| OscError::BadSignature)?;
+        Ok(packet)
+    }
+
+    /// Checks the signature of `signed` like [`verify`](HmacSigner::verify)
+    /// and decodes the original packet.
+    pub fn verify_packet(&self, signed: &[u8]) -> Result<OscPacket> {
+        self.verify(signed).and_then(decoder::decode)
+    }
+}
+
+impl fmt::Debug for HmacSigner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // don't leak the key
+        f.debug_struct("HmacSigner").finish_non_exhaustive()
+    }
+}
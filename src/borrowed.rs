@@ -0,0 +1,501 @@
+use crate::decoder::{DEFAULT_MAX_ARRAY_DEPTH, DEFAULT_MAX_BUNDLE_DEPTH};
+use crate::encoder;
+use crate::errors::{DecodeLimit, OscError};
+use crate::types::{
+    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType, Result,
+};
+
+use alloc::string::ToString;
+use core::{char, str};
+
+use byteorder::{BigEndian, ByteOrder};
+
+/// A borrowed view of an OSC packet.
+///
+/// Borrowed packets are produced by [`decoder::decode_ref`](crate::decoder::decode_ref) and
+/// reference the buffer they were decoded from, so no heap allocation takes place. Use
+/// [`From`]/[`Into`] to turn them into an owned [`OscPacket`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OscPacketRef<'a> {
+    Message(OscMessageRef<'a>),
+    Bundle(OscBundleRef<'a>),
+}
+
+/// A borrowed view of an OSC message.
+///
+/// The arguments are decoded lazily by iterating over [`OscMessageRef::args`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OscMessageRef<'a> {
+    pub addr: &'a str,
+    type_tags: &'a str,
+    args: &'a [u8],
+}
+
+/// A borrowed view of an OSC bundle.
+///
+/// The contained packets are decoded lazily by iterating over [`OscBundleRef::content`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OscBundleRef<'a> {
+    pub timetag: OscTime,
+    content: &'a [u8],
+}
+
+/// A borrowed OSC argument, see [`OscType`] for the owned counterpart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OscTypeRef<'a> {
+    Int(i32),
+    Float(f32),
+    String(&'a str),
+    Symbol(&'a str),
+    Blob(&'a [u8]),
+    Time(OscTime),
+    Long(i64),
+    Double(f64),
+    Char(char),
+    Color(OscColor),
+    Midi(OscMidiMessage),
+    Bool(bool),
+    Array(OscArrayRef<'a>),
+    Nil,
+    Inf,
+}
+
+/// A borrowed view of an OSC array argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OscArrayRef<'a> {
+    type_tags: &'a str,
+    data: &'a [u8],
+}
+
+impl<'a> OscPacketRef<'a> {
+    /// Validates `msg` and returns a borrowed view of the contained packet.
+    pub(crate) fn parse(msg: &'a [u8]) -> Result<OscPacketRef<'a>> {
+        OscPacketRef::parse_nested(msg, 0)
+    }
+
+    /// Like `parse`, for a packet nested within bundles up to a depth of
+    /// `depth`.
+    fn parse_nested(msg: &'a [u8], depth: usize) -> Result<OscPacketRef<'a>> {
+        match msg.first() {
+            Some(b'/') => OscMessageRef::parse(msg).map(OscPacketRef::Message),
+            Some(b'#') => OscBundleRef::parse(msg, depth + 1).map(OscPacketRef::Bundle),
+            Some(_) => Err(OscError::BadPacket("Unknown message format.")),
+            None => Err(OscError::BadPacket("Empty packet.")),
+        }
+    }
+}
+
+impl<'a> OscMessageRef<'a> {
+    fn parse(msg: &'a [u8]) -> Result<OscMessageRef<'a>> {
+        let (addr, type_tags, args) = split_message(msg)?;
+        let (_, data_len) = walk_args(type_tags, args, false)?;
+        Ok(OscMessageRef {
+            addr,
+            type_tags,
+            args: &args[..data_len],
+        })
+    }
+
+    /// Returns the type tags of the arguments, without the leading comma.
+    pub fn type_tags(&self) -> &'a str {
+        self.type_tags
+    }
+
+    /// Returns an iterator over the arguments of this message.
+    pub fn args(&self) -> OscArgsRef<'a> {
+        OscArgsRef {
+            type_tags: self.type_tags,
+            data: self.args,
+        }
+    }
+}
+
+impl<'a> OscBundleRef<'a> {
+    fn parse(msg: &'a [u8], depth: usize) -> Result<OscBundleRef<'a>> {
+        if depth > DEFAULT_MAX_BUNDLE_DEPTH {
+            return Err(OscError::LimitExceeded(DecodeLimit::BundleDepth));
+        }
+        let (bundle_tag, tag_len) = read_str(msg)?;
+        if bundle_tag != "#bundle" {
+            return Err(OscError::BadBundle(format!(
+                "Wrong bundle specifier: {}",
+                bundle_tag
+            )));
+        }
+        let rest = &msg[tag_len..];
+        let timetag = read_time_tag(rest)?;
+        let content = &rest[8..];
+
+        let mut elements = OscBundleContentRef { data: content };
+        while let Some(elem) = elements.next_element() {
+            OscPacketRef::parse_nested(elem?, depth)?;
+        }
+
+        Ok(OscBundleRef { timetag, content })
+    }
+
+    /// Returns an iterator over the packets contained in this bundle.
+    pub fn content(&self) -> OscBundleContentRef<'a> {
+        OscBundleContentRef { data: self.content }
+    }
+}
+
+impl<'a> OscArrayRef<'a> {
+    /// Returns the type tags of the array's elements.
+    pub fn type_tags(&self) -> &'a str {
+        self.type_tags
+    }
+
+    /// Returns an iterator over the elements of this array.
+    pub fn iter(&self) -> OscArgsRef<'a> {
+        OscArgsRef {
+            type_tags: self.type_tags,
+            data: self.data,
+        }
+    }
+}
+
+/// Iterator over the arguments of an [`OscMessageRef`] or [`OscArrayRef`].
+#[derive(Clone, Debug)]
+pub struct OscArgsRef<'a> {
+    type_tags: &'a str,
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for OscArgsRef<'a> {
+    type Item = OscTypeRef<'a>;
+
+    fn next(&mut self) -> Option<OscTypeRef<'a>> {
+        // The arguments were validated while decoding, errors can't occur here.
+        let (arg, tags_len, data_len) = read_arg(self.type_tags, self.data).ok()??;
+        self.type_tags = &self.type_tags[tags_len..];
+        self.data = &self.data[data_len..];
+        Some(arg)
+    }
+}
+
+/// Iterator that lazily decodes the arguments of a message, see
+/// [`decoder::decode_args`](crate::decoder::decode_args).
+///
+/// Arguments are only validated when they are reached, so the iterator
+/// yields an error for the first malformed argument and stops afterwards.
+#[derive(Clone, Debug)]
+pub struct OscArgs<'a> {
+    type_tags: &'a str,
+    data: &'a [u8],
+    failed: bool,
+}
+
+impl<'a> OscArgs<'a> {
+    /// Decodes the address of `msg` and returns it together with an iterator
+    /// over the message's arguments.
+    pub(crate) fn parse(msg: &'a [u8]) -> Result<(&'a str, OscArgs<'a>)> {
+        if msg.first() != Some(&b'/') {
+            return Err(OscError::BadPacket("Packet is not an OSC message."));
+        }
+        let (addr, type_tags, data) = split_message(msg)?;
+        let args = OscArgs {
+            type_tags,
+            data,
+            failed: false,
+        };
+        Ok((addr, args))
+    }
+
+    /// Returns the type tags of the arguments that were not decoded yet.
+    pub fn type_tags(&self) -> &'a str {
+        self.type_tags
+    }
+}
+
+impl<'a> Iterator for OscArgs<'a> {
+    type Item = Result<OscTypeRef<'a>>;
+
+    fn next(&mut self) -> Option<Result<OscTypeRef<'a>>> {
+        if self.failed {
+            return None;
+        }
+        match read_arg(self.type_tags, self.data) {
+            Ok(Some((arg, tags_len, data_len))) => {
+                self.type_tags = &self.type_tags[tags_len..];
+                self.data = &self.data[data_len..];
+                Some(Ok(arg))
+            }
+            Ok(None) => None,
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Iterator over the packets contained in an [`OscBundleRef`].
+#[derive(Clone, Debug)]
+pub struct OscBundleContentRef<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> OscBundleContentRef<'a> {
+    fn next_element(&mut self) -> Option<Result<&'a [u8]>> {
+        if self.data.is_empty() {
+            return None;
+        }
+        if self.data.len() < 4 {
+            self.data = &[];
+            return Some(Err(unexpected_eof()));
+        }
+        let size = BigEndian::read_u32(self.data) as usize;
+        let rest = &self.data[4..];
+        if rest.len() < size {
+            self.data = &[];
+            return Some(Err(OscError::BadBundle(format!(
+                "Bundle element of size {} exceeds packet length",
+                size
+            ))));
+        }
+        self.data = &rest[size..];
+        Some(Ok(&rest[..size]))
+    }
+}
+
+impl<'a> Iterator for OscBundleContentRef<'a> {
+    type Item = OscPacketRef<'a>;
+
+    fn next(&mut self) -> Option<OscPacketRef<'a>> {
+        // The elements were validated while decoding, errors can't occur here.
+        self.next_element()?.and_then(OscPacketRef::parse).ok()
+    }
+}
+
+impl<'a> From<OscPacketRef<'a>> for OscPacket {
+    fn from(packet: OscPacketRef<'a>) -> OscPacket {
+        match packet {
+            OscPacketRef::Message(msg) => OscPacket::Message(msg.into()),
+            OscPacketRef::Bundle(bundle) => OscPacket::Bundle(bundle.into()),
+        }
+    }
+}
+
+impl<'a> From<OscMessageRef<'a>> for OscMessage {
+    fn from(msg: OscMessageRef<'a>) -> OscMessage {
+        OscMessage {
+            addr: msg.addr.to_string(),
+            args: msg.args().map(OscType::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<OscBundleRef<'a>> for OscBundle {
+    fn from(bundle: OscBundleRef<'a>) -> OscBundle {
+        OscBundle {
+            timetag: bundle.timetag,
+            content: bundle.content().map(OscPacket::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<OscTypeRef<'a>> for OscType {
+    fn from(arg: OscTypeRef<'a>) -> OscType {
+        match arg {
+            OscTypeRef::Int(x) => OscType::Int(x),
+            OscTypeRef::Float(x) => OscType::Float(x),
+            OscTypeRef::String(x) => OscType::String(x.to_string()),
+            OscTypeRef::Symbol(x) => OscType::Symbol(x.to_string()),
+            OscTypeRef::Blob(x) => OscType::Blob(x.to_vec()),
+            OscTypeRef::Time(x) => OscType::Time(x),
+            OscTypeRef::Long(x) => OscType::Long(x),
+            OscTypeRef::Double(x) => OscType::Double(x),
+            OscTypeRef::Char(x) => OscType::Char(x),
+            OscTypeRef::Color(x) => OscType::Color(x),
+            OscTypeRef::Midi(x) => OscType::Midi(x),
+            OscTypeRef::Bool(x) => OscType::Bool(x),
+            OscTypeRef::Array(x) => OscType::Array(OscArray {
+                content: x.iter().map(OscType::from).collect(),
+            }),
+            OscTypeRef::Nil => OscType::Nil,
+            OscTypeRef::Inf => OscType::Inf,
+        }
+    }
+}
+
+/// Splits a message into its address, the type tags without the leading comma and
+/// the argument data.
+fn split_message(msg: &[u8]) -> Result<(&str, &str, &[u8])> {
+    let (addr, addr_len) = read_str(msg)?;
+    let rest = &msg[addr_len..];
+    if rest.is_empty() {
+        return Ok((addr, "", rest));
+    }
+    let (type_tags, tags_len) = read_str(rest)?;
+    if !type_tags.starts_with(',') {
+        return Err(OscError::BadMessage(
+            "Type tag string does not start with a comma",
+        ));
+    }
+    Ok((addr, &type_tags[1..], &rest[tags_len..]))
+}
+
+fn unexpected_eof() -> OscError {
+    OscError::BadPacket("Unexpected end of packet.")
+}
+
+/// Walks the arguments described by `type_tags` and returns the length of the consumed
+/// type tags and argument bytes. If `in_array` is set the walk stops at the `]` closing
+/// the array.
+///
+/// Nested arrays are tracked by a counter instead of recursion and their depth is
+/// limited, so crafted type tag strings can't exhaust the stack.
+fn walk_args(type_tags: &str, data: &[u8], in_array: bool) -> Result<(usize, usize)> {
+    let mut depth: usize = if in_array { 1 } else { 0 };
+    let mut tags_pos = 0;
+    let mut data_pos = 0;
+    for tag in type_tags.chars() {
+        match tag {
+            '[' if depth >= DEFAULT_MAX_ARRAY_DEPTH => {
+                return Err(OscError::LimitExceeded(DecodeLimit::ArrayDepth))
+            }
+            '[' => depth += 1,
+            ']' if depth == 0 => return Err(OscError::BadMessage("Encountered ] outside array")),
+            ']' => {
+                depth -= 1;
+                if in_array && depth == 0 {
+                    return Ok((tags_pos, data_pos));
+                }
+            }
+            _ => {
+                let data_len = arg_data_len(tag, &data[data_pos..])?;
+                data_pos += data_len;
+            }
+        }
+        tags_pos += tag.len_utf8();
+    }
+    if depth > 0 {
+        return Err(OscError::BadMessage("Encountered [ without matching ]"));
+    }
+    Ok((tags_pos, data_pos))
+}
+
+/// Returns the number of data bytes of the argument with the given non-array `tag`.
+fn arg_data_len(tag: char, data: &[u8]) -> Result<usize> {
+    let mut tags = [0u8; 4];
+    match read_arg(tag.encode_utf8(&mut tags), data)? {
+        Some((_, _, data_len)) => Ok(data_len),
+        None => Ok(0),
+    }
+}
+
+/// Reads the argument described by the first tag in `type_tags` from `data`. Returns the
+/// argument together with the number of consumed type tag and data bytes.
+fn read_arg<'a>(
+    type_tags: &'a str,
+    data: &'a [u8],
+) -> Result<Option<(OscTypeRef<'a>, usize, usize)>> {
+    let tag = match type_tags.chars().next() {
+        Some(tag) => tag,
+        None => return Ok(None),
+    };
+    let fixed = |size: usize| {
+        if data.len() < size {
+            Err(unexpected_eof())
+        } else {
+            Ok(&data[..size])
+        }
+    };
+    let (arg, data_len) = match tag {
+        'i' => (OscTypeRef::Int(BigEndian::read_i32(fixed(4)?)), 4),
+        'f' => (OscTypeRef::Float(BigEndian::read_f32(fixed(4)?)), 4),
+        'h' => (OscTypeRef::Long(BigEndian::read_i64(fixed(8)?)), 8),
+        'd' => (OscTypeRef::Double(BigEndian::read_f64(fixed(8)?)), 8),
+        't' => (OscTypeRef::Time(read_time_tag(data)?), 8),
+        's' => {
+            let (s, len) = read_str(data)?;
+            (OscTypeRef::String(s), len)
+        }
+        'S' => {
+            let (s, len) = read_str(data)?;
+            (OscTypeRef::Symbol(s), len)
+        }
+        'b' => {
+            let size = BigEndian::read_u32(fixed(4)?) as usize;
+            if data.len() - 4 < size {
+                return Err(unexpected_eof());
+            }
+            let len = (encoder::pad(4 + size as u64) as usize).min(data.len());
+            (OscTypeRef::Blob(&data[4..4 + size]), len)
+        }
+        'c' => match char::from_u32(BigEndian::read_u32(fixed(4)?)) {
+            Some(c) if c.is_ascii() => (OscTypeRef::Char(c), 4),
+            Some(_) => {
+                return Err(OscError::BadArg(
+                    "Argument is not an ASCII char!".to_string(),
+                ))
+            }
+            None => return Err(OscError::BadArg("Argument is not a char!".to_string())),
+        },
+        'r' => {
+            let b = fixed(4)?;
+            let color = OscColor {
+                red: b[0],
+                green: b[1],
+                blue: b[2],
+                alpha: b[3],
+            };
+            (OscTypeRef::Color(color), 4)
+        }
+        'm' => {
+            let b = fixed(4)?;
+            let midi = OscMidiMessage {
+                port: b[0],
+                status: b[1],
+                data1: b[2],
+                data2: b[3],
+            };
+            (OscTypeRef::Midi(midi), 4)
+        }
+        'T' => (OscTypeRef::Bool(true), 0),
+        'F' => (OscTypeRef::Bool(false), 0),
+        'N' => (OscTypeRef::Nil, 0),
+        'I' => (OscTypeRef::Inf, 0),
+        '[' => {
+            let inner_tags = &type_tags[1..];
+            let (tags_len, data_len) = walk_args(inner_tags, data, true)?;
+            let array = OscArrayRef {
+                type_tags: &inner_tags[..tags_len],
+                data: &data[..data_len],
+            };
+            // skip both brackets
+            return Ok(Some((OscTypeRef::Array(array), tags_len + 2, data_len)));
+        }
+        ']' => return Err(OscError::BadMessage("Encountered ] outside array")),
+        _ => {
+            return Err(OscError::BadArg(format!(
+                "Type tag \"{}\" is not implemented!",
+                tag
+            )))
+        }
+    };
+    Ok(Some((arg, tag.len_utf8(), data_len)))
+}
+
+/// Reads a nul terminated string and returns it together with its padded length.
+fn read_str(data: &[u8]) -> Result<(&str, usize)> {
+    let len = match data.iter().position(|b| *b == 0) {
+        Some(len) => len,
+        None => return Err(OscError::BadString("Missing nul terminator")),
+    };
+    let s = str::from_utf8(&data[..len])
+        .map_err(|_| OscError::BadString("String is not valid UTF-8"))?;
+    let padded_len = (encoder::pad(len as u64 + 1) as usize).min(data.len());
+    Ok((s, padded_len))
+}
+
+fn read_time_tag(data: &[u8]) -> Result<OscTime> {
+    if data.len() < 8 {
+        return Err(unexpected_eof());
+    }
+    Ok(OscTime {
+        seconds: BigEndian::read_u32(&data[..4]),
+        fractional: BigEndian::read_u32(&data[4..8]),
+    })
+}
@@ -0,0 +1,207 @@
+use crate::types::{OscArray, OscBundle, OscMessage, OscPacket, OscType};
+
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+use core::mem;
+
+/// A wrapper that implements `Eq` and `Hash` for OSC values containing
+/// floats, e.g. to use messages as `HashMap` keys for deduplication.
+///
+/// Floats are compared by their bit pattern instead of numerically, so
+/// all NaNs are equal to each other, while `0.0` and `-0.0` differ just
+/// like their encodings do. Everything else is compared like `PartialEq`
+/// does.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{Canonical, OscMessage};
+/// use std::collections::HashSet;
+///
+/// let msg = OscMessage {
+///     addr: "/level".to_string(),
+///     args: vec![f32::NAN.into()],
+/// };
+/// assert_ne!(msg, msg.clone());
+///
+/// let mut seen = HashSet::new();
+/// assert!(seen.insert(Canonical(msg.clone())));
+/// assert!(!seen.insert(Canonical(msg)));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Canonical<T>(pub T);
+
+impl<T> Canonical<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Comparison and hashing of OSC values with floats by their bit patterns,
+/// see [`Canonical`].
+pub trait CanonicalEq {
+    /// Returns whether `self` and `other` are equal, comparing floats by
+    /// their bit patterns.
+    fn canonical_eq(&self, other: &Self) -> bool;
+
+    /// Feeds `self` into `state` consistently with `canonical_eq`.
+    fn canonical_hash<H: Hasher>(&self, state: &mut H);
+}
+
+impl<T: CanonicalEq> PartialEq for Canonical<T> {
+    fn eq(&self, other: &Canonical<T>) -> bool {
+        self.0.canonical_eq(&other.0)
+    }
+}
+
+impl<T: CanonicalEq> Eq for Canonical<T> {}
+
+impl<T: CanonicalEq> Hash for Canonical<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.canonical_hash(state)
+    }
+}
+
+impl<T> From<T> for Canonical<T> {
+    fn from(value: T) -> Canonical<T> {
+        Canonical(value)
+    }
+}
+
+/// Returns the bit pattern of `x`, the same one for all NaNs.
+fn f32_bits(x: f32) -> u32 {
+    if x.is_nan() {
+        f32::NAN.to_bits()
+    } else {
+        x.to_bits()
+    }
+}
+
+/// Returns the bit pattern of `x`, the same one for all NaNs.
+fn f64_bits(x: f64) -> u64 {
+    if x.is_nan() {
+        f64::NAN.to_bits()
+    } else {
+        x.to_bits()
+    }
+}
+
+impl CanonicalEq for OscType {
+    fn canonical_eq(&self, other: &OscType) -> bool {
+        match (self, other) {
+            (OscType::Float(a), OscType::Float(b)) => f32_bits(*a) == f32_bits(*b),
+            (OscType::Double(a), OscType::Double(b)) => f64_bits(*a) == f64_bits(*b),
+            (OscType::Array(a), OscType::Array(b)) => a.canonical_eq(b),
+            (a, b) => a == b,
+        }
+    }
+
+    fn canonical_hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match *self {
+            OscType::Int(x) => x.hash(state),
+            OscType::Float(x) => f32_bits(x).hash(state),
+            OscType::String(ref x) | OscType::Symbol(ref x) => x.hash(state),
+            OscType::Blob(ref x) | OscType::RawString(ref x) => x.hash(state),
+            OscType::Time(x) => x.hash(state),
+            OscType::Long(x) => x.hash(state),
+            OscType::Double(x) => f64_bits(x).hash(state),
+            OscType::Char(x) => x.hash(state),
+            OscType::Color(x) => x.hash(state),
+            OscType::Midi(ref x) => x.hash(state),
+            OscType::Bool(x) => x.hash(state),
+            OscType::Array(ref x) => x.canonical_hash(state),
+            OscType::Nil | OscType::Inf => (),
+            OscType::Custom { tag, ref data } => {
+                tag.hash(state);
+                data.hash(state);
+            }
+        }
+    }
+}
+
+impl CanonicalEq for OscArray {
+    fn canonical_eq(&self, other: &OscArray) -> bool {
+        self.content.canonical_eq(&other.content)
+    }
+
+    fn canonical_hash<H: Hasher>(&self, state: &mut H) {
+        self.content.canonical_hash(state)
+    }
+}
+
+impl CanonicalEq for OscMessage {
+    fn canonical_eq(&self, other: &OscMessage) -> bool {
+        self.addr == other.addr && self.args.canonical_eq(&other.args)
+    }
+
+    fn canonical_hash<H: Hasher>(&self, state: &mut H) {
+        self.addr.hash(state);
+        self.args.canonical_hash(state);
+    }
+}
+
+impl CanonicalEq for OscBundle {
+    fn canonical_eq(&self, other: &OscBundle) -> bool {
+        self.timetag == other.timetag && self.content.canonical_eq(&other.content)
+    }
+
+    fn canonical_hash<H: Hasher>(&self, state: &mut H) {
+        self.timetag.hash(state);
+        self.content.canonical_hash(state);
+    }
+}
+
+impl CanonicalEq for OscPacket {
+    fn canonical_eq(&self, other: &OscPacket) -> bool {
+        match (self, other) {
+            (OscPacket::Message(a), OscPacket::Message(b)) => a.canonical_eq(b),
+            (OscPacket::Bundle(a), OscPacket::Bundle(b)) => a.canonical_eq(b),
+            (OscPacket::Raw(a), OscPacket::Raw(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    fn canonical_hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match *self {
+            OscPacket::Message(ref msg) => msg.canonical_hash(state),
+            OscPacket::Bundle(ref bundle) => bundle.canonical_hash(state),
+            OscPacket::Raw(ref x) => x.hash(state),
+        }
+    }
+}
+
+impl<T: CanonicalEq> CanonicalEq for [T] {
+    fn canonical_eq(&self, other: &[T]) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.canonical_eq(b))
+    }
+
+    fn canonical_hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.canonical_hash(state);
+        }
+    }
+}
+
+impl<T: CanonicalEq> CanonicalEq for Vec<T> {
+    fn canonical_eq(&self, other: &Vec<T>) -> bool {
+        self[..].canonical_eq(&other[..])
+    }
+
+    fn canonical_hash<H: Hasher>(&self, state: &mut H) {
+        self[..].canonical_hash(state)
+    }
+}
+
+impl<T: CanonicalEq + ?Sized> CanonicalEq for &T {
+    fn canonical_eq(&self, other: &Self) -> bool {
+        (**self).canonical_eq(*other)
+    }
+
+    fn canonical_hash<H: Hasher>(&self, state: &mut H) {
+        (**self).canonical_hash(state)
+    }
+}
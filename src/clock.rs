@@ -0,0 +1,306 @@
+//! Estimating the clock offset and drift to an OSC peer, so that bundles
+//! scheduled on machines with unsynchronized clocks are applied at the same
+//! time.
+//!
+//! Like NTP, [`estimate`] exchanges four time stamps with the peer: it
+//! sends a request to the clock address with its send time `t1` as single
+//! `t` argument. The peer replies to the sender with a message to the same
+//! address carrying `t1`, its receive time `t2` and its send time `t3`,
+//! which [`reply`] creates. The arrival time of the reply is `t4`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rosc::clock::{self, ClockOptions};
+//! use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+//! use std::time::Duration;
+//!
+//! let estimate = clock::estimate("192.168.1.20:9000", &ClockOptions::default()).unwrap();
+//! println!("offset {:.6} s, drift {:.2} ppm", estimate.offset, estimate.drift * 1e6);
+//!
+//! // scheduled by the peer's clock
+//! let local = OscTime::from_duration_since_now(Duration::from_secs(2)).unwrap();
+//! let bundle = OscPacket::Bundle(OscBundle {
+//!     timetag: estimate.to_remote(local),
+//!     content: vec![OscPacket::Message(OscMessage::from("/cue/go"))],
+//! });
+//! ```
+
+use crate::decoder::MTU;
+use crate::encoder;
+use crate::errors::OscError;
+use crate::ping::await_reply;
+use crate::types::{OscMessage, OscPacket, OscTime, OscType, Result};
+use crate::udp::{resolve, unspecified_addr};
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The four time stamps of a request and its reply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ClockSample {
+    /// When the request was sent, by the local clock.
+    pub t1: OscTime,
+    /// When the request was received, by the remote clock.
+    pub t2: OscTime,
+    /// When the reply was sent, by the remote clock.
+    pub t3: OscTime,
+    /// When the reply was received, by the local clock.
+    pub t4: OscTime,
+}
+
+impl ClockSample {
+    /// Returns how many seconds the remote clock is ahead of the local
+    /// clock, assuming that both directions took equally long.
+    pub fn offset(&self) -> f64 {
+        ((secs(self.t2) - secs(self.t1)) + (secs(self.t3) - secs(self.t4))) / 2.0
+    }
+
+    /// Returns the round-trip time in seconds, excluding the time the peer
+    /// took to reply.
+    pub fn delay(&self) -> f64 {
+        (secs(self.t4) - secs(self.t1)) - (secs(self.t3) - secs(self.t2))
+    }
+}
+
+/// The largest drift in either direction an estimate uses. Real clocks
+/// drift by a few parts per million, larger values come from samples
+/// distorted by delays or a clock that was set in between.
+pub const MAX_DRIFT: f64 = 0.5;
+
+/// An estimate of the remote clock relative to the local clock.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClockEstimate {
+    /// How many seconds the remote clock is ahead of the local clock at
+    /// `reference`.
+    pub offset: f64,
+    /// The local time the offset was measured at.
+    pub reference: OscTime,
+    /// How many seconds per second the remote clock runs faster than the
+    /// local one, e.g. `1e-6` for one microsecond per second. Limited to
+    /// [`MAX_DRIFT`] in either direction when converting times.
+    pub drift: f64,
+    /// The round-trip time in seconds of the sample the offset was taken
+    /// from, which bounds its error.
+    pub delay: f64,
+    /// The number of samples the estimate is based on.
+    pub samples: usize,
+}
+
+impl ClockEstimate {
+    /// Estimates the clock from `samples`, or returns `None` if there are
+    /// none. Like NTP, the offset is taken from the sample with the
+    /// shortest delay, as it is the least distorted by queuing. The drift
+    /// is fitted to the offsets of all samples, which needs samples spread
+    /// over a longer time to be accurate.
+    pub fn from_samples(samples: &[ClockSample]) -> Option<ClockEstimate> {
+        let best = samples
+            .iter()
+            .min_by(|a, b| a.delay().total_cmp(&b.delay()))?;
+        Some(ClockEstimate {
+            offset: best.offset(),
+            reference: best.t1,
+            drift: drift(samples).clamp(-MAX_DRIFT, MAX_DRIFT),
+            delay: best.delay(),
+            samples: samples.len(),
+        })
+    }
+
+    /// Returns the estimated offset at the local time `local`.
+    pub fn offset_at(&self, local: OscTime) -> f64 {
+        self.offset + self.drift() * (secs(local) - secs(self.reference))
+    }
+
+    /// Converts the local time `local` to the time the remote clock shows
+    /// at that moment, e.g. for the time tag of a bundle sent to the peer.
+    pub fn to_remote(&self, local: OscTime) -> OscTime {
+        add_secs(local, self.offset_at(local))
+    }
+
+    /// Converts the remote time `remote` to the local time, the inverse of
+    /// [`to_remote`](ClockEstimate::to_remote).
+    pub fn to_local(&self, remote: OscTime) -> OscTime {
+        // solves remote = local + offset_at(local) for local
+        let remote_secs = secs(remote);
+        let drift = self.drift();
+        let local_secs = (remote_secs - self.offset + drift * secs(self.reference)) / (1.0 + drift);
+        add_secs(remote, local_secs - remote_secs)
+    }
+
+    /// Returns the drift limited to [`MAX_DRIFT`], so that converting times
+    /// can't divide by zero or reverse their order.
+    fn drift(&self) -> f64 {
+        if self.drift.is_nan() {
+            0.0
+        } else {
+            self.drift.clamp(-MAX_DRIFT, MAX_DRIFT)
+        }
+    }
+}
+
+/// Options that control [`estimate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClockOptions {
+    /// The address the peer replies to clock requests on. Defaults to
+    /// `/clock`.
+    pub addr: String,
+    /// Number of requests to send. Defaults to 8.
+    pub count: usize,
+    /// Time between sending two requests. Defaults to 100 milliseconds.
+    pub interval: Duration,
+    /// How long to wait for a reply before the request counts as lost.
+    /// Defaults to 1 second.
+    pub timeout: Duration,
+}
+
+impl Default for ClockOptions {
+    fn default() -> ClockOptions {
+        ClockOptions {
+            addr: "/clock".to_string(),
+            count: 8,
+            interval: Duration::from_millis(100),
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Exchanges time stamps with the peer at `target` as described by
+/// `options` and returns the clock estimate. Returns an
+/// `OscError::SocketError` if the target can't be resolved or the socket
+/// can't be bound, an `OscError::WriteError` or `OscError::ReadError` if
+/// sending or receiving fails, or an `OscError::ReadError` with the kind
+/// `TimedOut` if the peer didn't reply to any request. Returns an
+/// `OscError::BadArg` if the system clock is out of the range of time tags.
+pub fn estimate<A: ToSocketAddrs>(target: A, options: &ClockOptions) -> Result<ClockEstimate> {
+    let target = resolve(target)?;
+    let socket = UdpSocket::bind(unspecified_addr(target)).map_err(OscError::SocketError)?;
+    let mut buf = vec![0u8; MTU];
+    let mut samples = vec![];
+    for i in 0..options.count {
+        let sent = Instant::now();
+        let t1 = now()?;
+        let request = OscPacket::Message(OscMessage {
+            addr: options.addr.clone(),
+            args: vec![OscType::Time(t1)],
+        });
+        socket
+            .send_to(&encoder::encode(&request)?, target)
+            .map_err(OscError::WriteError)?;
+        let is_reply = |msg: &OscMessage| msg.args.len() == 3 && msg.args[0] == OscType::Time(t1);
+        if let Some(reply) = await_reply(&socket, &mut buf, sent, options.timeout, is_reply)? {
+            let t4 = now()?;
+            if let (OscType::Time(t2), OscType::Time(t3)) = (&reply.args[1], &reply.args[2]) {
+                samples.push(ClockSample {
+                    t1,
+                    t2: *t2,
+                    t3: *t3,
+                    t4,
+                });
+            }
+        }
+        if i + 1 < options.count {
+            if let Some(wait) = options.interval.checked_sub(sent.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+    }
+    ClockEstimate::from_samples(&samples)
+        .ok_or_else(|| OscError::ReadError(io::ErrorKind::TimedOut.into()))
+}
+
+/// Returns the reply to the clock request `request` received at
+/// `received`, or `None` if it isn't a request. Send the reply to the
+/// sender of the request right away.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::udp::OscUdpServer;
+/// use rosc::{clock, encoder, OscPacket, OscTime};
+///
+/// let mut server = OscUdpServer::bind("0.0.0.0:9000").unwrap();
+/// loop {
+///     let (packet, from) = server.recv().unwrap();
+///     let received = OscTime::now().unwrap();
+///     if let OscPacket::Message(ref msg) = packet {
+///         if msg.addr == "/clock" {
+///             if let Some(reply) = clock::reply(msg, received) {
+///                 let reply = encoder::encode(&OscPacket::Message(reply)).unwrap();
+///                 server.socket().send_to(&reply, from).unwrap();
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub fn reply(request: &OscMessage, received: OscTime) -> Option<OscMessage> {
+    let t1 = match request.args[..] {
+        [OscType::Time(t1)] => t1,
+        _ => return None,
+    };
+    Some(OscMessage {
+        addr: request.addr.clone(),
+        args: vec![
+            OscType::Time(t1),
+            OscType::Time(received),
+            OscType::Time(OscTime::now().ok()?),
+        ],
+    })
+}
+
+fn now() -> Result<OscTime> {
+    OscTime::now().map_err(|err| OscError::BadArg(format!("current time as time tag: {}", err)))
+}
+
+/// Returns the seconds since the OSC epoch.
+fn secs(time: OscTime) -> f64 {
+    time.to_duration().as_secs_f64()
+}
+
+/// Returns `time` moved by `secs` seconds, saturating at the limits of
+/// `OscTime`. Returns `time` unchanged if `secs` is NaN.
+fn add_secs(time: OscTime, secs: f64) -> OscTime {
+    if secs.is_nan() {
+        return time;
+    }
+    let moved = Duration::try_from_secs_f64(secs.abs())
+        .ok()
+        .and_then(|duration| {
+            if secs >= 0.0 {
+                time.checked_add(duration)
+            } else {
+                time.checked_sub(duration)
+            }
+        });
+    moved.unwrap_or(if secs >= 0.0 {
+        OscTime::from((u32::MAX, u32::MAX))
+    } else {
+        OscTime::from((0, 0))
+    })
+}
+
+/// Fits a line to the offsets of `samples` over their local time by least
+/// squares and returns its slope, or 0 if there are too few samples.
+fn drift(samples: &[ClockSample]) -> f64 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let n = samples.len() as f64;
+    let start = secs(samples[0].t1);
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|sample| (secs(sample.t1) - start, sample.offset()))
+        .collect();
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,231 @@
+//! A [`tokio_util::codec`] implementation for OSC streams.
+//!
+//! `tokio_util` converts the I/O errors of the stream into the codec's
+//! error types, so the codec wraps `OscError` in a
+//! [`DecodeError`](crate::codec::DecodeError) and an
+//! [`EncodeError`](crate::codec::EncodeError), which turn them into an
+//! `OscError::ReadError` and an `OscError::WriteError`. Both convert into
+//! `OscError`.
+
+use crate::decoder::{DecoderOptions, StreamDecoder};
+use crate::encoder;
+use crate::errors::OscError;
+use crate::slip::{self, SlipDecoder};
+use crate::types::{OscPacket, Result};
+use bytes::BytesMut;
+use std::{error, fmt, io, result};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// How packets are delimited in a stream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Framing {
+    /// Every packet is preceded by its size as a big-endian `int32`, as
+    /// specified by OSC 1.0. This is the default.
+    #[default]
+    LengthPrefix,
+    /// Packets are SLIP encoded as specified by OSC 1.1, see the
+    /// [`slip`](crate::slip) module.
+    Slip,
+}
+
+/// Encodes and decodes OSC packets of a byte stream.
+///
+/// Wrap a stream in a `tokio_util::codec::Framed` to get a `Stream` and
+/// `Sink` of packets.
+///
+/// # Example
+///
+/// ```
+/// # extern crate bytes;
+/// # extern crate rosc;
+/// # extern crate tokio_util;
+/// use bytes::BytesMut;
+/// use rosc::codec::OscCodec;
+/// use rosc::{OscMessage, OscPacket};
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut codec = OscCodec::slip();
+/// let mut buf = BytesMut::new();
+/// codec.encode(packet.clone(), &mut buf).unwrap();
+/// assert_eq!(Some(packet), codec.decode(&mut buf).unwrap());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OscCodec {
+    framing: Framing,
+    length_prefix: StreamDecoder,
+    slip: SlipDecoder,
+}
+
+impl OscCodec {
+    /// Creates a codec for length-prefixed packets.
+    pub fn new() -> OscCodec {
+        OscCodec::default()
+    }
+
+    /// Creates a codec for SLIP encoded packets.
+    pub fn slip() -> OscCodec {
+        OscCodec::with_framing(Framing::Slip)
+    }
+
+    /// Creates a codec for packets delimited by `framing`.
+    pub fn with_framing(framing: Framing) -> OscCodec {
+        OscCodec {
+            framing,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a codec for packets delimited by `framing`, which decodes
+    /// packets using the given `options`.
+    pub fn with_options(framing: Framing, options: DecoderOptions) -> OscCodec {
+        OscCodec {
+            framing,
+            length_prefix: StreamDecoder::with_options(options.clone()),
+            slip: SlipDecoder::with_options(options),
+        }
+    }
+
+    /// Returns how packets are delimited.
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    fn encode_packet(&self, packet: &OscPacket, dst: &mut BytesMut) -> Result<()> {
+        let mut frame = vec![];
+        match self.framing {
+            Framing::LengthPrefix => {
+                encoder::encode_framed_into(packet, &mut frame)?;
+            }
+            Framing::Slip => slip::encode_into(&encoder::encode(packet)?, &mut frame),
+        }
+        dst.extend_from_slice(&frame);
+        Ok(())
+    }
+
+    fn buffered(&self) -> usize {
+        match self.framing {
+            Framing::LengthPrefix => self.length_prefix.buffered(),
+            Framing::Slip => self.slip.buffered(),
+        }
+    }
+}
+
+impl Decoder for OscCodec {
+    type Item = OscPacket;
+    type Error = DecodeError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> result::Result<Option<OscPacket>, DecodeError> {
+        let data = src.split();
+        match self.framing {
+            Framing::LengthPrefix => {
+                self.length_prefix.feed(&data);
+                Ok(self.length_prefix.decode()?.map(|(packet, _)| packet))
+            }
+            Framing::Slip => {
+                self.slip.feed(&data);
+                Ok(self.slip.decode()?)
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> result::Result<Option<OscPacket>, DecodeError> {
+        match self.decode(src)? {
+            Some(packet) => Ok(Some(packet)),
+            None if self.buffered() > 0 => {
+                Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl Encoder<&OscPacket> for OscCodec {
+    type Error = EncodeError;
+
+    fn encode(
+        &mut self,
+        packet: &OscPacket,
+        dst: &mut BytesMut,
+    ) -> result::Result<(), EncodeError> {
+        self.encode_packet(packet, dst).map_err(EncodeError)
+    }
+}
+
+impl Encoder<OscPacket> for OscCodec {
+    type Error = EncodeError;
+
+    fn encode(&mut self, packet: OscPacket, dst: &mut BytesMut) -> result::Result<(), EncodeError> {
+        self.encode(&packet, dst)
+    }
+}
+
+/// An error of decoding packets with an [`OscCodec`]. I/O errors of the
+/// stream are wrapped in an `OscError::ReadError`.
+#[derive(Debug)]
+pub struct DecodeError(pub OscError);
+
+/// An error of encoding packets with an [`OscCodec`]. I/O errors of the
+/// stream are wrapped in an `OscError::WriteError`.
+#[derive(Debug)]
+pub struct EncodeError(pub OscError);
+
+impl From<OscError> for DecodeError {
+    fn from(err: OscError) -> DecodeError {
+        DecodeError(err)
+    }
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> DecodeError {
+        DecodeError(OscError::ReadError(err))
+    }
+}
+
+impl From<DecodeError> for OscError {
+    fn from(err: DecodeError) -> OscError {
+        err.0
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl From<OscError> for EncodeError {
+    fn from(err: OscError) -> EncodeError {
+        EncodeError(err)
+    }
+}
+
+impl From<io::Error> for EncodeError {
+    fn from(err: io::Error) -> EncodeError {
+        EncodeError(OscError::WriteError(err))
+    }
+}
+
+impl From<EncodeError> for OscError {
+    fn from(err: EncodeError) -> OscError {
+        err.0
+    }
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl error::Error for EncodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.0.source()
+    }
+}
@@ -0,0 +1,249 @@
+use crate::borrowed::{OscMessageRef, OscTypeRef};
+use crate::types::{OscArray, OscColor, OscMessage, OscMidiMessage, OscTime, OscType};
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// An OSC message whose address and arguments are either borrowed or
+/// owned.
+///
+/// Forwarding servers often build messages from static addresses or from
+/// the strings and blobs of a received packet. Building an
+/// `OscMessageCow` from them and encoding it with
+/// [`encoder::encode_cow`](crate::encoder::encode_cow) avoids copying
+/// these into an [`OscMessage`] first. Use [`From`]/[`Into`] or
+/// [`OscMessageCow::into_owned`] to turn it into an owned [`OscMessage`].
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscMessageCow, OscPacket, OscPacketRef};
+///
+/// let received = encoder::encode(&OscPacket::Message(OscMessage {
+///     addr: "/1/fader3".to_string(),
+///     args: vec!["vocals".into(), 0.5f32.into()],
+/// }))
+/// .unwrap();
+///
+/// if let OscPacketRef::Message(msg) = decoder::decode_ref(&received).unwrap() {
+///     // forward the arguments to another address without copying them
+///     let forwarded = OscMessageCow {
+///         addr: "/mixer/gain".into(),
+///         args: msg.args().map(Into::into).collect(),
+///     };
+///     let bytes = encoder::encode_cow(&forwarded).unwrap();
+///     assert_eq!(
+///         OscPacket::Message(forwarded.into_owned()),
+///         decoder::decode(&bytes).unwrap()
+///     );
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscMessageCow<'a> {
+    pub addr: Cow<'a, str>,
+    pub args: Vec<OscTypeCow<'a>>,
+}
+
+/// An OSC argument whose string or blob is either borrowed or owned, see
+/// [`OscType`] for the owned counterpart.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscTypeCow<'a> {
+    Int(i32),
+    Float(f32),
+    String(Cow<'a, str>),
+    Symbol(Cow<'a, str>),
+    Blob(Cow<'a, [u8]>),
+    Time(OscTime),
+    Long(i64),
+    Double(f64),
+    Char(char),
+    Color(OscColor),
+    Midi(OscMidiMessage),
+    Bool(bool),
+    Array(Vec<OscTypeCow<'a>>),
+    Nil,
+    Inf,
+    Custom { tag: char, data: Cow<'a, [u8]> },
+    RawString(Cow<'a, [u8]>),
+}
+
+impl<'a> OscMessageCow<'a> {
+    /// Creates a message without arguments.
+    pub fn new<A: Into<Cow<'a, str>>>(addr: A) -> OscMessageCow<'a> {
+        OscMessageCow {
+            addr: addr.into(),
+            args: vec![],
+        }
+    }
+
+    /// Converts the message into an owned [`OscMessage`], copying borrowed
+    /// strings and blobs.
+    pub fn into_owned(self) -> OscMessage {
+        self.into()
+    }
+}
+
+impl OscTypeCow<'_> {
+    /// Returns the argument as an [`OscType`] if that doesn't require
+    /// copying a string, blob or array.
+    pub(crate) fn to_scalar(&self) -> Option<OscType> {
+        Some(match *self {
+            OscTypeCow::Int(x) => OscType::Int(x),
+            OscTypeCow::Float(x) => OscType::Float(x),
+            OscTypeCow::Time(x) => OscType::Time(x),
+            OscTypeCow::Long(x) => OscType::Long(x),
+            OscTypeCow::Double(x) => OscType::Double(x),
+            OscTypeCow::Char(x) => OscType::Char(x),
+            OscTypeCow::Color(x) => OscType::Color(x),
+            OscTypeCow::Midi(x) => OscType::Midi(x),
+            OscTypeCow::Bool(x) => OscType::Bool(x),
+            OscTypeCow::Nil => OscType::Nil,
+            OscTypeCow::Inf => OscType::Inf,
+            _ => return None,
+        })
+    }
+}
+
+impl<'a> From<OscMessageRef<'a>> for OscMessageCow<'a> {
+    fn from(msg: OscMessageRef<'a>) -> OscMessageCow<'a> {
+        OscMessageCow {
+            addr: Cow::Borrowed(msg.addr),
+            args: msg.args().map(OscTypeCow::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a OscMessage> for OscMessageCow<'a> {
+    fn from(msg: &'a OscMessage) -> OscMessageCow<'a> {
+        OscMessageCow {
+            addr: Cow::Borrowed(&msg.addr),
+            args: msg.args.iter().map(OscTypeCow::from).collect(),
+        }
+    }
+}
+
+impl From<OscMessage> for OscMessageCow<'_> {
+    fn from(msg: OscMessage) -> Self {
+        OscMessageCow {
+            addr: Cow::Owned(msg.addr),
+            args: msg.args.into_iter().map(OscTypeCow::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<OscMessageCow<'a>> for OscMessage {
+    fn from(msg: OscMessageCow<'a>) -> OscMessage {
+        OscMessage {
+            addr: msg.addr.into_owned(),
+            args: msg.args.into_iter().map(OscType::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<OscTypeRef<'a>> for OscTypeCow<'a> {
+    fn from(arg: OscTypeRef<'a>) -> OscTypeCow<'a> {
+        match arg {
+            OscTypeRef::Int(x) => OscTypeCow::Int(x),
+            OscTypeRef::Float(x) => OscTypeCow::Float(x),
+            OscTypeRef::String(x) => OscTypeCow::String(Cow::Borrowed(x)),
+            OscTypeRef::Symbol(x) => OscTypeCow::Symbol(Cow::Borrowed(x)),
+            OscTypeRef::Blob(x) => OscTypeCow::Blob(Cow::Borrowed(x)),
+            OscTypeRef::Time(x) => OscTypeCow::Time(x),
+            OscTypeRef::Long(x) => OscTypeCow::Long(x),
+            OscTypeRef::Double(x) => OscTypeCow::Double(x),
+            OscTypeRef::Char(x) => OscTypeCow::Char(x),
+            OscTypeRef::Color(x) => OscTypeCow::Color(x),
+            OscTypeRef::Midi(x) => OscTypeCow::Midi(x),
+            OscTypeRef::Bool(x) => OscTypeCow::Bool(x),
+            OscTypeRef::Array(x) => OscTypeCow::Array(x.iter().map(OscTypeCow::from).collect()),
+            OscTypeRef::Nil => OscTypeCow::Nil,
+            OscTypeRef::Inf => OscTypeCow::Inf,
+        }
+    }
+}
+
+impl<'a> From<&'a OscType> for OscTypeCow<'a> {
+    fn from(arg: &'a OscType) -> OscTypeCow<'a> {
+        match *arg {
+            OscType::String(ref x) => OscTypeCow::String(Cow::Borrowed(x)),
+            OscType::Symbol(ref x) => OscTypeCow::Symbol(Cow::Borrowed(x)),
+            OscType::Blob(ref x) => OscTypeCow::Blob(Cow::Borrowed(x)),
+            OscType::Array(ref x) => {
+                OscTypeCow::Array(x.content.iter().map(OscTypeCow::from).collect())
+            }
+            OscType::Custom { tag, ref data } => OscTypeCow::Custom {
+                tag,
+                data: Cow::Borrowed(data),
+            },
+            OscType::RawString(ref x) => OscTypeCow::RawString(Cow::Borrowed(x)),
+            ref scalar => scalar.clone().into(),
+        }
+    }
+}
+
+impl From<OscType> for OscTypeCow<'_> {
+    fn from(arg: OscType) -> Self {
+        match arg {
+            OscType::Int(x) => OscTypeCow::Int(x),
+            OscType::Float(x) => OscTypeCow::Float(x),
+            OscType::String(x) => OscTypeCow::String(Cow::Owned(x)),
+            OscType::Symbol(x) => OscTypeCow::Symbol(Cow::Owned(x)),
+            OscType::Blob(x) => OscTypeCow::Blob(Cow::Owned(x)),
+            OscType::Time(x) => OscTypeCow::Time(x),
+            OscType::Long(x) => OscTypeCow::Long(x),
+            OscType::Double(x) => OscTypeCow::Double(x),
+            OscType::Char(x) => OscTypeCow::Char(x),
+            OscType::Color(x) => OscTypeCow::Color(x),
+            OscType::Midi(x) => OscTypeCow::Midi(x),
+            OscType::Bool(x) => OscTypeCow::Bool(x),
+            OscType::Array(x) => {
+                OscTypeCow::Array(x.content.into_iter().map(OscTypeCow::from).collect())
+            }
+            OscType::Nil => OscTypeCow::Nil,
+            OscType::Inf => OscTypeCow::Inf,
+            OscType::Custom { tag, data } => OscTypeCow::Custom {
+                tag,
+                data: Cow::Owned(data),
+            },
+            OscType::RawString(x) => OscTypeCow::RawString(Cow::Owned(x)),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for OscTypeCow<'a> {
+    fn from(s: &'a str) -> OscTypeCow<'a> {
+        OscTypeCow::String(Cow::Borrowed(s))
+    }
+}
+
+impl From<String> for OscTypeCow<'_> {
+    fn from(s: String) -> Self {
+        OscTypeCow::String(Cow::Owned(s))
+    }
+}
+
+impl<'a> From<&'a [u8]> for OscTypeCow<'a> {
+    fn from(blob: &'a [u8]) -> OscTypeCow<'a> {
+        OscTypeCow::Blob(Cow::Borrowed(blob))
+    }
+}
+
+impl<'a> From<OscTypeCow<'a>> for OscType {
+    fn from(arg: OscTypeCow<'a>) -> OscType {
+        match arg {
+            OscTypeCow::String(x) => OscType::String(x.into_owned()),
+            OscTypeCow::Symbol(x) => OscType::Symbol(x.into_owned()),
+            OscTypeCow::Blob(x) => OscType::Blob(x.into_owned()),
+            OscTypeCow::Array(x) => OscType::Array(OscArray {
+                content: x.into_iter().map(OscType::from).collect(),
+            }),
+            OscTypeCow::Custom { tag, data } => OscType::Custom {
+                tag,
+                data: data.into_owned(),
+            },
+            OscTypeCow::RawString(x) => OscType::RawString(x.into_owned()),
+            scalar => scalar.to_scalar().expect("all other arguments are scalars"),
+        }
+    }
+}
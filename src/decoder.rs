@@ -1,50 +1,802 @@
+#[cfg(feature = "bumpalo")]
+use crate::arena::OscPacketArena;
+use crate::borrowed::{OscArgs, OscPacketRef};
 use crate::encoder;
-use crate::errors::OscError;
+use crate::errors::{DecodeLimit, OscError};
+#[cfg(feature = "bytes")]
+use crate::shared::OscPacketShared;
+#[cfg(feature = "smallvec")]
+use crate::small::OscPacketSmall;
 use crate::types::{
-    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType, Result,
+    NonAsciiChars, OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime,
+    OscType, Result,
 };
 
-use std::io::{BufRead, Read};
-use std::{char, io};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::char;
+#[cfg(feature = "std")]
+use std::io::{self, Read};
 
-use byteorder::{BigEndian, ReadBytesExt};
+#[cfg(feature = "bumpalo")]
+use bumpalo::Bump;
+use byteorder::{BigEndian, ByteOrder};
+#[cfg(feature = "bytes")]
+use bytes::Bytes;
 
 /// Common MTU size for ethernet
 pub const MTU: usize = 1536;
 
+/// The largest payload of a UDP datagram over IPv4, the size of the receive
+/// buffers of the UDP servers
+pub const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Default maximum nesting depth of arrays, deeper nesting could exhaust the
+/// stack when the decoded arguments are processed recursively.
+pub(crate) const DEFAULT_MAX_ARRAY_DEPTH: usize = 64;
+
+/// Default maximum nesting depth of bundles, deeper nesting could exhaust
+/// the stack while decoding or encoding bundles recursively.
+pub(crate) const DEFAULT_MAX_BUNDLE_DEPTH: usize = 32;
+
+/// Controls how strictly the decoder follows the OSC specification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeMode {
+    /// Reject every deviation from the specification, e.g. missing or
+    /// non-zero padding, missing type tag strings or trailing bytes.
+    Strict,
+    /// Recover from the deviations of sloppy senders on a best-effort basis.
+    /// The tolerated deviations are reported by [`decode_with_warnings`].
+    Lenient,
+}
+
+/// How to decode messages without a type tag string.
+///
+/// Implementations predating OSC 1.0 send the arguments of a message right
+/// after its address, without describing them by a type tag string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MissingTypeTags {
+    /// Reject the message.
+    Error,
+    /// Return the raw argument bytes as a single [`OscType::Blob`] argument.
+    Blob,
+}
+
+/// How to decode arguments whose type tag is unknown to this crate.
+///
+/// The size of an unknown argument can't be determined, so decoding can't
+/// reliably continue after one. Tolerated unknown type tags are reported by
+/// [`decode_with_warnings`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnknownTypeTags {
+    /// Reject the message.
+    Error,
+    /// Skip the argument assuming it carries no data, like `T` or `N` do,
+    /// and continue with the following arguments.
+    Skip,
+    /// Stop decoding at the argument and keep the arguments before it.
+    Truncate,
+}
+
+/// How to decode string arguments that aren't valid UTF-8, e.g. the Latin-1
+/// text of legacy senders.
+///
+/// Addresses and type tag strings must always be valid UTF-8. Tolerated
+/// invalid strings are reported by [`decode_with_warnings`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidStrings {
+    /// Reject the message.
+    Error,
+    /// Replace invalid byte sequences by `U+FFFD`, like
+    /// `String::from_utf8_lossy` does.
+    Lossy,
+    /// Keep the bytes as an [`OscType::RawString`] argument.
+    Raw,
+}
+
+/// A deviation from the specification that was tolerated while decoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeWarning {
+    /// Offset in bytes from the start of the packet.
+    pub offset: usize,
+    /// The address of the enclosing message.
+    pub addr: Option<String>,
+    /// The deviation that was tolerated.
+    pub kind: DecodeWarningKind,
+}
+
+/// Describes a [`DecodeWarning`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeWarningKind {
+    /// An argument with an unknown type tag was skipped or truncated.
+    UnknownTypeTag(char),
+    /// A string argument that isn't valid UTF-8 was decoded lossily or
+    /// kept raw.
+    InvalidString,
+    /// A `c` argument that isn't an ASCII character was replaced or kept.
+    NonAsciiChar(char),
+    /// Padding bytes were missing or not zero.
+    BadPadding,
+    /// A string lacked its nul terminator.
+    MissingNulTerminator,
+    /// A message lacked its type tag string.
+    MissingTypeTags,
+    /// An array lacked its closing `]`.
+    UnclosedArray,
+    /// The given number of bytes followed the arguments of a message and
+    /// was ignored.
+    TrailingBytes(usize),
+}
+
+/// Options that control the behavior of [`decode_with_options`].
+///
+/// The default options decode leniently and, apart from the nesting depth
+/// of arrays and bundles, without any resource limits, like [`decode`]
+/// does. Set the limits when decoding packets from untrusted sources,
+/// exceeding one of them returns an [`OscError::LimitExceeded`].
+///
+/// # Example
+///
+/// ```
+/// use rosc::decoder::{self, DecodeMode, DecoderOptions};
+///
+/// let options = DecoderOptions {
+///     mode: DecodeMode::Strict,
+///     ..Default::default()
+/// };
+/// // the address is not padded to a multiple of four bytes
+/// let msg = [b'/', b'a', 0, 0, b',', 0];
+/// assert!(decoder::decode_with_options(&msg, &options).is_err());
+/// assert!(decoder::decode(&msg).is_ok());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecoderOptions {
+    /// Whether to reject every deviation from the specification, see
+    /// [`DecodeMode::Strict`], or to recover from the deviations of sloppy
+    /// senders, see [`DecodeMode::Lenient`]. Defaults to `Lenient`.
+    pub mode: DecodeMode,
+    /// Maximum size of a packet in bytes.
+    pub max_packet_size: usize,
+    /// Maximum size of a blob argument in bytes.
+    pub max_blob_size: usize,
+    /// Maximum number of arguments of a message, array elements included.
+    pub max_args: usize,
+    /// Maximum nesting depth of bundles, a top-level bundle has a depth of one.
+    pub max_bundle_depth: usize,
+    /// Maximum nesting depth of arrays, an array argument has a depth of one.
+    pub max_array_depth: usize,
+    /// How to decode messages that lack a type tag string.
+    pub missing_type_tags: MissingTypeTags,
+    /// How to decode arguments with unknown type tags.
+    pub unknown_type_tags: UnknownTypeTags,
+    /// How to decode string arguments that aren't valid UTF-8.
+    pub invalid_strings: InvalidStrings,
+    /// How to decode `c` arguments that aren't ASCII characters.
+    pub non_ascii_chars: NonAsciiChars,
+    /// Nonstandard type tags together with the size of their data in
+    /// bytes, excluding padding. Arguments with these type tags are decoded
+    /// as `OscType::Custom`, standard type tags can't be overridden.
+    pub custom_type_tags: Vec<(char, usize)>,
+}
+
+impl Default for DecoderOptions {
+    fn default() -> DecoderOptions {
+        DecoderOptions {
+            mode: DecodeMode::Lenient,
+            max_packet_size: usize::MAX,
+            max_blob_size: usize::MAX,
+            max_args: usize::MAX,
+            max_bundle_depth: DEFAULT_MAX_BUNDLE_DEPTH,
+            max_array_depth: DEFAULT_MAX_ARRAY_DEPTH,
+            missing_type_tags: MissingTypeTags::Error,
+            unknown_type_tags: UnknownTypeTags::Error,
+            invalid_strings: InvalidStrings::Error,
+            non_ascii_chars: NonAsciiChars::Error,
+            custom_type_tags: vec![],
+        }
+    }
+}
+
+impl DecoderOptions {
+    fn is_strict(&self) -> bool {
+        self.mode == DecodeMode::Strict
+    }
+
+    fn custom_type_size(&self, tag: char) -> Option<usize> {
+        self.custom_type_tags
+            .iter()
+            .find(|&&(custom, _)| custom == tag)
+            .map(|&(_, size)| size)
+    }
+}
+
 /// Takes an byte slice as argument and returns an
 /// OSC packet on success or an `OscError` if the slice
 /// does not contain a valid OSC message.
+///
+/// The length of the packet is taken from the slice. Use
+/// [`decode_with_len`] to also get the number of bytes the packet
+/// occupied, e.g. to handle data that follows it. `decode` keeps
+/// returning just the packet, so that existing callers don't break.
 pub fn decode(msg: &[u8]) -> Result<OscPacket> {
+    decode_with_options(msg, &DecoderOptions::default())
+}
+
+/// Like [`decode`], but uses the given `options` to decide how to
+/// handle deviations from the specification.
+pub fn decode_with_options(msg: &[u8], options: &DecoderOptions) -> Result<OscPacket> {
+    decode_with_warnings(msg, options).map(|(packet, _)| packet)
+}
+
+/// Like [`decode_with_options`], but additionally returns the deviations
+/// from the specification that were tolerated while decoding.
+///
+/// # Example
+///
+/// ```
+/// use rosc::decoder::{self, DecodeWarningKind, DecoderOptions, UnknownTypeTags};
+/// use rosc::encoder;
+///
+/// let mut msg = encoder::encode_string("/vendor");
+/// msg.extend(encoder::encode_string(",Xi"));
+/// msg.extend(&42i32.to_be_bytes());
+///
+/// let options = DecoderOptions {
+///     unknown_type_tags: UnknownTypeTags::Skip,
+///     ..Default::default()
+/// };
+/// let (_, warnings) = decoder::decode_with_warnings(&msg, &options).unwrap();
+/// assert_eq!(DecodeWarningKind::UnknownTypeTag('X'), warnings[0].kind);
+/// ```
+pub fn decode_with_warnings(
+    msg: &[u8],
+    options: &DecoderOptions,
+) -> Result<(OscPacket, Vec<DecodeWarning>)> {
+    let mut warnings = vec![];
+    if msg.len() > options.max_packet_size {
+        return Err(OscError::LimitExceeded(DecodeLimit::PacketSize).at(0, None, None));
+    }
+    decode_packet(msg, options, 0, &mut warnings)
+        .map(|packet| (packet, warnings))
+        .map_err(|err| err.at(0, None, None))
+}
+
+fn decode_packet(
+    msg: &[u8],
+    options: &DecoderOptions,
+    depth: usize,
+    warnings: &mut Vec<DecodeWarning>,
+) -> Result<OscPacket> {
+    if options.is_strict() && !msg.len().is_multiple_of(4) {
+        return Err(OscError::BadPacket("Packet size is not a multiple of 4."));
+    }
+
+    let (packet, len) = decode_packet_prefix(msg, options, depth, warnings)?;
+    if len < msg.len() {
+        let addr = match packet {
+            OscPacket::Message(ref msg) => Some(msg.addr.as_str()),
+            _ => None,
+        };
+        if options.is_strict() {
+            return Err(OscError::BadMessage("Trailing bytes after arguments").at(len, None, addr));
+        }
+        warnings.push(DecodeWarning {
+            offset: len,
+            addr: addr.map(String::from),
+            kind: DecodeWarningKind::TrailingBytes(msg.len() - len),
+        });
+    }
+    Ok(packet)
+}
+
+/// Decodes the packet at the start of `msg` and returns it together with
+/// the number of bytes it occupied.
+fn decode_packet_prefix(
+    msg: &[u8],
+    options: &DecoderOptions,
+    depth: usize,
+    warnings: &mut Vec<DecodeWarning>,
+) -> Result<(OscPacket, usize)> {
     if msg.is_empty() {
         return Err(OscError::BadPacket("Empty packet."));
     }
 
     match msg[0] as char {
-        '/' => decode_message(msg),
-        '#' => decode_bundle(msg),
+        '/' => decode_message(msg, options, warnings),
+        // a bundle always extends to the end of the packet
+        '#' => decode_bundle(msg, options, depth + 1, warnings).map(|bundle| (bundle, msg.len())),
         _ => Err(OscError::BadPacket("Unknown message format.")),
     }
 }
 
-fn decode_message(msg: &[u8]) -> Result<OscPacket> {
-    let mut cursor: io::Cursor<&[u8]> = io::Cursor::new(msg);
+/// Like [`decode`], but additionally returns the number of bytes the
+/// packet occupied, so callers can handle data that follows it.
+///
+/// Bytes trailing the arguments of a message are neither ignored nor
+/// rejected, but left to the caller. A bundle always extends to the end
+/// of `msg`.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut buf = encoder::encode(&packet).unwrap();
+/// let len = buf.len();
+/// buf.extend(&[1, 2, 3, 4]);
+///
+/// assert_eq!((packet, len), decoder::decode_with_len(&buf).unwrap());
+/// ```
+pub fn decode_with_len(msg: &[u8]) -> Result<(OscPacket, usize)> {
+    decode_packet_prefix(msg, &DecoderOptions::default(), 0, &mut vec![])
+        .map_err(|err| err.at(0, None, None))
+}
+
+/// Takes a byte slice as argument and returns a borrowed view of
+/// the contained OSC packet on success or an `OscError` if the slice
+/// does not contain a valid OSC packet.
+///
+/// The whole packet is validated up front, afterwards strings and
+/// blobs are handed out as slices of `msg` and arguments and bundle
+/// elements are decoded on demand without any heap allocation.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket, OscPacketRef, OscTypeRef};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/greet/me".to_string(),
+///     args: vec!["hi!".into()],
+/// });
+/// let buf = encoder::encode(&packet).unwrap();
+///
+/// match decoder::decode_ref(&buf).unwrap() {
+///     OscPacketRef::Message(msg) => {
+///         assert_eq!("/greet/me", msg.addr);
+///         assert_eq!(Some(OscTypeRef::String("hi!")), msg.args().next());
+///     }
+///     OscPacketRef::Bundle(_) => unreachable!(),
+/// }
+/// ```
+pub fn decode_ref(msg: &[u8]) -> Result<OscPacketRef<'_>> {
+    OscPacketRef::parse(msg)
+}
+
+/// Like [`decode_ref`], but returns a packet whose strings and blobs are
+/// slices of `msg` sharing its reference count, so that large blobs can be
+/// stored or forwarded without copying them.
+///
+/// # Example
+///
+/// ```
+/// extern crate bytes;
+/// extern crate rosc;
+///
+/// use bytes::Bytes;
+/// use rosc::{decoder, encoder, OscMessage, OscPacket, OscPacketShared, OscTypeShared};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/sample".to_string(),
+///     args: vec![vec![0u8; 4096].into()],
+/// });
+/// let buf = Bytes::from(encoder::encode(&packet).unwrap());
+///
+/// match decoder::decode_bytes(&buf).unwrap() {
+///     OscPacketShared::Message(msg) => {
+///         assert_eq!("/sample", msg.addr);
+///         match msg.args[0] {
+///             OscTypeShared::Blob(ref blob) => assert_eq!(4096, blob.len()),
+///             _ => unreachable!(),
+///         }
+///     }
+///     OscPacketShared::Bundle(_) => unreachable!(),
+/// }
+/// ```
+#[cfg(feature = "bytes")]
+pub fn decode_bytes(msg: &Bytes) -> Result<OscPacketShared> {
+    OscPacketRef::parse(msg).map(|packet| OscPacketShared::from_ref(msg, packet))
+}
+
+/// Like [`decode`], but allocates the strings, blobs, arguments and bundle
+/// content of the packet in the arena `bump` instead of the heap. Reset the
+/// arena when the decoded packets aren't needed anymore, e.g. once per
+/// processed frame, to free them all at once.
+///
+/// # Example
+///
+/// ```
+/// extern crate bumpalo;
+/// extern crate rosc;
+///
+/// use bumpalo::Bump;
+/// use rosc::{decoder, encoder, OscMessage, OscPacket, OscPacketArena, OscTypeArena};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/sensor/1".to_string(),
+///     args: vec![0.5f32.into(), "ok".into()],
+/// });
+/// let buf = encoder::encode(&packet).unwrap();
+///
+/// let mut bump = Bump::new();
+/// for _frame in 0..3 {
+///     for _ in 0..100 {
+///         match decoder::decode_in(&buf, &bump).unwrap() {
+///             OscPacketArena::Message(msg) => {
+///                 assert_eq!("/sensor/1", msg.addr);
+///                 assert_eq!(OscTypeArena::String("ok"), msg.args[1]);
+///             }
+///             OscPacketArena::Bundle(_) => unreachable!(),
+///         }
+///     }
+///     bump.reset();
+/// }
+/// ```
+#[cfg(feature = "bumpalo")]
+pub fn decode_in<'a>(msg: &[u8], bump: &'a Bump) -> Result<OscPacketArena<'a>> {
+    OscPacketRef::parse(msg).map(|packet| OscPacketArena::from_ref(bump, packet))
+}
+
+/// Like [`decode`], but stores the arguments of messages with up to four
+/// arguments inline instead of allocating a vector for them.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket, OscPacketSmall};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/fader".to_string(),
+///     args: vec![1i32.into(), 0.5f32.into()],
+/// });
+/// let buf = encoder::encode(&packet).unwrap();
+///
+/// match decoder::decode_small(&buf).unwrap() {
+///     OscPacketSmall::Message(msg) => {
+///         assert_eq!("/fader", msg.addr);
+///         assert!(!msg.args.spilled());
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+#[cfg(feature = "smallvec")]
+pub fn decode_small(msg: &[u8]) -> Result<OscPacketSmall> {
+    OscPacketSmall::decode(msg)
+}
+
+/// Decodes the address of the message in `msg` and returns it together
+/// with an iterator that decodes the arguments on demand.
+///
+/// Unlike [`decode_ref`], only the arguments that are actually iterated
+/// over are validated, which makes this the cheapest way to look at the
+/// first few arguments of a long message.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket, OscType, OscTypeRef};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/mixer/fader".to_string(),
+///     args: (0..64).map(OscType::Int).collect(),
+/// });
+/// let buf = encoder::encode(&packet).unwrap();
+///
+/// let (addr, mut args) = decoder::decode_args(&buf).unwrap();
+/// assert_eq!("/mixer/fader", addr);
+/// assert_eq!(OscTypeRef::Int(0), args.next().unwrap().unwrap());
+/// ```
+pub fn decode_args(msg: &[u8]) -> Result<(&str, OscArgs<'_>)> {
+    OscArgs::parse(msg)
+}
+
+/// Reads exactly one packet from `reader` and decodes it.
+///
+/// Like on stream based transports, the packet has to be prefixed with its
+/// size as a big-endian `int32`, see [`StreamDecoder`]. Reading stops right
+/// after the packet, so consecutive calls yield consecutive packets.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let encoded = encoder::encode(&packet).unwrap();
+/// let mut file = (encoded.len() as u32).to_be_bytes().to_vec();
+/// file.extend(encoded);
+///
+/// assert_eq!(packet, decoder::decode_from(&mut file.as_slice()).unwrap());
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_from<R: Read>(reader: &mut R) -> Result<OscPacket> {
+    decode_from_with_options(reader, &DecoderOptions::default())
+}
+
+/// Like [`decode_from`], but uses the given `options`. A size prefix
+/// exceeding the maximum packet size is reported as an
+/// [`OscError::LimitExceeded`] right after reading the prefix, the packet
+/// itself is left unread.
+#[cfg(feature = "std")]
+pub fn decode_from_with_options<R: Read>(
+    reader: &mut R,
+    options: &DecoderOptions,
+) -> Result<OscPacket> {
+    let mut size_buf = [0u8; 4];
+    reader
+        .read_exact(&mut size_buf)
+        .map_err(OscError::ReadError)?;
+    let size = u32::from_be_bytes(size_buf) as usize;
+    if size > options.max_packet_size {
+        return Err(OscError::LimitExceeded(DecodeLimit::PacketSize));
+    }
+    // don't trust the size prefix for preallocation
+    let mut buf: Vec<u8> = Vec::with_capacity(size.min(MTU));
+    let cnt = reader
+        .take(size as u64)
+        .read_to_end(&mut buf)
+        .map_err(OscError::ReadError)?;
+    if cnt < size {
+        return Err(OscError::ReadError(io::ErrorKind::UnexpectedEof.into()));
+    }
+    decode_with_options(&buf, options)
+}
+
+/// Decodes all packets of a buffer that holds several size-prefixed
+/// packets back to back, like a capture of a stream transport does.
+///
+/// See [`StreamDecoder`] for the framing. A trailing incomplete packet
+/// is reported as an error.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let packets = vec![
+///     OscPacket::Message(OscMessage::from("/first")),
+///     OscPacket::Message(OscMessage::from("/second")),
+/// ];
+/// let mut buf = vec![];
+/// for packet in &packets {
+///     let encoded = encoder::encode(packet).unwrap();
+///     buf.extend(&(encoded.len() as u32).to_be_bytes());
+///     buf.extend(encoded);
+/// }
+///
+/// assert_eq!(packets, decoder::decode_all(&buf).unwrap());
+/// ```
+pub fn decode_all(msg: &[u8]) -> Result<Vec<OscPacket>> {
+    let mut packets = vec![];
+    let mut cursor = Cursor::new(msg);
+    while cursor.position() < msg.len() {
+        let offset = cursor.position();
+        let packet = cursor
+            .read_u32()
+            .and_then(|size| cursor.read_bytes(size as usize))
+            .and_then(decode)
+            .map_err(|err| match err.context() {
+                // the packet's content starts after its size prefix
+                Some(_) => err.shifted(offset + 4),
+                None => err.at(offset, None, None),
+            })?;
+        packets.push(packet);
+    }
+    Ok(packets)
+}
 
-    let addr: String = read_osc_string(&mut cursor)?;
-    let type_tags: String = read_osc_string(&mut cursor)?;
+/// Decodes OSC packets from a byte stream.
+///
+/// Stream based transports like TCP frame every packet with its size as a
+/// big-endian `int32` (see the *OSC Packets* section of the
+/// [OSC 1.0 specification](http://opensoundcontrol.org/spec-1_0)). The
+/// decoder buffers incomplete frames until enough data was fed to it.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder::StreamDecoder, encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let encoded = encoder::encode(&packet).unwrap();
+/// let mut stream = (encoded.len() as u32).to_be_bytes().to_vec();
+/// stream.extend(encoded);
+///
+/// let mut decoder = StreamDecoder::new();
+/// decoder.feed(&stream[..6]);
+/// assert!(decoder.decode().unwrap().is_none());
+/// decoder.feed(&stream[6..]);
+/// assert_eq!(Some((packet, stream.len())), decoder.decode().unwrap());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StreamDecoder {
+    buf: Vec<u8>,
+    pos: usize,
+    // bytes of an oversized packet that still need to be discarded
+    skip: usize,
+    options: DecoderOptions,
+}
+
+impl StreamDecoder {
+    /// Creates a decoder with an empty buffer.
+    pub fn new() -> StreamDecoder {
+        StreamDecoder::default()
+    }
+
+    /// Creates a decoder which decodes packets using the given `options`.
+    pub fn with_options(options: DecoderOptions) -> StreamDecoder {
+        StreamDecoder {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// Decodes the following packets using the given `options`.
+    pub fn set_options(&mut self, options: DecoderOptions) {
+        self.options = options;
+    }
+
+    /// Appends `data` received from the stream to the internal buffer.
+    pub fn feed(&mut self, data: &[u8]) {
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+        let skipped = self.skip.min(data.len());
+        self.skip -= skipped;
+        self.buf.extend_from_slice(&data[skipped..]);
+    }
+
+    /// Returns the number of buffered bytes that were not decoded yet.
+    pub fn buffered(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Decodes the next complete packet and returns it together with the
+    /// number of stream bytes it occupied, including the size prefix.
+    ///
+    /// Returns `Ok(None)` if more data needs to be fed to complete the next
+    /// packet. A malformed packet is skipped, so decoding can continue with
+    /// the following packet after an error was returned. Packets exceeding
+    /// the maximum packet size are rejected as soon as their size prefix
+    /// was received and are discarded without buffering them.
+    pub fn decode(&mut self) -> Result<Option<(OscPacket, usize)>> {
+        let data = &self.buf[self.pos..];
+        if data.len() < 4 {
+            return Ok(None);
+        }
+        let size = BigEndian::read_u32(&data[..4]) as usize;
+        if size > self.options.max_packet_size {
+            let buffered = size.min(data.len() - 4);
+            self.pos += 4 + buffered;
+            self.skip = size - buffered;
+            return Err(OscError::LimitExceeded(DecodeLimit::PacketSize));
+        }
+        if data.len() - 4 < size {
+            return Ok(None);
+        }
+        let consumed = 4 + size;
+        let packet = decode_with_options(&data[4..consumed], &self.options);
+        self.pos += consumed;
+        packet.map(|packet| Some((packet, consumed)))
+    }
+}
+
+fn decode_message(
+    msg: &[u8],
+    options: &DecoderOptions,
+    warnings: &mut Vec<DecodeWarning>,
+) -> Result<(OscPacket, usize)> {
+    let mut cursor = Cursor::new(msg);
+    let first_warning = warnings.len();
+
+    let addr: String =
+        read_osc_string(&mut cursor, options).map_err(|err| err.at(0, None, None))?;
+    let type_tags_pos = cursor.position();
+    if type_tags_pos >= msg.len() {
+        if options.is_strict() {
+            return Err(OscError::BadMessage("Missing type tag string").at(
+                type_tags_pos,
+                None,
+                Some(&addr),
+            ));
+        }
+        cursor.warn(type_tags_pos, DecodeWarningKind::MissingTypeTags);
+        collect_warnings(&mut cursor, first_warning, Some(&addr), warnings);
+        return Ok((
+            OscPacket::Message(OscMessage { addr, args: vec![] }),
+            msg.len(),
+        ));
+    }
+    if msg.len() > type_tags_pos && msg[type_tags_pos] != b',' {
+        let raw_args = &msg[type_tags_pos..];
+        let located = |err: OscError| err.at(type_tags_pos, None, Some(&addr));
+        return match options.missing_type_tags {
+            MissingTypeTags::Error => Err(located(OscError::BadMessage(
+                "Type tag string does not start with a comma",
+            ))),
+            MissingTypeTags::Blob if raw_args.len() > options.max_blob_size => {
+                Err(located(OscError::LimitExceeded(DecodeLimit::BlobSize)))
+            }
+            MissingTypeTags::Blob => {
+                cursor.warn(type_tags_pos, DecodeWarningKind::MissingTypeTags);
+                collect_warnings(&mut cursor, first_warning, Some(&addr), warnings);
+                Ok((
+                    OscPacket::Message(OscMessage {
+                        args: vec![OscType::Blob(raw_args.to_vec())],
+                        addr,
+                    }),
+                    msg.len(),
+                ))
+            }
+        };
+    }
+    let type_tags: String = read_osc_string(&mut cursor, options)
+        .map_err(|err| err.at(type_tags_pos, None, Some(&addr)))?;
 
-    if type_tags.len() > 1 {
-        let args: Vec<OscType> = read_osc_args(&mut cursor, type_tags)?;
-        Ok(OscPacket::Message(OscMessage { addr, args }))
+    let args: Vec<OscType> = if type_tags.len() > 1 {
+        read_osc_args(&mut cursor, type_tags, options, &addr, warnings)?
     } else {
-        Ok(OscPacket::Message(OscMessage { addr, args: vec![] }))
+        vec![]
+    };
+    collect_warnings(&mut cursor, first_warning, Some(&addr), warnings);
+
+    // lenient decoding tolerates missing padding at the end of the packet
+    let len = (cursor.position()).min(msg.len());
+    Ok((OscPacket::Message(OscMessage { addr, args }), len))
+}
+
+fn decode_bundle(
+    msg: &[u8],
+    options: &DecoderOptions,
+    depth: usize,
+    warnings: &mut Vec<DecodeWarning>,
+) -> Result<OscPacket> {
+    let mut cursor = Cursor::new(msg);
+    let first_warning = warnings.len();
+    let bundle = decode_bundle_content(&mut cursor, options, depth, warnings)
+        .map_err(|err| err.at(cursor.position(), None, None))?;
+    collect_warnings(&mut cursor, first_warning, None, warnings);
+    Ok(bundle)
+}
+
+/// Moves the warnings collected by `cursor` to `warnings`, keeping the
+/// warnings from `first` on ordered by their offset.
+fn collect_warnings(
+    cursor: &mut Cursor,
+    first: usize,
+    addr: Option<&str>,
+    warnings: &mut Vec<DecodeWarning>,
+) {
+    if cursor.warnings.is_empty() {
+        return;
     }
+    warnings.extend(
+        cursor
+            .warnings
+            .drain(..)
+            .map(|(offset, kind)| DecodeWarning {
+                offset,
+                addr: addr.map(String::from),
+                kind,
+            }),
+    );
+    warnings[first..].sort_by_key(|warning| warning.offset);
 }
 
-fn decode_bundle(msg: &[u8]) -> Result<OscPacket> {
-    let mut cursor: io::Cursor<&[u8]> = io::Cursor::new(msg);
+fn decode_bundle_content(
+    cursor: &mut Cursor,
+    options: &DecoderOptions,
+    depth: usize,
+    warnings: &mut Vec<DecodeWarning>,
+) -> Result<OscPacket> {
+    if depth > options.max_bundle_depth {
+        return Err(OscError::LimitExceeded(DecodeLimit::BundleDepth));
+    }
 
-    let bundle_tag = read_osc_string(&mut cursor)?;
+    let msg = cursor.get_ref();
+    let bundle_tag = read_osc_string(cursor, options)?;
     if bundle_tag != "#bundle" {
         return Err(OscError::BadBundle(format!(
             "Wrong bundle specifier: {}",
@@ -52,29 +804,32 @@ fn decode_bundle(msg: &[u8]) -> Result<OscPacket> {
         )));
     }
 
-    let time_tag = read_time_tag(&mut cursor)?;
+    let time_tag = read_time_tag(cursor)?;
 
     let mut bundle: Vec<OscPacket> = Vec::new();
 
-    // Empty bundle
-    if msg.len() == cursor.position() as usize {
-        return Ok(OscPacket::Bundle(OscBundle {
-            timetag: time_tag,
-            content: bundle,
-        }));
-    }
-
-    let mut elem_size = read_bundle_element_size(&mut cursor)?;
-
-    while msg.len() >= (cursor.position() as usize) + elem_size {
-        let packet = read_bundle_element_content(&mut cursor, elem_size)?;
-        bundle.push(packet);
-
-        if msg.len() == cursor.position() as usize {
-            break;
+    while msg.len() > cursor.position() {
+        let elem_size = read_bundle_element_size(cursor)?;
+        if elem_size > msg.len() - cursor.position() {
+            return Err(OscError::BadBundle(format!(
+                "Bundle element of size {} exceeds packet length",
+                elem_size
+            )));
         }
-
-        elem_size = read_bundle_element_size(&mut cursor)?;
+        if options.is_strict() && !elem_size.is_multiple_of(4) {
+            return Err(OscError::BadBundle(format!(
+                "Bundle element size {} is not a multiple of 4",
+                elem_size
+            )));
+        }
+        let elem_pos = cursor.position();
+        let elem_warnings = warnings.len();
+        let packet = read_bundle_element_content(cursor, elem_size, options, depth, warnings)
+            .map_err(|err| err.shifted(elem_pos))?;
+        for warning in &mut warnings[elem_warnings..] {
+            warning.offset += elem_pos;
+        }
+        bundle.push(packet);
     }
 
     Ok(OscPacket::Bundle(OscBundle {
@@ -83,52 +838,91 @@ fn decode_bundle(msg: &[u8]) -> Result<OscPacket> {
     }))
 }
 
-fn read_bundle_element_size(cursor: &mut io::Cursor<&[u8]>) -> Result<usize> {
-    cursor
-        .read_u32::<BigEndian>()
-        .map(|size| size as usize)
-        .map_err(OscError::ReadError)
+fn read_bundle_element_size(cursor: &mut Cursor) -> Result<usize> {
+    cursor.read_u32().map(|size| size as usize)
 }
 
 fn read_bundle_element_content(
-    cursor: &mut io::Cursor<&[u8]>,
+    cursor: &mut Cursor,
     elem_size: usize,
+    options: &DecoderOptions,
+    depth: usize,
+    warnings: &mut Vec<DecodeWarning>,
 ) -> Result<OscPacket> {
-    let mut buf: Vec<u8> = Vec::with_capacity(elem_size);
-
-    let mut handle = cursor.take(elem_size as u64);
+    match cursor.read_bytes(elem_size) {
+        Ok(buf) => decode_packet(buf, options, depth, warnings),
+        Err(_) => Err(OscError::BadBundle(
+            "Bundle shorter than expected!".to_string(),
+        )),
+    }
+}
 
-    let cnt = handle.read_to_end(&mut buf).map_err(OscError::ReadError)?;
+fn read_osc_string(cursor: &mut Cursor, options: &DecoderOptions) -> Result<String> {
+    String::from_utf8(read_string_bytes(cursor, options)?).map_err(OscError::StringError)
+}
 
-    if cnt == elem_size {
-        decode(&buf)
-    } else {
-        Err(OscError::BadBundle(
-            "Bundle shorter than expected!".to_string(),
-        ))
+/// Reads a string argument, decoding invalid UTF-8 as `options` say. Also
+/// returns whether the string was valid.
+fn read_string_arg(cursor: &mut Cursor, options: &DecoderOptions) -> Result<(OscType, bool)> {
+    match String::from_utf8(read_string_bytes(cursor, options)?) {
+        Ok(s) => Ok((OscType::String(s), true)),
+        Err(err) => match options.invalid_strings {
+            InvalidStrings::Error => Err(OscError::StringError(err)),
+            InvalidStrings::Lossy => {
+                let lossy = String::from_utf8_lossy(err.as_bytes()).into_owned();
+                Ok((OscType::String(lossy), false))
+            }
+            InvalidStrings::Raw => Ok((OscType::RawString(err.into_bytes()), false)),
+        },
     }
 }
 
-fn read_osc_string(cursor: &mut io::Cursor<&[u8]>) -> Result<String> {
-    let mut str_buf: Vec<u8> = Vec::new();
-    // ignore returned byte count
-    cursor
-        .read_until(0, &mut str_buf)
-        .map_err(OscError::ReadError)?;
-    pad_cursor(cursor);
-    // convert to String and remove nul bytes
-    String::from_utf8(str_buf)
-        .map_err(OscError::StringError)
-        .map(|s| s.trim_matches(0u8 as char).to_string())
+/// Reads the bytes of a string without its nul terminator and padding.
+fn read_string_bytes(cursor: &mut Cursor, options: &DecoderOptions) -> Result<Vec<u8>> {
+    let pos = cursor.position();
+    let str_buf = cursor.read_until_nul();
+    if str_buf.last() != Some(&0) {
+        if options.is_strict() {
+            return Err(OscError::BadString("Missing nul terminator"));
+        }
+        cursor.warn(pos + str_buf.len(), DecodeWarningKind::MissingNulTerminator);
+    }
+    pad_cursor(cursor, options)?;
+    Ok(str_buf.strip_suffix(&[0]).unwrap_or(str_buf).to_vec())
 }
 
-fn read_osc_args(cursor: &mut io::Cursor<&[u8]>, raw_type_tags: String) -> Result<Vec<OscType>> {
+fn read_osc_args(
+    cursor: &mut Cursor,
+    raw_type_tags: String,
+    options: &DecoderOptions,
+    addr: &str,
+    warnings: &mut Vec<DecodeWarning>,
+) -> Result<Vec<OscType>> {
     let type_tags: Vec<char> = raw_type_tags.chars().skip(1).collect();
+    let arg_count = type_tags
+        .iter()
+        .filter(|tag| **tag != '[' && **tag != ']')
+        .count();
+    if arg_count > options.max_args {
+        return Err(OscError::LimitExceeded(DecodeLimit::Args).at(
+            cursor.position(),
+            None,
+            Some(addr),
+        ));
+    }
 
     let mut args: Vec<OscType> = Vec::with_capacity(type_tags.len());
     let mut stack: Vec<Vec<OscType>> = Vec::new();
+    let mut truncated = false;
     for tag in type_tags {
         if tag == '[' {
+            if stack.len() >= options.max_array_depth {
+                return Err(OscError::LimitExceeded(DecodeLimit::ArrayDepth).at(
+                    cursor.position(),
+                    Some(tag),
+                    Some(addr),
+                ));
+            }
             // array start: save current frame and start a new frame
             // for the array's content
             stack.push(args);
@@ -139,86 +933,151 @@ fn read_osc_args(cursor: &mut io::Cursor<&[u8]>, raw_type_tags: String) -> Resul
             let array = OscType::Array(OscArray { content: args });
             match stack.pop() {
                 Some(stashed) => args = stashed,
-                None => return Err(OscError::BadMessage("Encountered ] outside array")),
+                None => {
+                    return Err(OscError::BadMessage("Encountered ] outside array").at(
+                        cursor.position(),
+                        Some(tag),
+                        Some(addr),
+                    ))
+                }
             }
             args.push(array);
+        } else if !is_known_type_tag(tag)
+            && options.custom_type_size(tag).is_none()
+            && options.unknown_type_tags != UnknownTypeTags::Error
+        {
+            warnings.push(DecodeWarning {
+                offset: cursor.position(),
+                addr: Some(addr.to_string()),
+                kind: DecodeWarningKind::UnknownTypeTag(tag),
+            });
+            if options.unknown_type_tags == UnknownTypeTags::Truncate {
+                // the remaining data can't be interpreted anymore
+                cursor.set_position(cursor.get_ref().len());
+                truncated = true;
+                break;
+            }
+        } else if tag == 's' {
+            let pos = cursor.position();
+            let (arg, valid) = read_string_arg(cursor, options)
+                .map_err(|err| err.at(pos, Some(tag), Some(addr)))?;
+            if !valid {
+                warnings.push(DecodeWarning {
+                    offset: pos,
+                    addr: Some(addr.to_string()),
+                    kind: DecodeWarningKind::InvalidString,
+                });
+            }
+            args.push(arg);
+        } else if tag == 'c' {
+            let pos = cursor.position();
+            let (arg, received) =
+                read_char(cursor, options).map_err(|err| err.at(pos, Some(tag), Some(addr)))?;
+            if !received.is_ascii() {
+                warnings.push(DecodeWarning {
+                    offset: pos,
+                    addr: Some(addr.to_string()),
+                    kind: DecodeWarningKind::NonAsciiChar(received),
+                });
+            }
+            args.push(arg);
         } else {
-            let arg: OscType = read_osc_arg(cursor, tag)?;
+            let pos = cursor.position();
+            let arg: OscType = read_osc_arg(cursor, tag, options)
+                .map_err(|err| err.at(pos, Some(tag), Some(addr)))?;
             args.push(arg);
         }
     }
+    if !truncated && !stack.is_empty() {
+        if options.is_strict() {
+            return Err(OscError::BadMessage("Encountered [ without matching ]").at(
+                cursor.position(),
+                Some('['),
+                Some(addr),
+            ));
+        }
+        cursor.warn(cursor.position(), DecodeWarningKind::UnclosedArray);
+    }
+    // close arrays that are missing their ]
+    while let Some(mut stashed) = stack.pop() {
+        stashed.push(OscType::Array(OscArray { content: args }));
+        args = stashed;
+    }
     Ok(args)
 }
 
-fn read_osc_arg(cursor: &mut io::Cursor<&[u8]>, tag: char) -> Result<OscType> {
+fn read_osc_arg(cursor: &mut Cursor, tag: char, options: &DecoderOptions) -> Result<OscType> {
     match tag {
-        'f' => cursor
-            .read_f32::<BigEndian>()
-            .map(OscType::Float)
-            .map_err(OscError::ReadError),
-        'd' => cursor
-            .read_f64::<BigEndian>()
-            .map(OscType::Double)
-            .map_err(OscError::ReadError),
-        'i' => cursor
-            .read_i32::<BigEndian>()
-            .map(OscType::Int)
-            .map_err(OscError::ReadError),
-        'h' => cursor
-            .read_i64::<BigEndian>()
-            .map(OscType::Long)
-            .map_err(OscError::ReadError),
-        's' => read_osc_string(cursor).map(OscType::String),
+        'f' => cursor.read_f32().map(OscType::Float),
+        'd' => cursor.read_f64().map(OscType::Double),
+        'i' => cursor.read_i32().map(OscType::Int),
+        'h' => cursor.read_i64().map(OscType::Long),
+        's' => read_string_arg(cursor, options).map(|(arg, _)| arg),
+        'S' => read_osc_string(cursor, options).map(OscType::Symbol),
         't' => read_time_tag(cursor).map(OscType::Time),
-        'b' => read_blob(cursor),
+        'b' => read_blob(cursor, options),
         'r' => read_osc_color(cursor),
         'T' => Ok(true.into()),
         'F' => Ok(false.into()),
         'N' => Ok(OscType::Nil),
         'I' => Ok(OscType::Inf),
-        'c' => read_char(cursor),
+        'c' => read_char(cursor, options).map(|(arg, _)| arg),
         'm' => read_midi_message(cursor),
-        _ => Err(OscError::BadArg(format!(
-            "Type tag \"{}\" is not implemented!",
-            tag
-        ))),
+        _ => match options.custom_type_size(tag) {
+            Some(size) => read_custom(cursor, tag, size, options),
+            None => Err(OscError::BadArg(format!(
+                "Type tag \"{}\" is not implemented!",
+                tag
+            ))),
+        },
     }
 }
 
-fn read_char(cursor: &mut io::Cursor<&[u8]>) -> Result<OscType> {
-    let opt_char = cursor
-        .read_u32::<BigEndian>()
-        .map(char::from_u32)
-        .map_err(OscError::ReadError)?;
-    match opt_char {
-        Some(c) => Ok(OscType::Char(c)),
-        None => Err(OscError::BadArg("Argument is not a char!".to_string())),
-    }
+fn is_known_type_tag(tag: char) -> bool {
+    "fdihsStbrTFNIcm".contains(tag)
 }
 
-fn read_blob(cursor: &mut io::Cursor<&[u8]>) -> Result<OscType> {
-    let size: usize = cursor
-        .read_u32::<BigEndian>()
-        .map_err(OscError::ReadError)? as usize;
-    let mut byte_buf: Vec<u8> = Vec::with_capacity(size);
+/// Reads a char argument, decoding non-ASCII characters as `options` say.
+/// Also returns the received character.
+fn read_char(cursor: &mut Cursor, options: &DecoderOptions) -> Result<(OscType, char)> {
+    let received = cursor
+        .read_u32()
+        .map(char::from_u32)?
+        .ok_or_else(|| OscError::BadArg("Argument is not a char!".to_string()))?;
+    match options.non_ascii_chars.apply(received) {
+        Some(c) => Ok((OscType::Char(c), received)),
+        None => Err(OscError::BadArg(
+            "Argument is not an ASCII char!".to_string(),
+        )),
+    }
+}
 
-    cursor
-        .take(size as u64)
-        .read_to_end(&mut byte_buf)
-        .map_err(OscError::ReadError)?;
+fn read_blob(cursor: &mut Cursor, options: &DecoderOptions) -> Result<OscType> {
+    let size = cursor.read_u32()? as usize;
+    if size > options.max_blob_size {
+        return Err(OscError::LimitExceeded(DecodeLimit::BlobSize));
+    }
+    let byte_buf = cursor.read_bytes(size)?.to_vec();
 
-    pad_cursor(cursor);
+    pad_cursor(cursor, options)?;
 
     Ok(OscType::Blob(byte_buf))
 }
 
-fn read_time_tag(cursor: &mut io::Cursor<&[u8]>) -> Result<OscTime> {
-    let seconds = cursor
-        .read_u32::<BigEndian>()
-        .map_err(OscError::ReadError)?;
-    let fractional = cursor
-        .read_u32::<BigEndian>()
-        .map_err(OscError::ReadError)?;
+fn read_custom(
+    cursor: &mut Cursor,
+    tag: char,
+    size: usize,
+    options: &DecoderOptions,
+) -> Result<OscType> {
+    let data = cursor.read_bytes(size)?.to_vec();
+    pad_cursor(cursor, options)?;
+    Ok(OscType::Custom { tag, data })
+}
+
+fn read_time_tag(cursor: &mut Cursor) -> Result<OscTime> {
+    let seconds = cursor.read_u32()?;
+    let fractional = cursor.read_u32()?;
 
     Ok(OscTime {
         seconds,
@@ -226,12 +1085,8 @@ fn read_time_tag(cursor: &mut io::Cursor<&[u8]>) -> Result<OscTime> {
     })
 }
 
-fn read_midi_message(cursor: &mut io::Cursor<&[u8]>) -> Result<OscType> {
-    let mut buf: Vec<u8> = Vec::with_capacity(4);
-    cursor
-        .take(4)
-        .read_to_end(&mut buf)
-        .map_err(OscError::ReadError)?;
+fn read_midi_message(cursor: &mut Cursor) -> Result<OscType> {
+    let buf: [u8; 4] = cursor.read_array()?;
 
     Ok(OscType::Midi(OscMidiMessage {
         port: buf[0],
@@ -241,12 +1096,8 @@ fn read_midi_message(cursor: &mut io::Cursor<&[u8]>) -> Result<OscType> {
     }))
 }
 
-fn read_osc_color(cursor: &mut io::Cursor<&[u8]>) -> Result<OscType> {
-    let mut buf: Vec<u8> = Vec::with_capacity(4);
-    cursor
-        .take(4)
-        .read_to_end(&mut buf)
-        .map_err(OscError::ReadError)?;
+fn read_osc_color(cursor: &mut Cursor) -> Result<OscType> {
+    let buf: [u8; 4] = cursor.read_array()?;
 
     Ok(OscType::Color(OscColor {
         red: buf[0],
@@ -256,7 +1107,105 @@ fn read_osc_color(cursor: &mut io::Cursor<&[u8]>) -> Result<OscType> {
     }))
 }
 
-fn pad_cursor(cursor: &mut io::Cursor<&[u8]>) {
+fn pad_cursor(cursor: &mut Cursor, options: &DecoderOptions) -> Result<()> {
     let pos = cursor.position();
-    cursor.set_position(encoder::pad(pos));
+    let padded_pos = encoder::pad(pos as u64) as usize;
+    match cursor.get_ref().get(pos..padded_pos) {
+        Some(padding) if padding.iter().all(|b| *b == 0) => (),
+        Some(_) if options.is_strict() => {
+            return Err(OscError::BadPacket("Non-zero padding bytes."))
+        }
+        None if options.is_strict() => return Err(OscError::BadPacket("Missing padding bytes.")),
+        _ => cursor.warn(pos, DecodeWarningKind::BadPadding),
+    }
+    cursor.set_position(padded_pos);
+    Ok(())
+}
+
+/// A read position within a packet, the decoder's replacement for
+/// `std::io::Cursor` that also works without `std`.
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// Tolerated deviations together with their offset, see
+    /// [`collect_warnings`].
+    warnings: Vec<(usize, DecodeWarningKind)>,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Cursor<'a> {
+        Cursor {
+            data,
+            pos: 0,
+            warnings: vec![],
+        }
+    }
+
+    fn warn(&mut self, offset: usize, kind: DecodeWarningKind) {
+        self.warnings.push((offset, kind));
+    }
+
+    fn get_ref(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Moves the position, which may point past the end of the data.
+    fn set_position(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        self.data.get(self.pos..).unwrap_or(&[])
+    }
+
+    /// Reads up to and including the next nul byte, or to the end of the
+    /// data if there is none.
+    fn read_until_nul(&mut self) -> &'a [u8] {
+        let remaining = self.remaining();
+        let len = remaining
+            .iter()
+            .position(|b| *b == 0)
+            .map_or(remaining.len(), |pos| pos + 1);
+        self.pos += len;
+        &remaining[..len]
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let remaining = self.remaining();
+        if len > remaining.len() {
+            return Err(OscError::BadPacket("Unexpected end of packet."));
+        }
+        self.pos += len;
+        Ok(&remaining[..len])
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.read_bytes(N)?);
+        Ok(buf)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        self.read_array().map(u32::from_be_bytes)
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        self.read_array().map(i32::from_be_bytes)
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        self.read_array().map(i64::from_be_bytes)
+    }
+
+    fn read_f32(&mut self) -> Result<f32> {
+        self.read_array().map(f32::from_be_bytes)
+    }
+
+    fn read_f64(&mut self) -> Result<f64> {
+        self.read_array().map(f64::from_be_bytes)
+    }
 }
@@ -0,0 +1,135 @@
+//! Discovery of OSC endpoints via mDNS/DNS-SD (Bonjour).
+//!
+//! Control surfaces like TouchOSC advertise the OSC servers they provide
+//! as `_osc._udp` services on the local network, and find the servers
+//! they can control the same way.
+
+use crate::errors::OscError;
+use crate::types::Result;
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+/// The service type of OSC servers reachable over UDP.
+pub const UDP_SERVICE: &str = "_osc._udp.local.";
+/// The service type of OSC servers reachable over TCP.
+pub const TCP_SERVICE: &str = "_osc._tcp.local.";
+
+/// An OSC endpoint that was found on the network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OscPeer {
+    /// The name of the service instance, e.g. `TouchOSC Bridge`.
+    pub name: String,
+    /// The addresses the endpoint is reachable at.
+    pub addrs: Vec<SocketAddr>,
+    /// The key-value pairs of the service's TXT record.
+    pub properties: HashMap<String, String>,
+}
+
+/// Advertises and browses for OSC services.
+///
+/// Services are advertised for as long as the `Discovery` is alive.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::discovery::{Discovery, UDP_SERVICE};
+/// use rosc::udp::OscUdpClient;
+/// use std::time::Duration;
+///
+/// let discovery = Discovery::new().unwrap();
+/// discovery.advertise("rosc synth", UDP_SERVICE, 9000).unwrap();
+///
+/// for peer in discovery.browse(UDP_SERVICE, Duration::from_secs(2)).unwrap() {
+///     println!("found {} at {:?}", peer.name, peer.addrs);
+///     let client = OscUdpClient::connect(&peer.addrs[..]).unwrap();
+/// }
+/// ```
+pub struct Discovery {
+    daemon: ServiceDaemon,
+}
+
+impl Discovery {
+    /// Starts the mDNS responder. Returns an `OscError::SocketError` if its
+    /// sockets can't be set up.
+    pub fn new() -> Result<Discovery> {
+        Ok(Discovery {
+            daemon: ServiceDaemon::new().map_err(mdns_error)?,
+        })
+    }
+
+    /// Advertises an OSC server named `name` of `service_type`, e.g.
+    /// [`UDP_SERVICE`], listening on `port` of all addresses of this host.
+    pub fn advertise(&self, name: &str, service_type: &str, port: u16) -> Result<()> {
+        let host_name: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let service = ServiceInfo::new(
+            service_type,
+            name,
+            &format!("{}.local.", host_name),
+            "",
+            port,
+            None,
+        )
+        .map_err(mdns_error)?
+        .enable_addr_auto();
+        self.daemon.register(service).map_err(mdns_error)
+    }
+
+    /// Looks for OSC servers of `service_type` for `timeout` and returns the
+    /// ones that were found.
+    pub fn browse(&self, service_type: &str, timeout: Duration) -> Result<Vec<OscPeer>> {
+        let events = self.daemon.browse(service_type).map_err(mdns_error)?;
+        let deadline = Instant::now() + timeout;
+        let mut peers: Vec<OscPeer> = vec![];
+        while let Ok(event) = events.recv_deadline(deadline) {
+            if let ServiceEvent::ServiceResolved(info) = event {
+                let peer = OscPeer {
+                    name: instance_name(info.get_fullname(), service_type),
+                    addrs: info
+                        .get_addresses()
+                        .iter()
+                        .map(|ip| SocketAddr::new(*ip, info.get_port()))
+                        .collect(),
+                    properties: info.get_properties().clone().into_property_map_str(),
+                };
+                match peers.iter_mut().find(|known| known.name == peer.name) {
+                    Some(known) => *known = peer,
+                    None => peers.push(peer),
+                }
+            }
+        }
+        let _ = self.daemon.stop_browse(service_type);
+        Ok(peers)
+    }
+}
+
+impl fmt::Debug for Discovery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Discovery").finish()
+    }
+}
+
+impl Drop for Discovery {
+    fn drop(&mut self) {
+        let _ = self.daemon.shutdown();
+    }
+}
+
+/// Strips the service type from the full name of a service instance.
+fn instance_name(fullname: &str, service_type: &str) -> String {
+    fullname
+        .strip_suffix(service_type)
+        .map(|name| name.trim_end_matches('.'))
+        .unwrap_or(fullname)
+        .to_string()
+}
+
+fn mdns_error(err: mdns_sd::Error) -> OscError {
+    OscError::SocketError(io::Error::other(err))
+}
@@ -0,0 +1,376 @@
+//! Dispatching of received OSC messages to handlers.
+//!
+//! Before the handlers run, a packet passes through the
+//! [`Middleware`] installed on the [`Dispatcher`] in the order it was
+//! added. Each middleware receives a [`Context`] holding the packet, which it
+//! may inspect or modify, and decides with the returned [`Flow`] whether the
+//! packet is passed on, dispatched right away or dropped. This allows
+//! logging, authentication or deduplication of packets without touching the
+//! handlers.
+//!
+//! # Example
+//!
+//! ```
+//! use rosc::dispatcher::{Context, Dispatcher, Flow};
+//! use rosc::{OscMessage, OscPacket};
+//!
+//! let mut dispatcher = Dispatcher::new();
+//! dispatcher
+//!     .add_middleware(|ctx: &mut Context| {
+//!         println!("{:?} from {:?}", ctx.packet, ctx.source);
+//!         Flow::Continue
+//!     })
+//!     .add_middleware(|ctx: &mut Context| match ctx.values.get("user") {
+//!         Some(_) => Flow::Continue,
+//!         None => Flow::Drop,
+//!     })
+//!     .on("/mixer/*/gain", |msg| println!("{:?}", msg.args))
+//!     .unwrap();
+//!
+//! let packet = OscPacket::Message(OscMessage::from("/mixer/1/gain"));
+//! assert_eq!(0, dispatcher.dispatch(&packet));
+//!
+//! let mut ctx = Context::new(packet);
+//! ctx.values.insert("user".to_string(), "stage".to_string());
+//! assert_eq!(1, dispatcher.dispatch_context(ctx));
+//! ```
+
+use crate::address;
+use crate::matcher::Matcher;
+use crate::rewrite::Rewriter;
+use crate::types::{OscBundle, OscMessage, OscPacket, Result};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::net::SocketAddr;
+
+/// A packet passing through the middleware of a [`Dispatcher`], together
+/// with what is known about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Context {
+    /// The packet to dispatch, middleware may modify it.
+    pub packet: OscPacket,
+    /// The sender of the packet, if the caller provided it.
+    pub source: Option<SocketAddr>,
+    /// Values that middleware passes on to the middleware after it, e.g.
+    /// the name of the user an authentication middleware identified.
+    pub values: BTreeMap<String, String>,
+}
+
+impl Context {
+    /// Creates a context for `packet` without a source or values.
+    pub fn new(packet: OscPacket) -> Context {
+        Context {
+            packet,
+            source: None,
+            values: BTreeMap::new(),
+        }
+    }
+}
+
+/// What happens to a packet after a [`Middleware`] handled it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Flow {
+    /// Passes the packet on to the next middleware, or to the handlers
+    /// after the last one.
+    Continue,
+    /// Dispatches the packet to the handlers, skipping the remaining
+    /// middleware.
+    Dispatch,
+    /// Discards the packet, neither the remaining middleware nor the
+    /// handlers see it.
+    Drop,
+}
+
+/// A stage that packets pass through before they are dispatched.
+///
+/// It is implemented for closures taking a `&mut Context` and returning a
+/// [`Flow`].
+pub trait Middleware {
+    /// Handles the packet in `ctx` and returns how to proceed with it.
+    fn handle(&mut self, ctx: &mut Context) -> Flow;
+}
+
+impl<F> Middleware for F
+where
+    F: FnMut(&mut Context) -> Flow,
+{
+    fn handle(&mut self, ctx: &mut Context) -> Flow {
+        self(ctx)
+    }
+}
+
+/// Calls the handlers registered for an address pattern with the messages
+/// whose address matches the pattern, or the handlers registered for a
+/// method address with the messages whose address pattern matches it.
+///
+/// # Example
+///
+/// ```
+/// use rosc::dispatcher::Dispatcher;
+/// use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// let gain_changes = Rc::new(Cell::new(0));
+/// let counter = gain_changes.clone();
+///
+/// let mut dispatcher = Dispatcher::new();
+/// dispatcher
+///     .on("/mixer/*/gain", move |msg| {
+///         println!("{}: {:?}", msg.addr, msg.args);
+///         counter.set(counter.get() + 1);
+///     })
+///     .unwrap();
+///
+/// let packet = OscPacket::Bundle(OscBundle {
+///     timetag: OscTime::IMMEDIATE,
+///     content: vec![
+///         OscPacket::Message(OscMessage::from("/mixer/1/gain")),
+///         OscPacket::Message(OscMessage::from("/mixer/1/pan")),
+///         OscPacket::Message(OscMessage::from("/mixer/2/gain")),
+///     ],
+/// });
+/// assert_eq!(2, dispatcher.dispatch(&packet));
+/// assert_eq!(2, gain_changes.get());
+/// ```
+#[derive(Default)]
+pub struct Dispatcher {
+    routes: Vec<Route>,
+    rewriter: Rewriter,
+    middleware: Vec<Box<dyn Middleware>>,
+    transaction: Option<Box<Transaction>>,
+}
+
+/// Runs the handler calls of a bundle, see [`Dispatcher::set_transaction`].
+type Transaction = dyn FnMut(&OscBundle, &mut dyn FnMut());
+
+struct Route {
+    matcher: Matcher,
+    /// Whether the route was registered for a method address rather than a
+    /// pattern, which incoming patterns can match.
+    method: bool,
+    handler: Box<dyn FnMut(&OscMessage)>,
+}
+
+impl Dispatcher {
+    /// Creates a dispatcher without any handlers.
+    pub fn new() -> Dispatcher {
+        Dispatcher::default()
+    }
+
+    /// Registers `handler` for the messages whose address matches
+    /// `pattern`, see the [`matcher`](crate::matcher) module for the
+    /// syntax. Returns an `OscError::BadAddress` if the pattern is not a
+    /// valid address or malformed.
+    ///
+    /// As the specification allows, incoming messages may carry a pattern
+    /// too, which is matched against the handlers registered for a method
+    /// address without pattern characters. Handlers registered for a
+    /// pattern are only called for messages without one.
+    pub fn on<F>(&mut self, pattern: &str, handler: F) -> Result<&mut Dispatcher>
+    where
+        F: FnMut(&OscMessage) + 'static,
+    {
+        address::validate(pattern)?;
+        self.routes.push(Route {
+            matcher: Matcher::new(pattern)?,
+            method: !address::is_pattern(pattern),
+            handler: Box::new(handler),
+        });
+        Ok(self)
+    }
+
+    /// Appends `middleware` to the chain that packets pass through before
+    /// they are dispatched.
+    pub fn add_middleware<M>(&mut self, middleware: M) -> &mut Dispatcher
+    where
+        M: Middleware + 'static,
+    {
+        self.middleware.push(Box::new(middleware));
+        self
+    }
+
+    /// Installs `transaction` to make the handling of bundles atomic, as
+    /// the specification requires. Instead of calling the handlers for the
+    /// messages of a bundle directly, the dispatcher calls `transaction`
+    /// with the bundle and a function that calls them in order. The
+    /// transaction may e.g. lock the state the handlers modify around it or
+    /// apply their changes as one snapshot afterwards.
+    ///
+    /// Nested bundles are handled within the transaction of the outermost
+    /// bundle. If the transaction doesn't call the function, the handlers
+    /// aren't called at all.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::dispatcher::Dispatcher;
+    /// use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// // shared with the audio engine
+    /// let engine = Arc::new(Mutex::new(vec![]));
+    /// let staged = Rc::new(RefCell::new(vec![]));
+    ///
+    /// let mut dispatcher = Dispatcher::new();
+    /// let changes = staged.clone();
+    /// dispatcher
+    ///     .on("/param/*", move |msg| changes.borrow_mut().push(msg.addr.clone()))
+    ///     .unwrap();
+    /// let applied = engine.clone();
+    /// dispatcher.set_transaction(move |_bundle, run| {
+    ///     run();
+    ///     // the engine sees either none or all parameters of the bundle
+    ///     applied.lock().unwrap().extend(staged.borrow_mut().drain(..));
+    /// });
+    ///
+    /// let packet = OscPacket::Bundle(OscBundle {
+    ///     timetag: OscTime::IMMEDIATE,
+    ///     content: vec![
+    ///         OscPacket::Message(OscMessage::from("/param/cutoff")),
+    ///         OscPacket::Message(OscMessage::from("/param/resonance")),
+    ///     ],
+    /// });
+    /// assert_eq!(2, dispatcher.dispatch(&packet));
+    /// assert_eq!(2, engine.lock().unwrap().len());
+    /// ```
+    pub fn set_transaction<F>(&mut self, transaction: F) -> &mut Dispatcher
+    where
+        F: FnMut(&OscBundle, &mut dyn FnMut()) + 'static,
+    {
+        self.transaction = Some(Box::new(transaction));
+        self
+    }
+
+    /// Installs `rewriter` to rewrite the addresses of messages before they
+    /// are matched against the registered patterns, see the
+    /// [`rewrite`](crate::rewrite) module. Handlers receive the rewritten
+    /// messages.
+    pub fn set_rewriter(&mut self, rewriter: Rewriter) -> &mut Dispatcher {
+        self.rewriter = rewriter;
+        self
+    }
+
+    /// Passes `packet` through the middleware and calls the matching
+    /// handlers for every message in it, descending into bundles. Handlers
+    /// are called in the order they were registered.
+    ///
+    /// Returns the number of handler calls. Raw packets are ignored.
+    pub fn dispatch(&mut self, packet: &OscPacket) -> usize {
+        if self.middleware.is_empty() {
+            self.dispatch_packet(packet)
+        } else {
+            self.dispatch_context(Context::new(packet.clone()))
+        }
+    }
+
+    /// Passes the packet of `ctx` through the middleware and calls the
+    /// matching handlers like [`dispatch`](Dispatcher::dispatch). Returns 0
+    /// if a middleware dropped the packet.
+    pub fn dispatch_context(&mut self, mut ctx: Context) -> usize {
+        for middleware in &mut self.middleware {
+            match middleware.handle(&mut ctx) {
+                Flow::Continue => (),
+                Flow::Dispatch => break,
+                Flow::Drop => return 0,
+            }
+        }
+        self.dispatch_packet(&ctx.packet)
+    }
+
+    /// Passes `msg` through the middleware and calls the matching handlers,
+    /// returns how many there were.
+    pub fn dispatch_message(&mut self, msg: &OscMessage) -> usize {
+        if self.middleware.is_empty() {
+            self.route_message(msg)
+        } else {
+            self.dispatch_context(Context::new(OscPacket::Message(msg.clone())))
+        }
+    }
+
+    fn dispatch_packet(&mut self, packet: &OscPacket) -> usize {
+        match *packet {
+            OscPacket::Message(ref msg) => self.route_message(msg),
+            OscPacket::Bundle(ref bundle) => {
+                // taken while it runs, so nested bundles don't start another
+                match self.transaction.take() {
+                    Some(mut transaction) => {
+                        let mut calls = 0;
+                        transaction(bundle, &mut || calls += self.dispatch_bundle(bundle));
+                        self.transaction = Some(transaction);
+                        calls
+                    }
+                    None => self.dispatch_bundle(bundle),
+                }
+            }
+            OscPacket::Raw(_) => 0,
+        }
+    }
+
+    fn dispatch_bundle(&mut self, bundle: &OscBundle) -> usize {
+        bundle
+            .content
+            .iter()
+            .map(|packet| self.dispatch_packet(packet))
+            .sum()
+    }
+
+    fn route_message(&mut self, msg: &OscMessage) -> usize {
+        match self.rewriter.rewrite(&msg.addr) {
+            Some(addr) => {
+                let msg = OscMessage {
+                    addr,
+                    args: msg.args.clone(),
+                };
+                self.call_handlers(&msg)
+            }
+            None => self.call_handlers(msg),
+        }
+    }
+
+    fn call_handlers(&mut self, msg: &OscMessage) -> usize {
+        let pattern = if address::is_pattern(&msg.addr) {
+            match Matcher::new(&msg.addr) {
+                Ok(pattern) => Some(pattern),
+                Err(_) => return 0,
+            }
+        } else {
+            None
+        };
+        let mut calls = 0;
+        for route in &mut self.routes {
+            let matched = match pattern {
+                Some(ref pattern) => route.method && pattern.is_match(route.matcher.pattern()),
+                None => route.matcher.is_match(&msg.addr),
+            };
+            if matched {
+                (route.handler)(msg);
+                calls += 1;
+            }
+        }
+        calls
+    }
+}
+
+impl fmt::Debug for Dispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dispatcher")
+            .field(
+                "patterns",
+                &self
+                    .routes
+                    .iter()
+                    .map(|route| route.matcher.pattern())
+                    .collect::<Vec<_>>(),
+            )
+            .field("rewriter", &self.rewriter)
+            .field("middleware", &self.middleware.len())
+            .field("transaction", &self.transaction.is_some())
+            .finish()
+    }
+}
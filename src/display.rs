@@ -0,0 +1,149 @@
+use crate::types::{OscArray, OscBundle, OscMessage, OscPacket, OscTime, OscType};
+use alloc::string::String;
+use core::fmt::{self, Display};
+
+/// Renders the time tag as a UTC date, or `immediately` for the special
+/// time tag [`OscTime::IMMEDIATE`].
+impl Display for OscTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_immediate() {
+            return write!(f, "immediately");
+        }
+        let days = i64::from(self.seconds / 86400) - OSC_EPOCH_DAYS_BEFORE_UNIX_EPOCH;
+        let (year, month, day) = civil_from_days(days);
+        let secs = self.seconds % 86400;
+        let micros = (u64::from(self.fractional) * 1_000_000) >> 32;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+            year,
+            month,
+            day,
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            micros
+        )
+    }
+}
+
+/// Number of days from 1900-01-01 to 1970-01-01.
+const OSC_EPOCH_DAYS_BEFORE_UNIX_EPOCH: i64 = 25_567;
+
+/// Converts days since the unix epoch into a proleptic Gregorian date, see
+/// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u64, u64) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe as i64 + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+/// Renders the argument as its type tag and value, e.g. `f:440.0` or
+/// `s:"sine"`.
+impl Display for OscType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OscType::Int(x) => write!(f, "i:{}", x),
+            OscType::Float(x) => write!(f, "f:{:?}", x),
+            OscType::String(ref x) => write!(f, "s:{:?}", x),
+            OscType::Symbol(ref x) => write!(f, "S:{:?}", x),
+            OscType::RawString(ref x) => write!(f, "s:{:?}", String::from_utf8_lossy(x)),
+            OscType::Blob(ref x) => {
+                write!(f, "b:")?;
+                fmt_bytes(x, f)
+            }
+            OscType::Time(ref x) => write!(f, "t:{}", x),
+            OscType::Long(x) => write!(f, "h:{}", x),
+            OscType::Double(x) => write!(f, "d:{:?}", x),
+            OscType::Char(x) => write!(f, "c:{:?}", x),
+            OscType::Color(ref x) => write!(
+                f,
+                "r:#{:02x}{:02x}{:02x}{:02x}",
+                x.red, x.green, x.blue, x.alpha
+            ),
+            OscType::Midi(ref x) => write!(
+                f,
+                "m:<{:02x} {:02x} {:02x} {:02x}>",
+                x.port, x.status, x.data1, x.data2
+            ),
+            OscType::Bool(true) => write!(f, "T"),
+            OscType::Bool(false) => write!(f, "F"),
+            OscType::Nil => write!(f, "N"),
+            OscType::Inf => write!(f, "I"),
+            OscType::Array(ref x) => write!(f, "{}", x),
+            OscType::Custom { tag, ref data } => {
+                write!(f, "{}:", tag)?;
+                fmt_bytes(data, f)
+            }
+        }
+    }
+}
+
+fn fmt_bytes(bytes: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "<")?;
+    for (i, byte) in bytes.iter().enumerate() {
+        let sep = if i == 0 { "" } else { " " };
+        write!(f, "{}{:02x}", sep, byte)?;
+    }
+    write!(f, ">")
+}
+
+impl Display for OscArray {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, arg) in self.content.iter().enumerate() {
+            let sep = if i == 0 { "" } else { " " };
+            write!(f, "{}{}", sep, arg)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// Renders the address followed by the arguments, e.g.
+/// `/synth/freq f:440.0 s:"sine"`.
+impl Display for OscMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.addr)?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the bundle as a tree with one line per element, nested
+/// elements are indented by two spaces.
+impl Display for OscBundle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_bundle(self, f, 0)
+    }
+}
+
+impl Display for OscPacket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OscPacket::Message(ref msg) => write!(f, "{}", msg),
+            OscPacket::Bundle(ref bundle) => write!(f, "{}", bundle),
+            OscPacket::Raw(ref data) => write!(f, "<raw packet of {} bytes>", data.len()),
+        }
+    }
+}
+
+fn fmt_bundle(bundle: &OscBundle, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+    write!(f, "#bundle {}", bundle.timetag)?;
+    for packet in &bundle.content {
+        write!(f, "\n{:width$}", "", width = 2 * (depth + 1))?;
+        match *packet {
+            OscPacket::Bundle(ref bundle) => fmt_bundle(bundle, f, depth + 1)?,
+            ref packet => write!(f, "{}", packet)?,
+        }
+    }
+    Ok(())
+}
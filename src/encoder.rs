@@ -1,7 +1,80 @@
-use crate::errors::OscError;
-use crate::types::{OscBundle, OscMessage, OscPacket, OscTime, OscType, Result};
+use crate::address;
+use crate::cow::{OscMessageCow, OscTypeCow};
+use crate::decoder::DEFAULT_MAX_BUNDLE_DEPTH;
+use crate::errors::{DecodeLimit, OscError};
+#[cfg(feature = "smallvec")]
+use crate::small::OscPacketSmall;
+use crate::types::{NonAsciiChars, OscBundle, OscMessage, OscPacket, OscTime, OscType, Result};
 
-use byteorder::{BigEndian, ByteOrder};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::slice;
+#[cfg(feature = "std")]
+use std::io::Write;
+
+/// Options that control the behavior of [`encode_with_options`] and the
+/// other `*_with_options` functions.
+///
+/// # Example
+///
+/// ```
+/// use rosc::encoder::{self, EncoderOptions};
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("no/leading/slash"));
+/// assert!(encoder::encode(&packet).is_err());
+///
+/// let options = EncoderOptions {
+///     validate_addresses: false,
+///     ..Default::default()
+/// };
+/// assert!(encoder::encode_with_options(&packet, &options).is_ok());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncoderOptions {
+    /// Whether to reject message addresses that receivers won't accept,
+    /// i.e. addresses that don't start with `/` or contain spaces, `#` or
+    /// control characters. Enabled by default.
+    pub validate_addresses: bool,
+    /// The set of argument types the receiver understands.
+    pub profile: EncoderProfile,
+    /// How to encode `c` arguments that aren't ASCII characters. Defaults
+    /// to `NonAsciiChars::Error`.
+    pub non_ascii_chars: NonAsciiChars,
+    /// Maximum nesting depth of bundles, a top-level bundle has a depth of
+    /// one. Exceeding it returns an `OscError::LimitExceeded`. Defaults to
+    /// the same depth the decoder accepts by default, so that encoded
+    /// packets can be decoded again.
+    pub max_bundle_depth: usize,
+}
+
+impl Default for EncoderOptions {
+    fn default() -> EncoderOptions {
+        EncoderOptions {
+            validate_addresses: true,
+            profile: EncoderProfile::Osc11,
+            non_ascii_chars: NonAsciiChars::Error,
+            max_bundle_depth: DEFAULT_MAX_BUNDLE_DEPTH,
+        }
+    }
+}
+
+/// The argument types an encoder may emit, see [`EncoderOptions::profile`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncoderProfile {
+    /// All types rosc supports. This is the default.
+    Osc11,
+    /// Only the core OSC 1.0 types `i`, `f`, `s` and `b`, other arguments
+    /// are rejected with an `OscError::BadArg`.
+    Osc10,
+    /// Like `Osc10`, but arguments of other types are converted where
+    /// possible: `h` to `i` if the value fits, `d` to `f`, `c` and `S` to
+    /// `s`, `T` and `F` to an `i` of 1 or 0, `I` to an infinite `f`, and `m`
+    /// and `r` to an `i` containing their four bytes. Time tags, nil and
+    /// arrays are still rejected.
+    Osc10Downgrade,
+}
 
 /// Takes a reference to an OSC packet and returns
 /// a byte vector on success. If the packet was invalid
@@ -21,143 +94,861 @@ use byteorder::{BigEndian, ByteOrder};
 /// assert!(encoder::encode(&packet).is_ok())
 /// ```
 pub fn encode(packet: &OscPacket) -> Result<Vec<u8>> {
-    match *packet {
-        OscPacket::Message(ref msg) => encode_message(msg),
-        OscPacket::Bundle(ref bundle) => encode_bundle(bundle),
+    encode_with_options(packet, &EncoderOptions::default())
+}
+
+/// Like [`encode`], but uses the given `options`.
+pub fn encode_with_options(packet: &OscPacket, options: &EncoderOptions) -> Result<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(encoded_size(packet));
+    encode_packet(packet, options, 0, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Appends the encoded `packet` to `buf` and returns the number of bytes
+/// written.
+///
+/// Reusing the same buffer for several packets avoids allocating a new
+/// vector per packet once the buffer's capacity suffices. On error `buf`
+/// is left unchanged.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let mut buf = Vec::with_capacity(1024);
+/// for i in 0..4 {
+///     buf.clear();
+///     let packet = OscPacket::Message(OscMessage {
+///         addr: "/counter".to_string(),
+///         args: vec![i.into()],
+///     });
+///     let len = encoder::encode_into(&packet, &mut buf).unwrap();
+///     assert_eq!(buf.len(), len);
+/// }
+/// ```
+pub fn encode_into(packet: &OscPacket, buf: &mut Vec<u8>) -> Result<usize> {
+    encode_into_with_options(packet, buf, &EncoderOptions::default())
+}
+
+/// Like [`encode_into`], but uses the given `options`.
+pub fn encode_into_with_options(
+    packet: &OscPacket,
+    buf: &mut Vec<u8>,
+    options: &EncoderOptions,
+) -> Result<usize> {
+    let start = buf.len();
+    match encode_packet(packet, options, 0, buf) {
+        Ok(()) => Ok(buf.len() - start),
+        Err(err) => {
+            buf.truncate(start);
+            Err(err)
+        }
+    }
+}
+
+/// Encodes a message whose address and arguments may be borrowed, see
+/// [`OscMessageCow`].
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscMessageCow, OscPacket};
+///
+/// let name = String::from("kick");
+/// let mut msg = OscMessageCow::new("/sample/play");
+/// msg.args.push(name.as_str().into());
+/// let bytes = encoder::encode_cow(&msg).unwrap();
+/// assert_eq!(
+///     OscPacket::Message(OscMessage {
+///         addr: "/sample/play".to_string(),
+///         args: vec!["kick".into()],
+///     }),
+///     decoder::decode(&bytes).unwrap()
+/// );
+/// ```
+pub fn encode_cow(msg: &OscMessageCow) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    encode_cow_message(msg, &EncoderOptions::default(), &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Like [`encode_cow`], but appends the encoded message to `buf` and
+/// returns the number of bytes written. On error `buf` is left unchanged.
+pub fn encode_cow_into(msg: &OscMessageCow, buf: &mut Vec<u8>) -> Result<usize> {
+    let start = buf.len();
+    match encode_cow_message(msg, &EncoderOptions::default(), buf) {
+        Ok(()) => Ok(buf.len() - start),
+        Err(err) => {
+            buf.truncate(start);
+            Err(err)
+        }
     }
 }
 
-fn encode_message(msg: &OscMessage) -> Result<Vec<u8>> {
-    let mut msg_bytes: Vec<u8> = Vec::new();
+/// Encodes a packet whose messages store their arguments inline, see
+/// [`OscPacketSmall`].
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessageSmall, OscPacketSmall};
+///
+/// let mut msg = OscMessageSmall {
+///     addr: "/fader".to_string(),
+///     args: Default::default(),
+/// };
+/// msg.args.push(0.5f32.into());
+/// let packet = OscPacketSmall::Message(msg);
+/// let bytes = encoder::encode_small(&packet).unwrap();
+/// assert_eq!(packet, decoder::decode_small(&bytes).unwrap());
+/// ```
+#[cfg(feature = "smallvec")]
+pub fn encode_small(packet: &OscPacketSmall) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    encode_small_packet(packet, &EncoderOptions::default(), 0, &mut bytes)?;
+    Ok(bytes)
+}
 
-    msg_bytes.extend(encode_string(msg.addr.clone()));
-    let mut type_tags: Vec<char> = vec![','];
-    let mut arg_bytes: Vec<u8> = Vec::new();
+/// Like [`encode_small`], but appends the encoded packet to `buf` and
+/// returns the number of bytes written. On error `buf` is left unchanged.
+#[cfg(feature = "smallvec")]
+pub fn encode_small_into(packet: &OscPacketSmall, buf: &mut Vec<u8>) -> Result<usize> {
+    let start = buf.len();
+    match encode_small_packet(packet, &EncoderOptions::default(), 0, buf) {
+        Ok(()) => Ok(buf.len() - start),
+        Err(err) => {
+            buf.truncate(start);
+            Err(err)
+        }
+    }
+}
 
-    for arg in &msg.args {
-        let (bytes, tags): (Option<Vec<u8>>, String) = encode_arg(arg)?;
+/// Writes the encoded `packet` to the start of `buf` and returns the
+/// number of bytes written.
+///
+/// Returns [`OscError::BufferTooSmall`] if the packet doesn't fit into
+/// `buf`, in which case the contents of `buf` are unspecified.
+///
+/// This never allocates unless the packet contains an invalid argument,
+/// so a packet prepared in advance can be encoded into a stack buffer in
+/// a realtime context such as an audio callback. See
+/// [`encode_message_into_slice`] to encode a message without building an
+/// [`OscMessage`].
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut buf = [0u8; 64];
+/// let len = encoder::encode_into_slice(&packet, &mut buf).unwrap();
+/// assert_eq!(encoder::encode(&packet).unwrap(), &buf[..len]);
+///
+/// assert!(encoder::encode_into_slice(&packet, &mut [0u8; 8]).is_err());
+/// ```
+pub fn encode_into_slice(packet: &OscPacket, buf: &mut [u8]) -> Result<usize> {
+    encode_into_slice_with_options(packet, buf, &EncoderOptions::default())
+}
+
+/// Like [`encode_into_slice`], but uses the given `options`.
+pub fn encode_into_slice_with_options(
+    packet: &OscPacket,
+    buf: &mut [u8],
+    options: &EncoderOptions,
+) -> Result<usize> {
+    let mut output = SliceOutput { buf, pos: 0 };
+    encode_packet(packet, options, 0, &mut output)?;
+    Ok(output.pos)
+}
+
+/// Writes a message with the given address and arguments to the start of
+/// `buf` and returns the number of bytes written, like
+/// [`encode_into_slice`].
+///
+/// Arguments without a string, blob or array can be passed in an array on
+/// the stack, so that encoding doesn't allocate at all.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscType};
+///
+/// let mut buf = [0u8; 64];
+/// let args = [OscType::Int(1), OscType::Float(0.5)];
+/// let len = encoder::encode_message_into_slice("/voice/gain", &args, &mut buf).unwrap();
+/// assert_eq!(24, len);
+/// ```
+pub fn encode_message_into_slice(addr: &str, args: &[OscType], buf: &mut [u8]) -> Result<usize> {
+    let mut output = SliceOutput { buf, pos: 0 };
+    write_message(addr, args, &EncoderOptions::default(), &mut output)?;
+    Ok(output.pos)
+}
+
+/// Writes the encoded `packet` to `writer` and returns the number of bytes
+/// written.
+///
+/// The packet is streamed directly to the writer without buffering it
+/// first, so wrap unbuffered writers such as a `TcpStream` in a
+/// `BufWriter` to avoid issuing many small writes. If writing fails
+/// part of the packet may already have been written.
+///
+/// Note that this writes the bare packet. Stream based transports such as
+/// TCP usually expect each packet to be preceded by its size, which is
+/// returned by [`encoded_size`], or use [`encode_framed`].
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut out = Vec::new();
+/// let len = encoder::encode_to(&packet, &mut out).unwrap();
+/// assert_eq!(len, out.len());
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_to<W: Write>(packet: &OscPacket, writer: &mut W) -> Result<usize> {
+    encode_to_with_options(packet, writer, &EncoderOptions::default())
+}
+
+/// Like [`encode_to`], but uses the given `options`.
+#[cfg(feature = "std")]
+pub fn encode_to_with_options<W: Write>(
+    packet: &OscPacket,
+    writer: &mut W,
+    options: &EncoderOptions,
+) -> Result<usize> {
+    let mut output = WriteOutput { writer, pos: 0 };
+    encode_packet(packet, options, 0, &mut output)?;
+    Ok(output.pos)
+}
+
+/// Encodes `packet` for a stream based transport such as TCP, where each
+/// packet is preceded by its size as a big-endian `int32`.
+///
+/// The frames can be decoded with [`decode_from`](crate::decoder::decode_from)
+/// or a [`StreamDecoder`](crate::decoder::StreamDecoder).
+///
+/// # Example
+///
+/// ```
+/// use rosc::{decoder, encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let frame = encoder::encode_framed(&packet).unwrap();
+/// assert_eq!([0, 0, 0, 12], frame[..4]);
+/// assert_eq!(packet, decoder::decode_from(&mut frame.as_slice()).unwrap());
+/// ```
+pub fn encode_framed(packet: &OscPacket) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(4 + encoded_size(packet));
+    encode_framed_into(packet, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Like [`encode_framed`], but appends the frame to `buf` and returns the
+/// number of bytes written, including the size prefix. On error `buf` is
+/// left unchanged.
+pub fn encode_framed_into(packet: &OscPacket, buf: &mut Vec<u8>) -> Result<usize> {
+    let start = buf.len();
+    buf.extend_from_slice(&[0; 4]);
+    let size = encode_into(packet, buf).inspect_err(|_| buf.truncate(start))?;
+    let size = u32::try_from(size).map_err(|_| {
+        buf.truncate(start);
+        OscError::BadPacket("Packet is too large to be framed.")
+    })?;
+    buf[start..start + 4].copy_from_slice(&size.to_be_bytes());
+    Ok(buf.len() - start)
+}
+
+/// Encodes packets into an internal buffer that is reused between calls.
+///
+/// Once the buffer has grown to the size of the largest packet, encoding
+/// doesn't allocate anymore.
+///
+/// # Example
+///
+/// ```
+/// use rosc::encoder::Encoder;
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let mut encoder = Encoder::new();
+/// for step in 0..16 {
+///     let packet = OscPacket::Message(OscMessage {
+///         addr: "/sequencer/step".to_string(),
+///         args: vec![step.into()],
+///     });
+///     let bytes = encoder.encode(&packet).unwrap();
+///     assert_eq!(bytes.len(), 24);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Encoder {
+    buf: Vec<u8>,
+    options: EncoderOptions,
+}
+
+impl Encoder {
+    /// Creates an encoder with an empty buffer.
+    pub fn new() -> Encoder {
+        Encoder::default()
+    }
+
+    /// Creates an encoder which encodes packets using the given `options`.
+    pub fn with_options(options: EncoderOptions) -> Encoder {
+        Encoder {
+            options,
+            ..Default::default()
+        }
+    }
 
-        type_tags.extend(tags.chars());
-        if let Some(data) = bytes {
-            arg_bytes.extend(data);
+    /// Creates an encoder whose buffer can hold `capacity` bytes without
+    /// reallocating.
+    pub fn with_capacity(capacity: usize) -> Encoder {
+        Encoder {
+            buf: Vec::with_capacity(capacity),
+            ..Default::default()
         }
     }
 
-    msg_bytes.extend(encode_string(type_tags.into_iter().collect::<String>()));
-    if !arg_bytes.is_empty() {
-        msg_bytes.extend(arg_bytes);
+    /// Encodes `packet` and returns its bytes, which stay valid until the
+    /// next call.
+    pub fn encode(&mut self, packet: &OscPacket) -> Result<&[u8]> {
+        self.buf.clear();
+        encode_packet(packet, &self.options, 0, &mut self.buf)?;
+        Ok(&self.buf)
     }
-    Ok(msg_bytes)
 }
 
-fn encode_bundle(bundle: &OscBundle) -> Result<Vec<u8>> {
-    let mut bundle_bytes: Vec<u8> = Vec::new();
-    bundle_bytes.extend(encode_string("#bundle".to_string()).into_iter());
+/// A destination for encoded bytes.
+trait Output: Sized {
+    /// Appends `data` to the output.
+    fn write(&mut self, data: &[u8]) -> Result<()>;
 
-    match encode_arg(&OscType::Time(bundle.timetag))? {
-        (Some(x), _) => {
-            bundle_bytes.extend(x.into_iter());
+    /// Writes the size of the bundle element `packet` followed by the
+    /// element, which `encode` writes. By default the size is computed
+    /// before the element is written, outputs that can overwrite the bytes
+    /// they hold fill it in afterwards instead.
+    fn write_element<F>(&mut self, packet: &OscPacket, encode: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.write(&(encoded_size(packet) as u32).to_be_bytes())?;
+        encode(self)
+    }
+}
+
+impl Output for Vec<u8> {
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn write_element<F>(&mut self, _packet: &OscPacket, encode: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        // reserve space for the element size and fill it in afterwards
+        let size_pos = self.len();
+        self.extend_from_slice(&[0u8; 4]);
+        encode(self)?;
+        let size = (self.len() - size_pos - 4) as u32;
+        self[size_pos..size_pos + 4].copy_from_slice(&size.to_be_bytes());
+        Ok(())
+    }
+}
+
+struct SliceOutput<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl Output for SliceOutput<'_> {
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        let end = self.pos + data.len();
+        if end > self.buf.len() {
+            return Err(OscError::BufferTooSmall);
         }
-        (None, _) => {
-            return Err(OscError::BadBundle("Missing time tag!".to_string()));
+        self.buf[self.pos..end].copy_from_slice(data);
+        self.pos = end;
+        Ok(())
+    }
+
+    fn write_element<F>(&mut self, _packet: &OscPacket, encode: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        // reserve space for the element size and fill it in afterwards
+        let size_pos = self.pos;
+        self.write(&[0u8; 4])?;
+        encode(self)?;
+        let size = (self.pos - size_pos - 4) as u32;
+        self.buf[size_pos..size_pos + 4].copy_from_slice(&size.to_be_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+struct WriteOutput<'a, W: 'a> {
+    writer: &'a mut W,
+    pos: usize,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> Output for WriteOutput<'_, W> {
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.writer.write_all(data).map_err(OscError::WriteError)?;
+        self.pos += data.len();
+        Ok(())
+    }
+}
+
+/// Encodes `packet`, which is nested within bundles up to a depth of
+/// `depth`.
+fn encode_packet<O: Output>(
+    packet: &OscPacket,
+    options: &EncoderOptions,
+    depth: usize,
+    out: &mut O,
+) -> Result<()> {
+    match *packet {
+        OscPacket::Message(ref msg) => encode_message(msg, options, out),
+        OscPacket::Bundle(ref bundle) => encode_bundle(bundle, options, depth + 1, out),
+        OscPacket::Raw(ref data) => out.write(data),
+    }
+}
+
+fn encode_message<O: Output>(
+    msg: &OscMessage,
+    options: &EncoderOptions,
+    out: &mut O,
+) -> Result<()> {
+    write_message(&msg.addr, &msg.args, options, out)
+}
+
+fn write_message<O: Output>(
+    addr: &str,
+    args: &[OscType],
+    options: &EncoderOptions,
+    out: &mut O,
+) -> Result<()> {
+    if options.validate_addresses {
+        address::validate(addr)?;
+    }
+    write_string(addr, out)?;
+
+    out.write(b",")?;
+    let tags = write_type_tags(args, options, out)?;
+    write_terminator(1 + tags, out)?;
+
+    args.iter().try_for_each(|arg| write_arg(arg, options, out))
+}
+
+fn encode_cow_message<O: Output>(
+    msg: &OscMessageCow,
+    options: &EncoderOptions,
+    out: &mut O,
+) -> Result<()> {
+    if options.validate_addresses {
+        address::validate(&msg.addr)?;
+    }
+    write_string(&msg.addr, out)?;
+
+    out.write(b",")?;
+    let tags = write_cow_type_tags(&msg.args, options, out)?;
+    write_terminator(1 + tags, out)?;
+
+    msg.args
+        .iter()
+        .try_for_each(|arg| write_cow_arg(arg, options, out))
+}
+
+#[cfg(feature = "smallvec")]
+fn encode_small_packet(
+    packet: &OscPacketSmall,
+    options: &EncoderOptions,
+    depth: usize,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    match *packet {
+        OscPacketSmall::Message(ref msg) => write_message(&msg.addr, &msg.args, options, out),
+        OscPacketSmall::Bundle(ref bundle) => {
+            check_bundle_depth(depth + 1, options)?;
+            write_string("#bundle", out)?;
+            write_time_tag(bundle.timetag, out)?;
+            for packet in &bundle.content {
+                // reserve space for the element size and fill it in afterwards
+                let size_pos = out.len();
+                out.extend_from_slice(&[0u8; 4]);
+                encode_small_packet(packet, options, depth + 1, out)?;
+                let size = (out.len() - size_pos - 4) as u32;
+                out[size_pos..size_pos + 4].copy_from_slice(&size.to_be_bytes());
+            }
+            Ok(())
         }
     }
+}
+
+fn check_bundle_depth(depth: usize, options: &EncoderOptions) -> Result<()> {
+    if depth > options.max_bundle_depth {
+        return Err(OscError::LimitExceeded(DecodeLimit::BundleDepth));
+    }
+    Ok(())
+}
 
-    if bundle.content.is_empty() {
-        return Ok(bundle_bytes);
+/// Encodes `bundle`, which has a nesting depth of `depth`.
+fn encode_bundle<O: Output>(
+    bundle: &OscBundle,
+    options: &EncoderOptions,
+    depth: usize,
+    out: &mut O,
+) -> Result<()> {
+    check_bundle_depth(depth, options)?;
+    write_string("#bundle", out)?;
+    write_time_tag(bundle.timetag, out)?;
+
+    for packet in &bundle.content {
+        out.write_element(packet, |out| encode_packet(packet, options, depth, out))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the exact number of bytes `encode` produces for `packet`,
+/// including padding and the size prefixes of bundle elements, without
+/// encoding or allocating anything.
+///
+/// The size is computed for the default `EncoderProfile`, packets encoded
+/// with a downgrading profile may be smaller.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage {
+///     addr: "/volume".to_string(),
+///     args: vec![0.5f32.into()],
+/// });
+/// assert_eq!(encoder::encoded_size(&packet), 16);
+/// assert!(encoder::encoded_size(&packet) <= rosc::decoder::MTU);
+/// ```
+pub fn encoded_size(packet: &OscPacket) -> usize {
+    match *packet {
+        OscPacket::Message(ref msg) => {
+            let (tags, data) = args_size(&msg.args);
+            padded_string_size(msg.addr.len()) + padded_string_size(1 + tags) + data
+        }
+        OscPacket::Bundle(ref bundle) => {
+            let content: usize = bundle
+                .content
+                .iter()
+                .map(|packet| 4 + encoded_size(packet))
+                .sum();
+            // "#bundle" and the time tag
+            16 + content
+        }
+        OscPacket::Raw(ref data) => data.len(),
     }
+}
 
+/// Splits `bundle` into bundles with the same time tag whose encoded size
+/// is at most `max_size` bytes, e.g. to send a large bundle in multiple
+/// datagrams. The elements keep their order. Nested bundles that don't fit
+/// are split as well.
+///
+/// Note that receivers can't tell that the resulting bundles belong
+/// together, so the elements of different parts may be applied at
+/// slightly different times if the time tag is "immediately".
+///
+/// Returns an `OscError::BadPacket` if a message doesn't fit into a bundle
+/// of `max_size` bytes on its own.
+///
+/// # Example
+///
+/// ```
+/// use rosc::{encoder, OscBundle, OscMessage, OscPacket, OscTime};
+///
+/// let bundle = OscBundle {
+///     timetag: OscTime::IMMEDIATE,
+///     content: (0..100)
+///         .map(|i| OscPacket::Message(OscMessage {
+///             addr: "/fader".to_string(),
+///             args: vec![i.into()],
+///         }))
+///         .collect(),
+/// };
+/// let parts = encoder::split_bundle(&bundle, 512).unwrap();
+/// assert_eq!(5, parts.len());
+/// for part in &parts {
+///     assert!(encoder::encoded_size(&OscPacket::Bundle(part.clone())) <= 512);
+/// }
+/// ```
+pub fn split_bundle(bundle: &OscBundle, max_size: usize) -> Result<Vec<OscBundle>> {
+    // "#bundle" and the time tag
+    const HEADER_SIZE: usize = 16;
+    let new_part = || OscBundle {
+        timetag: bundle.timetag,
+        content: vec![],
+    };
+    let mut parts = vec![];
+    let mut part = new_part();
+    let mut size = HEADER_SIZE;
     for packet in &bundle.content {
-        match *packet {
-            OscPacket::Message(ref m) => {
-                let msg = encode_message(m)?;
-                let mut msg_size = vec![0u8; 4];
-                BigEndian::write_u32(&mut msg_size, msg.len() as u32);
-                bundle_bytes.extend(msg_size.into_iter().chain(msg.into_iter()));
+        let element_size = 4 + encoded_size(packet);
+        if size + element_size <= max_size {
+            part.content.push(packet.clone());
+            size += element_size;
+            continue;
+        }
+        if !part.content.is_empty() {
+            parts.push(core::mem::replace(&mut part, new_part()));
+            size = HEADER_SIZE;
+        }
+        if HEADER_SIZE + element_size <= max_size {
+            part.content.push(packet.clone());
+            size += element_size;
+            continue;
+        }
+        // the element doesn't fit into a part of its own
+        let nested = match packet {
+            OscPacket::Bundle(nested) if max_size > 2 * HEADER_SIZE + 4 => {
+                split_bundle(nested, max_size - HEADER_SIZE - 4)?
             }
-            OscPacket::Bundle(ref b) => {
-                let bdl = encode_bundle(b)?;
-                let mut bdl_size = vec![0u8; 4];
-                BigEndian::write_u32(&mut bdl_size, bdl.len() as u32);
-                bundle_bytes.extend(bdl_size.into_iter().chain(bdl.into_iter()));
+            _ => {
+                return Err(OscError::BadPacket(
+                    "Bundle element exceeds the maximum size.",
+                ))
             }
+        };
+        for nested in nested {
+            parts.push(OscBundle {
+                timetag: bundle.timetag,
+                content: vec![OscPacket::Bundle(nested)],
+            });
         }
     }
+    if !part.content.is_empty() || parts.is_empty() {
+        parts.push(part);
+    }
+    Ok(parts)
+}
 
-    Ok(bundle_bytes)
+/// Returns the number of type tags and data bytes of `args`.
+fn args_size(args: &[OscType]) -> (usize, usize) {
+    args.iter().fold((0, 0), |(tags, data), arg| {
+        let (arg_tags, arg_data) = match *arg {
+            OscType::Int(_) | OscType::Float(_) | OscType::Char(_) => (1, 4),
+            OscType::Midi(_) | OscType::Color(_) => (1, 4),
+            OscType::Long(_) | OscType::Double(_) | OscType::Time(_) => (1, 8),
+            OscType::String(ref x) | OscType::Symbol(ref x) => (1, padded_string_size(x.len())),
+            OscType::RawString(ref x) => (1, padded_string_size(x.len())),
+            OscType::Blob(ref x) => (1, 4 + pad(x.len() as u64) as usize),
+            OscType::Bool(_) | OscType::Nil | OscType::Inf => (1, 0),
+            OscType::Custom { ref data, .. } => (1, pad(data.len() as u64) as usize),
+            OscType::Array(ref x) => {
+                let (tags, data) = args_size(&x.content);
+                (tags + 2, data)
+            }
+        };
+        (tags + arg_tags, data + arg_data)
+    })
 }
 
-fn encode_arg(arg: &OscType) -> Result<(Option<Vec<u8>>, String)> {
-    match *arg {
-        OscType::Int(ref x) => {
-            let mut bytes = vec![0u8; 4];
-            BigEndian::write_i32(&mut bytes, *x);
-            Ok((Some(bytes), "i".into()))
-        }
-        OscType::Long(ref x) => {
-            let mut bytes = vec![0u8; 8];
-            BigEndian::write_i64(&mut bytes, *x);
-            Ok((Some(bytes), "h".into()))
-        }
-        OscType::Float(ref x) => {
-            let mut bytes = vec![0u8; 4];
-            BigEndian::write_f32(&mut bytes, *x);
-            Ok((Some(bytes), "f".into()))
-        }
-        OscType::Double(ref x) => {
-            let mut bytes = vec![0u8; 8];
-            BigEndian::write_f64(&mut bytes, *x);
-            Ok((Some(bytes), "d".into()))
-        }
-        OscType::Char(ref x) => {
-            let mut bytes = vec![0u8; 4];
-            BigEndian::write_u32(&mut bytes, *x as u32);
-            Ok((Some(bytes), "c".into()))
-        }
-        OscType::String(ref x) => Ok((Some(encode_string(x.clone())), "s".into())),
-        OscType::Blob(ref x) => {
-            let padded_blob_length: usize = pad(x.len() as u64) as usize;
-            let mut bytes = vec![0u8; 4 + padded_blob_length];
-            // write length
-            BigEndian::write_i32(&mut bytes[..4], x.len() as i32);
-            for (i, v) in x.iter().enumerate() {
-                bytes[i + 4] = *v;
+/// Size of a string of `len` bytes once nul terminated and padded.
+fn padded_string_size(len: usize) -> usize {
+    pad(len as u64 + 1) as usize
+}
+
+/// Writes the type tags of `args` and returns how many were written.
+fn write_type_tags<O: Output>(
+    args: &[OscType],
+    options: &EncoderOptions,
+    out: &mut O,
+) -> Result<usize> {
+    let mut count = 0;
+    for arg in args {
+        let converted = convert_to_profile(arg, options.profile)?;
+        match *converted.as_ref().unwrap_or(arg) {
+            OscType::Array(ref x) => {
+                out.write(b"[")?;
+                count += write_type_tags(&x.content, options, out)? + 2;
+                out.write(b"]")?;
+            }
+            OscType::Custom { tag, .. } if !is_valid_custom_type_tag(tag) => {
+                return Err(OscError::BadArg(format!(
+                    "Invalid custom type tag '{}'",
+                    tag.escape_default()
+                )));
             }
-            Ok((Some(bytes), "b".into()))
-        }
-        OscType::Time(time) => Ok((Some(encode_time_tag(time)), "t".into())),
-        OscType::Midi(ref x) => Ok((Some(vec![x.port, x.status, x.data1, x.data2]), "m".into())),
-        OscType::Color(ref x) => Ok((Some(vec![x.red, x.green, x.blue, x.alpha]), "r".into())),
-        OscType::Bool(ref x) => {
-            if *x {
-                Ok((None, "T".into()))
-            } else {
-                Ok((None, "F".into()))
+            ref arg => {
+                out.write(&[arg.type_tag() as u8])?;
+                count += 1;
             }
         }
-        OscType::Nil => Ok((None, "N".into())),
-        OscType::Inf => Ok((None, "I".into())),
-        OscType::Array(ref x) => {
-            let mut bytes = vec![0u8; 0];
-            let mut type_tags = String::from("[");
-            for v in x.content.iter() {
-                match encode_arg(v) {
-                    Ok((Some(other_bytes), other_type_tags)) => {
-                        bytes.extend(other_bytes);
-                        type_tags.push_str(&other_type_tags);
-                    }
-                    Ok((None, other_type_tags)) => {
-                        type_tags.push_str(&other_type_tags);
-                    }
-                    Err(err) => return Err(err),
-                }
+    }
+    Ok(count)
+}
+
+/// Custom type tags must be printable ASCII characters that don't clash
+/// with the standard type tags.
+fn is_valid_custom_type_tag(tag: char) -> bool {
+    tag.is_ascii_graphic() && !"ifsbhtdSrmcTFNI[],".contains(tag)
+}
+
+/// Returns the replacement of `arg` under `profile`, or `None` if it can
+/// be encoded as it is.
+fn convert_to_profile(arg: &OscType, profile: EncoderProfile) -> Result<Option<OscType>> {
+    let converted = match *arg {
+        OscType::Int(_)
+        | OscType::Float(_)
+        | OscType::String(_)
+        | OscType::RawString(_)
+        | OscType::Blob(_) => return Ok(None),
+        _ if profile == EncoderProfile::Osc11 => return Ok(None),
+        _ if profile == EncoderProfile::Osc10 => None,
+        OscType::Long(x) => i32::try_from(x).ok().map(OscType::Int),
+        OscType::Double(x) => Some(OscType::Float(x as f32)),
+        OscType::Char(x) => Some(OscType::String(x.to_string())),
+        OscType::Symbol(ref x) => Some(OscType::String(x.clone())),
+        OscType::Bool(x) => Some(OscType::Int(x as i32)),
+        OscType::Inf => Some(OscType::Float(f32::INFINITY)),
+        OscType::Midi(ref x) => Some(OscType::Int(i32::from_be_bytes([
+            x.port, x.status, x.data1, x.data2,
+        ]))),
+        OscType::Color(ref x) => Some(OscType::Int(i32::from_be_bytes([
+            x.red, x.green, x.blue, x.alpha,
+        ]))),
+        OscType::Time(_) | OscType::Nil | OscType::Array(_) | OscType::Custom { .. } => None,
+    };
+    match converted {
+        Some(arg) => Ok(Some(arg)),
+        None => Err(OscError::BadArg(format!(
+            "Can't encode type tag '{}' for OSC 1.0: {:?}",
+            arg.type_tag(),
+            arg
+        ))),
+    }
+}
+
+fn write_arg<O: Output>(arg: &OscType, options: &EncoderOptions, out: &mut O) -> Result<()> {
+    let converted = convert_to_profile(arg, options.profile)?;
+    match *converted.as_ref().unwrap_or(arg) {
+        OscType::Int(x) => out.write(&x.to_be_bytes()),
+        OscType::Long(x) => out.write(&x.to_be_bytes()),
+        OscType::Float(x) => out.write(&x.to_be_bytes()),
+        OscType::Double(x) => out.write(&x.to_be_bytes()),
+        OscType::Char(x) => match options.non_ascii_chars.apply(x) {
+            Some(c) => out.write(&(c as u32).to_be_bytes()),
+            None => Err(OscError::BadArg(format!(
+                "Char {:?} is not an ASCII character",
+                x
+            ))),
+        },
+        OscType::String(ref x) | OscType::Symbol(ref x) => write_string(x, out),
+        OscType::RawString(ref x) => {
+            out.write(x)?;
+            write_terminator(x.len(), out)
+        }
+        OscType::Blob(ref x) => {
+            out.write(&(x.len() as i32).to_be_bytes())?;
+            out.write(x)?;
+            write_padding(x.len(), out)
+        }
+        OscType::Time(time) => write_time_tag(time, out),
+        OscType::Midi(ref x) => out.write(&[x.port, x.status, x.data1, x.data2]),
+        OscType::Color(ref x) => out.write(&[x.red, x.green, x.blue, x.alpha]),
+        OscType::Bool(_) | OscType::Nil | OscType::Inf => Ok(()),
+        OscType::Custom { ref data, .. } => {
+            out.write(data)?;
+            write_padding(data.len(), out)
+        }
+        OscType::Array(ref x) => x
+            .content
+            .iter()
+            .try_for_each(|arg| write_arg(arg, options, out)),
+    }
+}
+
+/// Like [`write_type_tags`], but for borrowed arguments.
+fn write_cow_type_tags<O: Output>(
+    args: &[OscTypeCow],
+    options: &EncoderOptions,
+    out: &mut O,
+) -> Result<usize> {
+    let mut count = 0;
+    for arg in args {
+        // arguments without their payload, which doesn't affect the tags
+        let shape = match *arg {
+            OscTypeCow::Array(ref x) => {
+                out.write(b"[")?;
+                count += write_cow_type_tags(x, options, out)? + 2;
+                out.write(b"]")?;
+                continue;
             }
-            type_tags.push(']');
-            Ok((Some(bytes), type_tags))
+            OscTypeCow::String(_) => OscType::String(String::new()),
+            OscTypeCow::Symbol(_) => OscType::Symbol(String::new()),
+            OscTypeCow::Blob(_) => OscType::Blob(vec![]),
+            OscTypeCow::Custom { tag, .. } => OscType::Custom { tag, data: vec![] },
+            OscTypeCow::RawString(_) => OscType::RawString(vec![]),
+            ref scalar => scalar.to_scalar().expect("all other arguments are scalars"),
+        };
+        count += write_type_tags(slice::from_ref(&shape), options, out)?;
+    }
+    Ok(count)
+}
+
+/// Like [`write_arg`], but for borrowed arguments.
+fn write_cow_arg<O: Output>(arg: &OscTypeCow, options: &EncoderOptions, out: &mut O) -> Result<()> {
+    match *arg {
+        // symbols are only ever converted to strings
+        OscTypeCow::String(ref x) | OscTypeCow::Symbol(ref x) => write_string(x, out),
+        OscTypeCow::RawString(ref x) => {
+            out.write(x)?;
+            write_terminator(x.len(), out)
         }
+        OscTypeCow::Blob(ref x) => {
+            out.write(&(x.len() as i32).to_be_bytes())?;
+            out.write(x)?;
+            write_padding(x.len(), out)
+        }
+        OscTypeCow::Custom { ref data, .. } => {
+            out.write(data)?;
+            write_padding(data.len(), out)
+        }
+        OscTypeCow::Array(ref x) => x
+            .iter()
+            .try_for_each(|arg| write_cow_arg(arg, options, out)),
+        ref scalar => write_arg(
+            &scalar.to_scalar().expect("all other arguments are scalars"),
+            options,
+            out,
+        ),
     }
 }
 
+/// Writes `s` followed by a nul terminator and padding.
+fn write_string<O: Output>(s: &str, out: &mut O) -> Result<()> {
+    out.write(s.as_bytes())?;
+    write_terminator(s.len(), out)
+}
+
+/// Writes the nul terminator and padding of a string of `len` bytes.
+fn write_terminator<O: Output>(len: usize, out: &mut O) -> Result<()> {
+    out.write(&[0u8; 4][..4 - len % 4])
+}
+
+/// Writes the zero bytes needed to pad `len` bytes of data to 4 bytes.
+fn write_padding<O: Output>(len: usize, out: &mut O) -> Result<()> {
+    out.write(&[0u8; 3][..pad(len as u64) as usize - len])
+}
+
+fn write_time_tag<O: Output>(time: OscTime, out: &mut O) -> Result<()> {
+    out.write(&time.seconds.to_be_bytes())?;
+    out.write(&time.fractional.to_be_bytes())
+}
+
 /// Null terminates the byte representation of string `s` and
 /// adds null bytes until the length of the result is a
 /// multiple of 4.
@@ -192,13 +983,6 @@ pub fn pad(pos: u64) -> u64 {
     }
 }
 
-fn encode_time_tag(time: OscTime) -> Vec<u8> {
-    let mut bytes = vec![0u8; 8];
-    BigEndian::write_u32(&mut bytes[..4], time.seconds);
-    BigEndian::write_u32(&mut bytes[4..], time.fractional);
-    bytes
-}
-
 #[test]
 fn test_pad() {
     assert_eq!(4, pad(4));
@@ -1,30 +1,165 @@
-use std::{error, fmt, io, string};
+use alloc::boxed::Box;
+use alloc::string::{self, String};
+use core::{error, fmt};
+#[cfg(feature = "std")]
+use std::io;
 
 /// Represents errors returned by `decode` or `encode`.
+///
+/// The decoder wraps its errors in [`OscError::Context`] to tell where in
+/// the packet decoding failed, so matching a decoding error directly on
+/// e.g. `OscError::BadMessage` doesn't work. Match on [`OscError::root`]
+/// instead:
+///
+/// ```
+/// use rosc::{decoder, OscError};
+///
+/// match decoder::decode(b"no packet").unwrap_err().root() {
+///     OscError::BadPacket(msg) => println!("not an OSC packet: {}", msg),
+///     err => println!("{}", err),
+/// }
+/// ```
 #[derive(Debug)]
 pub enum OscError {
     StringError(string::FromUtf8Error),
+    #[cfg(feature = "std")]
     ReadError(io::Error),
+    #[cfg(feature = "std")]
+    WriteError(io::Error),
+    /// Creating or configuring a socket failed.
+    #[cfg(feature = "std")]
+    SocketError(io::Error),
     BadPacket(&'static str),
     BadAddress(&'static str),
     BadMessage(&'static str),
     BadString(&'static str),
     BadArg(String),
     BadBundle(String),
+    LimitExceeded(DecodeLimit),
+    /// An error that occurred while decoding, together with its location in
+    /// the packet. It displays as the underlying error followed by the
+    /// location, and has the source of the underlying error.
+    Context(Box<ErrorContext>),
+    /// The buffer passed to the encoder is too small to hold the packet.
+    BufferTooSmall,
+    /// Sending the packet would exceed the rate limit of the sender.
+    RateLimited,
+    /// The signature of a received packet is missing or doesn't match its
+    /// content, see the `auth` module.
+    BadSignature,
     Unimplemented,
 }
 
+/// Describes where in a packet decoding failed.
+#[derive(Debug)]
+pub struct ErrorContext {
+    /// Offset in bytes from the start of the packet.
+    pub offset: usize,
+    /// The type tag of the argument that was being decoded.
+    pub type_tag: Option<char>,
+    /// The address of the enclosing message.
+    pub addr: Option<String>,
+    /// The underlying error.
+    pub error: OscError,
+}
+
+impl OscError {
+    /// Returns the location of a decoding error, if known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            OscError::Context(ctx) => Some(ctx),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying error without its decoding context, or this
+    /// error if it has none.
+    pub fn root(&self) -> &OscError {
+        match self {
+            OscError::Context(ctx) => &ctx.error,
+            err => err,
+        }
+    }
+
+    /// Attaches a location to this error, unless it already has one.
+    pub(crate) fn at(self, offset: usize, type_tag: Option<char>, addr: Option<&str>) -> OscError {
+        match self {
+            OscError::Context(_) => self,
+            error => OscError::Context(Box::new(ErrorContext {
+                offset,
+                type_tag,
+                addr: addr.map(String::from),
+                error,
+            })),
+        }
+    }
+
+    /// Moves the location of this error by `offset` bytes, used for errors
+    /// of packets that are nested within a bundle.
+    pub(crate) fn shifted(self, offset: usize) -> OscError {
+        match self {
+            OscError::Context(mut ctx) => {
+                ctx.offset += offset;
+                OscError::Context(ctx)
+            }
+            error => error.at(offset, None, None),
+        }
+    }
+}
+
+/// A resource limit of the decoder, see `decoder::DecoderOptions`. The
+/// encoder enforces the bundle depth as well, see `encoder::EncoderOptions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeLimit {
+    PacketSize,
+    BlobSize,
+    Args,
+    BundleDepth,
+    ArrayDepth,
+}
+
+impl fmt::Display for DecodeLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeLimit::PacketSize => write!(f, "maximum packet size"),
+            DecodeLimit::BlobSize => write!(f, "maximum blob size"),
+            DecodeLimit::Args => write!(f, "maximum number of arguments"),
+            DecodeLimit::BundleDepth => write!(f, "maximum bundle depth"),
+            DecodeLimit::ArrayDepth => write!(f, "maximum array depth"),
+        }
+    }
+}
+
 impl fmt::Display for OscError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             OscError::StringError(err) => write!(f, "reading OSC string as utf-8: {}", err),
+            #[cfg(feature = "std")]
             OscError::ReadError(err) => write!(f, "reading from buffer: {}", err),
+            #[cfg(feature = "std")]
+            OscError::WriteError(err) => write!(f, "writing encoded packet: {}", err),
+            #[cfg(feature = "std")]
+            OscError::SocketError(err) => write!(f, "setting up socket: {}", err),
             OscError::BadPacket(msg) => write!(f, "{}", msg),
             OscError::BadAddress(msg) => write!(f, "{}", msg),
             OscError::BadMessage(msg) => write!(f, "bad OSC message: {}", msg),
             OscError::BadString(msg) => write!(f, "bad OSC string: {}", msg),
             OscError::BadArg(msg) => write!(f, "bad OSC argument: {}", msg),
             OscError::BadBundle(msg) => write!(f, "bad OSC bundle: {}", msg),
+            OscError::LimitExceeded(limit) => write!(f, "exceeded {}", limit),
+            OscError::Context(ctx) => {
+                write!(f, "{} at byte {}", ctx.error, ctx.offset)?;
+                if let Some(ref addr) = ctx.addr {
+                    write!(f, " in message {}", addr)?;
+                }
+                if let Some(tag) = ctx.type_tag {
+                    write!(f, " while decoding type tag '{}'", tag)?;
+                }
+                Ok(())
+            }
+            OscError::BufferTooSmall => write!(f, "buffer too small to hold the packet"),
+            OscError::RateLimited => write!(f, "exceeded the rate limit"),
+            OscError::BadSignature => write!(f, "missing or invalid packet signature"),
             OscError::Unimplemented => write!(f, "unimplemented"),
         }
     }
@@ -34,7 +169,14 @@ impl error::Error for OscError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
             OscError::StringError(ref err) => Some(err),
+            #[cfg(feature = "std")]
             OscError::ReadError(ref err) => Some(err),
+            #[cfg(feature = "std")]
+            OscError::WriteError(ref err) => Some(err),
+            #[cfg(feature = "std")]
+            OscError::SocketError(ref err) => Some(err),
+            // the context displays the underlying error already
+            OscError::Context(ref ctx) => ctx.error.source(),
             _ => None,
         }
     }
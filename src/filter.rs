@@ -0,0 +1,185 @@
+//! Filtering of received messages by their address, e.g. so that only the
+//! public part of a namespace can be controlled from the network.
+//!
+//! An [`AddressFilter`] holds lists of allowed and denied address patterns.
+//! A message passes the filter if its address matches none of the denied
+//! patterns and, unless no allowed patterns were added, one of the allowed
+//! ones. Patterns use the syntax of the [`matcher`](crate::matcher) module,
+//! and a trailing `/**` additionally matches any number of address parts,
+//! so `/admin/**` matches `/admin` and every address below it.
+//!
+//! Install a filter on a server with `set_filter`, e.g.
+//! [`OscUdpServer::set_filter`](crate::udp::OscUdpServer::set_filter), to
+//! drop the messages it rejects before they reach the handler.
+//!
+//! # Example
+//!
+//! ```
+//! use rosc::filter::AddressFilter;
+//!
+//! let mut filter = AddressFilter::new();
+//! filter
+//!     .allow("/public/**")
+//!     .unwrap()
+//!     .deny("/public/*/secret")
+//!     .unwrap();
+//!
+//! assert!(filter.is_allowed("/public/mixer/1/gain"));
+//! assert!(!filter.is_allowed("/public/mixer/secret"));
+//! assert!(!filter.is_allowed("/admin/shutdown"));
+//! ```
+
+use crate::address;
+use crate::matcher::Matcher;
+use crate::types::{OscMessage, OscPacket, Result};
+use alloc::vec::Vec;
+
+/// Decides which messages pass by the patterns their address matches, see
+/// the [module documentation](self).
+#[derive(Clone, Debug, Default)]
+pub struct AddressFilter {
+    allowed: Vec<Rule>,
+    denied: Vec<Rule>,
+    allow_patterns: bool,
+}
+
+#[derive(Clone, Debug)]
+enum Rule {
+    /// Matches the addresses the pattern matches.
+    Pattern(Matcher),
+    /// Matches the addresses whose leading parts the pattern matches, for
+    /// patterns ending in `/**`.
+    Subtree(Matcher),
+}
+
+impl AddressFilter {
+    /// Creates a filter that lets all messages with a method address pass.
+    pub fn new() -> AddressFilter {
+        AddressFilter::default()
+    }
+
+    /// Allows the messages whose address matches `pattern`. Once a pattern
+    /// was allowed, messages that match none of the allowed patterns are
+    /// rejected. Returns an `OscError::BadAddress` if the pattern is not a
+    /// valid address or malformed.
+    pub fn allow(&mut self, pattern: &str) -> Result<&mut AddressFilter> {
+        self.allowed.push(Rule::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Rejects the messages whose address matches `pattern`, even if an
+    /// allowed pattern matches them as well. Returns an
+    /// `OscError::BadAddress` if the pattern is not a valid address or
+    /// malformed.
+    pub fn deny(&mut self, pattern: &str) -> Result<&mut AddressFilter> {
+        self.denied.push(Rule::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Sets whether messages whose address is itself a pattern may pass.
+    ///
+    /// Such an address may match methods outside of the allowed ones, e.g.
+    /// `/*/shutdown` matches `/admin/shutdown`, so they are rejected by
+    /// default. If allowed, they are filtered like other addresses.
+    pub fn allow_patterns(&mut self, allow: bool) -> &mut AddressFilter {
+        self.allow_patterns = allow;
+        self
+    }
+
+    /// Returns whether a message sent to `addr` passes the filter.
+    pub fn is_allowed(&self, addr: &str) -> bool {
+        if !self.allow_patterns && address::is_pattern(addr) {
+            return false;
+        }
+        if self.denied.iter().any(|rule| rule.is_match(addr)) {
+            return false;
+        }
+        self.allowed.is_empty() || self.allowed.iter().any(|rule| rule.is_match(addr))
+    }
+
+    /// Removes the messages that don't pass the filter from `packet`,
+    /// descending into bundles, and calls `rejected` with each of them.
+    /// Returns `None` if `packet` is a message that doesn't pass.
+    ///
+    /// Bundles are kept even if all of their messages were removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::filter::AddressFilter;
+    /// use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+    ///
+    /// let mut filter = AddressFilter::new();
+    /// filter.deny("/admin/**").unwrap();
+    ///
+    /// let packet = OscPacket::Bundle(OscBundle {
+    ///     timetag: OscTime::IMMEDIATE,
+    ///     content: vec![
+    ///         OscPacket::Message(OscMessage::from("/admin/shutdown")),
+    ///         OscPacket::Message(OscMessage::from("/mixer/1/gain")),
+    ///     ],
+    /// });
+    /// let mut rejected = vec![];
+    /// let filtered = filter.filter_packet(packet, |msg| rejected.push(msg.addr.clone()));
+    ///
+    /// let expected = OscPacket::Bundle(OscBundle {
+    ///     timetag: OscTime::IMMEDIATE,
+    ///     content: vec![OscPacket::Message(OscMessage::from("/mixer/1/gain"))],
+    /// });
+    /// assert_eq!(Some(expected), filtered);
+    /// assert_eq!(vec!["/admin/shutdown".to_string()], rejected);
+    /// ```
+    pub fn filter_packet<F>(&self, packet: OscPacket, mut rejected: F) -> Option<OscPacket>
+    where
+        F: FnMut(&OscMessage),
+    {
+        self.filter(packet, &mut rejected)
+    }
+
+    fn filter(
+        &self,
+        packet: OscPacket,
+        rejected: &mut dyn FnMut(&OscMessage),
+    ) -> Option<OscPacket> {
+        match packet {
+            OscPacket::Message(msg) => {
+                if self.is_allowed(&msg.addr) {
+                    Some(OscPacket::Message(msg))
+                } else {
+                    rejected(&msg);
+                    None
+                }
+            }
+            OscPacket::Bundle(mut bundle) => {
+                bundle.content = bundle
+                    .content
+                    .into_iter()
+                    .filter_map(|packet| self.filter(packet, rejected))
+                    .collect();
+                Some(OscPacket::Bundle(bundle))
+            }
+            raw => Some(raw),
+        }
+    }
+}
+
+impl Rule {
+    fn new(pattern: &str) -> Result<Rule> {
+        address::validate(pattern)?;
+        match pattern.strip_suffix("/**") {
+            Some(prefix) => Matcher::new(prefix).map(Rule::Subtree),
+            None => Matcher::new(pattern).map(Rule::Pattern),
+        }
+    }
+
+    fn is_match(&self, addr: &str) -> bool {
+        match *self {
+            Rule::Pattern(ref matcher) => matcher.is_match(addr),
+            Rule::Subtree(ref matcher) => addr
+                .match_indices('/')
+                .map(|(pos, _)| &addr[..pos])
+                .chain(Some(addr))
+                .any(|leading| matcher.is_match(leading)),
+        }
+    }
+}
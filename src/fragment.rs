@@ -0,0 +1,277 @@
+//! Fragmentation of packets that are too large for a single UDP datagram,
+//! e.g. blobs of waveform data, and their reassembly on the receiving side.
+//!
+//! A [`Fragmenter`] splits an encoded packet into fragments, which are
+//! messages to the reserved address [`FRAGMENT_ADDR`] with the type tags
+//! `,iiib`:
+//!
+//! | Argument | Content                                             |
+//! |----------|-----------------------------------------------------|
+//! | `i`      | id of the fragmented packet, counting up per sender |
+//! | `i`      | index of the fragment, starting at 0                |
+//! | `i`      | number of fragments of the packet                   |
+//! | `b`      | the fragment's part of the encoded packet           |
+//!
+//! A [`Reassembler`] collects the fragments and returns the original packet
+//! once all of them arrived. Receivers without a reassembler see the
+//! fragments as ordinary messages. The UDP transports support fragmentation
+//! with [`OscUdpClient::set_fragment_size`](crate::udp::OscUdpClient::set_fragment_size)
+//! and [`OscUdpServer::set_reassembler`](crate::udp::OscUdpServer::set_reassembler).
+//!
+//! # Example
+//!
+//! ```
+//! use rosc::fragment::{Fragmenter, Reassembler};
+//! use rosc::{decoder, encoder, OscMessage, OscPacket};
+//!
+//! let packet = OscPacket::Message(OscMessage {
+//!     addr: "/waveform".to_string(),
+//!     args: vec![vec![0u8; 10_000].into()],
+//! });
+//! let mut fragmenter = Fragmenter::new(1400);
+//! let datagrams = fragmenter.fragment(&encoder::encode(&packet).unwrap());
+//! assert_eq!(8, datagrams.len());
+//!
+//! let mut reassembler = Reassembler::new();
+//! let from = "127.0.0.1:9000".parse().unwrap();
+//! let mut reassembled = None;
+//! for datagram in datagrams {
+//!     let fragment = decoder::decode(&datagram).unwrap();
+//!     reassembled = reassembler.push(fragment, from).unwrap();
+//! }
+//! assert_eq!(Some(packet), reassembled);
+//! ```
+
+use crate::decoder::{self, DecoderOptions};
+use crate::encoder;
+use crate::errors::{DecodeLimit, OscError};
+use crate::types::{OscMessage, OscPacket, OscType, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::mem;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+/// The address of fragment messages.
+pub const FRAGMENT_ADDR: &str = "/rosc/fragment";
+
+/// Size of a fragment without its part of the packet: the address, the
+/// type tags, three integers and the blob size.
+pub const FRAGMENT_OVERHEAD: usize = 40;
+
+/// Splits encoded packets into fragments of a maximum size.
+#[derive(Clone, Debug)]
+pub struct Fragmenter {
+    max_size: usize,
+    next_id: u32,
+}
+
+impl Fragmenter {
+    /// Creates a fragmenter producing datagrams of at most `max_size`
+    /// bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_size` doesn't exceed [`FRAGMENT_OVERHEAD`] by at
+    /// least 4 bytes.
+    pub fn new(max_size: usize) -> Fragmenter {
+        assert!(
+            max_size >= FRAGMENT_OVERHEAD + 4,
+            "fragment size must be at least {} bytes",
+            FRAGMENT_OVERHEAD + 4
+        );
+        Fragmenter {
+            max_size,
+            next_id: 0,
+        }
+    }
+
+    /// Returns the maximum size of the produced datagrams.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Splits the encoded `packet` into encoded fragment messages. A packet
+    /// that fits into a single datagram is returned as is.
+    pub fn fragment(&mut self, packet: &[u8]) -> Vec<Vec<u8>> {
+        if packet.len() <= self.max_size {
+            return vec![packet.to_vec()];
+        }
+        // blobs are padded to a multiple of four bytes
+        let chunk_size = (self.max_size - FRAGMENT_OVERHEAD) / 4 * 4;
+        let count = packet.len().div_ceil(chunk_size);
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        packet
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let fragment = OscPacket::Message(OscMessage {
+                    addr: FRAGMENT_ADDR.to_string(),
+                    args: vec![
+                        OscType::Int(id as i32),
+                        OscType::Int(index as i32),
+                        OscType::Int(count as i32),
+                        OscType::Blob(chunk.to_vec()),
+                    ],
+                });
+                encoder::encode(&fragment).expect("fragments are valid packets")
+            })
+            .collect()
+    }
+}
+
+/// Options that control the resources a [`Reassembler`] may use.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReassemblerOptions {
+    /// How long to wait for the missing fragments of a packet, counted from
+    /// its first fragment. Defaults to 5 seconds.
+    pub timeout: Duration,
+    /// Maximum size of a reassembled packet in bytes. Defaults to 16 MiB.
+    pub max_packet_size: usize,
+    /// Maximum number of packets being reassembled at once per sender, so
+    /// that one sender can't keep the fragments of others from being
+    /// accepted. Defaults to 16.
+    pub max_pending: usize,
+}
+
+impl Default for ReassemblerOptions {
+    fn default() -> ReassemblerOptions {
+        ReassemblerOptions {
+            timeout: Duration::from_secs(5),
+            max_packet_size: 16 << 20,
+            max_pending: 16,
+        }
+    }
+}
+
+/// Reassembles the packets split by a [`Fragmenter`].
+#[derive(Clone, Debug, Default)]
+pub struct Reassembler {
+    options: ReassemblerOptions,
+    decoder_options: DecoderOptions,
+    pending: HashMap<SocketAddr, HashMap<i32, Pending>>,
+}
+
+/// The fragments of a packet received so far.
+#[derive(Clone, Debug)]
+struct Pending {
+    chunks: BTreeMap<usize, Vec<u8>>,
+    count: usize,
+    size: usize,
+    started: Instant,
+}
+
+impl Reassembler {
+    /// Creates a reassembler with the default options.
+    pub fn new() -> Reassembler {
+        Reassembler::default()
+    }
+
+    /// Creates a reassembler using the given `options`.
+    pub fn with_options(options: ReassemblerOptions) -> Reassembler {
+        Reassembler {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// Decodes reassembled packets using the given `options`. The UDP
+    /// server sets its own decoder options on its reassembler.
+    pub fn set_decoder_options(&mut self, options: DecoderOptions) -> &mut Reassembler {
+        self.decoder_options = options;
+        self
+    }
+
+    /// Takes a received `packet` sent by `from`. Packets that aren't
+    /// fragments are returned as is. Fragments are collected and the
+    /// reassembled packet is returned with the last one, `None` otherwise.
+    ///
+    /// Returns an `OscError::BadPacket` if a fragment is malformed or empty
+    /// or too many packets of the sender are pending, an
+    /// `OscError::LimitExceeded` if the packet exceeds the maximum size or
+    /// has more fragments than chunks of the fragment's size fit into it,
+    /// or the decoding error of the reassembled packet. Fragments of packets that weren't completed within the
+    /// timeout are discarded.
+    pub fn push(&mut self, packet: OscPacket, from: SocketAddr) -> Result<Option<OscPacket>> {
+        let (id, index, count, chunk) = match packet {
+            OscPacket::Message(msg) if msg.addr == FRAGMENT_ADDR => parse_fragment(msg)?,
+            _ => return Ok(Some(packet)),
+        };
+        // empty chunks wouldn't count towards the maximum packet size
+        if chunk.is_empty() {
+            return Err(OscError::BadPacket("Malformed fragment."));
+        }
+        if count > self.options.max_packet_size.div_ceil(chunk.len()) {
+            return Err(OscError::LimitExceeded(DecodeLimit::PacketSize));
+        }
+        let now = Instant::now();
+        let timeout = self.options.timeout;
+        self.pending.retain(|_, packets| {
+            packets.retain(|_, pending| now.duration_since(pending.started) < timeout);
+            !packets.is_empty()
+        });
+        let packets = self.pending.entry(from).or_default();
+        if !packets.contains_key(&id) && packets.len() >= self.options.max_pending {
+            if packets.is_empty() {
+                self.pending.remove(&from);
+            }
+            return Err(OscError::BadPacket("Too many fragmented packets pending."));
+        }
+        let pending = packets.entry(id).or_insert_with(|| Pending {
+            chunks: BTreeMap::new(),
+            count,
+            size: 0,
+            started: now,
+        });
+        if pending.count != count || index >= count {
+            self.remove(from, id);
+            return Err(OscError::BadPacket("Malformed fragment."));
+        }
+        if pending.chunks.contains_key(&index) {
+            // a duplicate
+            return Ok(None);
+        }
+        pending.size += chunk.len();
+        if pending.size > self.options.max_packet_size {
+            self.remove(from, id);
+            return Err(OscError::LimitExceeded(DecodeLimit::PacketSize));
+        }
+        pending.chunks.insert(index, chunk);
+        if pending.chunks.len() < pending.count {
+            return Ok(None);
+        }
+        let pending = self.remove(from, id).unwrap();
+        let mut data = Vec::with_capacity(pending.size);
+        for chunk in pending.chunks.into_values() {
+            data.extend(chunk);
+        }
+        decoder::decode_with_options(&data, &self.decoder_options).map(Some)
+    }
+
+    /// Returns the number of packets whose fragments are being collected.
+    pub fn pending(&self) -> usize {
+        self.pending.values().map(HashMap::len).sum()
+    }
+
+    /// Removes the packet `id` of `from` and returns its fragments.
+    fn remove(&mut self, from: SocketAddr, id: i32) -> Option<Pending> {
+        let packets = self.pending.get_mut(&from)?;
+        let pending = packets.remove(&id);
+        if packets.is_empty() {
+            self.pending.remove(&from);
+        }
+        pending
+    }
+}
+
+/// Returns the id, index, count and data of a fragment message.
+fn parse_fragment(mut msg: OscMessage) -> Result<(i32, usize, usize, Vec<u8>)> {
+    match msg.args[..] {
+        [OscType::Int(id), OscType::Int(index), OscType::Int(count), OscType::Blob(ref mut chunk)]
+            if index >= 0 && count > 0 =>
+        {
+            Ok((id, index as usize, count as usize, mem::take(chunk)))
+        }
+        _ => Err(OscError::BadPacket("Malformed fragment.")),
+    }
+}
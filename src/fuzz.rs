@@ -0,0 +1,122 @@
+// Every generated packet can be encoded, and decoding it with the default
+// options yields an equal packet: addresses are valid, strings contain no
+// nul bytes, chars are ASCII, floats are never NaN and arguments never use
+// custom type tags.
+
+use crate::types::{OscArray, OscBundle, OscMessage, OscPacket, OscType};
+
+use std::ops::ControlFlow;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Maximum nesting depth of generated arrays and bundles.
+const MAX_DEPTH: usize = 3;
+
+const ADDRESS_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-.";
+
+impl<'a> Arbitrary<'a> for OscPacket {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_packet(u, 0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for OscMessage {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let addr = arbitrary_address(u)?;
+        let args = arbitrary_args(u, 0)?;
+        Ok(OscMessage { addr, args })
+    }
+}
+
+impl<'a> Arbitrary<'a> for OscBundle {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_bundle(u, 0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for OscType {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_arg(u, 0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for OscArray {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_args(u, 1).map(|content| OscArray { content })
+    }
+}
+
+fn arbitrary_packet(u: &mut Unstructured, depth: usize) -> Result<OscPacket> {
+    if depth < MAX_DEPTH && u.ratio(1, 4)? {
+        arbitrary_bundle(u, depth + 1).map(OscPacket::Bundle)
+    } else {
+        OscMessage::arbitrary(u).map(OscPacket::Message)
+    }
+}
+
+fn arbitrary_bundle(u: &mut Unstructured, depth: usize) -> Result<OscBundle> {
+    let timetag = u.arbitrary()?;
+    let mut content = vec![];
+    u.arbitrary_loop(None, Some(8), |u| {
+        content.push(arbitrary_packet(u, depth)?);
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(OscBundle { timetag, content })
+}
+
+fn arbitrary_address(u: &mut Unstructured) -> Result<String> {
+    let mut addr = String::new();
+    for _ in 0..u.int_in_range(1..=4)? {
+        addr.push('/');
+        for _ in 0..u.int_in_range(1..=8)? {
+            addr.push(*u.choose(ADDRESS_CHARS)? as char);
+        }
+    }
+    Ok(addr)
+}
+
+fn arbitrary_args(u: &mut Unstructured, depth: usize) -> Result<Vec<OscType>> {
+    let mut args = vec![];
+    u.arbitrary_loop(None, Some(16), |u| {
+        args.push(arbitrary_arg(u, depth)?);
+        Ok(ControlFlow::Continue(()))
+    })?;
+    Ok(args)
+}
+
+fn arbitrary_arg(u: &mut Unstructured, depth: usize) -> Result<OscType> {
+    let max_kind = if depth < MAX_DEPTH { 14 } else { 13 };
+    Ok(match u.int_in_range(0..=max_kind)? {
+        0 => OscType::Int(u.arbitrary()?),
+        1 => OscType::Float(
+            Some(u.arbitrary::<f32>()?)
+                .filter(|f| !f.is_nan())
+                .unwrap_or(0.0),
+        ),
+        2 => OscType::String(arbitrary_string(u)?),
+        3 => OscType::Blob(u.arbitrary()?),
+        4 => OscType::Time(u.arbitrary()?),
+        5 => OscType::Long(u.arbitrary()?),
+        6 => OscType::Double(
+            Some(u.arbitrary::<f64>()?)
+                .filter(|f| !f.is_nan())
+                .unwrap_or(0.0),
+        ),
+        7 => OscType::Char(char::from(u.int_in_range(0..=0x7f)?)),
+        8 => OscType::Color(u.arbitrary()?),
+        9 => OscType::Midi(u.arbitrary()?),
+        10 => OscType::Bool(u.arbitrary()?),
+        11 => OscType::Nil,
+        12 => OscType::Inf,
+        13 => OscType::Symbol(arbitrary_string(u)?),
+        _ => OscType::Array(OscArray {
+            content: arbitrary_args(u, depth + 1)?,
+        }),
+    })
+}
+
+/// OSC strings are nul terminated and therefore can't contain nul bytes.
+fn arbitrary_string(u: &mut Unstructured) -> Result<String> {
+    let s: String = u.arbitrary()?;
+    Ok(s.replace('\0', ""))
+}
@@ -0,0 +1,242 @@
+//! Annotated hexdumps of encoded packets for debugging interoperability
+//! problems on the wire.
+//!
+//! Every part of a packet is dumped on its own line(s), starting with its
+//! offset in the packet and followed by what it was decoded to. Malformed
+//! packets are dumped up to the first problem, which is annotated as well.
+//! Like the decoder, the dump stops descending into bundles nested deeper
+//! than the default bundle depth limit and dumps them as raw bytes.
+//!
+//! # Example
+//!
+//! ```
+//! use rosc::{encoder, hexdump, OscMessage, OscPacket};
+//!
+//! let packet = OscPacket::Message(OscMessage {
+//!     addr: "/synth/freq".to_string(),
+//!     args: vec![440.0f32.into()],
+//! });
+//! let dump = hexdump::hexdump(&encoder::encode(&packet).unwrap());
+//! assert_eq!(
+//!     dump,
+//!     "\
+//! 0000  2f 73 79 6e 74 68 2f 66 72 65 71 00              address \"/synth/freq\"
+//! 000c  2c 66 00 00                                      type tags \",f\"
+//! 0010  43 dc 00 00                                      f:440.0
+//! "
+//! );
+//! ```
+
+use crate::decoder::DEFAULT_MAX_BUNDLE_DEPTH;
+use crate::encoder;
+use crate::types::{OscColor, OscMidiMessage, OscTime, OscType};
+use alloc::string::String;
+use core::fmt::Write;
+
+const BYTES_PER_LINE: usize = 16;
+
+/// Returns an annotated hexdump of the encoded packet `packet`.
+pub fn hexdump(packet: &[u8]) -> String {
+    let mut dump = Dump { out: String::new() };
+    dump.packet(packet, 0, 0);
+    dump.out
+}
+
+struct Dump {
+    out: String,
+}
+
+impl Dump {
+    /// Writes `bytes`, which start at `offset`, and annotates their first line.
+    fn span(&mut self, offset: usize, bytes: &[u8], annotation: &str) {
+        let mut lines = bytes.chunks(BYTES_PER_LINE);
+        let first = lines.next().unwrap_or(&[]);
+        self.line(offset, first, annotation);
+        for (i, line) in lines.enumerate() {
+            self.line(offset + (i + 1) * BYTES_PER_LINE, line, "");
+        }
+    }
+
+    fn line(&mut self, offset: usize, bytes: &[u8], annotation: &str) {
+        let _ = write!(self.out, "{:04x} ", offset);
+        for b in bytes {
+            let _ = write!(self.out, " {:02x}", b);
+        }
+        if !annotation.is_empty() {
+            let width = 3 * (BYTES_PER_LINE - bytes.len()) + 2;
+            let _ = write!(self.out, "{:width$}{}", "", annotation, width = width);
+        }
+        self.out.push('\n');
+    }
+
+    /// Writes the packet `data`, which starts at `base` and is nested in
+    /// `depth` bundles.
+    fn packet(&mut self, data: &[u8], base: usize, depth: usize) {
+        match data.first() {
+            Some(b'/') => self.message(data, base),
+            Some(b'#') if depth >= DEFAULT_MAX_BUNDLE_DEPTH => {
+                self.span(base, data, "bundle nested too deeply")
+            }
+            Some(b'#') => self.bundle(data, base, depth),
+            Some(_) => self.span(base, data, "unknown packet format"),
+            None => self.span(base, data, "empty packet"),
+        }
+    }
+
+    fn bundle(&mut self, data: &[u8], base: usize, depth: usize) {
+        let mut pos = match read_string(data, 0) {
+            Some((tag, end)) if tag == "#bundle" => {
+                self.span(base, &data[..end], "bundle");
+                end
+            }
+            _ => return self.span(base, data, "bad bundle tag"),
+        };
+        match data.get(pos..pos + 8) {
+            Some(timetag) => {
+                let time = OscTime::from((read_u32(&timetag[..4]), read_u32(&timetag[4..])));
+                self.span(base + pos, timetag, &format!("time tag {}", time));
+                pos += 8;
+            }
+            None => return self.span(base + pos, &data[pos..], "truncated time tag"),
+        }
+        while pos < data.len() {
+            let size = match data.get(pos..pos + 4) {
+                Some(size) => read_u32(size) as usize,
+                None => return self.span(base + pos, &data[pos..], "truncated element size"),
+            };
+            self.span(
+                base + pos,
+                &data[pos..pos + 4],
+                &format!("element of {} bytes", size),
+            );
+            pos += 4;
+            match data[pos..].get(..size) {
+                Some(element) => self.packet(element, base + pos, depth + 1),
+                None => {
+                    return self.span(base + pos, &data[pos..], "element exceeds bundle");
+                }
+            }
+            pos += size;
+        }
+    }
+
+    fn message(&mut self, data: &[u8], base: usize) {
+        let mut pos = match read_string(data, 0) {
+            Some((addr, end)) => {
+                self.span(base, &data[..end], &format!("address {:?}", addr));
+                end
+            }
+            None => return self.span(base, data, "unterminated address"),
+        };
+        if data.get(pos) != Some(&b',') {
+            if pos < data.len() {
+                self.span(base + pos, &data[pos..], "arguments without type tags");
+            }
+            return;
+        }
+        let tags = match read_string(data, pos) {
+            Some((tags, end)) => {
+                self.span(
+                    base + pos,
+                    &data[pos..end],
+                    &format!("type tags {:?}", tags),
+                );
+                pos = end;
+                tags
+            }
+            None => return self.span(base + pos, &data[pos..], "unterminated type tags"),
+        };
+        for tag in tags.chars().skip(1) {
+            let (arg, end) = match read_arg(data, pos, tag) {
+                Ok(arg) => arg,
+                Err(problem) => {
+                    let annotation = format!("{} '{}'", problem, tag);
+                    return self.span(base + pos, data.get(pos..).unwrap_or(&[]), &annotation);
+                }
+            };
+            let annotation = match arg {
+                Some(arg) => format!("{}", arg),
+                None => tag.into(),
+            };
+            self.span(base + pos, &data[pos..end], &annotation);
+            pos = end;
+        }
+        if pos < data.len() {
+            self.span(base + pos, &data[pos..], "trailing bytes");
+        }
+    }
+}
+
+/// Reads the argument with type tag `tag` at `pos` and returns it with the
+/// position after it. Type tags without data, like arrays, yield no value.
+fn read_arg(data: &[u8], pos: usize, tag: char) -> Result<(Option<OscType>, usize), &'static str> {
+    let fixed = |len: usize| data.get(pos..pos + len).ok_or("truncated argument");
+    let arg = match tag {
+        'i' => OscType::Int(read_u32(fixed(4)?) as i32),
+        'f' => OscType::Float(f32::from_bits(read_u32(fixed(4)?))),
+        'c' => match char::from_u32(read_u32(fixed(4)?)) {
+            Some(c) => OscType::Char(c),
+            None => return Err("invalid char"),
+        },
+        'r' => {
+            let rgba = fixed(4)?;
+            OscType::Color(OscColor::from_rgba_u32(read_u32(rgba)))
+        }
+        'm' => {
+            let midi = fixed(4)?;
+            OscType::Midi(OscMidiMessage {
+                port: midi[0],
+                status: midi[1],
+                data1: midi[2],
+                data2: midi[3],
+            })
+        }
+        'h' => OscType::Long(read_u64(fixed(8)?) as i64),
+        'd' => OscType::Double(f64::from_bits(read_u64(fixed(8)?))),
+        't' => {
+            let time = fixed(8)?;
+            OscType::Time(OscTime::from((read_u32(&time[..4]), read_u32(&time[4..]))))
+        }
+        's' | 'S' => {
+            let (s, end) = read_string(data, pos).ok_or("unterminated string")?;
+            let arg = if tag == 's' {
+                OscType::String(s)
+            } else {
+                OscType::Symbol(s)
+            };
+            return Ok((Some(arg), end));
+        }
+        'b' => {
+            let size = read_u32(fixed(4)?) as usize;
+            let blob = data[pos + 4..].get(..size).ok_or("truncated argument")?;
+            let end = (encoder::pad((pos + 4 + size) as u64) as usize).min(data.len());
+            return Ok((Some(OscType::Blob(blob.to_vec())), end));
+        }
+        'T' | 'F' | 'N' | 'I' | '[' | ']' => return Ok((None, pos)),
+        _ => return Err("unknown type tag"),
+    };
+    let len = match tag {
+        'h' | 'd' | 't' => 8,
+        _ => 4,
+    };
+    Ok((Some(arg), pos + len))
+}
+
+/// Reads the nul terminated string at `pos` and returns it with the
+/// position after its padding, which may be missing at the end of `data`.
+fn read_string(data: &[u8], pos: usize) -> Option<(String, usize)> {
+    let len = data.get(pos..)?.iter().position(|b| *b == 0)?;
+    let s = String::from_utf8_lossy(&data[pos..pos + len]).into_owned();
+    let end = (encoder::pad((pos + len + 1) as u64) as usize).min(data.len());
+    Some((s, end))
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_be_bytes(buf)
+}
@@ -0,0 +1,560 @@
+//! A stable JSON mapping of OSC packets, independent of the `serde`
+//! feature, for consumers that can't parse the binary format, e.g. web
+//! dashboards or logging pipelines.
+//!
+//! A message maps to an object with its address and arguments, a bundle to
+//! an object with its time tag and elements:
+//!
+//! ```json
+//! {"address": "/synth/freq", "args": [{"type": "f", "value": 440.0}]}
+//! {"timetag": {"seconds": 1, "fractional": 0}, "elements": [...]}
+//! ```
+//!
+//! Every argument is an object with its type tag and, depending on the type,
+//! a value:
+//!
+//! | Type tag          | Value                                                   |
+//! |-------------------|---------------------------------------------------------|
+//! | `i`, `h`          | number                                                  |
+//! | `f`, `d`          | number, or `"NaN"`, `"Infinity"` or `"-Infinity"`       |
+//! | `s`               | string                                                  |
+//! | `S`               | string holding the symbol                               |
+//! | `c`               | string holding a single character                      |
+//! | `b`               | string of lowercase hex digits                          |
+//! | `t`               | object with `seconds` and `fractional`                  |
+//! | `r`               | string of the form `#rrggbbaa`                          |
+//! | `m`               | array of `port`, `status`, `data1` and `data2`          |
+//! | `T`, `F`, `N`, `I`| none                                                    |
+//! | `[`               | array of arguments                                      |
+//! | any other         | string of lowercase hex digits holding the custom data  |
+//!
+//! Raw packets map to `{"raw": "<hex digits>"}`. Unknown object members are
+//! ignored when parsing.
+//!
+//! # Example
+//!
+//! ```
+//! use rosc::{json, OscMessage, OscPacket};
+//!
+//! let packet = OscPacket::Message(OscMessage {
+//!     addr: "/synth/freq".to_string(),
+//!     args: vec![440i32.into()],
+//! });
+//! let json = json::to_json(&packet);
+//! assert_eq!(
+//!     r#"{"address":"/synth/freq","args":[{"type":"i","value":440}]}"#,
+//!     json
+//! );
+//! assert_eq!(packet, json::from_json(&json).unwrap());
+//! ```
+
+use crate::errors::OscError;
+use crate::types::{
+    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType, Result,
+};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Maximum nesting depth of JSON arrays and objects accepted by
+/// [`from_json`].
+const MAX_DEPTH: usize = 128;
+
+/// Converts `packet` to its JSON representation.
+pub fn to_json(packet: &OscPacket) -> String {
+    let mut json = String::new();
+    write_packet(&mut json, packet);
+    json
+}
+
+/// Parses the JSON representation of a packet. Returns an
+/// `OscError::BadPacket` if `json` isn't valid JSON or doesn't describe a
+/// packet, or an `OscError::BadArg` if an argument is malformed.
+pub fn from_json(json: &str) -> Result<OscPacket> {
+    let mut parser = Parser {
+        input: json.as_bytes(),
+        pos: 0,
+    };
+    let value = parser.parse_value(0)?;
+    parser.skip_whitespace();
+    if parser.pos < parser.input.len() {
+        return Err(OscError::BadPacket("Trailing characters after JSON value."));
+    }
+    packet_from_value(&value)
+}
+
+fn write_packet(json: &mut String, packet: &OscPacket) {
+    match *packet {
+        OscPacket::Message(ref msg) => {
+            json.push_str("{\"address\":");
+            write_string(json, &msg.addr);
+            json.push_str(",\"args\":");
+            write_args(json, &msg.args);
+            json.push('}');
+        }
+        OscPacket::Bundle(ref bundle) => {
+            json.push_str("{\"timetag\":");
+            write_time(json, bundle.timetag);
+            json.push_str(",\"elements\":[");
+            for (i, packet) in bundle.content.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                write_packet(json, packet);
+            }
+            json.push_str("]}");
+        }
+        OscPacket::Raw(ref data) => {
+            json.push_str("{\"raw\":");
+            write_hex(json, data);
+            json.push('}');
+        }
+    }
+}
+
+fn write_args(json: &mut String, args: &[OscType]) {
+    json.push('[');
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_arg(json, arg);
+    }
+    json.push(']');
+}
+
+fn write_arg(json: &mut String, arg: &OscType) {
+    let tag = arg.type_tag();
+    json.push_str("{\"type\":");
+    write_string(json, tag.encode_utf8(&mut [0; 4]));
+    if !matches!(*arg, OscType::Bool(_) | OscType::Nil | OscType::Inf) {
+        json.push_str(",\"value\":");
+    }
+    match *arg {
+        OscType::Int(i) => {
+            let _ = write!(json, "{}", i);
+        }
+        OscType::Long(h) => {
+            let _ = write!(json, "{}", h);
+        }
+        // `{:?}` keeps the fraction of integral values, e.g. `1.0`
+        OscType::Float(f) if f.is_finite() => {
+            let _ = write!(json, "{:?}", f);
+        }
+        OscType::Double(d) if d.is_finite() => {
+            let _ = write!(json, "{:?}", d);
+        }
+        OscType::Float(f) => write_non_finite(json, f as f64),
+        OscType::Double(d) => write_non_finite(json, d),
+        OscType::String(ref s) | OscType::Symbol(ref s) => write_string(json, s),
+        // JSON strings are Unicode, so invalid bytes can't be preserved
+        OscType::RawString(ref s) => write_string(json, &String::from_utf8_lossy(s)),
+        OscType::Char(c) => write_string(json, c.encode_utf8(&mut [0; 4])),
+        OscType::Blob(ref data) | OscType::Custom { ref data, .. } => write_hex(json, data),
+        OscType::Time(time) => write_time(json, time),
+        OscType::Color(ref color) => write_string(json, &color.to_hex()),
+        OscType::Midi(ref midi) => {
+            let _ = write!(
+                json,
+                "[{},{},{},{}]",
+                midi.port, midi.status, midi.data1, midi.data2
+            );
+        }
+        OscType::Array(ref array) => write_args(json, &array.content),
+        OscType::Bool(_) | OscType::Nil | OscType::Inf => (),
+    }
+    json.push('}');
+}
+
+fn write_non_finite(json: &mut String, value: f64) {
+    if value.is_nan() {
+        json.push_str("\"NaN\"");
+    } else if value > 0.0 {
+        json.push_str("\"Infinity\"");
+    } else {
+        json.push_str("\"-Infinity\"");
+    }
+}
+
+fn write_time(json: &mut String, time: OscTime) {
+    let _ = write!(
+        json,
+        "{{\"seconds\":{},\"fractional\":{}}}",
+        time.seconds, time.fractional
+    );
+}
+
+fn write_hex(json: &mut String, data: &[u8]) {
+    json.push('"');
+    for b in data {
+        let _ = write!(json, "{:02x}", b);
+    }
+    json.push('"');
+}
+
+/// Appends `s` to `json` as a quoted and escaped JSON string.
+pub(crate) fn write_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// A parsed JSON value. Numbers are kept as text until the type they are
+/// converted to is known, so that 64-bit integers don't lose precision.
+enum Value {
+    Null,
+    Bool,
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match *self {
+            Value::Object(ref members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match *self {
+            Value::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[Value]> {
+        match *self {
+            Value::Array(ref values) => Some(values),
+            _ => None,
+        }
+    }
+
+    fn parse_number<T: core::str::FromStr>(&self) -> Option<T> {
+        match *self {
+            Value::Number(ref n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Parses a float that may also be given as `"NaN"`, `"Infinity"` or
+    /// `"-Infinity"`.
+    fn parse_float<T: core::str::FromStr>(&self) -> Option<T> {
+        match *self {
+            Value::Number(ref n) => n.parse().ok(),
+            Value::String(ref s) if s == "NaN" || s == "Infinity" || s == "-Infinity" => {
+                s.parse().ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+fn packet_from_value(value: &Value) -> Result<OscPacket> {
+    if let Some(addr) = value.get("address") {
+        let addr = addr
+            .as_str()
+            .ok_or(OscError::BadPacket("Address is not a string."))?;
+        let args = match value.get("args") {
+            Some(args) => args_from_value(args)?,
+            None => vec![],
+        };
+        Ok(OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args,
+        }))
+    } else if let Some(timetag) = value.get("timetag") {
+        let timetag =
+            time_from_value(timetag).ok_or(OscError::BadPacket("Invalid bundle time tag."))?;
+        let content = match value.get("elements") {
+            Some(elements) => elements
+                .as_array()
+                .ok_or(OscError::BadPacket("Bundle elements are not an array."))?
+                .iter()
+                .map(packet_from_value)
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![],
+        };
+        Ok(OscPacket::Bundle(OscBundle { timetag, content }))
+    } else if let Some(raw) = value.get("raw") {
+        raw.as_str()
+            .and_then(hex_to_bytes)
+            .map(OscPacket::Raw)
+            .ok_or(OscError::BadPacket("Raw packet is not a hex string."))
+    } else {
+        Err(OscError::BadPacket(
+            "JSON object is neither a message, a bundle nor a raw packet.",
+        ))
+    }
+}
+
+fn args_from_value(value: &Value) -> Result<Vec<OscType>> {
+    value
+        .as_array()
+        .ok_or(OscError::BadPacket("Arguments are not an array."))?
+        .iter()
+        .map(arg_from_value)
+        .collect()
+}
+
+fn arg_from_value(value: &Value) -> Result<OscType> {
+    let mut tags = value
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| OscError::BadArg("argument without type".to_string()))?
+        .chars();
+    let tag = match (tags.next(), tags.next()) {
+        (Some(tag), None) => tag,
+        _ => {
+            return Err(OscError::BadArg(
+                "type is not a single type tag".to_string(),
+            ))
+        }
+    };
+    let data = value.get("value").unwrap_or(&Value::Null);
+    let arg = match tag {
+        'i' => data.parse_number().map(OscType::Int),
+        'h' => data.parse_number().map(OscType::Long),
+        'f' => data.parse_float().map(OscType::Float),
+        'd' => data.parse_float().map(OscType::Double),
+        's' => data.as_str().map(|s| OscType::String(s.to_string())),
+        'S' => data.as_str().map(|s| OscType::Symbol(s.to_string())),
+        'c' => data.as_str().and_then(|s| {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(OscType::Char(c)),
+                _ => None,
+            }
+        }),
+        'b' => data.as_str().and_then(hex_to_bytes).map(OscType::Blob),
+        't' => time_from_value(data).map(OscType::Time),
+        'r' => data
+            .as_str()
+            .and_then(OscColor::from_hex)
+            .map(OscType::Color),
+        'm' => data.as_array().and_then(|bytes| match *bytes {
+            [ref port, ref status, ref data1, ref data2] => Some(OscType::Midi(OscMidiMessage {
+                port: port.parse_number()?,
+                status: status.parse_number()?,
+                data1: data1.parse_number()?,
+                data2: data2.parse_number()?,
+            })),
+            _ => None,
+        }),
+        'T' => Some(OscType::Bool(true)),
+        'F' => Some(OscType::Bool(false)),
+        'N' => Some(OscType::Nil),
+        'I' => Some(OscType::Inf),
+        '[' => {
+            return args_from_value(data).map(|content| OscType::Array(OscArray { content }));
+        }
+        tag => data
+            .as_str()
+            .and_then(hex_to_bytes)
+            .map(|data| OscType::Custom { tag, data }),
+    };
+    arg.ok_or_else(|| OscError::BadArg(format!("invalid value for type tag '{}'", tag)))
+}
+
+fn time_from_value(value: &Value) -> Option<OscTime> {
+    Some(OscTime {
+        seconds: value.get("seconds")?.parse_number()?,
+        fractional: value.get("fractional")?.parse_number()?,
+    })
+}
+
+/// Parses a string of hex digits, e.g. `cafe`.
+pub(crate) fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<()> {
+        if self.input[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(OscError::BadPacket("Invalid JSON."))
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Value> {
+        if depth > MAX_DEPTH {
+            return Err(OscError::BadPacket("JSON is nested too deeply."));
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.expect("null").map(|()| Value::Null),
+            Some(b't') => self.expect("true").map(|()| Value::Bool),
+            Some(b'f') => self.expect("false").map(|()| Value::Bool),
+            Some(b'"') => self.parse_string().map(Value::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut values = vec![];
+                self.parse_sequence(b']', |parser| {
+                    values.push(parser.parse_value(depth + 1)?);
+                    Ok(())
+                })?;
+                Ok(Value::Array(values))
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut members = vec![];
+                self.parse_sequence(b'}', |parser| {
+                    parser.skip_whitespace();
+                    let name = parser.parse_string()?;
+                    parser.skip_whitespace();
+                    parser.expect(":")?;
+                    members.push((name, parser.parse_value(depth + 1)?));
+                    Ok(())
+                })?;
+                Ok(Value::Object(members))
+            }
+            Some(b'-' | b'0'..=b'9') => {
+                let start = self.pos;
+                while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+                    self.pos += 1;
+                }
+                let number = core::str::from_utf8(&self.input[start..self.pos])
+                    .map_err(|_| OscError::BadPacket("Invalid JSON."))?;
+                Ok(Value::Number(number.to_string()))
+            }
+            _ => Err(OscError::BadPacket("Invalid JSON.")),
+        }
+    }
+
+    /// Parses comma separated items up to and including `end`.
+    fn parse_sequence<F>(&mut self, end: u8, mut item: F) -> Result<()>
+    where
+        F: FnMut(&mut Self) -> Result<()>,
+    {
+        self.skip_whitespace();
+        if self.peek() == Some(end) {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            item(self)?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b) if b == end => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err(OscError::BadPacket("Invalid JSON.")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.expect("\"")?;
+        let mut s = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(b) = self.peek() {
+                if b == b'"' || b == b'\\' {
+                    break;
+                }
+                self.pos += 1;
+            }
+            s.push_str(
+                core::str::from_utf8(&self.input[start..self.pos])
+                    .map_err(|_| OscError::BadPacket("Invalid JSON."))?,
+            );
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    s.push(self.parse_escape()?);
+                }
+                _ => return Err(OscError::BadPacket("Unterminated JSON string.")),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char> {
+        let escaped = self
+            .peek()
+            .ok_or(OscError::BadPacket("Unterminated JSON string."))?;
+        self.pos += 1;
+        Ok(match escaped {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let high = self.parse_hex4()?;
+                let code = if (0xd800..0xdc00).contains(&high) {
+                    // a surrogate pair encodes a character outside the BMP
+                    self.expect("\\u")?;
+                    let low = self.parse_hex4()?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(OscError::BadPacket("Invalid JSON string escape."));
+                    }
+                    0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                } else {
+                    high
+                };
+                char::from_u32(code).ok_or(OscError::BadPacket("Invalid JSON string escape."))?
+            }
+            _ => return Err(OscError::BadPacket("Invalid JSON string escape.")),
+        })
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32> {
+        let hex = self
+            .input
+            .get(self.pos..self.pos + 4)
+            .and_then(|hex| core::str::from_utf8(hex).ok())
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or(OscError::BadPacket("Invalid JSON string escape."))?;
+        self.pos += 4;
+        u32::from_str_radix(hex, 16).map_err(|_| OscError::BadPacket("Invalid JSON string escape."))
+    }
+}
@@ -1,17 +1,138 @@
 //! **rosc** is an implementation of the [OSC 1.0](http://opensoundcontrol.org/spec-1_0) protocol in pure Rust.
 //!
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "async")]
+extern crate async_io;
+#[cfg(feature = "bumpalo")]
+extern crate bumpalo;
 extern crate byteorder;
+#[cfg(feature = "bytes")]
+extern crate bytes;
+#[cfg(feature = "std")]
+extern crate core;
+#[cfg(feature = "hmac")]
+extern crate hmac;
+#[cfg(feature = "mdns")]
+extern crate mdns_sd;
+#[cfg(feature = "mio")]
+extern crate mio;
+#[cfg(feature = "derive")]
+extern crate rosc_derive;
+#[cfg(feature = "tls")]
+extern crate rustls;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serial")]
+extern crate serialport;
+#[cfg(feature = "hmac")]
+extern crate sha2;
+#[cfg(feature = "smallvec")]
+extern crate smallvec;
+#[cfg(feature = "std")]
+extern crate socket2;
+#[cfg(feature = "tokio")]
+extern crate tokio_util;
+#[cfg(feature = "websocket")]
+extern crate tungstenite;
 
+pub mod address;
+/// Packets allocated in an arena.
+#[cfg(feature = "bumpalo")]
+mod arena;
+#[cfg(feature = "async")]
+pub mod async_transport;
+#[cfg(feature = "hmac")]
+pub mod auth;
+/// Borrowed, zero-copy views of OSC packets.
+mod borrowed;
+/// Comparing and hashing OSC values with floats by their bit patterns.
+mod canonical;
+/// Messages that borrow or own their address and arguments.
+mod cow;
+/// Human readable `Display` implementations of the OSC types.
+mod display;
 /// Crate specific error types.
 mod errors;
+/// `Arbitrary` implementations that generate structurally valid packets.
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+/// The `osc!` macro.
+#[macro_use]
+mod macros;
+/// The accept loop shared by the stream servers.
+#[cfg(feature = "std")]
+mod server;
+/// Packets sharing the buffer they were decoded from.
+#[cfg(feature = "bytes")]
+mod shared;
+/// Packets storing the arguments of their messages inline.
+#[cfg(feature = "smallvec")]
+mod small;
 /// OSC data types, see [OSC 1.0 specification](http://opensoundcontrol.org/spec-1_0) for details.
 mod types;
 
+#[cfg(feature = "bumpalo")]
+pub use crate::arena::*;
+pub use crate::borrowed::*;
+pub use crate::canonical::*;
+pub use crate::cow::*;
 pub use crate::errors::*;
+#[cfg(feature = "bytes")]
+pub use crate::shared::*;
+#[cfg(feature = "smallvec")]
+pub use crate::small::*;
 pub use crate::types::*;
+/// Derive macros for [`ToOsc`] and [`FromOsc`].
+#[cfg(feature = "derive")]
+pub use rosc_derive::{FromOsc, ToOsc};
 
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "tokio")]
+pub mod codec;
 /// Provides a decoding method for OSC packets.
 pub mod decoder;
+#[cfg(feature = "mdns")]
+pub mod discovery;
+pub mod dispatcher;
 /// Encodes an `OscPacket` to a byte vector.
 pub mod encoder;
+pub mod filter;
+#[cfg(feature = "std")]
+pub mod fragment;
+pub mod hexdump;
+pub mod json;
+pub mod matcher;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "midi")]
+pub mod midi;
+pub mod namespace;
+#[cfg(feature = "std")]
+pub mod oscquery;
+#[cfg(feature = "std")]
+pub mod ping;
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod ratelimit;
+#[cfg(feature = "std")]
+pub mod recording;
+pub mod rewrite;
+#[cfg(feature = "serial")]
+pub mod serial;
+pub mod slip;
+#[cfg(feature = "std")]
+pub mod socket;
+#[cfg(feature = "std")]
+pub mod tcp;
+pub mod text;
+#[cfg(feature = "std")]
+pub mod udp;
+#[cfg(feature = "websocket")]
+pub mod websocket;
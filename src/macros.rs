@@ -0,0 +1,65 @@
+/// Creates an [`OscPacket::Message`](crate::OscPacket::Message) from an
+/// address and arguments, which are converted with [`Into<OscType>`].
+///
+/// Unsuffixed literals have their default types, so `440.0` becomes an
+/// `OscType::Double` and `1` an `OscType::Int`.
+///
+/// # Panics
+///
+/// Panics if the address is invalid, see [`address::validate`]. Addresses
+/// given as string literals are checked at compile time instead.
+///
+/// [`Into<OscType>`]: crate::OscType
+/// [`address::validate`]: crate::address::validate
+///
+/// # Example
+///
+/// ```
+/// #[macro_use]
+/// extern crate rosc;
+///
+/// use rosc::{OscMessage, OscPacket};
+///
+/// # fn main() {
+/// let packet = osc!("/synth/freq", 440.0f32, "sine", true);
+/// assert_eq!(
+///     OscPacket::Message(OscMessage {
+///         addr: "/synth/freq".to_string(),
+///         args: vec![440.0f32.into(), "sine".into(), true.into()],
+///     }),
+///     packet
+/// );
+///
+/// let voice = 3;
+/// let packet = osc!(format!("/voice/{}/gate", voice), 1);
+/// # }
+/// ```
+///
+/// An invalid literal address fails to compile:
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate rosc;
+///
+/// # fn main() {
+/// let packet = osc!("/synth freq", 440.0f32);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! osc {
+    (@build $addr:expr $(, $arg:expr)*) => {
+        $crate::OscPacket::Message(
+            $crate::OscMessage::builder($addr)
+                $(.arg($arg))*
+                .build()
+                .expect("invalid OSC address"),
+        )
+    };
+    ($addr:literal $(, $arg:expr)* $(,)?) => {{
+        const _: () = assert!($crate::address::is_valid($addr), "invalid OSC address");
+        $crate::osc!(@build $addr $(, $arg)*)
+    }};
+    ($addr:expr $(, $arg:expr)* $(,)?) => {
+        $crate::osc!(@build $addr $(, $arg)*)
+    };
+}
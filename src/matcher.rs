@@ -0,0 +1,356 @@
+//! Matching of OSC address patterns against addresses.
+//!
+//! See the *OSC Message Dispatching and Pattern Matching* section of the
+//! [OSC 1.0 specification](http://opensoundcontrol.org/spec-1_0) for the
+//! pattern syntax:
+//!
+//! - `?` matches any single character
+//! - `*` matches any sequence of zero or more characters
+//! - `[abc]` matches any of the listed characters, `[a-z]` any character in
+//!   the range and `[!abc]` any character that is not listed
+//! - `{foo,bar}` matches any of the comma separated strings
+//!
+//! Wildcards never match the `/` that separates the parts of an address.
+
+use crate::errors::OscError;
+use crate::types::Result;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Returns whether the OSC address `pattern` matches `address`.
+///
+/// Malformed patterns, e.g. with an unclosed `[` or `{`, don't match any
+/// address. Use a [`Matcher`] to match the same pattern repeatedly.
+///
+/// # Example
+///
+/// ```
+/// use rosc::matcher;
+///
+/// assert!(matcher::matches("/mixer/*/gain", "/mixer/3/gain"));
+/// assert!(matcher::matches("/synth/{osc,lfo}[1-4]/freq", "/synth/lfo2/freq"));
+/// assert!(!matcher::matches("/mixer/*", "/mixer/3/gain"));
+/// ```
+pub fn matches(pattern: &str, address: &str) -> bool {
+    Matcher::new(pattern).is_ok_and(|matcher| matcher.is_match(address))
+}
+
+/// A compiled OSC address pattern.
+///
+/// Compiling the pattern once makes matching it against many addresses
+/// cheaper than calling [`matches`] for each of them.
+///
+/// # Example
+///
+/// ```
+/// use rosc::matcher::Matcher;
+///
+/// let matcher = Matcher::new("/mixer/*/{gain,pan}").unwrap();
+/// assert!(matcher.is_match("/mixer/1/gain"));
+/// assert!(matcher.is_match("/mixer/2/pan"));
+/// assert!(!matcher.is_match("/mixer/2/mute"));
+///
+/// assert!(Matcher::new("/mixer/[12").is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    pattern: String,
+    parts: Vec<Part>,
+}
+
+/// The compiled pattern of an address part.
+#[derive(Clone, Debug)]
+enum Part {
+    /// A part without special characters.
+    Literal(String),
+    /// A part that is just `*`.
+    Any,
+    Pattern(Automaton),
+}
+
+impl Matcher {
+    /// Compiles `pattern`, returns an `OscError::BadAddress` if it is
+    /// malformed.
+    pub fn new(pattern: &str) -> Result<Matcher> {
+        let parts = pattern
+            .split('/')
+            .map(|part| {
+                if !part.contains(|c| "?*[{".contains(c)) {
+                    return Ok(Part::Literal(part.to_string()));
+                }
+                let tokens = parse_part(part)?;
+                if tokens == [Token::Star] {
+                    Ok(Part::Any)
+                } else {
+                    Ok(Part::Pattern(Automaton::new(&tokens)))
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(Matcher {
+            pattern: pattern.to_string(),
+            parts,
+        })
+    }
+
+    /// Returns the pattern this matcher was compiled from.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns whether the pattern matches `address`.
+    pub fn is_match(&self, address: &str) -> bool {
+        let mut address_parts = address.split('/');
+        for part in &self.parts {
+            let address_part = match address_parts.next() {
+                Some(address_part) => address_part,
+                None => return false,
+            };
+            let matched = match *part {
+                Part::Literal(ref literal) => literal == address_part,
+                Part::Any => true,
+                Part::Pattern(ref automaton) => automaton.is_match(address_part),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        address_parts.next().is_none()
+    }
+}
+
+/// A single element of the pattern of an address part.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Char(char),
+    /// `?`
+    Any,
+    /// `*`
+    Star,
+    /// `[...]`, a list of inclusive character ranges
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+    /// `{...}`
+    Alternatives(Vec<Vec<char>>),
+}
+
+/// Parses the pattern of a single address part.
+fn parse_part(pattern: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '?' => Token::Any,
+            // consecutive stars are equivalent to a single one
+            '*' if tokens.last() == Some(&Token::Star) => continue,
+            '*' => Token::Star,
+            '[' => {
+                let negated = chars.peek() == Some(&'!');
+                if negated {
+                    chars.next();
+                }
+                let mut class = vec![];
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(c) => class.push(c),
+                        None => {
+                            return Err(OscError::BadAddress("Unclosed '[' in address pattern."))
+                        }
+                    }
+                }
+                let mut ranges = vec![];
+                let mut i = 0;
+                while i < class.len() {
+                    // a '-' at the start or the end of the class is literal
+                    if i + 2 < class.len() && class[i + 1] == '-' {
+                        ranges.push((class[i], class[i + 2]));
+                        i += 3;
+                    } else {
+                        ranges.push((class[i], class[i]));
+                        i += 1;
+                    }
+                }
+                Token::Class { negated, ranges }
+            }
+            '{' => {
+                let mut alternatives = vec![vec![]];
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(',') => alternatives.push(vec![]),
+                        Some(c) => alternatives.last_mut().unwrap().push(c),
+                        None => {
+                            return Err(OscError::BadAddress("Unclosed '{' in address pattern."))
+                        }
+                    }
+                }
+                Token::Alternatives(alternatives)
+            }
+            c => Token::Char(c),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// A nondeterministic automaton matching a single address part.
+///
+/// Every node consumes one character, matching runs in
+/// `O(nodes * characters)` time regardless of the number of wildcards.
+#[derive(Clone, Debug)]
+struct Automaton {
+    nodes: Vec<Node>,
+    /// The nodes active before the first character, the index `nodes.len()`
+    /// denotes the accepting state.
+    start: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    kind: NodeKind,
+    /// The nodes active after this node consumed a character.
+    follow: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+    Char(char),
+    Any,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl Automaton {
+    fn new(tokens: &[Token]) -> Automaton {
+        // the index of the first node of every token, the last entry is
+        // the accepting state
+        let mut first_node = Vec::with_capacity(tokens.len() + 1);
+        let mut nodes: Vec<Node> = vec![];
+        for token in tokens {
+            first_node.push(nodes.len());
+            let mut push = |kind| {
+                nodes.push(Node {
+                    kind,
+                    follow: vec![],
+                })
+            };
+            match *token {
+                Token::Char(c) => push(NodeKind::Char(c)),
+                Token::Any | Token::Star => push(NodeKind::Any),
+                Token::Class {
+                    negated,
+                    ref ranges,
+                } => push(NodeKind::Class {
+                    negated,
+                    ranges: ranges.clone(),
+                }),
+                Token::Alternatives(ref alternatives) => {
+                    for c in alternatives.iter().flatten() {
+                        push(NodeKind::Char(*c));
+                    }
+                }
+            }
+        }
+        first_node.push(nodes.len());
+
+        // the nodes that are active when token `index` is reached
+        let enter = |index: usize| {
+            let mut active = vec![];
+            for (index, token) in tokens.iter().enumerate().skip(index) {
+                match *token {
+                    Token::Star => active.push(first_node[index]),
+                    Token::Alternatives(ref alternatives) => {
+                        let mut node = first_node[index];
+                        for alternative in alternatives.iter().filter(|a| !a.is_empty()) {
+                            active.push(node);
+                            node += alternative.len();
+                        }
+                        if alternatives.iter().all(|a| !a.is_empty()) {
+                            return active;
+                        }
+                        continue;
+                    }
+                    _ => {
+                        active.push(first_node[index]);
+                        return active;
+                    }
+                }
+            }
+            active.push(first_node[tokens.len()]);
+            active
+        };
+
+        for (index, token) in tokens.iter().enumerate() {
+            match *token {
+                Token::Star => {
+                    // a star stays active and may also be skipped
+                    nodes[first_node[index]].follow = enter(index);
+                }
+                Token::Alternatives(ref alternatives) => {
+                    let mut node = first_node[index];
+                    for alternative in alternatives {
+                        for i in 0..alternative.len() {
+                            nodes[node].follow = if i + 1 < alternative.len() {
+                                vec![node + 1]
+                            } else {
+                                enter(index + 1)
+                            };
+                            node += 1;
+                        }
+                    }
+                }
+                _ => nodes[first_node[index]].follow = enter(index + 1),
+            }
+        }
+
+        Automaton {
+            start: enter(0),
+            nodes,
+        }
+    }
+
+    fn is_match(&self, part: &str) -> bool {
+        let accepting = self.nodes.len();
+        let mut active = self.start.clone();
+        let mut next = vec![];
+        // the last character for which a node was activated, to avoid
+        // activating nodes twice
+        let mut activated = vec![usize::MAX; accepting + 1];
+        for (i, c) in part.chars().enumerate() {
+            next.clear();
+            for &index in &active {
+                let node = match self.nodes.get(index) {
+                    Some(node) => node,
+                    // the accepting state doesn't consume any characters
+                    None => continue,
+                };
+                let matched = match node.kind {
+                    NodeKind::Char(expected) => expected == c,
+                    NodeKind::Any => true,
+                    NodeKind::Class {
+                        negated,
+                        ref ranges,
+                    } => ranges.iter().any(|&(from, to)| from <= c && c <= to) != negated,
+                };
+                if !matched {
+                    continue;
+                }
+                for &follow in &node.follow {
+                    if activated[follow] != i {
+                        activated[follow] = i;
+                        next.push(follow);
+                    }
+                }
+            }
+            if next.is_empty() {
+                return false;
+            }
+            core::mem::swap(&mut active, &mut next);
+        }
+        active.contains(&accepting)
+    }
+}
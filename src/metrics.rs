@@ -0,0 +1,155 @@
+//! Hooks to collect metrics about the packets passing the UDP and TCP
+//! transports.
+//!
+//! Transports report to an [`OscMetricsSink`] set with their `set_metrics`
+//! method. Implement the trait to forward the events to a telemetry system
+//! of your choice, or use [`OscMetrics`], which just counts them.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rosc::metrics::OscMetrics;
+//! use rosc::udp::OscUdpServer;
+//! use std::sync::Arc;
+//! use std::thread;
+//! use std::time::Duration;
+//!
+//! let metrics = Arc::new(OscMetrics::new());
+//! let mut server = OscUdpServer::bind("0.0.0.0:9000").unwrap();
+//! server.set_metrics(metrics.clone());
+//! thread::spawn(move || server.run(|_, _| ()));
+//!
+//! loop {
+//!     thread::sleep(Duration::from_secs(60));
+//!     println!(
+//!         "{} packets received, {} malformed",
+//!         metrics.packets_received(),
+//!         metrics.decode_errors()
+//!     );
+//! }
+//! ```
+
+use crate::errors::OscError;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Receives the events of the transports it is set on.
+///
+/// The methods are called on the thread doing the sending or receiving,
+/// so they should return quickly. All methods do nothing by default.
+pub trait OscMetricsSink: Send + Sync {
+    /// Called after a packet of `bytes` bytes was sent.
+    fn packet_sent(&self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    /// Called after a packet of `bytes` bytes was received and decoded.
+    fn packet_received(&self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    /// Called when a received packet couldn't be decoded.
+    fn decode_error(&self, err: &OscError) {
+        let _ = err;
+    }
+
+    /// Called when `bytes` bytes of received data were discarded without
+    /// being decoded, like the unfinished last packet of a closed TCP
+    /// connection.
+    fn packet_dropped(&self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    /// Called when a received message was dropped by the address filter
+    /// of the server, see [`filter`](crate::filter).
+    fn message_filtered(&self, addr: &str) {
+        let _ = addr;
+    }
+}
+
+/// The sink of a transport, if any.
+pub(crate) type Metrics = Option<Arc<dyn OscMetricsSink>>;
+
+/// An [`OscMetricsSink`] counting the events reported to it.
+///
+/// Share it between transports and the code reading the counters with an
+/// `Arc`. The counters only ever increase.
+#[derive(Debug, Default)]
+pub struct OscMetrics {
+    packets_sent: AtomicU64,
+    bytes_sent: AtomicU64,
+    packets_received: AtomicU64,
+    bytes_received: AtomicU64,
+    decode_errors: AtomicU64,
+    packets_dropped: AtomicU64,
+    messages_filtered: AtomicU64,
+}
+
+impl OscMetrics {
+    /// Creates a sink with all counters at 0.
+    pub fn new() -> OscMetrics {
+        OscMetrics::default()
+    }
+
+    /// Returns the number of packets sent.
+    pub fn packets_sent(&self) -> u64 {
+        self.packets_sent.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes sent.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of packets received and decoded.
+    pub fn packets_received(&self) -> u64 {
+        self.packets_received.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes of the packets received and decoded.
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of received packets that couldn't be decoded.
+    pub fn decode_errors(&self) -> u64 {
+        self.decode_errors.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of times received data was discarded without
+    /// being decoded.
+    pub fn packets_dropped(&self) -> u64 {
+        self.packets_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of received messages dropped by an address
+    /// filter.
+    pub fn messages_filtered(&self) -> u64 {
+        self.messages_filtered.load(Ordering::Relaxed)
+    }
+}
+
+impl OscMetricsSink for OscMetrics {
+    fn packet_sent(&self, bytes: usize) {
+        self.packets_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    fn packet_received(&self, bytes: usize) {
+        self.packets_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    fn decode_error(&self, _err: &OscError) {
+        self.decode_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn packet_dropped(&self, _bytes: usize) {
+        self.packets_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn message_filtered(&self, _addr: &str) {
+        self.messages_filtered.fetch_add(1, Ordering::Relaxed);
+    }
+}
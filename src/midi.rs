@@ -0,0 +1,214 @@
+//! A bridge between MIDI events and OSC messages, to drive OSC software
+//! with MIDI controllers and vice versa.
+//!
+//! A [`MidiBridge`] holds a table of mappings from note, control change
+//! and pitch bend events to OSC addresses. MIDI events are represented by
+//! [`OscMidiMessage`], use [`OscMidiMessage::from_bytes`] and
+//! [`OscMidiMessage::to_bytes`] to convert from and to the raw bytes of a
+//! MIDI library.
+//!
+//! # Example
+//!
+//! ```
+//! use rosc::midi::{MidiBridge, MidiSource};
+//! use rosc::{OscMessage, OscMidiMessage, OscType};
+//!
+//! let mut bridge = MidiBridge::new();
+//! bridge
+//!     .map(MidiSource::ControlChange { channel: 0, controller: 7 }, "/mixer/volume")
+//!     .unwrap();
+//!
+//! let cc = OscMidiMessage::control_change(0, 0, 7, 127);
+//! let msg = bridge.to_osc(&cc).unwrap();
+//! assert_eq!("/mixer/volume", msg.addr);
+//! assert_eq!(vec![OscType::Float(1.0)], msg.args);
+//!
+//! let msg = OscMessage {
+//!     addr: "/mixer/volume".to_string(),
+//!     args: vec![0.0f32.into()],
+//! };
+//! assert_eq!(Some(OscMidiMessage::control_change(0, 0, 7, 0)), bridge.to_midi(&msg));
+//! ```
+
+use crate::address;
+use crate::types::{OscMessage, OscMidiMessage, OscType, Result};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// The MIDI events a mapping applies to. Channels are numbered from 0 to 15.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MidiSource {
+    /// Note on and off events of a note. Note off events, and note on events
+    /// with a velocity of 0, map to the value 0.
+    Note { channel: u8, note: u8 },
+    /// Control change events of a controller.
+    ControlChange { channel: u8, controller: u8 },
+    /// Pitch bend events.
+    PitchBend { channel: u8 },
+}
+
+impl MidiSource {
+    /// Returns the source of `midi` and its value, or `None` if it isn't a
+    /// note, control change or pitch bend event.
+    fn of(midi: &OscMidiMessage) -> Option<(MidiSource, u16)> {
+        let channel = midi.status & 0x0f;
+        let (data1, data2) = (midi.data1 & 0x7f, midi.data2 & 0x7f);
+        match midi.status & 0xf0 {
+            0x80 => Some((
+                MidiSource::Note {
+                    channel,
+                    note: data1,
+                },
+                0,
+            )),
+            0x90 => Some((
+                MidiSource::Note {
+                    channel,
+                    note: data1,
+                },
+                data2.into(),
+            )),
+            0xb0 => Some((
+                MidiSource::ControlChange {
+                    channel,
+                    controller: data1,
+                },
+                data2.into(),
+            )),
+            0xe0 => Some((
+                MidiSource::PitchBend { channel },
+                u16::from(data2) << 7 | u16::from(data1),
+            )),
+            _ => None,
+        }
+    }
+
+    fn max_value(self) -> u16 {
+        match self {
+            MidiSource::PitchBend { .. } => 0x3fff,
+            _ => 0x7f,
+        }
+    }
+}
+
+/// How the value of a MIDI event is represented by the argument of an OSC
+/// message.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ValueFormat {
+    /// A float from 0.0 to 1.0, or from -1.0 to 1.0 for pitch bends with 0.0
+    /// meaning no bend.
+    #[default]
+    Normalized,
+    /// The MIDI value as integer, from 0 to 127, or from 0 to 16383 for
+    /// pitch bends.
+    Raw,
+}
+
+/// Maps the events of a MIDI source to the messages of an OSC address.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MidiMapping {
+    pub source: MidiSource,
+    /// The address of the OSC messages, which must not contain patterns.
+    pub addr: String,
+    pub format: ValueFormat,
+}
+
+/// Translates between MIDI events and OSC messages by a table of
+/// [`MidiMapping`]s.
+#[derive(Clone, Debug, Default)]
+pub struct MidiBridge {
+    mappings: Vec<MidiMapping>,
+    port: u8,
+}
+
+impl MidiBridge {
+    /// Creates a bridge without mappings, producing MIDI events for port 0.
+    pub fn new() -> MidiBridge {
+        MidiBridge::default()
+    }
+
+    /// Sets the port of the MIDI events returned by [`to_midi`](MidiBridge::to_midi).
+    pub fn set_port(&mut self, port: u8) -> &mut MidiBridge {
+        self.port = port;
+        self
+    }
+
+    /// Maps `source` to `addr` with normalized values. Returns an
+    /// `OscError::BadAddress` if `addr` isn't a valid address without
+    /// patterns.
+    pub fn map<S: Into<String>>(&mut self, source: MidiSource, addr: S) -> Result<&mut MidiBridge> {
+        self.add(MidiMapping {
+            source,
+            addr: addr.into(),
+            format: ValueFormat::Normalized,
+        })
+    }
+
+    /// Adds `mapping`, mappings added earlier take precedence. Returns an
+    /// `OscError::BadAddress` if its address isn't a valid address without
+    /// patterns.
+    pub fn add(&mut self, mapping: MidiMapping) -> Result<&mut MidiBridge> {
+        address::validate_method(&mapping.addr)?;
+        self.mappings.push(mapping);
+        Ok(self)
+    }
+
+    /// Returns the mappings.
+    pub fn mappings(&self) -> &[MidiMapping] {
+        &self.mappings
+    }
+
+    /// Translates a MIDI event into an OSC message with the event's value
+    /// as single argument, or returns `None` if there is no mapping for it.
+    pub fn to_osc(&self, midi: &OscMidiMessage) -> Option<OscMessage> {
+        let (source, value) = MidiSource::of(midi)?;
+        let mapping = self.mappings.iter().find(|m| m.source == source)?;
+        let arg = match (mapping.format, source) {
+            (ValueFormat::Raw, _) => OscType::Int(value.into()),
+            (ValueFormat::Normalized, MidiSource::PitchBend { .. }) => {
+                OscType::Float(((f32::from(value) - 8192.0) / 8192.0).max(-1.0))
+            }
+            (ValueFormat::Normalized, _) => {
+                OscType::Float(f32::from(value) / f32::from(source.max_value()))
+            }
+        };
+        Some(OscMessage {
+            addr: mapping.addr.clone(),
+            args: vec![arg],
+        })
+    }
+
+    /// Translates an OSC message into a MIDI event, or returns `None` if
+    /// there is no mapping for its address or its first argument isn't a
+    /// number. Values out of range are clamped. Notes with a value of 0 are
+    /// turned into note off events.
+    pub fn to_midi(&self, msg: &OscMessage) -> Option<OscMidiMessage> {
+        let mapping = self.mappings.iter().find(|m| m.addr == msg.addr)?;
+        let arg = msg.args.first()?.coerce_double()?;
+        let max = f64::from(mapping.source.max_value());
+        let value = match (mapping.format, mapping.source) {
+            (ValueFormat::Raw, _) => arg,
+            (ValueFormat::Normalized, MidiSource::PitchBend { .. }) => arg * 8192.0 + 8192.0,
+            (ValueFormat::Normalized, _) => arg * max,
+        };
+        if value.is_nan() {
+            return None;
+        }
+        // rounds to the nearest value, `f64::round` needs std
+        let value = (value.clamp(0.0, max) + 0.5) as u16;
+        let port = self.port;
+        Some(match mapping.source {
+            MidiSource::Note { channel, note } if value == 0 => {
+                OscMidiMessage::note_off(port, channel, note, 0)
+            }
+            MidiSource::Note { channel, note } => {
+                OscMidiMessage::note_on(port, channel, note, value as u8)
+            }
+            MidiSource::ControlChange {
+                channel,
+                controller,
+            } => OscMidiMessage::control_change(port, channel, controller, value as u8),
+            MidiSource::PitchBend { channel } => OscMidiMessage::pitch_bend(port, channel, value),
+        })
+    }
+}
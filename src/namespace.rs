@@ -0,0 +1,179 @@
+//! A tree of the methods an OSC server exposes.
+
+use crate::address;
+use crate::errors::OscError;
+use crate::types::Result;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Stores a value for every method of an OSC address space.
+///
+/// Methods are the leaves of a tree whose inner nodes are containers, e.g.
+/// inserting `/synth/1/freq` creates the containers `/synth` and
+/// `/synth/1`. Containers exist as long as they contain at least one
+/// method.
+///
+/// # Example
+///
+/// ```
+/// use rosc::namespace::Namespace;
+///
+/// let mut namespace = Namespace::new();
+/// namespace.insert("/synth/1/freq", 440.0).unwrap();
+/// namespace.insert("/synth/1/gain", 0.5).unwrap();
+/// namespace.insert("/synth/2/freq", 220.0).unwrap();
+///
+/// assert_eq!(Some(&440.0), namespace.get("/synth/1/freq"));
+/// assert_eq!(
+///     vec!["1", "2"],
+///     namespace.children("/synth").unwrap().collect::<Vec<_>>()
+/// );
+///
+/// namespace.remove("/synth/2/freq");
+/// assert_eq!(
+///     vec!["1"],
+///     namespace.children("/synth").unwrap().collect::<Vec<_>>()
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Namespace<T> {
+    root: Node<T>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Node<T> {
+    value: Option<T>,
+    children: BTreeMap<String, Node<T>>,
+}
+
+impl<T> Default for Namespace<T> {
+    fn default() -> Namespace<T> {
+        Namespace { root: Node::new() }
+    }
+}
+
+impl<T> Node<T> {
+    fn new() -> Node<T> {
+        Node {
+            value: None,
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> Namespace<T> {
+    /// Creates an empty namespace.
+    pub fn new() -> Namespace<T> {
+        Namespace::default()
+    }
+
+    /// Inserts a method at `addr` and returns the value previously stored
+    /// for it.
+    ///
+    /// Returns an `OscError::BadAddress` if `addr` is not a valid method
+    /// address, e.g. because it contains pattern characters, or if it is
+    /// the address of a container.
+    pub fn insert(&mut self, addr: &str, value: T) -> Result<Option<T>> {
+        address::validate_method(addr)?;
+        let mut node = &mut self.root;
+        for part in parts(addr) {
+            if node.value.is_some() {
+                return Err(OscError::BadAddress("Address is inside a method."));
+            }
+            node = node
+                .children
+                .entry(part.to_string())
+                .or_insert_with(Node::new);
+        }
+        if !node.children.is_empty() {
+            return Err(OscError::BadAddress("Address is a container."));
+        }
+        Ok(node.value.replace(value))
+    }
+
+    /// Removes the method at `addr` and returns its value, containers that
+    /// become empty are removed as well.
+    pub fn remove(&mut self, addr: &str) -> Option<T> {
+        let parts: Vec<&str> = parts(addr).collect();
+        remove(&mut self.root, &parts)
+    }
+
+    /// Returns the value of the method at `addr`.
+    pub fn get(&self, addr: &str) -> Option<&T> {
+        self.node(addr).and_then(|node| node.value.as_ref())
+    }
+
+    /// Returns a mutable reference to the value of the method at `addr`.
+    pub fn get_mut(&mut self, addr: &str) -> Option<&mut T> {
+        let mut node = &mut self.root;
+        for part in parts(addr) {
+            node = node.children.get_mut(part)?;
+        }
+        node.value.as_mut()
+    }
+
+    /// Returns the names of the methods and containers in the container at
+    /// `addr` in alphabetical order, or `None` if there is no such
+    /// container. The root container's address is `/`.
+    pub fn children<'a>(&'a self, addr: &str) -> Option<impl Iterator<Item = &'a str>> {
+        self.node(addr)
+            .filter(|node| node.value.is_none())
+            .map(|node| node.children.keys().map(String::as_str))
+    }
+
+    /// Returns whether `addr` is the address of a container.
+    pub fn is_container(&self, addr: &str) -> bool {
+        self.node(addr).is_some_and(|node| node.value.is_none())
+    }
+
+    /// Returns the addresses and values of all methods in alphabetical
+    /// order.
+    pub fn methods(&self) -> Vec<(String, &T)> {
+        let mut methods = vec![];
+        let mut stack = vec![(String::new(), &self.root)];
+        while let Some((addr, node)) = stack.pop() {
+            if let Some(ref value) = node.value {
+                methods.push((addr.clone(), value));
+            }
+            // reversed so that the children are popped in order
+            for (name, child) in node.children.iter().rev() {
+                stack.push((format!("{}/{}", addr, name), child));
+            }
+        }
+        methods
+    }
+
+    /// Returns whether the namespace contains no methods.
+    pub fn is_empty(&self) -> bool {
+        self.root.children.is_empty()
+    }
+
+    fn node(&self, addr: &str) -> Option<&Node<T>> {
+        let mut node = &self.root;
+        for part in parts(addr) {
+            node = node.children.get(part)?;
+        }
+        Some(node)
+    }
+}
+
+/// Splits an address into its parts, ignoring empty ones so that e.g.
+/// `/synth/` refers to the container `/synth`.
+fn parts(addr: &str) -> impl Iterator<Item = &str> {
+    address::split(addr).filter(|part| !part.is_empty())
+}
+
+fn remove<T>(node: &mut Node<T>, parts: &[&str]) -> Option<T> {
+    match parts.split_first() {
+        None => node.value.take(),
+        Some((part, rest)) => {
+            let child = node.children.get_mut(*part)?;
+            let value = remove(child, rest);
+            if child.value.is_none() && child.children.is_empty() {
+                node.children.remove(*part);
+            }
+            value
+        }
+    }
+}
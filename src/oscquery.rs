@@ -0,0 +1,428 @@
+//! An [OSCQuery](https://github.com/Vidvox/OSCQueryProposal) server, which
+//! lets tools like VDMX or Vezér browse the address space of an application.
+//!
+//! The address space is a [`Namespace`] describing every method. The server
+//! answers HTTP `GET` requests for an address with the JSON description of
+//! the method or container at that address, e.g.
+//!
+//! ```text
+//! {"FULL_PATH":"/synth","CONTENTS":{"freq":{"FULL_PATH":"/synth/freq","TYPE":"f","VALUE":[440.0],"ACCESS":3}}}
+//! ```
+//!
+//! Requesting `/synth/freq?VALUE` returns only the given attribute, and
+//! `/?HOST_INFO` describes the server.
+
+use crate::errors::OscError;
+use crate::json::write_string;
+use crate::namespace::Namespace;
+use crate::types::{OscType, Result};
+use std::fmt::Write as FmtWrite;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// The maximum size in bytes of the request line and headers of a request
+/// the server answers, larger requests are rejected.
+const MAX_REQUEST_SIZE: u64 = 8192;
+
+/// How long the server waits for a client to send its request unless
+/// another timeout is set.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How clients may access a method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// The method has no value.
+    None = 0,
+    /// The value can only be read.
+    Read = 1,
+    /// Messages can be sent to the method, but its value can't be read.
+    Write = 2,
+    /// The value can be read and set.
+    ReadWrite = 3,
+}
+
+/// The description of a method of the address space.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MethodInfo {
+    /// The type tags of the arguments the method expects, e.g. `ff`.
+    pub type_tags: String,
+    /// The current value of the method's arguments.
+    pub value: Vec<OscType>,
+    /// Whether clients may read the value, send messages to the method, or
+    /// both.
+    pub access: Access,
+    /// A human readable description.
+    pub description: Option<String>,
+}
+
+impl MethodInfo {
+    /// Describes a method with the given type tags that can be read and
+    /// written, without a value.
+    pub fn new<S: Into<String>>(type_tags: S) -> MethodInfo {
+        MethodInfo {
+            type_tags: type_tags.into(),
+            value: vec![],
+            access: Access::ReadWrite,
+            description: None,
+        }
+    }
+}
+
+/// Describes the server itself, see [`OscQueryServer::set_host_info`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostInfo {
+    /// The name of the application, shown to the users of OSCQuery
+    /// clients.
+    pub name: String,
+    /// The port the application receives OSC messages on.
+    pub osc_port: u16,
+    /// The transport of the OSC server, `UDP` or `TCP`.
+    pub osc_transport: String,
+}
+
+/// Returns the JSON description of the method or container at `addr`, or
+/// `None` if there is neither.
+///
+/// # Example
+///
+/// ```
+/// use rosc::namespace::Namespace;
+/// use rosc::oscquery::{self, MethodInfo};
+///
+/// let mut namespace = Namespace::new();
+/// let mut freq = MethodInfo::new("f");
+/// freq.value = vec![440.0f32.into()];
+/// namespace.insert("/synth/freq", freq).unwrap();
+///
+/// assert_eq!(
+///     Some(r#"{"FULL_PATH":"/synth/freq","TYPE":"f","VALUE":[440.0],"ACCESS":3}"#.to_string()),
+///     oscquery::describe(&namespace, "/synth/freq")
+/// );
+/// assert_eq!(None, oscquery::describe(&namespace, "/drums"));
+/// ```
+pub fn describe(namespace: &Namespace<MethodInfo>, addr: &str) -> Option<String> {
+    let mut json = String::new();
+    write_node(&mut json, namespace, &normalize(addr))?;
+    Some(json)
+}
+
+/// Returns the JSON object containing only `attribute` (e.g. `VALUE`) of
+/// the method or container at `addr`, or `None` if it doesn't exist.
+pub fn describe_attribute(
+    namespace: &Namespace<MethodInfo>,
+    addr: &str,
+    attribute: &str,
+) -> Option<String> {
+    let addr = normalize(addr);
+    let mut json = String::new();
+    if let Some(method) = namespace.get(&addr) {
+        json.push('{');
+        if !write_method_attribute(&mut json, method, attribute) {
+            return None;
+        }
+        json.push('}');
+        return Some(json);
+    }
+    match attribute {
+        "FULL_PATH" if namespace.is_container(&addr) => {
+            json.push_str("{\"FULL_PATH\":");
+            write_string(&mut json, &addr);
+            json.push('}');
+            Some(json)
+        }
+        "CONTENTS" => {
+            json.push_str("{\"CONTENTS\":");
+            write_contents(&mut json, namespace, &addr)?;
+            json.push('}');
+            Some(json)
+        }
+        _ => None,
+    }
+}
+
+/// Removes a trailing slash, except from the root.
+fn normalize(addr: &str) -> String {
+    match addr.trim_end_matches('/') {
+        "" => "/".to_string(),
+        addr => addr.to_string(),
+    }
+}
+
+fn write_node(json: &mut String, namespace: &Namespace<MethodInfo>, addr: &str) -> Option<()> {
+    json.push_str("{\"FULL_PATH\":");
+    write_string(json, addr);
+    if let Some(method) = namespace.get(addr) {
+        for attribute in &["TYPE", "VALUE", "ACCESS", "DESCRIPTION"] {
+            json.push(',');
+            if !write_method_attribute(json, method, attribute) {
+                json.pop();
+            }
+        }
+    } else {
+        json.push_str(",\"CONTENTS\":");
+        write_contents(json, namespace, addr)?;
+    }
+    json.push('}');
+    Some(())
+}
+
+fn write_contents(json: &mut String, namespace: &Namespace<MethodInfo>, addr: &str) -> Option<()> {
+    json.push('{');
+    for (i, name) in namespace.children(addr)?.enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_string(json, name);
+        json.push(':');
+        let child = if addr == "/" {
+            format!("/{}", name)
+        } else {
+            format!("{}/{}", addr, name)
+        };
+        write_node(json, namespace, &child)?;
+    }
+    json.push('}');
+    Some(())
+}
+
+/// Writes `"ATTRIBUTE":value`, returns false if the method doesn't have the
+/// attribute.
+fn write_method_attribute(json: &mut String, method: &MethodInfo, attribute: &str) -> bool {
+    match attribute {
+        "TYPE" => {
+            json.push_str("\"TYPE\":");
+            write_string(json, &method.type_tags);
+        }
+        "VALUE" if method.access != Access::Write && !method.value.is_empty() => {
+            json.push_str("\"VALUE\":");
+            write_values(json, &method.value);
+        }
+        "ACCESS" => {
+            let _ = write!(json, "\"ACCESS\":{}", method.access as u8);
+        }
+        "DESCRIPTION" => match method.description {
+            Some(ref description) => {
+                json.push_str("\"DESCRIPTION\":");
+                write_string(json, description);
+            }
+            None => return false,
+        },
+        _ => return false,
+    }
+    true
+}
+
+fn write_values(json: &mut String, values: &[OscType]) {
+    json.push('[');
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write_value(json, value);
+    }
+    json.push(']');
+}
+
+fn write_value(json: &mut String, value: &OscType) {
+    let _ = match *value {
+        OscType::Int(i) => write!(json, "{}", i),
+        OscType::Long(h) => write!(json, "{}", h),
+        OscType::Float(f) if f.is_finite() => write!(json, "{:?}", f),
+        OscType::Double(d) if d.is_finite() => write!(json, "{:?}", d),
+        OscType::Bool(b) => write!(json, "{}", b),
+        OscType::String(ref s) | OscType::Symbol(ref s) => {
+            write_string(json, s);
+            Ok(())
+        }
+        OscType::RawString(ref s) => {
+            write_string(json, &String::from_utf8_lossy(s));
+            Ok(())
+        }
+        OscType::Char(c) => {
+            write_string(json, c.encode_utf8(&mut [0; 4]));
+            Ok(())
+        }
+        OscType::Color(ref color) => {
+            write_string(json, &color.to_hex());
+            Ok(())
+        }
+        OscType::Array(ref array) => {
+            write_values(json, &array.content);
+            Ok(())
+        }
+        _ => write!(json, "null"),
+    };
+}
+
+/// Serves the description of an address space over HTTP.
+///
+/// The namespace is shared, so the application can keep the values of its
+/// methods up to date while the server is running.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::namespace::Namespace;
+/// use rosc::oscquery::{HostInfo, MethodInfo, OscQueryServer};
+/// use std::sync::{Arc, RwLock};
+///
+/// let namespace = Arc::new(RwLock::new(Namespace::new()));
+/// namespace
+///     .write()
+///     .unwrap()
+///     .insert("/synth/freq", MethodInfo::new("f"))
+///     .unwrap();
+///
+/// let mut server = OscQueryServer::bind("0.0.0.0:8000", namespace).unwrap();
+/// server.set_host_info(HostInfo {
+///     name: "rosc synth".to_string(),
+///     osc_port: 9000,
+///     osc_transport: "UDP".to_string(),
+/// });
+/// server.run().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct OscQueryServer {
+    listener: TcpListener,
+    namespace: Arc<RwLock<Namespace<MethodInfo>>>,
+    host_info: Option<HostInfo>,
+    request_timeout: Duration,
+}
+
+impl OscQueryServer {
+    /// Creates a server listening on `addr`. Returns an
+    /// `OscError::SocketError` if the socket can't be bound.
+    pub fn bind<A: ToSocketAddrs>(
+        addr: A,
+        namespace: Arc<RwLock<Namespace<MethodInfo>>>,
+    ) -> Result<OscQueryServer> {
+        Ok(OscQueryServer {
+            listener: TcpListener::bind(addr).map_err(OscError::SocketError)?,
+            namespace,
+            host_info: None,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        })
+    }
+
+    /// Sets the description returned for `HOST_INFO` requests, which are
+    /// answered with a 404 otherwise.
+    pub fn set_host_info(&mut self, host_info: HostInfo) -> &mut OscQueryServer {
+        self.host_info = Some(host_info);
+        self
+    }
+
+    /// Closes connections whose client doesn't send its whole request
+    /// within `timeout`, so that a slow client can't hold up others.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`].
+    pub fn set_request_timeout(&mut self, timeout: Duration) -> &mut OscQueryServer {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Answers requests one at a time. Only returns if accepting a
+    /// connection fails, errors of single requests are ignored.
+    pub fn run(&mut self) -> Result<()> {
+        loop {
+            let (stream, _) = self.listener.accept().map_err(OscError::SocketError)?;
+            let _ = self.handle(stream);
+        }
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(OscError::SocketError)
+    }
+
+    fn handle(&self, stream: TcpStream) -> io::Result<()> {
+        let stream = DeadlineReader {
+            stream,
+            deadline: Instant::now() + self.request_timeout,
+        };
+        let mut reader = BufReader::new(stream.take(MAX_REQUEST_SIZE));
+        let mut request = String::new();
+        reader.read_line(&mut request)?;
+        // skip the headers
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+        let too_large = header.is_empty() && reader.get_ref().limit() == 0;
+        let reader = reader.into_inner().into_inner();
+        let mut stream = &reader.stream;
+        if too_large {
+            write!(
+                stream,
+                "HTTP/1.1 431 Request Header Fields Too Large\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )?;
+            // closing with unread data would reset the connection before
+            // the client read the response
+            stream.shutdown(Shutdown::Write)?;
+            io::copy(&mut reader.take(MAX_REQUEST_SIZE), &mut io::sink())?;
+            return Ok(());
+        }
+
+        let mut parts = request.split_whitespace();
+        let body = match (parts.next(), parts.next()) {
+            (Some("GET"), Some(target)) => self.respond(target),
+            _ => None,
+        };
+        match body {
+            Some(body) => write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            ),
+            None => write!(
+                stream,
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            ),
+        }
+    }
+
+    /// Returns the JSON answering a request for `target`.
+    fn respond(&self, target: &str) -> Option<String> {
+        let (path, query) = match target.find('?') {
+            Some(i) => (&target[..i], Some(&target[i + 1..])),
+            None => (target, None),
+        };
+        let namespace = self.namespace.read().ok()?;
+        match query {
+            Some("HOST_INFO") => self.host_info.as_ref().map(host_info_json),
+            Some(attribute) => describe_attribute(&namespace, path, attribute),
+            None => describe(&namespace, path),
+        }
+    }
+}
+
+/// Reads a request, failing with `io::ErrorKind::TimedOut` once the
+/// deadline has passed.
+struct DeadlineReader {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.deadline.checked_duration_since(Instant::now()) {
+            Some(left) if !left.is_zero() => {
+                self.stream.set_read_timeout(Some(left))?;
+                self.stream.read(buf)
+            }
+            _ => Err(io::ErrorKind::TimedOut.into()),
+        }
+    }
+}
+
+fn host_info_json(info: &HostInfo) -> String {
+    let mut json = String::from("{\"NAME\":");
+    write_string(&mut json, &info.name);
+    let _ = write!(json, ",\"OSC_PORT\":{},\"OSC_TRANSPORT\":", info.osc_port);
+    write_string(&mut json, &info.osc_transport);
+    json.push_str(
+        ",\"EXTENSIONS\":{\"ACCESS\":true,\"VALUE\":true,\"DESCRIPTION\":true,\"CONTENTS\":true,\"TYPE\":true}}",
+    );
+    json
+}
@@ -0,0 +1,186 @@
+//! Measuring the round-trip time to an OSC peer over UDP, e.g. to validate
+//! show networks.
+//!
+//! [`ping`] sends messages with a nonce, a unique `h` argument, to an echo
+//! address of the peer, which is expected to send them back to their
+//! sender unchanged. Replies are matched by their nonce, so the address of
+//! the reply doesn't matter and late replies of earlier pings are ignored.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rosc::ping::{self, PingOptions};
+//!
+//! let stats = ping::ping("192.168.1.20:9000", &PingOptions::default()).unwrap();
+//! println!(
+//!     "{}/{} replies, min/avg/max/jitter {:?}/{:?}/{:?}/{:?}",
+//!     stats.received, stats.sent, stats.min, stats.avg, stats.max, stats.jitter
+//! );
+//! ```
+
+use crate::decoder::{self, MTU};
+use crate::encoder;
+use crate::errors::OscError;
+use crate::types::{OscMessage, OscPacket, OscType, Result};
+use crate::udp::{resolve, unspecified_addr};
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Options that control [`ping`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PingOptions {
+    /// The address the peer echoes messages on. Defaults to `/ping`.
+    pub addr: String,
+    /// Number of pings to send. Defaults to 10.
+    pub count: usize,
+    /// Time between sending two pings. Defaults to 100 milliseconds.
+    pub interval: Duration,
+    /// How long to wait for a reply before the ping counts as lost.
+    /// Defaults to 1 second.
+    pub timeout: Duration,
+}
+
+impl Default for PingOptions {
+    fn default() -> PingOptions {
+        PingOptions {
+            addr: "/ping".to_string(),
+            count: 10,
+            interval: Duration::from_millis(100),
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Round-trip time statistics of the replies received by [`ping`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PingStats {
+    /// Number of pings sent.
+    pub sent: usize,
+    /// Number of replies received in time.
+    pub received: usize,
+    /// Shortest round-trip time of a reply, zero if none was received.
+    pub min: Duration,
+    /// Mean round-trip time of the replies, zero if none was received.
+    pub avg: Duration,
+    /// Longest round-trip time of a reply, zero if none was received.
+    pub max: Duration,
+    /// Mean difference between the round-trip times of consecutive
+    /// replies.
+    pub jitter: Duration,
+    /// The round-trip times of all replies in the order they were sent.
+    pub rtts: Vec<Duration>,
+}
+
+impl PingStats {
+    /// Computes the statistics of `rtts` out of `sent` pings.
+    pub fn new(sent: usize, rtts: Vec<Duration>) -> PingStats {
+        let received = rtts.len();
+        if received == 0 {
+            return PingStats {
+                sent,
+                ..Default::default()
+            };
+        }
+        let diffs: Duration = rtts.windows(2).map(|pair| pair[0].abs_diff(pair[1])).sum();
+        PingStats {
+            sent,
+            received,
+            min: *rtts.iter().min().unwrap(),
+            avg: rtts.iter().sum::<Duration>() / received as u32,
+            max: *rtts.iter().max().unwrap(),
+            jitter: if received > 1 {
+                diffs / (received - 1) as u32
+            } else {
+                Duration::ZERO
+            },
+            rtts,
+        }
+    }
+
+    /// Returns the fraction of pings that weren't answered in time, from
+    /// 0.0 to 1.0.
+    pub fn loss(&self) -> f64 {
+        if self.sent == 0 {
+            return 0.0;
+        }
+        (self.sent - self.received) as f64 / self.sent as f64
+    }
+}
+
+/// Pings the peer at `target` as described by `options` and returns the
+/// statistics. Returns an `OscError::SocketError` if the target can't be
+/// resolved or the socket can't be bound, or an `OscError::WriteError` or
+/// `OscError::ReadError` if sending or receiving fails.
+pub fn ping<A: ToSocketAddrs>(target: A, options: &PingOptions) -> Result<PingStats> {
+    let target = resolve(target)?;
+    let socket = UdpSocket::bind(unspecified_addr(target)).map_err(OscError::SocketError)?;
+    // makes replies to earlier runs unlikely to match
+    let first_nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_nanos() as i64)
+        .unwrap_or(0);
+    let mut buf = vec![0u8; MTU];
+    let mut rtts = vec![];
+    for i in 0..options.count {
+        let nonce = first_nonce.wrapping_add(i as i64);
+        let packet = OscPacket::Message(OscMessage {
+            addr: options.addr.clone(),
+            args: vec![OscType::Long(nonce)],
+        });
+        let sent = Instant::now();
+        socket
+            .send_to(&encoder::encode(&packet)?, target)
+            .map_err(OscError::WriteError)?;
+        let is_reply = |msg: &OscMessage| msg.args.first() == Some(&OscType::Long(nonce));
+        if await_reply(&socket, &mut buf, sent, options.timeout, is_reply)?.is_some() {
+            rtts.push(sent.elapsed());
+        }
+        if i + 1 < options.count {
+            if let Some(wait) = options.interval.checked_sub(sent.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+    }
+    Ok(PingStats::new(options.count, rtts))
+}
+
+/// Waits for a message on `socket` for which `is_reply` returns true and
+/// returns it, or `None` if it didn't arrive within `timeout` after `sent`.
+/// Other packets are ignored.
+pub(crate) fn await_reply<F>(
+    socket: &UdpSocket,
+    buf: &mut [u8],
+    sent: Instant,
+    timeout: Duration,
+    mut is_reply: F,
+) -> Result<Option<OscMessage>>
+where
+    F: FnMut(&OscMessage) -> bool,
+{
+    loop {
+        let remaining = match timeout.checked_sub(sent.elapsed()) {
+            Some(remaining) if remaining > Duration::ZERO => remaining,
+            _ => return Ok(None),
+        };
+        socket
+            .set_read_timeout(Some(remaining))
+            .map_err(OscError::SocketError)?;
+        let len = match socket.recv_from(buf) {
+            Ok((len, _)) => len,
+            Err(ref err)
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut =>
+            {
+                return Ok(None)
+            }
+            Err(err) => return Err(OscError::ReadError(err)),
+        };
+        if let Ok(packet) = decoder::decode(&buf[..len]) {
+            if let Some((msg, _)) = packet.iter_messages().find(|(msg, _)| is_reply(msg)) {
+                return Ok(Some(msg.clone()));
+            }
+        }
+    }
+}
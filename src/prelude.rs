@@ -0,0 +1,34 @@
+//! Re-exports the types and functions most applications need, so that a
+//! single glob import suffices.
+//!
+//! # Example
+//!
+//! ```
+//! #[macro_use]
+//! extern crate rosc;
+//!
+//! use rosc::prelude::*;
+//!
+//! # fn main() {
+//! let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+//! let mut client = OscUdpClient::connect(server.local_addr().unwrap()).unwrap();
+//! client.send(&osc!("/synth/freq", 440.0f32)).unwrap();
+//!
+//! let (packet, _) = server.recv().unwrap();
+//! assert_eq!(osc!("/synth/freq", 440.0f32), packet);
+//! assert_eq!(packet, decode(&encode(&packet).unwrap()).unwrap());
+//! # }
+//! ```
+
+pub use crate::decoder::decode;
+pub use crate::dispatcher::Dispatcher;
+pub use crate::encoder::encode;
+pub use crate::errors::OscError;
+#[cfg(feature = "std")]
+pub use crate::tcp::{OscTcpClient, OscTcpServer};
+pub use crate::types::{
+    FromOscArgs, IntoOscArgs, OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket,
+    OscTime, OscType,
+};
+#[cfg(feature = "std")]
+pub use crate::udp::{OscUdpClient, OscUdpServer};
@@ -0,0 +1,190 @@
+//! Limiting the rate at which packets are sent, to keep a misbehaving
+//! program from flooding its receivers.
+//!
+//! [`RateLimit`] describes the limits, which can be set on the UDP and TCP
+//! clients with their `set_rate_limit` method. A [`RateLimiter`] enforces
+//! them for other senders.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rosc::ratelimit::RateLimit;
+//! use rosc::udp::OscUdpClient;
+//! use rosc::{OscMessage, OscPacket};
+//!
+//! let mut client = OscUdpClient::connect("192.168.1.20:9000").unwrap();
+//! client.set_rate_limit(RateLimit {
+//!     packets_per_sec: Some(100.0),
+//!     packet_burst: 10,
+//!     ..Default::default()
+//! });
+//! // sends 10 packets at once, then one every 10 ms
+//! for i in 0..1000 {
+//!     client
+//!         .send(&OscPacket::Message(OscMessage {
+//!             addr: "/fader/1".to_string(),
+//!             args: vec![(i as f32 / 1000.0).into()],
+//!         }))
+//!         .unwrap();
+//! }
+//! ```
+
+use crate::decoder::MTU;
+use crate::errors::OscError;
+use crate::types::Result;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What a sender does with a packet exceeding its rate limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RateLimitAction {
+    /// Waits until the packet can be sent.
+    #[default]
+    Wait,
+    /// Doesn't send the packet and returns an `OscError::RateLimited`.
+    Reject,
+}
+
+/// Limits the average number of packets and bytes sent per second.
+///
+/// The limits are enforced by token buckets: after having been idle a
+/// sender may send `packet_burst` packets and `byte_burst` bytes at once,
+/// before it is slowed down to the average rates. A packet larger than the
+/// byte burst is sent when the bucket is full and delays the following
+/// packets accordingly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Maximum average number of packets per second, `None` for no limit.
+    /// Defaults to `None`.
+    pub packets_per_sec: Option<f64>,
+    /// Maximum average number of bytes per second, `None` for no limit.
+    /// Defaults to `None`.
+    pub bytes_per_sec: Option<f64>,
+    /// Number of packets that may be sent at once. Defaults to 1.
+    pub packet_burst: u32,
+    /// Number of bytes that may be sent at once. Defaults to
+    /// [`MTU`](crate::decoder::MTU).
+    pub byte_burst: usize,
+    /// What to do with packets exceeding the limit. Defaults to
+    /// [`RateLimitAction::Wait`].
+    pub action: RateLimitAction,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        RateLimit {
+            packets_per_sec: None,
+            bytes_per_sec: None,
+            packet_burst: 1,
+            byte_burst: MTU,
+            action: RateLimitAction::Wait,
+        }
+    }
+}
+
+/// Enforces a [`RateLimit`] for a sender.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    packets: Option<Bucket>,
+    bytes: Option<Bucket>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter enforcing `limit`, starting with full buckets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rate isn't positive and finite.
+    pub fn new(limit: RateLimit) -> RateLimiter {
+        let now = Instant::now();
+        RateLimiter {
+            limit,
+            packets: limit
+                .packets_per_sec
+                .map(|rate| Bucket::new(rate, limit.packet_burst.max(1).into(), now)),
+            bytes: limit
+                .bytes_per_sec
+                .map(|rate| Bucket::new(rate, limit.byte_burst as f64, now)),
+        }
+    }
+
+    /// Returns the enforced limit.
+    pub fn limit(&self) -> &RateLimit {
+        &self.limit
+    }
+
+    /// Accounts for sending a packet of `len` bytes. If it exceeds the limit
+    /// this waits until it can be sent, or returns an
+    /// `OscError::RateLimited`, depending on the limit's action.
+    pub fn acquire(&mut self, len: usize) -> Result<()> {
+        let delay = self.delay(1.0, len as f64, Instant::now());
+        if delay > Duration::ZERO {
+            if self.limit.action == RateLimitAction::Reject {
+                return Err(OscError::RateLimited);
+            }
+            thread::sleep(delay);
+        }
+        let now = Instant::now();
+        if let Some(ref mut bucket) = self.packets {
+            bucket.take(1.0, now);
+        }
+        if let Some(ref mut bucket) = self.bytes {
+            bucket.take(len as f64, now);
+        }
+        Ok(())
+    }
+
+    /// Returns how long to wait until `packets` and `bytes` may be sent.
+    fn delay(&mut self, packets: f64, bytes: f64, now: Instant) -> Duration {
+        let packets = self.packets.as_mut().map(|b| b.delay(packets, now));
+        let bytes = self.bytes.as_mut().map(|b| b.delay(bytes, now));
+        packets.max(bytes).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Bucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(rate: f64, capacity: f64, now: Instant) -> Bucket {
+        assert!(
+            rate > 0.0 && rate.is_finite(),
+            "rate must be positive and finite"
+        );
+        Bucket {
+            rate,
+            capacity,
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Returns how long to wait until `cost` may be taken. Costs exceeding
+    /// the capacity only need a full bucket.
+    fn delay(&mut self, cost: f64, now: Instant) -> Duration {
+        self.refill(now);
+        let missing = cost.min(self.capacity) - self.tokens;
+        if missing > 0.0 {
+            Duration::from_secs_f64(missing / self.rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    fn take(&mut self, cost: f64, now: Instant) {
+        self.refill(now);
+        self.tokens -= cost;
+    }
+}
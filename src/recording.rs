@@ -0,0 +1,277 @@
+//! Recording received packets to a log file and replaying them with their
+//! original timing.
+//!
+//! A log starts with the 8 byte magic [`MAGIC`], followed by one entry per
+//! packet:
+//!
+//! | Field     | Size    | Content                                              |
+//! |-----------|---------|------------------------------------------------------|
+//! | length    | 4 bytes | size of the packet, big-endian                       |
+//! | timestamp | 8 bytes | arrival time in microseconds since the unix epoch, big-endian |
+//! | packet    | length  | the encoded packet                                   |
+
+use crate::decoder::{self, MTU};
+use crate::dispatcher::Dispatcher;
+use crate::encoder;
+use crate::errors::OscError;
+use crate::types::{OscPacket, Result};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The first bytes of every log file.
+pub const MAGIC: &[u8; 8] = b"#rosclog";
+
+/// Size of an entry without the packet.
+pub(crate) const ENTRY_HEADER_SIZE: usize = 12;
+
+/// Appends packets with their arrival time to a log.
+///
+/// Every entry is written with a single write, so wrap the writer in a
+/// `BufWriter` to record many packets per second. Unbuffered, the log
+/// holds every packet recorded before a crash.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::recording::Recorder;
+/// use rosc::udp::OscUdpServer;
+///
+/// let mut recorder = Recorder::create("show.rosclog").unwrap();
+/// let mut server = OscUdpServer::bind("0.0.0.0:9000").unwrap();
+/// server
+///     .run(|packet, _| recorder.record(&packet).unwrap())
+///     .unwrap();
+/// ```
+pub struct Recorder<W: Write = File> {
+    writer: W,
+    entry: Vec<u8>,
+}
+
+impl Recorder {
+    /// Creates the log file at `path`, replacing an existing file. Returns
+    /// an `OscError::WriteError` if the file can't be created.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Recorder> {
+        let file = File::create(path).map_err(OscError::WriteError)?;
+        Recorder::new(file)
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Writes the log header to `writer` and records to it. Returns an
+    /// `OscError::WriteError` if writing fails.
+    pub fn new(mut writer: W) -> Result<Recorder<W>> {
+        writer.write_all(MAGIC).map_err(OscError::WriteError)?;
+        Ok(Recorder {
+            writer,
+            entry: vec![],
+        })
+    }
+
+    /// Encodes `packet` and records it with the current time as arrival
+    /// time.
+    pub fn record(&mut self, packet: &OscPacket) -> Result<()> {
+        self.entry.clear();
+        self.entry.extend(&[0; ENTRY_HEADER_SIZE]);
+        encoder::encode_into(packet, &mut self.entry)?;
+        self.write_entry(SystemTime::now())
+    }
+
+    /// Records an already encoded packet with the given arrival `time`.
+    pub fn record_bytes(&mut self, packet: &[u8], time: SystemTime) -> Result<()> {
+        self.entry.clear();
+        self.entry.extend(&[0; ENTRY_HEADER_SIZE]);
+        self.entry.extend(packet);
+        self.write_entry(time)
+    }
+
+    fn write_entry(&mut self, time: SystemTime) -> Result<()> {
+        let len = self.entry.len() - ENTRY_HEADER_SIZE;
+        if len > u32::MAX as usize {
+            return Err(OscError::BadPacket("Packet is too large to be recorded."));
+        }
+        let micros = time
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_micros() as u64)
+            .unwrap_or(0);
+        self.entry[..4].copy_from_slice(&(len as u32).to_be_bytes());
+        self.entry[4..ENTRY_HEADER_SIZE].copy_from_slice(&micros.to_be_bytes());
+        self.writer
+            .write_all(&self.entry)
+            .map_err(OscError::WriteError)
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().map_err(OscError::WriteError)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write + fmt::Debug> fmt::Debug for Recorder<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Recorder")
+            .field("writer", &self.writer)
+            .finish()
+    }
+}
+
+/// A packet read from a log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// The time the packet arrived.
+    pub time: SystemTime,
+    /// The encoded packet.
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// Decodes the recorded packet.
+    pub fn packet(&self) -> Result<OscPacket> {
+        decoder::decode(&self.data)
+    }
+}
+
+/// Reads a log written by a [`Recorder`] and replays its packets,
+/// waiting the recorded intervals between them.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::recording::Player;
+/// use rosc::udp::OscUdpClient;
+///
+/// let mut client = OscUdpClient::connect("127.0.0.1:9000").unwrap();
+/// let mut player = Player::open("show.rosclog").unwrap();
+/// player
+///     .set_speed(2.0)
+///     .play(|packet| client.send(&packet).unwrap())
+///     .unwrap();
+/// ```
+pub struct Player<R: Read = BufReader<File>> {
+    reader: R,
+    speed: f64,
+}
+
+impl Player {
+    /// Opens the log file at `path`. Returns an `OscError::ReadError` if the
+    /// file can't be read, or an `OscError::BadPacket` if it isn't a log.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Player> {
+        let file = File::open(path).map_err(OscError::ReadError)?;
+        Player::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read> Player<R> {
+    /// Reads the log header from `reader` and replays the log at the
+    /// original speed.
+    pub fn new(mut reader: R) -> Result<Player<R>> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).map_err(OscError::ReadError)?;
+        if &magic != MAGIC {
+            return Err(OscError::BadPacket("Not a rosc log."));
+        }
+        Ok(Player { reader, speed: 1.0 })
+    }
+
+    /// Sets the speed multiplier, e.g. `2.0` replays the log twice as fast
+    /// and `0.5` at half the speed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `speed` isn't positive and finite.
+    pub fn set_speed(&mut self, speed: f64) -> &mut Self {
+        assert!(
+            speed > 0.0 && speed.is_finite(),
+            "speed must be positive and finite"
+        );
+        self.speed = speed;
+        self
+    }
+
+    /// Returns the speed multiplier.
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Reads the next entry without waiting, or returns `None` at the end
+    /// of the log. Returns an `OscError::ReadError` if reading fails or the
+    /// log ends within an entry.
+    pub fn next_entry(&mut self) -> Result<Option<Entry>> {
+        let mut header = [0u8; ENTRY_HEADER_SIZE];
+        let mut filled = 0;
+        while filled < header.len() {
+            match self.reader.read(&mut header[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(OscError::ReadError(io::ErrorKind::UnexpectedEof.into())),
+                Ok(len) => filled += len,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) => return Err(OscError::ReadError(err)),
+            }
+        }
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let mut micros = [0u8; 8];
+        micros.copy_from_slice(&header[4..]);
+        let time = UNIX_EPOCH + Duration::from_micros(u64::from_be_bytes(micros));
+
+        // don't trust the size prefix for preallocation
+        let mut data = Vec::with_capacity(len.min(MTU));
+        let cnt = self
+            .reader
+            .by_ref()
+            .take(len as u64)
+            .read_to_end(&mut data)
+            .map_err(OscError::ReadError)?;
+        if cnt < len {
+            return Err(OscError::ReadError(io::ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(Some(Entry { time, data }))
+    }
+
+    /// Replays the remaining packets, passing each one to `handler` after
+    /// waiting the recorded interval since the previous one divided by the
+    /// speed. Returns when the end of the log is reached, or the first
+    /// reading or decoding error.
+    pub fn play<F>(&mut self, mut handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket),
+    {
+        let mut start: Option<(SystemTime, Instant)> = None;
+        while let Some(entry) = self.next_entry()? {
+            let (first, started) = *start.get_or_insert((entry.time, Instant::now()));
+            // entries recorded while the clock was set back are replayed immediately
+            let offset = entry.time.duration_since(first).unwrap_or_default();
+            let due = started + offset.div_f64(self.speed);
+            let now = Instant::now();
+            if due > now {
+                thread::sleep(due - now);
+            }
+            handler(entry.packet()?);
+        }
+        Ok(())
+    }
+
+    /// Replays the remaining packets like [`play`](Player::play) and
+    /// dispatches them to the matching handlers of `dispatcher`.
+    pub fn play_dispatcher(&mut self, dispatcher: &mut Dispatcher) -> Result<()> {
+        self.play(|packet| {
+            dispatcher.dispatch(&packet);
+        })
+    }
+}
+
+impl<R: Read + fmt::Debug> fmt::Debug for Player<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Player")
+            .field("reader", &self.reader)
+            .field("speed", &self.speed)
+            .finish()
+    }
+}
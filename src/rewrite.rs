@@ -0,0 +1,245 @@
+//! Rewriting of message addresses, e.g. so that a hardware controller with
+//! a fixed layout can drive the namespace of arbitrary software.
+//!
+//! A [`Rewriter`] holds an ordered list of rules, of which the first one
+//! that matches an address rewrites it:
+//!
+//! - A prefix rule replaces a leading part of the address, e.g. the prefix
+//!   `/1` of `/1/fader3` by `/mixer` to get `/mixer/fader3`. It only
+//!   matches whole address parts, so `/1` doesn't match `/10/fader3`.
+//! - A template rule matches the whole address against a template whose
+//!   captures like `{n}` match one or more characters within an address
+//!   part, and substitutes them into the target template. For example the
+//!   rule `/1/fader{n}` → `/mixer/channel/{n}/gain` rewrites `/1/fader3` to
+//!   `/mixer/channel/3/gain`.
+//!
+//! Install a rewriter on a [`Dispatcher`](crate::dispatcher::Dispatcher)
+//! with [`set_rewriter`](crate::dispatcher::Dispatcher::set_rewriter) to
+//! rewrite messages before they are dispatched.
+//!
+//! # Example
+//!
+//! ```
+//! use rosc::rewrite::Rewriter;
+//!
+//! let mut rewriter = Rewriter::new();
+//! rewriter
+//!     .template("/1/fader{n}", "/mixer/channel/{n}/gain")
+//!     .unwrap()
+//!     .prefix("/1", "/controller")
+//!     .unwrap();
+//!
+//! assert_eq!(Some("/mixer/channel/3/gain".to_string()), rewriter.rewrite("/1/fader3"));
+//! assert_eq!(Some("/controller/push1".to_string()), rewriter.rewrite("/1/push1"));
+//! assert_eq!(None, rewriter.rewrite("/2/fader3"));
+//! ```
+
+use crate::address;
+use crate::errors::OscError;
+use crate::types::{OscPacket, Result};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Rewrites addresses by the first of its rules that matches.
+#[derive(Clone, Debug, Default)]
+pub struct Rewriter {
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug)]
+enum Rule {
+    Prefix {
+        from: String,
+        to: String,
+    },
+    Template {
+        from: Vec<Segment>,
+        to: Vec<Segment>,
+    },
+}
+
+/// A piece of a template, captures are referred to by their index in the
+/// source template.
+#[derive(Clone, Debug)]
+enum Segment {
+    Literal(String),
+    Capture(usize),
+}
+
+impl Rewriter {
+    /// Creates a rewriter without any rules.
+    pub fn new() -> Rewriter {
+        Rewriter::default()
+    }
+
+    /// Adds a rule replacing the leading address parts `from` by `to`.
+    /// Returns an `OscError::BadAddress` if either is not a valid method
+    /// address.
+    pub fn prefix(&mut self, from: &str, to: &str) -> Result<&mut Rewriter> {
+        address::validate_method(from)?;
+        address::validate_method(to)?;
+        self.rules.push(Rule::Prefix {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+        Ok(self)
+    }
+
+    /// Adds a rule rewriting the addresses matching the template `from` to
+    /// the template `to`. Captures are written as `{name}`, where the name
+    /// consists of ASCII letters, digits and `_`.
+    ///
+    /// Returns an `OscError::BadAddress` if a template is malformed, `from`
+    /// defines a capture twice or `to` uses a capture that `from` doesn't
+    /// define.
+    pub fn template(&mut self, from: &str, to: &str) -> Result<&mut Rewriter> {
+        let mut names = vec![];
+        let from = parse_template(from, |name| {
+            if names.contains(&name) {
+                return Err(OscError::BadAddress("Template defines a capture twice."));
+            }
+            names.push(name);
+            Ok(names.len() - 1)
+        })?;
+        let to = parse_template(to, |name| {
+            names
+                .iter()
+                .position(|defined| *defined == name)
+                .ok_or(OscError::BadAddress("Template uses an undefined capture."))
+        })?;
+        self.rules.push(Rule::Template { from, to });
+        Ok(self)
+    }
+
+    /// Returns `addr` rewritten by the first matching rule, or `None` if no
+    /// rule matches.
+    pub fn rewrite(&self, addr: &str) -> Option<String> {
+        self.rules.iter().find_map(|rule| rule.apply(addr))
+    }
+
+    /// Rewrites the addresses of all messages in `packet`, descending into
+    /// bundles, and returns how many were rewritten.
+    pub fn rewrite_packet(&self, packet: &mut OscPacket) -> usize {
+        match *packet {
+            OscPacket::Message(ref mut msg) => match self.rewrite(&msg.addr) {
+                Some(addr) => {
+                    msg.addr = addr;
+                    1
+                }
+                None => 0,
+            },
+            OscPacket::Bundle(ref mut bundle) => bundle
+                .content
+                .iter_mut()
+                .map(|packet| self.rewrite_packet(packet))
+                .sum(),
+            OscPacket::Raw(_) => 0,
+        }
+    }
+
+    /// Returns whether the rewriter has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl Rule {
+    fn apply(&self, addr: &str) -> Option<String> {
+        match *self {
+            Rule::Prefix { ref from, ref to } => {
+                let rest = addr.strip_prefix(from.as_str())?;
+                if !rest.is_empty() && !rest.starts_with('/') {
+                    return None;
+                }
+                Some(format!("{}{}", to, rest))
+            }
+            Rule::Template { ref from, ref to } => {
+                let mut captures = vec![];
+                if !match_segments(from, addr, &mut captures) {
+                    return None;
+                }
+                let mut rewritten = String::new();
+                for segment in to {
+                    match *segment {
+                        Segment::Literal(ref literal) => rewritten.push_str(literal),
+                        Segment::Capture(index) => rewritten.push_str(captures[index]),
+                    }
+                }
+                Some(rewritten)
+            }
+        }
+    }
+}
+
+/// Splits `template` into segments, `capture` returns the index of a
+/// capture name.
+fn parse_template<'a, F>(template: &'a str, mut capture: F) -> Result<Vec<Segment>>
+where
+    F: FnMut(&'a str) -> Result<usize>,
+{
+    address::validate(template)?;
+    let mut segments = vec![];
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or(OscError::BadAddress("Template contains an unclosed '{'."))?;
+        let name = &rest[start + 1..end];
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(OscError::BadAddress(
+                "Template contains a malformed capture.",
+            ));
+        }
+        if start > 0 {
+            segments.push(Segment::Literal(rest[..start].to_string()));
+        }
+        segments.push(Segment::Capture(capture(name)?));
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest.to_string()));
+    }
+    let literals = segments.iter().filter_map(|segment| match *segment {
+        Segment::Literal(ref literal) => Some(literal),
+        Segment::Capture(_) => None,
+    });
+    for literal in literals {
+        if address::is_pattern(literal) {
+            return Err(OscError::BadAddress(
+                "Template must not contain pattern characters.",
+            ));
+        }
+    }
+    Ok(segments)
+}
+
+/// Matches `addr` against `segments`, collecting the captured strings. A
+/// capture matches as few characters as possible, at least one and no `/`.
+fn match_segments<'a>(segments: &[Segment], addr: &'a str, captures: &mut Vec<&'a str>) -> bool {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => return addr.is_empty(),
+    };
+    match *segment {
+        Segment::Literal(ref literal) => match addr.strip_prefix(literal.as_str()) {
+            Some(addr) => match_segments(rest, addr, captures),
+            None => false,
+        },
+        Segment::Capture(index) => {
+            let part_len = addr.find('/').unwrap_or(addr.len());
+            let ends = addr[..part_len]
+                .char_indices()
+                .map(|(i, c)| i + c.len_utf8());
+            for end in ends {
+                // captures are numbered in order of appearance
+                captures.truncate(index);
+                captures.push(&addr[..end]);
+                if match_segments(rest, &addr[end..], captures) {
+                    return true;
+                }
+            }
+            false
+        }
+    }
+}
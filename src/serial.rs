@@ -0,0 +1,110 @@
+//! Sending and receiving OSC packets over serial ports.
+//!
+//! Microcontrollers, e.g. those running the CNMAT OSC library, send SLIP
+//! framed packets over (USB) serial connections, see the
+//! [`slip`](crate::slip) module.
+
+use crate::decoder::DecoderOptions;
+use crate::errors::OscError;
+use crate::slip::{self, SlipDecoder};
+use crate::types::{OscPacket, Result};
+use serialport::SerialPort;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+/// Sends and receives SLIP framed OSC packets over a serial port, or any
+/// other byte stream.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::serial::OscSerialPort;
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let mut port = OscSerialPort::open("/dev/ttyACM0", 115_200).unwrap();
+/// port.send(&OscPacket::Message(OscMessage::from("/led/on")))
+///     .unwrap();
+/// println!("received {:?}", port.recv().unwrap());
+/// ```
+pub struct OscSerialPort<S = Box<dyn SerialPort>> {
+    port: S,
+    decoder: SlipDecoder,
+    buf: Vec<u8>,
+}
+
+impl OscSerialPort {
+    /// Opens the serial port at `path`, e.g. `/dev/ttyACM0` or `COM3`.
+    /// Reading blocks for up to a second before failing with an
+    /// `OscError::ReadError`. Returns an `OscError::SocketError` if the port
+    /// can't be opened.
+    pub fn open(path: &str, baud_rate: u32) -> Result<OscSerialPort> {
+        let port = serialport::new(path, baud_rate)
+            .timeout(Duration::from_secs(1))
+            .open()
+            .map_err(|err| OscError::SocketError(err.into()))?;
+        Ok(OscSerialPort::from_port(port))
+    }
+}
+
+impl<S: Read + Write> OscSerialPort<S> {
+    /// Sends and receives packets over an already opened `port`.
+    pub fn from_port(port: S) -> OscSerialPort<S> {
+        OscSerialPort::with_options(port, DecoderOptions::default())
+    }
+
+    /// Like [`from_port`](OscSerialPort::from_port), but decodes received
+    /// packets using the given `options`.
+    pub fn with_options(port: S, options: DecoderOptions) -> OscSerialPort<S> {
+        OscSerialPort {
+            port,
+            decoder: SlipDecoder::with_options(options),
+            buf: vec![],
+        }
+    }
+
+    /// Encodes `packet` and sends it as a SLIP frame. Returns an
+    /// `OscError::WriteError` if writing fails.
+    pub fn send(&mut self, packet: &OscPacket) -> Result<()> {
+        let frame = slip::encode_packet(packet)?;
+        self.port
+            .write_all(&frame)
+            .and_then(|()| self.port.flush())
+            .map_err(OscError::WriteError)
+    }
+
+    /// Waits for the next packet. Returns an `OscError::ReadError` if
+    /// reading fails or times out, or the decoding error if the packet is
+    /// malformed. Receiving can continue after an error.
+    pub fn recv(&mut self) -> Result<OscPacket> {
+        loop {
+            if let Some(packet) = self.decoder.decode()? {
+                return Ok(packet);
+            }
+            self.buf.resize(4096, 0);
+            let len = self.port.read(&mut self.buf).map_err(OscError::ReadError)?;
+            if len == 0 {
+                return Err(OscError::ReadError(io::ErrorKind::UnexpectedEof.into()));
+            }
+            self.decoder.feed(&self.buf[..len]);
+        }
+    }
+
+    /// Returns the underlying port.
+    pub fn port(&self) -> &S {
+        &self.port
+    }
+
+    /// Returns the underlying port mutably, e.g. to change its settings.
+    pub fn port_mut(&mut self) -> &mut S {
+        &mut self.port
+    }
+}
+
+impl<S> fmt::Debug for OscSerialPort<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OscSerialPort")
+            .field("decoder", &self.decoder)
+            .finish()
+    }
+}
@@ -0,0 +1,196 @@
+use crate::errors::OscError;
+use crate::types::{OscPacket, Result};
+use crate::udp::time_left;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Maximum number of events the threads of a running server queue up
+/// before a connection thread waits for them to be handled.
+const MAX_QUEUED_EVENTS: usize = 64;
+
+/// A callback for packets that couldn't be decoded.
+pub(crate) type ErrorHook = Box<dyn FnMut(&OscError, SocketAddr) + Send>;
+
+/// What reading a connection of a stream server yields.
+pub(crate) enum Received {
+    Packet(OscPacket),
+    /// A malformed packet, the connection stays open.
+    BadPacket(OscError),
+    /// The connection was closed by the peer, or because of the error.
+    Closed(Option<OscError>),
+}
+
+/// An accepted connection, which knows how packets are framed on it.
+pub(crate) trait Connection: Send + 'static {
+    /// Waits for the next packet.
+    fn read_packet(&mut self) -> Received;
+}
+
+/// What the threads of a running server report to it.
+enum Event {
+    Packet(OscPacket, SocketAddr),
+    BadPacket(OscError, SocketAddr),
+    Failed(OscError),
+}
+
+/// The threads of a stream server, which keep accepting and reading
+/// connections between the calls of its `run_until` method. They stop
+/// once the server is dropped.
+#[derive(Default)]
+pub(crate) struct Runner {
+    events: Option<mpsc::Receiver<Event>>,
+    accepting: Option<Accepting>,
+}
+
+/// The thread accepting connections, and how to stop it.
+struct Accepting {
+    stop: Arc<AtomicBool>,
+    addr: SocketAddr,
+}
+
+impl Runner {
+    /// Accepts connections on `listener` and calls `handler` with each
+    /// packet received from them and their sender. Every connection is set
+    /// up by `open`, e.g. by a handshake, and read on its own thread until
+    /// it is closed. Connections that fail to open and malformed packets
+    /// are passed to `on_error`.
+    ///
+    /// Returns once `deadline` has passed, leaving the threads running for
+    /// the next call, or if accepting a connection fails.
+    pub(crate) fn run<C, O, F>(
+        &mut self,
+        listener: &TcpListener,
+        open: O,
+        on_error: &mut Option<ErrorHook>,
+        deadline: Option<Instant>,
+        mut handler: F,
+    ) -> Result<()>
+    where
+        C: Connection,
+        O: Fn(TcpStream) -> Result<C> + Send + Sync + 'static,
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        let received = match self.events.take() {
+            Some(received) => received,
+            None => {
+                let addr = listener.local_addr().map_err(OscError::SocketError)?;
+                let listener = listener.try_clone().map_err(OscError::SocketError)?;
+                let stop = Arc::new(AtomicBool::new(false));
+                let (events, received) = mpsc::sync_channel(MAX_QUEUED_EVENTS);
+                let stopped = stop.clone();
+                thread::spawn(move || {
+                    accept_connections(listener, Arc::new(open), stopped, events)
+                });
+                self.accepting = Some(Accepting { stop, addr });
+                received
+            }
+        };
+        loop {
+            let event = match deadline.map(time_left) {
+                None => received.recv().ok(),
+                Some(Ok(timeout)) => match received.recv_timeout(timeout) {
+                    Err(mpsc::RecvTimeoutError::Timeout) => break,
+                    result => result.ok(),
+                },
+                Some(Err(_)) => break,
+            };
+            match event.expect("the accepting thread stopped without reporting an error") {
+                Event::Packet(packet, from) => handler(packet, from),
+                Event::BadPacket(err, from) => {
+                    if let Some(ref mut hook) = *on_error {
+                        hook(&err, from);
+                    }
+                }
+                // the next call starts accepting again
+                Event::Failed(err) => {
+                    self.accepting = None;
+                    return Err(err);
+                }
+            }
+        }
+        self.events = Some(received);
+        Ok(())
+    }
+}
+
+impl Drop for Runner {
+    fn drop(&mut self) {
+        if let Some(accepting) = self.accepting.take() {
+            accepting.stop.store(true, Ordering::SeqCst);
+            // wakes up the accepting thread, which closes the listener
+            let _ = TcpStream::connect_timeout(&reachable(accepting.addr), Duration::from_secs(1));
+        }
+    }
+}
+
+/// Returns an address to connect to a listener bound to `addr`.
+fn reachable(mut addr: SocketAddr) -> SocketAddr {
+    if addr.ip().is_unspecified() {
+        addr.set_ip(match addr.ip() {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        });
+    }
+    addr
+}
+
+/// Accepts connections and spawns a thread reading each of them, until
+/// accepting fails or the server is dropped.
+fn accept_connections<C, O>(
+    listener: TcpListener,
+    open: Arc<O>,
+    stop: Arc<AtomicBool>,
+    events: mpsc::SyncSender<Event>,
+) where
+    C: Connection,
+    O: Fn(TcpStream) -> Result<C> + Send + Sync + 'static,
+{
+    loop {
+        let accepted = listener.accept();
+        if stop.load(Ordering::SeqCst) {
+            return;
+        }
+        let (stream, from) = match accepted {
+            Ok(connection) => connection,
+            Err(err) => {
+                let _ = events.send(Event::Failed(OscError::SocketError(err)));
+                return;
+            }
+        };
+        let open = open.clone();
+        let events = events.clone();
+        thread::spawn(move || match open(stream) {
+            Ok(connection) => read_connection(connection, from, events),
+            Err(err) => {
+                let _ = events.send(Event::BadPacket(err, from));
+            }
+        });
+    }
+}
+
+/// Reports the packets received on a connection until it is closed or the
+/// server is dropped.
+fn read_connection<C: Connection>(
+    mut connection: C,
+    from: SocketAddr,
+    events: mpsc::SyncSender<Event>,
+) {
+    loop {
+        let event = match connection.read_packet() {
+            Received::Packet(packet) => Event::Packet(packet, from),
+            Received::BadPacket(err) => Event::BadPacket(err, from),
+            Received::Closed(err) => {
+                if let Some(err) = err {
+                    let _ = events.send(Event::BadPacket(err, from));
+                }
+                return;
+            }
+        };
+        if events.send(event).is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use crate::borrowed::{OscPacketRef, OscTypeRef};
+use crate::types::{
+    OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType,
+};
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::{fmt, ops, str};
+
+use bytes::Bytes;
+
+/// An OSC packet whose strings and blobs share the buffer it was decoded
+/// from.
+///
+/// Shared packets are produced by
+/// [`decoder::decode_bytes`](crate::decoder::decode_bytes). Unlike a
+/// [`OscPacketRef`] they don't borrow the buffer but hold a reference count
+/// of it, so they can be stored or sent to other threads, and unlike an
+/// [`OscPacket`] their payloads aren't copied out of it. Use
+/// [`From`]/[`Into`] to turn them into an owned [`OscPacket`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscPacketShared {
+    Message(OscMessageShared),
+    Bundle(OscBundleShared),
+}
+
+/// An OSC message whose address and arguments share the buffer it was
+/// decoded from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscMessageShared {
+    pub addr: SharedStr,
+    pub args: Vec<OscTypeShared>,
+}
+
+/// An OSC bundle whose content shares the buffer it was decoded from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscBundleShared {
+    pub timetag: OscTime,
+    pub content: Vec<OscPacketShared>,
+}
+
+/// An OSC argument whose string or blob shares the buffer it was decoded
+/// from, see [`OscType`] for the owned counterpart.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscTypeShared {
+    Int(i32),
+    Float(f32),
+    String(SharedStr),
+    Symbol(SharedStr),
+    Blob(Bytes),
+    Time(OscTime),
+    Long(i64),
+    Double(f64),
+    Char(char),
+    Color(OscColor),
+    Midi(OscMidiMessage),
+    Bool(bool),
+    Array(Vec<OscTypeShared>),
+    Nil,
+    Inf,
+}
+
+/// A string stored in a [`Bytes`] buffer, which is guaranteed to be valid
+/// UTF-8.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SharedStr(Bytes);
+
+impl SharedStr {
+    /// Returns the string slice.
+    pub fn as_str(&self) -> &str {
+        // only created from valid strings
+        str::from_utf8(&self.0).expect("shared strings are valid UTF-8")
+    }
+
+    /// Returns the buffer holding the string.
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.0
+    }
+}
+
+impl ops::Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SharedStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<SharedStr> for str {
+    fn eq(&self, other: &SharedStr) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<SharedStr> for &str {
+    fn eq(&self, other: &SharedStr) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl From<&'static str> for SharedStr {
+    fn from(s: &'static str) -> SharedStr {
+        SharedStr(Bytes::from_static(s.as_bytes()))
+    }
+}
+
+impl fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl OscPacketShared {
+    /// Converts `packet`, which must have been decoded from `buf`, sharing
+    /// its strings and blobs with `buf`.
+    pub(crate) fn from_ref(buf: &Bytes, packet: OscPacketRef) -> OscPacketShared {
+        match packet {
+            OscPacketRef::Message(msg) => OscPacketShared::Message(OscMessageShared {
+                addr: shared_str(buf, msg.addr),
+                args: msg.args().map(|arg| shared_arg(buf, arg)).collect(),
+            }),
+            OscPacketRef::Bundle(bundle) => OscPacketShared::Bundle(OscBundleShared {
+                timetag: bundle.timetag,
+                content: bundle
+                    .content()
+                    .map(|packet| OscPacketShared::from_ref(buf, packet))
+                    .collect(),
+            }),
+        }
+    }
+}
+
+fn shared_str(buf: &Bytes, s: &str) -> SharedStr {
+    SharedStr(buf.slice_ref(s.as_bytes()))
+}
+
+fn shared_arg(buf: &Bytes, arg: OscTypeRef) -> OscTypeShared {
+    match arg {
+        OscTypeRef::Int(x) => OscTypeShared::Int(x),
+        OscTypeRef::Float(x) => OscTypeShared::Float(x),
+        OscTypeRef::String(x) => OscTypeShared::String(shared_str(buf, x)),
+        OscTypeRef::Symbol(x) => OscTypeShared::Symbol(shared_str(buf, x)),
+        OscTypeRef::Blob(x) => OscTypeShared::Blob(buf.slice_ref(x)),
+        OscTypeRef::Time(x) => OscTypeShared::Time(x),
+        OscTypeRef::Long(x) => OscTypeShared::Long(x),
+        OscTypeRef::Double(x) => OscTypeShared::Double(x),
+        OscTypeRef::Char(x) => OscTypeShared::Char(x),
+        OscTypeRef::Color(x) => OscTypeShared::Color(x),
+        OscTypeRef::Midi(x) => OscTypeShared::Midi(x),
+        OscTypeRef::Bool(x) => OscTypeShared::Bool(x),
+        OscTypeRef::Array(x) => {
+            OscTypeShared::Array(x.iter().map(|arg| shared_arg(buf, arg)).collect())
+        }
+        OscTypeRef::Nil => OscTypeShared::Nil,
+        OscTypeRef::Inf => OscTypeShared::Inf,
+    }
+}
+
+impl From<OscPacketShared> for OscPacket {
+    fn from(packet: OscPacketShared) -> OscPacket {
+        match packet {
+            OscPacketShared::Message(msg) => OscPacket::Message(msg.into()),
+            OscPacketShared::Bundle(bundle) => OscPacket::Bundle(bundle.into()),
+        }
+    }
+}
+
+impl From<OscMessageShared> for OscMessage {
+    fn from(msg: OscMessageShared) -> OscMessage {
+        OscMessage {
+            addr: msg.addr.to_string(),
+            args: msg.args.into_iter().map(OscType::from).collect(),
+        }
+    }
+}
+
+impl From<OscBundleShared> for OscBundle {
+    fn from(bundle: OscBundleShared) -> OscBundle {
+        OscBundle {
+            timetag: bundle.timetag,
+            content: bundle.content.into_iter().map(OscPacket::from).collect(),
+        }
+    }
+}
+
+impl From<OscTypeShared> for OscType {
+    fn from(arg: OscTypeShared) -> OscType {
+        match arg {
+            OscTypeShared::Int(x) => OscType::Int(x),
+            OscTypeShared::Float(x) => OscType::Float(x),
+            OscTypeShared::String(x) => OscType::String(x.to_string()),
+            OscTypeShared::Symbol(x) => OscType::Symbol(x.to_string()),
+            OscTypeShared::Blob(x) => OscType::Blob(x.to_vec()),
+            OscTypeShared::Time(x) => OscType::Time(x),
+            OscTypeShared::Long(x) => OscType::Long(x),
+            OscTypeShared::Double(x) => OscType::Double(x),
+            OscTypeShared::Char(x) => OscType::Char(x),
+            OscTypeShared::Color(x) => OscType::Color(x),
+            OscTypeShared::Midi(x) => OscType::Midi(x),
+            OscTypeShared::Bool(x) => OscType::Bool(x),
+            OscTypeShared::Array(x) => OscType::Array(OscArray {
+                content: x.into_iter().map(OscType::from).collect(),
+            }),
+            OscTypeShared::Nil => OscType::Nil,
+            OscTypeShared::Inf => OscType::Inf,
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! SLIP framing of OSC packets.
+//!
+//! OSC 1.1 stream transports and most serial devices delimit packets with
+//! [SLIP](https://tools.ietf.org/html/rfc1055): every packet is sent between
+//! two `END` bytes, and `END` and `ESC` bytes within the packet are escaped.
+//! Unlike the size prefix of OSC 1.0 streams, the framing allows a receiver
+//! to resynchronize after data was lost.
+
+use crate::decoder::{self, DecoderOptions};
+use crate::encoder;
+use crate::errors::{DecodeLimit, OscError};
+use crate::types::{OscPacket, Result};
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::mem;
+
+/// Delimits frames.
+pub const END: u8 = 0xc0;
+/// Starts an escape sequence.
+pub const ESC: u8 = 0xdb;
+/// Follows `ESC` to encode an `END` byte of the frame.
+pub const ESC_END: u8 = 0xdc;
+/// Follows `ESC` to encode an `ESC` byte of the frame.
+pub const ESC_ESC: u8 = 0xdd;
+
+/// Returns `data` escaped and enclosed in `END` bytes.
+///
+/// # Example
+///
+/// ```
+/// use rosc::slip;
+///
+/// assert_eq!(
+///     vec![0xc0, 1, 0xdb, 0xdc, 0xdb, 0xdd, 0xc0],
+///     slip::encode(&[1, 0xc0, 0xdb])
+/// );
+/// ```
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(data.len() + 2);
+    encode_into(data, &mut frame);
+    frame
+}
+
+/// Like [`encode`], but appends the frame to `buf`.
+pub fn encode_into(data: &[u8], buf: &mut Vec<u8>) {
+    buf.push(END);
+    for &byte in data {
+        match byte {
+            END => buf.extend_from_slice(&[ESC, ESC_END]),
+            ESC => buf.extend_from_slice(&[ESC, ESC_ESC]),
+            byte => buf.push(byte),
+        }
+    }
+    buf.push(END);
+}
+
+/// Encodes `packet` and returns it as a SLIP frame.
+///
+/// # Example
+///
+/// ```
+/// use rosc::slip::{self, SlipDecoder};
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::from("/ping"));
+/// let mut decoder = SlipDecoder::new();
+/// decoder.feed(&slip::encode_packet(&packet).unwrap());
+/// assert_eq!(Some(packet), decoder.decode().unwrap());
+/// ```
+pub fn encode_packet(packet: &OscPacket) -> Result<Vec<u8>> {
+    Ok(encode(&encoder::encode(packet)?))
+}
+
+/// Splits a SLIP encoded byte stream into frames and decodes them.
+///
+/// Data can be fed in chunks of any size, frames are returned once their
+/// closing `END` byte was received. Empty frames, like the ones between two
+/// consecutive `END` bytes, are skipped.
+///
+/// # Example
+///
+/// ```
+/// use rosc::slip::SlipDecoder;
+///
+/// let mut decoder = SlipDecoder::new();
+/// decoder.feed(&[0xc0, 1, 0xdb]);
+/// assert_eq!(None, decoder.next_frame().unwrap());
+/// decoder.feed(&[0xdc, 2, 0xc0, 0xc0, 3, 0xc0]);
+/// assert_eq!(Some(vec![1, 0xc0, 2]), decoder.next_frame().unwrap());
+/// assert_eq!(Some(vec![3]), decoder.next_frame().unwrap());
+/// assert_eq!(None, decoder.next_frame().unwrap());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SlipDecoder {
+    frames: VecDeque<Frame>,
+    // the unescaped data of the incomplete frame
+    frame: Vec<u8>,
+    escaped: bool,
+    oversized: bool,
+    options: DecoderOptions,
+}
+
+#[derive(Clone, Debug)]
+enum Frame {
+    Data(Vec<u8>),
+    Oversized,
+}
+
+impl SlipDecoder {
+    /// Creates a decoder with an empty buffer.
+    pub fn new() -> SlipDecoder {
+        SlipDecoder::default()
+    }
+
+    /// Creates a decoder which decodes packets using the given `options`.
+    /// Frames larger than the maximum packet size are discarded without
+    /// buffering them.
+    pub fn with_options(options: DecoderOptions) -> SlipDecoder {
+        SlipDecoder {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// Unescapes `data` received from the stream and buffers it.
+    pub fn feed(&mut self, data: &[u8]) {
+        for &byte in data {
+            let byte = match (mem::replace(&mut self.escaped, false), byte) {
+                (false, END) => {
+                    self.end_frame();
+                    continue;
+                }
+                (false, ESC) => {
+                    self.escaped = true;
+                    continue;
+                }
+                (true, ESC_END) => END,
+                (true, ESC_ESC) => ESC,
+                // RFC 1055 leaves the byte as it is in case of a protocol violation
+                (_, byte) => byte,
+            };
+            if self.frame.len() < self.options.max_packet_size {
+                self.frame.push(byte);
+            } else {
+                self.oversized = true;
+            }
+        }
+    }
+
+    fn end_frame(&mut self) {
+        if mem::replace(&mut self.oversized, false) {
+            self.frame.clear();
+            self.frames.push_back(Frame::Oversized);
+        } else if !self.frame.is_empty() {
+            self.frames
+                .push_back(Frame::Data(mem::take(&mut self.frame)));
+        }
+    }
+
+    /// Returns the number of bytes of the incomplete frame.
+    pub fn buffered(&self) -> usize {
+        self.frame.len()
+    }
+
+    /// Returns the next complete frame, or `Ok(None)` if more data needs to
+    /// be fed to complete it. Returns an `OscError::LimitExceeded` in place
+    /// of a frame that exceeded the maximum packet size.
+    pub fn next_frame(&mut self) -> Result<Option<Vec<u8>>> {
+        match self.frames.pop_front() {
+            Some(Frame::Data(frame)) => Ok(Some(frame)),
+            Some(Frame::Oversized) => Err(OscError::LimitExceeded(DecodeLimit::PacketSize)),
+            None => Ok(None),
+        }
+    }
+
+    /// Decodes the next complete frame, or returns `Ok(None)` if more data
+    /// needs to be fed to complete it. Decoding can continue with the
+    /// following frame after an error was returned.
+    pub fn decode(&mut self) -> Result<Option<OscPacket>> {
+        match self.next_frame()? {
+            Some(frame) => decoder::decode_with_options(&frame, &self.options).map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::borrowed::{OscArgs, OscPacketRef};
+use crate::types::{OscBundle, OscMessage, OscPacket, OscTime, OscType, Result};
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use smallvec::SmallVec;
+
+/// The arguments of an [`OscMessageSmall`], stored inline for up to four
+/// arguments.
+pub type SmallArgs = SmallVec<[OscType; 4]>;
+
+/// An OSC packet whose messages store their arguments inline.
+///
+/// Small packets are produced by
+/// [`decoder::decode_small`](crate::decoder::decode_small) and encoded by
+/// [`encoder::encode_small`](crate::encoder::encode_small). Most messages
+/// carry only a few arguments, which small messages store without the heap
+/// allocation of an [`OscMessage`]'s argument vector. Use
+/// [`From`]/[`Into`] to turn them into an owned [`OscPacket`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscPacketSmall {
+    Message(OscMessageSmall),
+    Bundle(OscBundleSmall),
+}
+
+/// An OSC message storing up to four arguments inline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscMessageSmall {
+    pub addr: String,
+    pub args: SmallArgs,
+}
+
+/// An OSC bundle of small packets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscBundleSmall {
+    pub timetag: OscTime,
+    pub content: Vec<OscPacketSmall>,
+}
+
+impl OscPacketSmall {
+    /// Decodes `msg` into a small packet.
+    pub(crate) fn decode(msg: &[u8]) -> Result<OscPacketSmall> {
+        if msg.first() != Some(&b'/') {
+            return OscPacketRef::parse(msg).map(OscPacketSmall::from_ref);
+        }
+        // iterating over all arguments validates them, so messages are
+        // decoded in a single pass
+        let (addr, args) = OscArgs::parse(msg)?;
+        Ok(OscPacketSmall::Message(OscMessageSmall {
+            addr: addr.to_string(),
+            args: args
+                .map(|arg| arg.map(OscType::from))
+                .collect::<Result<_>>()?,
+        }))
+    }
+
+    /// Copies `packet` into a small packet.
+    pub(crate) fn from_ref(packet: OscPacketRef) -> OscPacketSmall {
+        match packet {
+            OscPacketRef::Message(msg) => OscPacketSmall::Message(OscMessageSmall {
+                addr: msg.addr.to_string(),
+                args: msg.args().map(OscType::from).collect(),
+            }),
+            OscPacketRef::Bundle(bundle) => OscPacketSmall::Bundle(OscBundleSmall {
+                timetag: bundle.timetag,
+                content: bundle.content().map(OscPacketSmall::from_ref).collect(),
+            }),
+        }
+    }
+}
+
+impl From<OscMessageSmall> for OscPacketSmall {
+    fn from(msg: OscMessageSmall) -> OscPacketSmall {
+        OscPacketSmall::Message(msg)
+    }
+}
+
+impl From<OscBundleSmall> for OscPacketSmall {
+    fn from(bundle: OscBundleSmall) -> OscPacketSmall {
+        OscPacketSmall::Bundle(bundle)
+    }
+}
+
+impl From<OscMessage> for OscMessageSmall {
+    fn from(msg: OscMessage) -> OscMessageSmall {
+        OscMessageSmall {
+            addr: msg.addr,
+            args: SmallVec::from_vec(msg.args),
+        }
+    }
+}
+
+impl From<OscPacketSmall> for OscPacket {
+    fn from(packet: OscPacketSmall) -> OscPacket {
+        match packet {
+            OscPacketSmall::Message(msg) => OscPacket::Message(msg.into()),
+            OscPacketSmall::Bundle(bundle) => OscPacket::Bundle(bundle.into()),
+        }
+    }
+}
+
+impl From<OscMessageSmall> for OscMessage {
+    fn from(msg: OscMessageSmall) -> OscMessage {
+        OscMessage {
+            addr: msg.addr,
+            args: msg.args.into_vec(),
+        }
+    }
+}
+
+impl From<OscBundleSmall> for OscBundle {
+    fn from(bundle: OscBundleSmall) -> OscBundle {
+        OscBundle {
+            timetag: bundle.timetag,
+            content: bundle.content.into_iter().map(OscPacket::from).collect(),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Options for the sockets the transports create.
+//!
+//! Build [`SocketOptions`] and pass them to a transport's `bind_with_options`
+//! or `connect_with_options` constructor, e.g.
+//! [`OscUdpServer::bind_with_options`], instead of
+//! setting up the socket yourself.
+//!
+//! [`OscUdpServer::bind_with_options`]: crate::udp::OscUdpServer::bind_with_options
+
+use socket2::{Domain, Protocol, Socket, Type};
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
+
+/// Options applied to a socket before it is bound or connected. Options
+/// that aren't set keep the operating system's defaults.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::socket::SocketOptions;
+/// use rosc::udp::OscUdpServer;
+///
+/// let options = SocketOptions::new()
+///     .reuse_address(true)
+///     .recv_buffer_size(1 << 20)
+///     .dscp(46);
+/// let mut server = OscUdpServer::bind_with_options("0.0.0.0:9000", &options).unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SocketOptions {
+    reuse_address: Option<bool>,
+    recv_buffer_size: Option<usize>,
+    send_buffer_size: Option<usize>,
+    ttl: Option<u32>,
+    tos: Option<u8>,
+}
+
+impl SocketOptions {
+    /// Creates options that leave all settings to the operating system.
+    pub fn new() -> SocketOptions {
+        SocketOptions::default()
+    }
+
+    /// Sets `SO_REUSEADDR`, which allows a server to bind its TCP port again
+    /// while connections of its previous run linger, or several servers on
+    /// the same host to receive multicast or broadcast datagrams on one UDP
+    /// port. It doesn't share unicast datagrams between the servers, on
+    /// Linux only the last one bound receives them.
+    pub fn reuse_address(mut self, reuse: bool) -> SocketOptions {
+        self.reuse_address = Some(reuse);
+        self
+    }
+
+    /// Sets the size of the receive buffer in bytes, `SO_RCVBUF`. A larger
+    /// buffer keeps bursts of packets from being dropped.
+    pub fn recv_buffer_size(mut self, size: usize) -> SocketOptions {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the size of the send buffer in bytes, `SO_SNDBUF`.
+    pub fn send_buffer_size(mut self, size: usize) -> SocketOptions {
+        self.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the number of routers unicast packets may pass, the `IP_TTL`
+    /// for IPv4 or the `IPV6_UNICAST_HOPS` for IPv6.
+    pub fn ttl(mut self, ttl: u32) -> SocketOptions {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the type of service byte of sent IPv4 packets, `IP_TOS`. It is
+    /// not applied to IPv6 sockets.
+    pub fn tos(mut self, tos: u8) -> SocketOptions {
+        self.tos = Some(tos);
+        self
+    }
+
+    /// Sets the type of service byte to the differentiated services code
+    /// point `dscp`, e.g. 46 for expedited forwarding as commonly used for
+    /// AV traffic. Only the lower six bits of `dscp` are used.
+    pub fn dscp(self, dscp: u8) -> SocketOptions {
+        self.tos((dscp & 0x3f) << 2)
+    }
+
+    /// Creates a UDP socket bound to `addr`.
+    pub(crate) fn bind_udp(&self, addr: SocketAddr) -> io::Result<UdpSocket> {
+        let socket = self.socket(addr, Type::DGRAM, Protocol::UDP)?;
+        socket.bind(&addr.into())?;
+        Ok(socket.into())
+    }
+
+    /// Creates a TCP listener bound to `addr`.
+    pub(crate) fn bind_tcp(&self, addr: SocketAddr) -> io::Result<TcpListener> {
+        let socket = self.socket(addr, Type::STREAM, Protocol::TCP)?;
+        socket.bind(&addr.into())?;
+        socket.listen(128)?;
+        Ok(socket.into())
+    }
+
+    /// Creates a TCP stream connected to `addr`.
+    pub(crate) fn connect_tcp(&self, addr: SocketAddr) -> io::Result<TcpStream> {
+        let socket = self.socket(addr, Type::STREAM, Protocol::TCP)?;
+        socket.connect(&addr.into())?;
+        Ok(socket.into())
+    }
+
+    /// Creates a socket for the address family of `addr` with the options
+    /// applied.
+    fn socket(&self, addr: SocketAddr, ty: Type, protocol: Protocol) -> io::Result<Socket> {
+        let socket = Socket::new(Domain::for_address(addr), ty, Some(protocol))?;
+        if let Some(reuse) = self.reuse_address {
+            socket.set_reuse_address(reuse)?;
+        }
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+        match (addr, self.ttl) {
+            (SocketAddr::V4(_), Some(ttl)) => socket.set_ttl_v4(ttl)?,
+            (SocketAddr::V6(_), Some(hops)) => socket.set_unicast_hops_v6(hops)?,
+            (_, None) => (),
+        }
+        if let (SocketAddr::V4(_), Some(tos)) = (addr, self.tos) {
+            socket.set_tos_v4(u32::from(tos))?;
+        }
+        Ok(socket)
+    }
+}
@@ -0,0 +1,639 @@
+//! Sending and receiving OSC packets over TCP.
+//!
+//! As described in the *OSC Packets* section of the
+//! [OSC 1.0 specification](http://opensoundcontrol.org/spec-1_0), every
+//! packet sent over the stream is preceded by its size as a big-endian
+//! `int32`, see [`encoder::encode_framed`] and [`StreamDecoder`].
+//!
+//! With the `tls` feature, connections can be secured by TLS using
+//! [rustls](https://docs.rs/rustls), see [`OscTcpClient::connect_tls`] and
+//! [`OscTcpServer::bind_tls`]. The packets are framed the same way inside
+//! the encrypted stream.
+
+use crate::decoder::{DecoderOptions, StreamDecoder};
+use crate::encoder;
+use crate::errors::{DecodeLimit, OscError};
+use crate::filter::AddressFilter;
+use crate::metrics::{Metrics, OscMetricsSink};
+use crate::ratelimit::{RateLimit, RateLimiter};
+use crate::server::{Connection, ErrorHook, Received, Runner};
+use crate::socket::SocketOptions;
+use crate::types::{OscPacket, Result};
+use crate::udp::{filter_packet, resolve, time_left, timed_out};
+#[cfg(feature = "tls")]
+use rustls::pki_types::ServerName;
+#[cfg(feature = "tls")]
+use rustls::{ClientConfig, ClientConnection, ServerConfig, ServerConnection, StreamOwned};
+#[cfg(feature = "tls")]
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The maximum size of received packets in bytes unless other decoder
+/// options are set. Without a limit, a peer could announce a packet of
+/// almost 4 GiB and make the receiver buffer it.
+pub const DEFAULT_MAX_PACKET_SIZE: usize = 1 << 20;
+
+/// Sends and receives OSC packets over a TCP connection.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::tcp::OscTcpClient;
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let mut client = OscTcpClient::connect("127.0.0.1:9000").unwrap();
+/// client
+///     .send(&OscPacket::Message(OscMessage::from("/status")))
+///     .unwrap();
+/// println!("reply: {:?}", client.recv().unwrap());
+/// ```
+pub struct OscTcpClient {
+    stream: Stream,
+    decoder: StreamDecoder,
+    buf: Vec<u8>,
+    metrics: Metrics,
+    limiter: Option<RateLimiter>,
+}
+
+impl OscTcpClient {
+    /// Connects to `addr`. Returns an `OscError::SocketError` if the
+    /// connection can't be established.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<OscTcpClient> {
+        let stream = TcpStream::connect(addr).map_err(OscError::SocketError)?;
+        Ok(OscTcpClient::from_stream(stream))
+    }
+
+    /// Like [`connect`](OscTcpClient::connect), but creates the socket with
+    /// the given `options`.
+    pub fn connect_with_options<A: ToSocketAddrs>(
+        addr: A,
+        options: &SocketOptions,
+    ) -> Result<OscTcpClient> {
+        let stream = options
+            .connect_tcp(resolve(addr)?)
+            .map_err(OscError::SocketError)?;
+        Ok(OscTcpClient::from_stream(stream))
+    }
+
+    /// Creates a client that uses an existing connection.
+    pub fn from_stream(stream: TcpStream) -> OscTcpClient {
+        OscTcpClient::with_decoder(Stream::Plain(stream), default_decoder(), None)
+    }
+
+    /// Connects to `addr` and secures the connection by TLS with `config`,
+    /// verifying that the server's certificate is valid for `server_name`.
+    /// Returns an `OscError::SocketError` if the connection can't be
+    /// established or `server_name` isn't a valid DNS name or IP address.
+    ///
+    /// The TLS handshake is completed when the first packet is sent or
+    /// received, which fails with an `OscError::WriteError` or
+    /// `OscError::ReadError` if the server can't be verified.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # extern crate rosc;
+    /// # extern crate rustls;
+    /// use rosc::tcp::OscTcpClient;
+    /// use rosc::{OscMessage, OscPacket};
+    /// use rustls::{ClientConfig, RootCertStore};
+    /// use std::sync::Arc;
+    ///
+    /// let mut roots = RootCertStore::empty();
+    /// // add the certificate authorities to trust
+    /// let config = ClientConfig::builder()
+    ///     .with_root_certificates(roots)
+    ///     .with_no_client_auth();
+    /// let mut client =
+    ///     OscTcpClient::connect_tls("mixer.example.com:9000", "mixer.example.com", Arc::new(config))
+    ///         .unwrap();
+    /// client
+    ///     .send(&OscPacket::Message(OscMessage::from("/status")))
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "tls")]
+    pub fn connect_tls<A: ToSocketAddrs>(
+        addr: A,
+        server_name: &str,
+        config: Arc<ClientConfig>,
+    ) -> Result<OscTcpClient> {
+        let server_name = ServerName::try_from(server_name.to_string()).map_err(|_| {
+            OscError::SocketError(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid server name",
+            ))
+        })?;
+        let connection = ClientConnection::new(config, server_name).map_err(tls_error)?;
+        let stream = TcpStream::connect(addr).map_err(OscError::SocketError)?;
+        let stream = Stream::TlsClient(Box::new(StreamOwned::new(connection, stream)));
+        Ok(OscTcpClient::with_decoder(stream, default_decoder(), None))
+    }
+
+    fn with_decoder(stream: Stream, decoder: StreamDecoder, metrics: Metrics) -> OscTcpClient {
+        OscTcpClient {
+            stream,
+            decoder,
+            buf: vec![],
+            metrics,
+            limiter: None,
+        }
+    }
+
+    /// Decodes received packets using the given `options`. Defaults to
+    /// [`DecoderOptions::default`] limited to packets of
+    /// [`DEFAULT_MAX_PACKET_SIZE`] bytes.
+    pub fn set_decoder_options(&mut self, options: DecoderOptions) -> &mut OscTcpClient {
+        self.decoder.set_options(options);
+        self
+    }
+
+    /// Reports the sent and received packets and decoding errors to
+    /// `metrics`.
+    pub fn set_metrics(&mut self, metrics: Arc<dyn OscMetricsSink>) -> &mut OscTcpClient {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Limits the rate at which [`send`](OscTcpClient::send) sends packets, see
+    /// [`RateLimit`]. Packets exceeding the limit are delayed, or rejected
+    /// with an `OscError::RateLimited`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rate of `limit` isn't positive and finite.
+    pub fn set_rate_limit(&mut self, limit: RateLimit) -> &mut OscTcpClient {
+        self.limiter = Some(RateLimiter::new(limit));
+        self
+    }
+
+    /// Encodes `packet` and sends it, preceded by its size. Returns an
+    /// `OscError::WriteError` if sending fails.
+    pub fn send(&mut self, packet: &OscPacket) -> Result<()> {
+        self.buf.clear();
+        encoder::encode_framed_into(packet, &mut self.buf)?;
+        if let Some(ref mut limiter) = self.limiter {
+            limiter.acquire(self.buf.len())?;
+        }
+        self.stream
+            .write_all(&self.buf)
+            .and_then(|()| self.stream.flush())
+            .map_err(OscError::WriteError)?;
+        if let Some(ref metrics) = self.metrics {
+            metrics.packet_sent(self.buf.len());
+        }
+        Ok(())
+    }
+
+    /// Waits for the next packet. Returns an `OscError::ReadError` if
+    /// receiving fails or the connection was closed, or the decoding error
+    /// if the packet is malformed. Decoding can continue with the next
+    /// packet after a decoding error.
+    pub fn recv(&mut self) -> Result<OscPacket> {
+        loop {
+            if let Some(packet) = self.decode()? {
+                return Ok(packet);
+            }
+            self.fill().map_err(OscError::ReadError)?;
+        }
+    }
+
+    /// Like [`recv`](OscTcpClient::recv), but gives up after `timeout` with
+    /// an `OscError::ReadError` of the kind `io::ErrorKind::TimedOut`. The
+    /// read timeout of the stream is restored afterwards.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<OscPacket> {
+        let deadline = Instant::now() + timeout;
+        let previous = self
+            .stream()
+            .read_timeout()
+            .map_err(OscError::SocketError)?;
+        let received = self.recv_before(deadline);
+        self.stream()
+            .set_read_timeout(previous)
+            .map_err(OscError::SocketError)?;
+        received
+    }
+
+    fn recv_before(&mut self, deadline: Instant) -> Result<OscPacket> {
+        loop {
+            if let Some(packet) = self.decode()? {
+                return Ok(packet);
+            }
+            self.stream()
+                .set_read_timeout(Some(time_left(deadline)?))
+                .map_err(OscError::SocketError)?;
+            self.fill()
+                .map_err(OscError::ReadError)
+                .map_err(timed_out)?;
+        }
+    }
+
+    /// Decodes the next buffered packet and reports the result to the
+    /// metrics sink.
+    fn decode(&mut self) -> Result<Option<OscPacket>> {
+        let decoded = self.decoder.decode();
+        if let Some(ref metrics) = self.metrics {
+            match decoded {
+                Ok(Some((_, len))) => metrics.packet_received(len),
+                Ok(None) => (),
+                Err(ref err) => metrics.decode_error(err),
+            }
+        }
+        decoded.map(|decoded| decoded.map(|(packet, _)| packet))
+    }
+
+    /// Reads more data from the stream into the decoder.
+    fn fill(&mut self) -> io::Result<()> {
+        let mut chunk = [0u8; 4096];
+        let len = self.stream.read(&mut chunk)?;
+        if len == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.decoder.feed(&chunk[..len]);
+        Ok(())
+    }
+
+    /// Returns the address of the peer.
+    pub fn peer_addr(&self) -> Result<SocketAddr> {
+        self.stream().peer_addr().map_err(OscError::SocketError)
+    }
+
+    /// Returns the underlying TCP stream, which carries the encrypted data
+    /// of TLS connections.
+    pub fn stream(&self) -> &TcpStream {
+        self.stream.tcp()
+    }
+
+    /// Returns whether the connection is secured by TLS.
+    pub fn is_tls(&self) -> bool {
+        match self.stream {
+            Stream::Plain(_) => false,
+            #[cfg(feature = "tls")]
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Debug for OscTcpClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OscTcpClient")
+            .field("stream", self.stream())
+            .field("tls", &self.is_tls())
+            .field("decoder", &self.decoder)
+            .field("limiter", &self.limiter)
+            .finish()
+    }
+}
+
+/// Accepts TCP connections and receives OSC packets from them.
+///
+/// [`run`](OscTcpServer::run) serves any number of connections at once and
+/// passes the packets received from them to a callback on the calling
+/// thread. Malformed packets are passed to the error hook set with
+/// [`on_error`](OscTcpServer::on_error) and don't close the connection.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::tcp::OscTcpServer;
+///
+/// let mut server = OscTcpServer::bind("0.0.0.0:9000").unwrap();
+/// server.on_error(|err, from| eprintln!("bad packet from {}: {}", from, err));
+/// server
+///     .run(|packet, from| println!("{}: {:?}", from, packet))
+///     .unwrap();
+/// ```
+pub struct OscTcpServer {
+    listener: TcpListener,
+    options: DecoderOptions,
+    on_error: Option<ErrorHook>,
+    metrics: Metrics,
+    filter: Option<AddressFilter>,
+    #[cfg(feature = "tls")]
+    tls: Option<Arc<ServerConfig>>,
+    runner: Runner,
+}
+
+impl OscTcpServer {
+    /// Creates a server listening on `addr`. Returns an
+    /// `OscError::SocketError` if the socket can't be bound.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<OscTcpServer> {
+        let listener = TcpListener::bind(addr).map_err(OscError::SocketError)?;
+        Ok(OscTcpServer::from_listener(listener))
+    }
+
+    /// Like [`bind`](OscTcpServer::bind), but creates the socket with the
+    /// given `options`, see [`SocketOptions`].
+    pub fn bind_with_options<A: ToSocketAddrs>(
+        addr: A,
+        options: &SocketOptions,
+    ) -> Result<OscTcpServer> {
+        let listener = options
+            .bind_tcp(resolve(addr)?)
+            .map_err(OscError::SocketError)?;
+        Ok(OscTcpServer::from_listener(listener))
+    }
+
+    /// Creates a server accepting connections on an existing listener.
+    pub fn from_listener(listener: TcpListener) -> OscTcpServer {
+        OscTcpServer {
+            listener,
+            options: default_options(),
+            on_error: None,
+            metrics: None,
+            filter: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+            runner: Runner::default(),
+        }
+    }
+
+    /// Creates a server listening on `addr` that secures all connections
+    /// by TLS with `config`. Returns an `OscError::SocketError` if the
+    /// socket can't be bound.
+    ///
+    /// The TLS handshake is completed when the first packet is received
+    /// from a connection, and connections failing it are closed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # extern crate rosc;
+    /// # extern crate rustls;
+    /// use rosc::tcp::OscTcpServer;
+    /// use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+    /// use rustls::ServerConfig;
+    /// use std::sync::Arc;
+    ///
+    /// # fn load() -> (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>) { unimplemented!() }
+    /// let (cert_chain, key) = load();
+    /// let config = ServerConfig::builder()
+    ///     .with_no_client_auth()
+    ///     .with_single_cert(cert_chain, key)
+    ///     .unwrap();
+    /// let mut server = OscTcpServer::bind_tls("0.0.0.0:9000", Arc::new(config)).unwrap();
+    /// server
+    ///     .run(|packet, from| println!("{}: {:?}", from, packet))
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "tls")]
+    pub fn bind_tls<A: ToSocketAddrs>(addr: A, config: Arc<ServerConfig>) -> Result<OscTcpServer> {
+        let mut server = OscTcpServer::bind(addr)?;
+        server.tls = Some(config);
+        Ok(server)
+    }
+
+    /// Decodes received packets using the given `options`. Defaults to
+    /// [`DecoderOptions::default`] limited to packets of
+    /// [`DEFAULT_MAX_PACKET_SIZE`] bytes. [`run`](OscTcpServer::run) closes
+    /// connections announcing a packet exceeding the maximum packet size.
+    pub fn set_decoder_options(&mut self, options: DecoderOptions) -> &mut OscTcpServer {
+        self.options = options;
+        self
+    }
+
+    /// Sets the hook that [`run`](OscTcpServer::run) calls with the error
+    /// and sender of each packet that can't be decoded. Without a hook such
+    /// packets are dropped silently.
+    pub fn on_error<F>(&mut self, hook: F) -> &mut OscTcpServer
+    where
+        F: FnMut(&OscError, SocketAddr) + Send + 'static,
+    {
+        self.on_error = Some(Box::new(hook));
+        self
+    }
+
+    /// Reports the packets received from all connections, decoding errors
+    /// and unfinished packets of closed connections to `metrics`. Also
+    /// applies to clients returned by [`accept`](OscTcpServer::accept).
+    pub fn set_metrics(&mut self, metrics: Arc<dyn OscMetricsSink>) -> &mut OscTcpServer {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Drops the messages received by [`run`](OscTcpServer::run) that don't
+    /// pass `filter`, see [`filter`](crate::filter). Packets are only
+    /// passed to the handler if messages remain, dropped messages are
+    /// reported to the metrics sink.
+    pub fn set_filter(&mut self, filter: AddressFilter) -> &mut OscTcpServer {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Waits for the next connection. Returns an `OscError::SocketError` if
+    /// accepting fails.
+    pub fn accept(&self) -> Result<(OscTcpClient, SocketAddr)> {
+        let (stream, addr) = self.listener.accept().map_err(OscError::SocketError)?;
+        let decoder = StreamDecoder::with_options(self.options.clone());
+        let stream = self.acceptor().wrap(stream)?;
+        let client = OscTcpClient::with_decoder(stream, decoder, self.metrics.clone());
+        Ok((client, addr))
+    }
+
+    fn acceptor(&self) -> Acceptor {
+        Acceptor {
+            #[cfg(feature = "tls")]
+            tls: self.tls.clone(),
+        }
+    }
+
+    /// Accepts connections and calls `handler` with each packet received
+    /// from them and their sender. Every connection is read on its own
+    /// thread until it is closed.
+    ///
+    /// Only returns if accepting a connection fails.
+    pub fn run<F>(&mut self, handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        self.serve(None, handler)
+    }
+
+    /// Like [`run`](OscTcpServer::run), but returns once `deadline` has
+    /// passed, e.g. to check for a shutdown request regularly. Connections
+    /// stay open and are still read in the background until the next call,
+    /// which receives the packets that arrived in between, but reading
+    /// pauses once a few dozen packets are waiting. They use the decoder
+    /// options set when the server first ran. Dropping the server stops
+    /// accepting connections and closes the listening socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rosc::tcp::OscTcpServer;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let shutdown = AtomicBool::new(false);
+    /// let mut server = OscTcpServer::bind("0.0.0.0:9000").unwrap();
+    /// while !shutdown.load(Ordering::Relaxed) {
+    ///     let deadline = Instant::now() + Duration::from_millis(500);
+    ///     server
+    ///         .run_until(deadline, |packet, from| println!("{}: {:?}", from, packet))
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn run_until<F>(&mut self, deadline: Instant, handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        self.serve(Some(deadline), handler)
+    }
+
+    fn serve<F>(&mut self, deadline: Option<Instant>, mut handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        let options = self.options.clone();
+        let metrics = self.metrics.clone();
+        let acceptor = self.acceptor();
+        let open = move |stream| {
+            let decoder = StreamDecoder::with_options(options.clone());
+            let stream = acceptor.wrap(stream)?;
+            Ok(OscTcpClient::with_decoder(stream, decoder, metrics.clone()))
+        };
+        let (filter, metrics) = (&self.filter, &self.metrics);
+        let on_error = &mut self.on_error;
+        self.runner
+            .run(&self.listener, open, on_error, deadline, |packet, from| {
+                if let Some(packet) = filter_packet(filter, metrics, packet) {
+                    handler(packet, from);
+                }
+            })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(OscError::SocketError)
+    }
+}
+
+impl fmt::Debug for OscTcpServer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OscTcpServer")
+            .field("listener", &self.listener)
+            .field("options", &self.options)
+            .field("filter", &self.filter)
+            .finish()
+    }
+}
+
+impl Connection for OscTcpClient {
+    fn read_packet(&mut self) -> Received {
+        loop {
+            match self.decode() {
+                Ok(Some(packet)) => return Received::Packet(packet),
+                Ok(None) => {
+                    if self.fill().is_err() {
+                        let buffered = self.decoder.buffered();
+                        if let (Some(metrics), true) = (&self.metrics, buffered > 0) {
+                            metrics.packet_dropped(buffered);
+                        }
+                        return Received::Closed(None);
+                    }
+                }
+                Err(err @ OscError::LimitExceeded(DecodeLimit::PacketSize)) => {
+                    // the peer doesn't follow the protocol, so don't read the
+                    // rest of the oversized packet
+                    return Received::Closed(Some(err));
+                }
+                Err(err) => return Received::BadPacket(err),
+            }
+        }
+    }
+}
+
+/// Returns the decoder options of connections unless others are set.
+pub(crate) fn default_options() -> DecoderOptions {
+    DecoderOptions {
+        max_packet_size: DEFAULT_MAX_PACKET_SIZE,
+        ..DecoderOptions::default()
+    }
+}
+
+fn default_decoder() -> StreamDecoder {
+    StreamDecoder::with_options(default_options())
+}
+
+/// The connection of a client, either plain or secured by TLS.
+enum Stream {
+    Plain(TcpStream),
+    #[cfg(feature = "tls")]
+    TlsClient(Box<StreamOwned<ClientConnection, TcpStream>>),
+    #[cfg(feature = "tls")]
+    TlsServer(Box<StreamOwned<ServerConnection, TcpStream>>),
+}
+
+impl Stream {
+    fn tcp(&self) -> &TcpStream {
+        match *self {
+            Stream::Plain(ref stream) => stream,
+            #[cfg(feature = "tls")]
+            Stream::TlsClient(ref stream) => stream.get_ref(),
+            #[cfg(feature = "tls")]
+            Stream::TlsServer(ref stream) => stream.get_ref(),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match *self {
+            Stream::Plain(ref mut stream) => stream.read(buf),
+            #[cfg(feature = "tls")]
+            Stream::TlsClient(ref mut stream) => stream.read(buf),
+            #[cfg(feature = "tls")]
+            Stream::TlsServer(ref mut stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            Stream::Plain(ref mut stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            Stream::TlsClient(ref mut stream) => stream.write(buf),
+            #[cfg(feature = "tls")]
+            Stream::TlsServer(ref mut stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            Stream::Plain(ref mut stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            Stream::TlsClient(ref mut stream) => stream.flush(),
+            #[cfg(feature = "tls")]
+            Stream::TlsServer(ref mut stream) => stream.flush(),
+        }
+    }
+}
+
+/// Turns the connections accepted by a server into streams.
+struct Acceptor {
+    #[cfg(feature = "tls")]
+    tls: Option<Arc<ServerConfig>>,
+}
+
+impl Acceptor {
+    /// Secures `stream` by TLS if the server has a TLS config.
+    fn wrap(&self, stream: TcpStream) -> Result<Stream> {
+        #[cfg(feature = "tls")]
+        if let Some(ref config) = self.tls {
+            let connection = ServerConnection::new(config.clone()).map_err(tls_error)?;
+            return Ok(Stream::TlsServer(Box::new(StreamOwned::new(
+                connection, stream,
+            ))));
+        }
+        Ok(Stream::Plain(stream))
+    }
+}
+
+#[cfg(feature = "tls")]
+fn tls_error(err: rustls::Error) -> OscError {
+    OscError::SocketError(io::Error::other(err))
+}
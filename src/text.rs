@@ -0,0 +1,240 @@
+//! Parsing messages from the human readable text form used by the liblo
+//! tools like `oscsend`, e.g. for REPLs, configuration files or test
+//! scripts.
+//!
+//! A message is written as its address followed by the arguments, separated
+//! by whitespace. The types of the arguments can be given explicitly by a
+//! type tag string after the address:
+//!
+//! ```text
+//! /synth/freq if 440 0.5
+//! /synth/freq ,if 440 0.5
+//! ```
+//!
+//! The leading comma is optional, without it the second word is only taken
+//! as type tag string if it consists of supported type tags and describes as
+//! many arguments as follow it. Otherwise the types are inferred:
+//!
+//! | Argument                                   | Type          |
+//! |--------------------------------------------|---------------|
+//! | integer, e.g. `440`                        | `i`, or `h` if it exceeds 32 bits |
+//! | number with fraction or exponent, e.g. `0.5` | `f`         |
+//! | `true`, `false`                            | `T`, `F`      |
+//! | `nil`                                      | `N`           |
+//! | anything else                              | `s`           |
+//!
+//! Arguments containing whitespace can be enclosed in double quotes, which
+//! also makes them strings when types are inferred. Within quotes `\"`,
+//! `\\`, `\n` and `\t` are escaped.
+//!
+//! The supported type tags are `i`, `h`, `f`, `d`, `s`, `S`, `c`, `T`, `F`,
+//! `N`, `I`, `b` (hex digits) and `r` (a `#rrggbbaa` color).
+//!
+//! # Example
+//!
+//! ```
+//! use rosc::{text, OscMessage, OscType};
+//!
+//! let msg = text::parse_message("/synth/freq if 440 0.5").unwrap();
+//! assert_eq!(vec![OscType::Int(440), OscType::Float(0.5)], msg.args);
+//!
+//! let msg: OscMessage = r#"/synth/name "sine wave" 3"#.parse().unwrap();
+//! assert_eq!(vec![OscType::from("sine wave"), OscType::Int(3)], msg.args);
+//! ```
+
+use crate::address;
+use crate::errors::OscError;
+use crate::json::hex_to_bytes;
+use crate::types::{OscColor, OscMessage, OscType, Result};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+/// Type tags that can be written in text form.
+const TYPE_TAGS: &str = "ihfdsScTFNIbr";
+
+/// Parses a message from its text form, see the [module documentation](self).
+/// Returns an `OscError::BadAddress` if the address is invalid, an
+/// `OscError::BadMessage` if the text can't be split into words, or an
+/// `OscError::BadArg` if an argument doesn't match its type tag.
+pub fn parse_message(text: &str) -> Result<OscMessage> {
+    let words = split_words(text)?;
+    let mut words = words.into_iter();
+    let addr = match words.next() {
+        Some(Word {
+            text,
+            quoted: false,
+        }) => text,
+        _ => return Err(OscError::BadMessage("Missing address")),
+    };
+    address::validate(&addr)?;
+
+    let rest: Vec<Word> = words.collect();
+    let args = match rest.split_first() {
+        Some((first, values)) if is_type_tag_string(first, values.len()) => {
+            typed_args(first.text.trim_start_matches(','), values)?
+        }
+        _ => rest.iter().map(infer_arg).collect(),
+    };
+    Ok(OscMessage { addr, args })
+}
+
+impl FromStr for OscMessage {
+    type Err = OscError;
+
+    /// Parses a message from its text form, see [`text::parse_message`](crate::text::parse_message).
+    fn from_str(s: &str) -> Result<OscMessage> {
+        parse_message(s)
+    }
+}
+
+struct Word {
+    text: String,
+    quoted: bool,
+}
+
+fn split_words(text: &str) -> Result<Vec<Word>> {
+    let mut words = vec![];
+    let mut chars = text.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let mut word = String::new();
+        match chars.peek() {
+            None => return Ok(words),
+            Some('"') => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => word.push('\n'),
+                            Some('t') => word.push('\t'),
+                            Some(c @ ('"' | '\\')) => word.push(c),
+                            _ => return Err(OscError::BadMessage("Invalid escape sequence")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(OscError::BadMessage("Unterminated quoted string")),
+                    }
+                }
+                words.push(Word {
+                    text: word,
+                    quoted: true,
+                });
+            }
+            Some(_) => {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    word.push(c);
+                }
+                words.push(Word {
+                    text: word,
+                    quoted: false,
+                });
+            }
+        }
+    }
+}
+
+fn is_type_tag_string(word: &Word, values: usize) -> bool {
+    if word.quoted {
+        return false;
+    }
+    let (explicit, tags) = match word.text.strip_prefix(',') {
+        Some(tags) => (true, tags),
+        None => (false, word.text.as_str()),
+    };
+    explicit
+        || (!tags.is_empty()
+            && tags.chars().all(|tag| TYPE_TAGS.contains(tag))
+            && tags.chars().filter(|tag| takes_value(*tag)).count() == values)
+}
+
+fn takes_value(tag: char) -> bool {
+    !"TFNI".contains(tag)
+}
+
+fn typed_args(tags: &str, values: &[Word]) -> Result<Vec<OscType>> {
+    let mut values = values.iter();
+    let args = tags
+        .chars()
+        .map(|tag| {
+            if !TYPE_TAGS.contains(tag) {
+                return Err(OscError::BadArg(format!(
+                    "type tag '{}' is not supported in text form",
+                    tag
+                )));
+            }
+            let value = if takes_value(tag) {
+                match values.next() {
+                    Some(value) => value.text.as_str(),
+                    None => {
+                        return Err(OscError::BadArg(format!(
+                            "missing value for type tag '{}'",
+                            tag
+                        )))
+                    }
+                }
+            } else {
+                ""
+            };
+            typed_arg(tag, value).ok_or_else(|| {
+                OscError::BadArg(format!("invalid value {:?} for type tag '{}'", value, tag))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if values.next().is_some() {
+        return Err(OscError::BadArg("more values than type tags".to_string()));
+    }
+    Ok(args)
+}
+
+fn typed_arg(tag: char, value: &str) -> Option<OscType> {
+    Some(match tag {
+        'i' => OscType::Int(value.parse().ok()?),
+        'h' => OscType::Long(value.parse().ok()?),
+        'f' => OscType::Float(value.parse().ok()?),
+        'd' => OscType::Double(value.parse().ok()?),
+        's' => OscType::String(value.to_string()),
+        'S' => OscType::Symbol(value.to_string()),
+        'c' => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => OscType::Char(c),
+                _ => return None,
+            }
+        }
+        'T' => OscType::Bool(true),
+        'F' => OscType::Bool(false),
+        'N' => OscType::Nil,
+        'I' => OscType::Inf,
+        'b' => OscType::Blob(hex_to_bytes(value)?),
+        'r' => OscType::Color(OscColor::from_hex(value)?),
+        _ => return None,
+    })
+}
+
+fn infer_arg(word: &Word) -> OscType {
+    let text = word.text.as_str();
+    if word.quoted {
+        return OscType::String(word.text.clone());
+    }
+    if let Ok(i) = text.parse() {
+        return OscType::Int(i);
+    }
+    if let Ok(h) = text.parse() {
+        return OscType::Long(h);
+    }
+    // rule out words like `inf` or `NaN`, which parse as floats
+    if text.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+' || c == '.') {
+        if let Ok(f) = text.parse() {
+            return OscType::Float(f);
+        }
+    }
+    match text {
+        "true" => OscType::Bool(true),
+        "false" => OscType::Bool(false),
+        "nil" => OscType::Nil,
+        _ => OscType::String(word.text.clone()),
+    }
+}
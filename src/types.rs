@@ -1,12 +1,24 @@
+use crate::address;
 use crate::errors;
-use std::{
-    convert::{TryFrom, TryInto},
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use core::convert::TryInto;
+use core::{
+    convert::TryFrom,
     error,
     fmt::{self, Display},
     iter::FromIterator,
-    result,
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    ops::{Add, AddAssign, Sub, SubAssign},
+    result, slice,
+    time::Duration,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// A time tag in OSC message consists of two 32-bit integers where the first one denotes the number of seconds since 1900-01-01 and the second the fractions of a second.
 /// For details on its semantics see http://opensoundcontrol.org/node/3/#timetags
@@ -32,12 +44,11 @@ use std::{
 /// # Conversions between [`std::time::SystemTime`]
 ///
 /// The traits in `std::convert` are implemented for converting between
-/// [`SystemTime`](std::time::SystemTime) and `OscTime` in both directions. An `OscTime` can be
-/// converted into a `SystemTime` using [`From`](std::convert::From)/[`Into`](std::convert::Into).
-/// A `SystemTime` can be converted into an `OscTime` using
+/// [`SystemTime`](std::time::SystemTime) and `OscTime` in both directions, using
 /// [`TryFrom`](std::convert::TryFrom)/[`TryInto`](std::convert::TryInto). The fallible variants of
-/// the conversion traits are used this case because not every `SystemTime` can be represented as
-/// an `OscTime`.
+/// the conversion traits are used because not every `SystemTime` can be represented as an
+/// `OscTime`, and [`OscTime::IMMEDIATE`] or an `OscTime` before the Unix epoch can't be
+/// represented as a `SystemTime`.
 ///
 /// **These conversions are lossy**, but are tested to have a deviation within
 /// 5 nanoseconds when converted back and forth in either direction.
@@ -47,11 +58,14 @@ use std::{
 /// the [`UNIX_EPOCH`](std::time::UNIX_EPOCH). This allows the math used in the conversions to work
 /// on 32-bit systems which cannot represent times that far back.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OscTime {
     pub seconds: u32,
     pub fractional: u32,
 }
 
+#[cfg(feature = "std")]
 impl OscTime {
     const UNIX_OFFSET: u64 = 2_208_988_800; // From RFC 5905
     const TWO_POW_32: f64 = (u32::MAX as f64) + 1.0; // Number of bits in a `u32`
@@ -60,10 +74,178 @@ impl OscTime {
     const SECONDS_PER_NANO: f64 = 1.0 / OscTime::NANOS_PER_SECOND;
 }
 
+impl OscTime {
+    /// The special time tag `0x0000000000000001`, which tells the receiver
+    /// to process a bundle immediately.
+    pub const IMMEDIATE: OscTime = OscTime {
+        seconds: 0,
+        fractional: 1,
+    };
+
+    /// Returns [`OscTime::IMMEDIATE`].
+    pub fn immediate() -> OscTime {
+        OscTime::IMMEDIATE
+    }
+
+    /// Returns whether this is the time tag meaning "immediately".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::OscTime;
+    ///
+    /// assert!(OscTime::immediate().is_immediate());
+    /// assert!(OscTime::from((0, 1)).is_immediate());
+    /// assert!(!OscTime::from((0, 2)).is_immediate());
+    /// ```
+    pub fn is_immediate(self) -> bool {
+        self == OscTime::IMMEDIATE
+    }
+
+    /// Returns the current time, or an error if the system clock is set
+    /// before the unix epoch or beyond what an `OscTime` can represent
+    /// (February 2036).
+    #[cfg(feature = "std")]
+    pub fn now() -> result::Result<OscTime, OscTimeError> {
+        OscTime::try_from(SystemTime::now())
+    }
+
+    /// Returns the time `delay` from now, e.g. to schedule a bundle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::OscTime;
+    /// use std::time::Duration;
+    ///
+    /// let now = OscTime::now().unwrap();
+    /// let later = OscTime::from_duration_since_now(Duration::from_secs(1)).unwrap();
+    /// assert!(later.duration_since(now).unwrap() >= Duration::from_secs(1));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_duration_since_now(delay: Duration) -> result::Result<OscTime, OscTimeError> {
+        OscTime::try_from(SystemTime::now() + delay)
+    }
+
+    /// Returns the time elapsed since the OSC epoch
+    /// (`1900-01-01 00:00:00 UTC`), the inverse of
+    /// `OscTime::try_from(Duration)`.
+    pub fn to_duration(self) -> Duration {
+        fixed_to_duration(self.to_fixed())
+    }
+
+    /// Returns `self + duration`, or `None` if the result can't be
+    /// represented as an `OscTime`.
+    pub fn checked_add(self, duration: Duration) -> Option<OscTime> {
+        duration_to_fixed(duration)
+            .and_then(|d| self.to_fixed().checked_add(d))
+            .map(OscTime::from_fixed)
+    }
+
+    /// Returns `self - duration`, or `None` if the result would be before
+    /// the OSC epoch.
+    pub fn checked_sub(self, duration: Duration) -> Option<OscTime> {
+        duration_to_fixed(duration)
+            .and_then(|d| self.to_fixed().checked_sub(d))
+            .map(OscTime::from_fixed)
+    }
+
+    /// Returns the time elapsed from `earlier` to `self`, or `None` if
+    /// `earlier` is later than `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::OscTime;
+    /// use std::time::Duration;
+    ///
+    /// let start = OscTime::from((100, 0));
+    /// let end = start + Duration::from_millis(1500);
+    /// assert_eq!(Some(Duration::from_millis(1500)), end.duration_since(start));
+    /// assert_eq!(None, start.duration_since(end));
+    /// ```
+    pub fn duration_since(self, earlier: OscTime) -> Option<Duration> {
+        self.to_fixed()
+            .checked_sub(earlier.to_fixed())
+            .map(fixed_to_duration)
+    }
+
+    /// The time as a 32.32 fixed point number of seconds.
+    fn to_fixed(self) -> u64 {
+        (u64::from(self.seconds) << 32) | u64::from(self.fractional)
+    }
+
+    fn from_fixed(fixed: u64) -> OscTime {
+        OscTime {
+            seconds: (fixed >> 32) as u32,
+            fractional: fixed as u32,
+        }
+    }
+}
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+/// Converts `duration` into a 32.32 fixed point number of seconds, rounding
+/// to the nearest fraction.
+fn duration_to_fixed(duration: Duration) -> Option<u64> {
+    let fractional =
+        ((u64::from(duration.subsec_nanos()) << 32) + NANOS_PER_SECOND / 2) / NANOS_PER_SECOND;
+    u32::try_from(duration.as_secs())
+        .ok()
+        .and_then(|seconds| (u64::from(seconds) << 32).checked_add(fractional))
+}
+
+/// Converts a 32.32 fixed point number of seconds into a `Duration`, rounding
+/// to the nearest nanosecond.
+fn fixed_to_duration(fixed: u64) -> Duration {
+    let nanos = ((fixed & 0xffff_ffff) * NANOS_PER_SECOND + (1 << 31)) >> 32;
+    Duration::from_secs(fixed >> 32) + Duration::from_nanos(nanos)
+}
+
+impl Add<Duration> for OscTime {
+    type Output = OscTime;
+
+    fn add(self, duration: Duration) -> OscTime {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to OSC time")
+    }
+}
+
+impl AddAssign<Duration> for OscTime {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for OscTime {
+    type Output = OscTime;
+
+    fn sub(self, duration: Duration) -> OscTime {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from OSC time")
+    }
+}
+
+impl SubAssign<Duration> for OscTime {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+impl Sub<OscTime> for OscTime {
+    type Output = Duration;
+
+    fn sub(self, earlier: OscTime) -> Duration {
+        self.duration_since(earlier)
+            .expect("OSC time is later than self")
+    }
+}
+
+#[cfg(feature = "std")]
 impl TryFrom<SystemTime> for OscTime {
     type Error = OscTimeError;
 
-    fn try_from(time: SystemTime) -> std::result::Result<OscTime, OscTimeError> {
+    fn try_from(time: SystemTime) -> result::Result<OscTime, OscTimeError> {
         let duration_since_epoch = time
             .duration_since(UNIX_EPOCH)
             .map_err(|_| OscTimeError(OscTimeErrorKind::BeforeEpoch))?
@@ -79,14 +261,35 @@ impl TryFrom<SystemTime> for OscTime {
     }
 }
 
-impl From<OscTime> for SystemTime {
-    fn from(time: OscTime) -> SystemTime {
+/// Converts the time elapsed since the OSC epoch (`1900-01-01 00:00:00 UTC`)
+/// into an `OscTime`, rounding to the nearest fraction of a second.
+impl TryFrom<Duration> for OscTime {
+    type Error = OscTimeError;
+
+    fn try_from(duration: Duration) -> result::Result<OscTime, OscTimeError> {
+        duration_to_fixed(duration)
+            .map(OscTime::from_fixed)
+            .ok_or(OscTimeError(OscTimeErrorKind::Overflow))
+    }
+}
+
+/// Fails for [`OscTime::IMMEDIATE`], which doesn't stand for a point in
+/// time, and for times before the [`UNIX_EPOCH`].
+#[cfg(feature = "std")]
+impl TryFrom<OscTime> for SystemTime {
+    type Error = OscTimeError;
+
+    fn try_from(time: OscTime) -> result::Result<SystemTime, OscTimeError> {
+        if time.is_immediate() {
+            return Err(OscTimeError(OscTimeErrorKind::Immediate));
+        }
         let nanos =
             (time.fractional as f64) * OscTime::ONE_OVER_TWO_POW_32 * OscTime::NANOS_PER_SECOND;
         let duration_since_osc_epoch = Duration::new(time.seconds as u64, nanos as u32);
-        let duration_since_unix_epoch =
-            duration_since_osc_epoch - Duration::new(OscTime::UNIX_OFFSET, 0);
-        UNIX_EPOCH + duration_since_unix_epoch
+        let duration_since_unix_epoch = duration_since_osc_epoch
+            .checked_sub(Duration::new(OscTime::UNIX_OFFSET, 0))
+            .ok_or(OscTimeError(OscTimeErrorKind::BeforeEpoch))?;
+        Ok(UNIX_EPOCH + duration_since_unix_epoch)
     }
 }
 
@@ -107,12 +310,15 @@ impl From<OscTime> for (u32, u32) {
 }
 
 /// An error returned by conversions involving [`OscTime`].
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct OscTimeError(OscTimeErrorKind);
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 enum OscTimeErrorKind {
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
     BeforeEpoch,
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    Immediate,
     Overflow,
 }
 
@@ -122,6 +328,9 @@ impl Display for OscTimeError {
             OscTimeErrorKind::BeforeEpoch => {
                 write!(f, "time is before the unix epoch and cannot be stored")
             }
+            OscTimeErrorKind::Immediate => {
+                write!(f, "time is the immediate time tag and has no calendar time")
+            }
             OscTimeErrorKind::Overflow => {
                 write!(f, "time overflows what OSC time can store")
             }
@@ -134,15 +343,23 @@ impl error::Error for OscTimeError {}
 /// see OSC Type Tag String: [OSC Spec. 1.0](http://opensoundcontrol.org/spec-1_0)
 /// padding: zero bytes (n*4)
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum OscType {
     Int(i32),
     Float(f32),
     String(String),
+    /// A symbol, the alternate string type `S` that e.g. SuperCollider uses
+    /// for names. It is encoded like a string.
+    Symbol(String),
     Blob(Vec<u8>),
     // use struct for time tag to avoid destructuring
     Time(OscTime),
     Long(i64),
     Double(f64),
+    /// An ASCII character, the specification doesn't allow other
+    /// characters. How the encoder and decoder treat non-ASCII characters
+    /// is set by their `non_ascii_chars` option, see [`NonAsciiChars`].
     Char(char),
     Color(OscColor),
     Midi(OscMidiMessage),
@@ -150,9 +367,57 @@ pub enum OscType {
     Array(OscArray),
     Nil,
     Inf,
+    /// An argument with a nonstandard type tag, whose data is encoded
+    /// verbatim followed by padding.
+    ///
+    /// The decoder only returns custom arguments for the type tags
+    /// configured in `decoder::DecoderOptions::custom_type_tags`.
+    Custom {
+        tag: char,
+        data: Vec<u8>,
+    },
+    /// A string argument that isn't valid UTF-8, e.g. Latin-1 text of a
+    /// legacy sender. The bytes are kept without the nul terminator and
+    /// encoded as an `s` argument.
+    ///
+    /// The decoder only returns raw strings if
+    /// `decoder::DecoderOptions::invalid_strings` is
+    /// `decoder::InvalidStrings::Raw`.
+    RawString(Vec<u8>),
 }
+
+/// How to encode and decode `c` arguments that aren't ASCII characters.
+///
+/// The specification defines `c` as an ASCII character sent as 32 bits,
+/// and many receivers can't handle other values. Both
+/// `encoder::EncoderOptions` and `decoder::DecoderOptions` default to
+/// `Error`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonAsciiChars {
+    /// Reject the argument with an `OscError::BadArg`.
+    Error,
+    /// Replace the character by `?`.
+    Lossy,
+    /// Send or accept any Unicode scalar value, like some implementations
+    /// do.
+    Allow,
+}
+
+impl NonAsciiChars {
+    /// Applies the policy to `c`, returning the character to use or `None`
+    /// if it is rejected.
+    pub(crate) fn apply(self, c: char) -> Option<char> {
+        match self {
+            _ if c.is_ascii() => Some(c),
+            NonAsciiChars::Error => None,
+            NonAsciiChars::Lossy => Some('?'),
+            NonAsciiChars::Allow => Some(c),
+        }
+    }
+}
+
 macro_rules! value_impl {
-    ($(($name:ident, $variant:ident, $ty:ty)),*) => {
+    ($(($name:ident, $variant:ident, $ty:ty, $kind:expr)),*) => {
         $(
         impl OscType {
             #[allow(dead_code)]
@@ -168,50 +433,292 @@ macro_rules! value_impl {
                 OscType::$variant(v)
             }
         }
+        impl TryFrom<OscType> for $ty {
+            type Error = OscTypeError;
+
+            fn try_from(arg: OscType) -> result::Result<$ty, OscTypeError> {
+                match arg {
+                    OscType::$variant(v) => Ok(v),
+                    found => Err(OscTypeError { expected: $kind, found }),
+                }
+            }
+        }
         )*
     }
 }
 value_impl! {
-    (int, Int, i32),
-    (float, Float, f32),
-    (string, String, String),
-    (blob, Blob, Vec<u8>),
-    (array, Array, OscArray),
-    (long, Long, i64),
-    (double, Double, f64),
-    (char, Char, char),
-    (color, Color, OscColor),
-    (midi, Midi, OscMidiMessage),
-    (bool, Bool, bool)
+    (int, Int, i32, "int"),
+    (float, Float, f32, "float"),
+    (string, String, String, "string"),
+    (blob, Blob, Vec<u8>, "blob"),
+    (array, Array, OscArray, "array"),
+    (long, Long, i64, "long"),
+    (double, Double, f64, "double"),
+    (char, Char, char, "char"),
+    (color, Color, OscColor, "color"),
+    (midi, Midi, OscMidiMessage, "midi"),
+    (bool, Bool, bool, "bool"),
+    (time, Time, OscTime, "time")
+}
+macro_rules! as_value_impl {
+    ($(($name:ident, $variant:ident, $ty:ty)),*) => {
+        impl OscType {
+            $(
+            /// Returns the value if the argument is an
+            #[doc = concat!("`OscType::", stringify!($variant), "`.")]
+            pub fn $name(&self) -> Option<$ty> {
+                match *self {
+                    OscType::$variant(v) => Some(v),
+                    _ => None
+                }
+            }
+            )*
+        }
+    }
+}
+as_value_impl! {
+    (as_int, Int, i32),
+    (as_float, Float, f32),
+    (as_long, Long, i64),
+    (as_double, Double, f64),
+    (as_char, Char, char),
+    (as_color, Color, OscColor),
+    (as_midi, Midi, OscMidiMessage),
+    (as_bool, Bool, bool),
+    (as_time, Time, OscTime)
 }
+impl OscType {
+    /// Returns the string if the argument is an `OscType::String`.
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            OscType::String(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes if the argument is an `OscType::Blob`.
+    pub fn as_blob(&self) -> Option<&[u8]> {
+        match *self {
+            OscType::Blob(ref blob) => Some(blob),
+            _ => None,
+        }
+    }
+
+    /// Returns the array if the argument is an `OscType::Array`.
+    pub fn as_array(&self) -> Option<&OscArray> {
+        match *self {
+            OscType::Array(ref array) => Some(array),
+            _ => None,
+        }
+    }
+
+    /// Returns the type tag of the argument, `[` for arrays.
+    pub fn type_tag(&self) -> char {
+        match *self {
+            OscType::Int(_) => 'i',
+            OscType::Long(_) => 'h',
+            OscType::Float(_) => 'f',
+            OscType::Double(_) => 'd',
+            OscType::Char(_) => 'c',
+            OscType::String(_) | OscType::RawString(_) => 's',
+            OscType::Symbol(_) => 'S',
+            OscType::Blob(_) => 'b',
+            OscType::Time(_) => 't',
+            OscType::Midi(_) => 'm',
+            OscType::Color(_) => 'r',
+            OscType::Bool(true) => 'T',
+            OscType::Bool(false) => 'F',
+            OscType::Nil => 'N',
+            OscType::Inf => 'I',
+            OscType::Array(_) => '[',
+            OscType::Custom { tag, .. } => tag,
+        }
+    }
+}
+
+/// Lenient conversions between the numeric types, for receivers that
+/// accept e.g. a float from a controller that sends every value as `f`.
+///
+/// The source may be an `i`, `h`, `f`, `d`, `T` or `F` argument, booleans
+/// count as 0 and 1. Floats are rounded to the nearest integer. A value
+/// that is NaN or out of the target type's range yields `None`, as does
+/// any other type.
+///
+/// # Example
+///
+/// ```
+/// use rosc::OscType;
+///
+/// assert_eq!(Some(3), OscType::Float(2.6).coerce_int());
+/// assert_eq!(Some(1.0), OscType::Bool(true).coerce_float());
+/// assert_eq!(None, OscType::Long(1 << 40).coerce_int());
+/// assert_eq!(None, OscType::String("3".to_string()).coerce_int());
+/// ```
+impl OscType {
+    /// Converts a numeric argument to an `i32`.
+    pub fn coerce_int(&self) -> Option<i32> {
+        self.coerce_long().and_then(|h| i32::try_from(h).ok())
+    }
+
+    /// Converts a numeric argument to an `i64`.
+    pub fn coerce_long(&self) -> Option<i64> {
+        match *self {
+            OscType::Int(i) => Some(i.into()),
+            OscType::Long(h) => Some(h),
+            OscType::Float(f) => float_to_long(f.into()),
+            OscType::Double(d) => float_to_long(d),
+            OscType::Bool(b) => Some(b.into()),
+            _ => None,
+        }
+    }
+
+    /// Converts a numeric argument to an `f32`, large integers may lose
+    /// precision.
+    pub fn coerce_float(&self) -> Option<f32> {
+        match *self {
+            OscType::Float(f) if f.is_nan() => None,
+            OscType::Float(f) => Some(f),
+            OscType::Double(d) if d.is_finite() && d.abs() > f32::MAX as f64 => None,
+            _ => self.coerce_double().map(|d| d as f32),
+        }
+    }
+
+    /// Converts a numeric argument to an `f64`, large integers may lose
+    /// precision.
+    pub fn coerce_double(&self) -> Option<f64> {
+        match *self {
+            OscType::Int(i) => Some(i.into()),
+            OscType::Long(h) => Some(h as f64),
+            OscType::Float(f) if f.is_nan() => None,
+            OscType::Float(f) => Some(f.into()),
+            OscType::Double(d) if d.is_nan() => None,
+            OscType::Double(d) => Some(d),
+            OscType::Bool(b) => Some(if b { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+    }
+
+    /// Converts a numeric argument to a `bool`, which is true unless the
+    /// value is 0. NaN yields `None`.
+    pub fn coerce_bool(&self) -> Option<bool> {
+        match *self {
+            OscType::Bool(b) => Some(b),
+            _ => self.coerce_double().map(|d| d != 0.0),
+        }
+    }
+}
+
+/// Rounds `f` to the nearest `i64`, halfway cases away from zero, or
+/// returns `None` if it is NaN or out of range.
+fn float_to_long(f: f64) -> Option<i64> {
+    // i64::MAX isn't representable as f64, 2^63 is the first value too large
+    if f.is_nan() || f < i64::MIN as f64 || f >= 9_223_372_036_854_775_808.0 {
+        return None;
+    }
+    // `f64::round` needs std, floats this large have no fraction anyway
+    let truncated = f as i64;
+    let fraction = f - truncated as f64;
+    Some(if fraction >= 0.5 {
+        truncated + 1
+    } else if fraction <= -0.5 {
+        truncated - 1
+    } else {
+        truncated
+    })
+}
+
+/// An error returned when converting an [`OscType`] into a value of the
+/// wrong type with `TryFrom`.
+///
+/// # Example
+///
+/// ```
+/// use rosc::OscType;
+/// use std::convert::TryFrom;
+///
+/// assert_eq!(Ok(42), i32::try_from(OscType::Int(42)));
+///
+/// let err = i32::try_from(OscType::Float(4.2)).unwrap_err();
+/// assert_eq!("expected int argument, found Float(4.2)", err.to_string());
+/// assert_eq!(OscType::Float(4.2), err.into_inner());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscTypeError {
+    expected: &'static str,
+    found: OscType,
+}
+
+impl OscTypeError {
+    /// Returns the argument that could not be converted.
+    pub fn into_inner(self) -> OscType {
+        self.found
+    }
+}
+
+impl Display for OscTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "expected {} argument, found {:?}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl error::Error for OscTypeError {}
+
 impl From<(u32, u32)> for OscType {
     fn from(time: (u32, u32)) -> Self {
         OscType::Time(time.into())
     }
 }
+#[cfg(feature = "std")]
 impl TryFrom<SystemTime> for OscType {
     type Error = OscTimeError;
 
-    fn try_from(time: SystemTime) -> std::result::Result<OscType, OscTimeError> {
+    fn try_from(time: SystemTime) -> result::Result<OscType, OscTimeError> {
         time.try_into().map(OscType::Time)
     }
 }
-impl OscType {
-    pub fn time(self) -> Option<OscTime> {
-        match self {
-            OscType::Time(time) => Some(time),
-            _ => None,
+macro_rules! widening_impl {
+    ($(($ty:ty, $variant:ident, $target:ty)),*) => {
+        $(
+        impl From<$ty> for OscType {
+            fn from(v: $ty) -> Self {
+                OscType::$variant(<$target>::from(v))
+            }
         }
+        )*
     }
 }
+widening_impl! {
+    (i8, Int, i32),
+    (u8, Int, i32),
+    (i16, Int, i32),
+    (u16, Int, i32),
+    (u32, Long, i64)
+}
 impl<'a> From<&'a str> for OscType {
     fn from(string: &'a str) -> Self {
         OscType::String(string.to_string())
     }
 }
+impl<'a> From<&'a [u8]> for OscType {
+    fn from(blob: &'a [u8]) -> Self {
+        OscType::Blob(blob.to_vec())
+    }
+}
+/// `None` is converted to `OscType::Nil`.
+impl<T: Into<OscType>> From<Option<T>> for OscType {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(OscType::Nil, T::into)
+    }
+}
 /// Represents the parts of a Midi message. Mainly used for
 /// tunneling midi over a network using the OSC protocol.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OscMidiMessage {
     pub port: u8,
     pub status: u8,
@@ -219,12 +726,196 @@ pub struct OscMidiMessage {
     pub data2: u8,
 }
 
+impl OscMidiMessage {
+    /// Creates a message from a raw MIDI message of up to three bytes,
+    /// i.e. a status byte followed by its data bytes.
+    ///
+    /// Returns `None` if `bytes` is not a complete MIDI message or a
+    /// system exclusive message, which doesn't fit into an OSC MIDI message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::OscMidiMessage;
+    ///
+    /// let msg = OscMidiMessage::from_bytes(0, &[0x90, 60, 127]).unwrap();
+    /// assert_eq!(OscMidiMessage::note_on(0, 0, 60, 127), msg);
+    /// assert_eq!(vec![0x90, 60, 127], msg.to_bytes());
+    ///
+    /// // program changes only have one data byte
+    /// assert_eq!(vec![0xc2, 5], OscMidiMessage::from_bytes(0, &[0xc2, 5]).unwrap().to_bytes());
+    /// assert!(OscMidiMessage::from_bytes(0, &[0x90, 60]).is_none());
+    /// ```
+    pub fn from_bytes(port: u8, bytes: &[u8]) -> Option<OscMidiMessage> {
+        let (&status, data) = bytes.split_first()?;
+        if data.len() != midi_data_len(status)? || data.iter().any(|&b| b & 0x80 != 0) {
+            return None;
+        }
+        Some(OscMidiMessage {
+            port,
+            status,
+            data1: data.first().cloned().unwrap_or(0),
+            data2: data.get(1).cloned().unwrap_or(0),
+        })
+    }
+
+    /// Returns the raw MIDI message, consisting of the status byte and as
+    /// many data bytes as the status requires.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let len = midi_data_len(self.status).unwrap_or(2);
+        [self.status, self.data1, self.data2][..1 + len].to_vec()
+    }
+
+    /// Creates a note on message, only the lower four bits of `channel` and
+    /// seven bits of `note` and `velocity` are used.
+    pub fn note_on(port: u8, channel: u8, note: u8, velocity: u8) -> OscMidiMessage {
+        OscMidiMessage::channel_message(port, 0x90, channel, note, velocity)
+    }
+
+    /// Creates a note off message, only the lower four bits of `channel` and
+    /// seven bits of `note` and `velocity` are used.
+    pub fn note_off(port: u8, channel: u8, note: u8, velocity: u8) -> OscMidiMessage {
+        OscMidiMessage::channel_message(port, 0x80, channel, note, velocity)
+    }
+
+    /// Creates a control change message, only the lower four bits of
+    /// `channel` and seven bits of `controller` and `value` are used.
+    pub fn control_change(port: u8, channel: u8, controller: u8, value: u8) -> OscMidiMessage {
+        OscMidiMessage::channel_message(port, 0xb0, channel, controller, value)
+    }
+
+    /// Creates a pitch bend message from a 14 bit `value`, where `0x2000`
+    /// means no bend. Only the lower four bits of `channel` are used and
+    /// larger values are clamped.
+    pub fn pitch_bend(port: u8, channel: u8, value: u16) -> OscMidiMessage {
+        let value = value.min(0x3fff);
+        OscMidiMessage::channel_message(port, 0xe0, channel, value as u8, (value >> 7) as u8)
+    }
+
+    /// Returns the channel of channel messages such as note on or control
+    /// change messages.
+    pub fn channel(&self) -> Option<u8> {
+        match self.status {
+            0x80..=0xef => Some(self.status & 0x0f),
+            _ => None,
+        }
+    }
+
+    fn channel_message(port: u8, kind: u8, channel: u8, data1: u8, data2: u8) -> OscMidiMessage {
+        OscMidiMessage {
+            port,
+            status: kind | (channel & 0x0f),
+            data1: data1 & 0x7f,
+            data2: data2 & 0x7f,
+        }
+    }
+}
+
+/// Returns the number of data bytes following a MIDI status byte, or `None`
+/// if `status` is not a status byte or starts a system exclusive message.
+fn midi_data_len(status: u8) -> Option<usize> {
+    match status {
+        0x80..=0xbf | 0xe0..=0xef | 0xf2 => Some(2),
+        0xc0..=0xdf | 0xf1 | 0xf3 => Some(1),
+        0xf4..=0xff => Some(0),
+        _ => None,
+    }
+}
+
 /// An *osc packet* can contain an *osc message* or a bundle of nested messages
 /// which is called *osc bundle*.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum OscPacket {
     Message(OscMessage),
     Bundle(OscBundle),
+    /// An already encoded packet, which is written verbatim when encoding.
+    ///
+    /// This allows wrapping packets into a bundle without decoding them
+    /// first. The decoder never returns raw packets.
+    Raw(Vec<u8>),
+}
+
+impl OscPacket {
+    /// Returns an iterator over the messages of this packet, walking nested
+    /// bundles depth-first. Each message is yielded together with the time
+    /// tag of the innermost bundle containing it, or `None` if the packet
+    /// is a message itself. Raw packets are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+    ///
+    /// let time = OscTime::from((3_900_000_000, 0));
+    /// let packet = OscPacket::Bundle(OscBundle {
+    ///     timetag: time,
+    ///     content: vec![
+    ///         OscPacket::Message(OscMessage::from("/a")),
+    ///         OscPacket::Bundle(OscBundle {
+    ///             timetag: OscTime::IMMEDIATE,
+    ///             content: vec![OscPacket::Message(OscMessage::from("/b"))],
+    ///         }),
+    ///         OscPacket::Message(OscMessage::from("/c")),
+    ///     ],
+    /// });
+    /// let messages: Vec<_> = packet
+    ///     .iter_messages()
+    ///     .map(|(msg, time)| (msg.addr.as_str(), time.copied()))
+    ///     .collect();
+    /// assert_eq!(
+    ///     vec![("/a", Some(time)), ("/b", Some(OscTime::IMMEDIATE)), ("/c", Some(time))],
+    ///     messages
+    /// );
+    /// ```
+    pub fn iter_messages(&self) -> Messages<'_> {
+        match self {
+            OscPacket::Message(msg) => Messages {
+                message: Some(msg),
+                stack: vec![],
+            },
+            OscPacket::Bundle(bundle) => Messages {
+                message: None,
+                stack: vec![(bundle.content.iter(), &bundle.timetag)],
+            },
+            OscPacket::Raw(_) => Messages {
+                message: None,
+                stack: vec![],
+            },
+        }
+    }
+}
+
+/// An iterator over the messages of a packet and the time tags of the
+/// bundles containing them, see [`OscPacket::iter_messages`].
+#[derive(Clone, Debug)]
+pub struct Messages<'a> {
+    message: Option<&'a OscMessage>,
+    stack: Vec<(slice::Iter<'a, OscPacket>, &'a OscTime)>,
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = (&'a OscMessage, Option<&'a OscTime>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(msg) = self.message.take() {
+            return Some((msg, None));
+        }
+        loop {
+            let (content, timetag) = self.stack.last_mut()?;
+            let timetag = *timetag;
+            match content.next() {
+                Some(OscPacket::Message(msg)) => return Some((msg, Some(timetag))),
+                Some(OscPacket::Bundle(bundle)) => {
+                    self.stack.push((bundle.content.iter(), &bundle.timetag))
+                }
+                Some(OscPacket::Raw(_)) => (),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
 }
 
 /// An OSC message consists of an address and
@@ -234,22 +925,464 @@ pub enum OscPacket {
 /// are used to set properties of the element to the
 /// respective values.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OscMessage {
     pub addr: String,
     pub args: Vec<OscType>,
 }
 
+impl OscMessage {
+    /// Creates a message sent to `addr` with the given arguments, which may
+    /// be a tuple, a vector or a single value, see [`IntoOscArgs`].
+    ///
+    /// The address is not validated, the encoder rejects invalid ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::{OscMessage, OscType};
+    ///
+    /// let msg = OscMessage::new("/synth/1", (1i32, 2.5f32, "hi"));
+    /// assert_eq!(
+    ///     vec![OscType::Int(1), OscType::Float(2.5), OscType::String("hi".to_string())],
+    ///     msg.args
+    /// );
+    ///
+    /// assert_eq!(1, OscMessage::new("/synth/freq", 440.0f32).args.len());
+    /// assert_eq!(3, OscMessage::new("/levels", vec![0.1f32, 0.2, 0.3]).args.len());
+    /// assert!(OscMessage::new("/ping", ()).args.is_empty());
+    /// ```
+    pub fn new<S: Into<String>, A: IntoOscArgs>(addr: S, args: A) -> OscMessage {
+        OscMessage {
+            addr: addr.into(),
+            args: args.into_osc_args(),
+        }
+    }
+
+    /// Returns a builder for a message sent to `addr`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::{OscMessage, OscType};
+    ///
+    /// let msg = OscMessage::builder("/synth/freq")
+    ///     .arg(440.0f32)
+    ///     .arg("sine")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     vec![OscType::Float(440.0), OscType::String("sine".to_string())],
+    ///     msg.args
+    /// );
+    ///
+    /// assert!(OscMessage::builder("no slash").build().is_err());
+    /// ```
+    pub fn builder<S: Into<String>>(addr: S) -> OscMessageBuilder {
+        OscMessageBuilder {
+            msg: OscMessage::from(addr.into()),
+        }
+    }
+
+    /// Converts the arguments into a tuple of values. Returns an
+    /// `OscError::BadArg` if the number of arguments or their types don't
+    /// match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::{OscMessage, OscType};
+    ///
+    /// let msg = OscMessage {
+    ///     addr: "/synth/freq".to_string(),
+    ///     args: vec![OscType::Float(440.0), OscType::String("sine".to_string())],
+    /// };
+    /// let (freq, name): (f32, String) = msg.parse_args().unwrap();
+    /// assert_eq!((440.0, "sine".to_string()), (freq, name));
+    ///
+    /// assert!(msg.parse_args::<(f32,)>().is_err());
+    /// assert!(msg.parse_args::<(i32, String)>().is_err());
+    /// ```
+    pub fn parse_args<T: FromOscArgs>(&self) -> Result<T> {
+        T::from_osc_args(&self.args)
+    }
+
+    /// Returns an iterator over the arguments together with their type
+    /// tags, in the order they are encoded. Arrays have the type tag `[`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::OscMessage;
+    ///
+    /// let msg = OscMessage::new("/synth/freq", (440.0f32, "sine", true));
+    /// let tags: Vec<char> = msg.tagged_args().map(|(tag, _)| tag).collect();
+    /// assert_eq!(vec!['f', 's', 'T'], tags);
+    /// ```
+    pub fn tagged_args(&self) -> impl Iterator<Item = (char, &OscType)> {
+        self.args.iter().map(|arg| (arg.type_tag(), arg))
+    }
+
+    /// Returns the type tags of the arguments without the leading comma,
+    /// as the encoder writes them with the default options. Arrays are
+    /// enclosed in brackets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::{OscArray, OscMessage, OscType};
+    ///
+    /// let array = OscType::Array(OscArray {
+    ///     content: vec![1i32.into(), 2i32.into()],
+    /// });
+    /// let msg = OscMessage::new("/synth/1", ("saw", array, OscType::Nil));
+    /// assert_eq!("s[ii]N", msg.type_tags());
+    /// ```
+    pub fn type_tags(&self) -> String {
+        let mut tags = String::with_capacity(self.args.len());
+        push_type_tags(&self.args, &mut tags);
+        tags
+    }
+}
+
+fn push_type_tags(args: &[OscType], tags: &mut String) {
+    for arg in args {
+        tags.push(arg.type_tag());
+        if let OscType::Array(ref array) = *arg {
+            push_type_tags(&array.content, tags);
+            tags.push(']');
+        }
+    }
+}
+
+/// Conversion of a list of arguments into a value, see
+/// [`OscMessage::parse_args`].
+///
+/// Implemented for tuples of up to eight values that can be converted from
+/// an [`OscType`] with `TryFrom`.
+pub trait FromOscArgs: Sized {
+    fn from_osc_args(args: &[OscType]) -> Result<Self>;
+}
+
+macro_rules! from_osc_args_impl {
+    ($(($($ty:ident),*)),*) => {
+        $(
+        impl<$($ty),*> FromOscArgs for ($($ty,)*)
+        where
+            $($ty: TryFrom<OscType, Error = OscTypeError>),*
+        {
+            #[allow(unused_variables, unused_mut)]
+            fn from_osc_args(args: &[OscType]) -> Result<Self> {
+                __private::check_arg_count(args, <[&str]>::len(&[$(stringify!($ty)),*]))?;
+                let mut index = 0;
+                Ok(($({
+                    index += 1;
+                    __private::parse_arg::<$ty>(args, index - 1)?
+                },)*))
+            }
+        }
+        )*
+    }
+}
+from_osc_args_impl! {
+    (),
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H)
+}
+
+/// Conversion of a value into a list of arguments, see
+/// [`OscMessage::new`].
+///
+/// Implemented for tuples of up to eight values that can be converted into
+/// an [`OscType`], for vectors of such values and for single values of the
+/// types that convert into an `OscType`. Note that a `Vec<u8>` is a list of
+/// `int` arguments, pass a single blob as the tuple `(blob,)` instead.
+pub trait IntoOscArgs {
+    fn into_osc_args(self) -> Vec<OscType>;
+}
+
+macro_rules! into_osc_args_impl {
+    ($(($($ty:ident),*)),*) => {
+        $(
+        impl<$($ty),*> IntoOscArgs for ($($ty,)*)
+        where
+            $($ty: Into<OscType>),*
+        {
+            #[allow(non_snake_case)]
+            fn into_osc_args(self) -> Vec<OscType> {
+                let ($($ty,)*) = self;
+                vec![$($ty.into()),*]
+            }
+        }
+        )*
+    }
+}
+into_osc_args_impl! {
+    (),
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H)
+}
+
+impl<T: Into<OscType>> IntoOscArgs for Vec<T> {
+    fn into_osc_args(self) -> Vec<OscType> {
+        self.into_iter().map(T::into).collect()
+    }
+}
+
+macro_rules! single_osc_arg_impl {
+    ($($ty:ty),*) => {
+        $(
+        impl IntoOscArgs for $ty {
+            fn into_osc_args(self) -> Vec<OscType> {
+                vec![self.into()]
+            }
+        }
+        )*
+    }
+}
+single_osc_arg_impl! {
+    OscType, i32, f32, String, OscArray, i64, f64, char, OscColor, OscMidiMessage, bool,
+    OscTime, i8, u8, i16, u16, u32
+}
+
+impl IntoOscArgs for &str {
+    fn into_osc_args(self) -> Vec<OscType> {
+        vec![self.into()]
+    }
+}
+
+/// Conversion of a value into an OSC message.
+///
+/// With the `derive` feature, `#[derive(ToOsc)]` implements this for
+/// structs whose fields convert into an [`OscType`]. The fields become the
+/// arguments in declaration order, the address is given by the
+/// `#[osc(addr = "...")]` attribute.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use rosc::{FromOsc, OscMessage, ToOsc};
+///
+/// #[derive(Debug, PartialEq, ToOsc, FromOsc)]
+/// #[osc(addr = "/synth/voice")]
+/// struct SynthVoice {
+///     freq: f32,
+///     amp: f32,
+/// }
+///
+/// let voice = SynthVoice {
+///     freq: 440.0,
+///     amp: 0.5,
+/// };
+/// let msg = voice.to_osc();
+/// assert_eq!("/synth/voice", msg.addr);
+/// assert_eq!("ff", msg.type_tags());
+/// assert_eq!(voice, SynthVoice::from_osc(&msg).unwrap());
+/// # }
+/// ```
+pub trait ToOsc {
+    fn to_osc(&self) -> OscMessage;
+}
+
+/// Conversion of an OSC message into a value, the counterpart of
+/// [`ToOsc`].
+///
+/// `#[derive(FromOsc)]` implements this for structs whose fields can be
+/// converted from an [`OscType`] with `TryFrom`. Conversion fails with an
+/// `OscError::BadAddress` if the message is sent to another address than
+/// the one of the `#[osc(addr = "...")]` attribute and with an
+/// `OscError::BadArg` if the number or types of the arguments don't match
+/// the fields.
+pub trait FromOsc: Sized {
+    fn from_osc(msg: &OscMessage) -> Result<Self>;
+}
+
+/// Helpers for the code generated by the derive macros, not part of the
+/// public API.
+#[doc(hidden)]
+pub mod __private {
+    use super::{OscMessage, OscType, OscTypeError, Result};
+    use crate::errors::OscError;
+    use core::convert::TryFrom;
+
+    pub fn check_addr(msg: &OscMessage, addr: &str) -> Result<()> {
+        if msg.addr == addr {
+            Ok(())
+        } else {
+            Err(OscError::BadAddress("Message was sent to another address."))
+        }
+    }
+
+    pub fn check_arg_count(args: &[OscType], expected: usize) -> Result<()> {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(OscError::BadArg(format!(
+                "expected {} arguments, found {}",
+                expected,
+                args.len()
+            )))
+        }
+    }
+
+    pub fn parse_arg<T>(args: &[OscType], index: usize) -> Result<T>
+    where
+        T: TryFrom<OscType, Error = OscTypeError>,
+    {
+        T::try_from(args[index].clone())
+            .map_err(|err| OscError::BadArg(format!("argument {}: {}", index, err)))
+    }
+}
+
+/// Builds an [`OscMessage`], see [`OscMessage::builder`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscMessageBuilder {
+    msg: OscMessage,
+}
+
+impl OscMessageBuilder {
+    /// Appends an argument.
+    pub fn arg<T: Into<OscType>>(mut self, arg: T) -> OscMessageBuilder {
+        self.msg.args.push(arg.into());
+        self
+    }
+
+    /// Appends all arguments of `args`.
+    pub fn args<T: Into<OscType>, I: IntoIterator<Item = T>>(
+        mut self,
+        args: I,
+    ) -> OscMessageBuilder {
+        self.msg.args.extend(args.into_iter().map(T::into));
+        self
+    }
+
+    /// Returns the message, or an `OscError::BadAddress` if its address
+    /// would be rejected by the encoder.
+    pub fn build(self) -> Result<OscMessage> {
+        address::validate(&self.msg.addr)?;
+        Ok(self.msg)
+    }
+}
+
 /// An OSC bundle contains zero or more OSC packets
 /// and a time tag. The contained packets *should* be
 /// applied at the given time tag.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OscBundle {
     pub timetag: OscTime,
     pub content: Vec<OscPacket>,
 }
 
-/// An RGBA color.
+impl OscBundle {
+    /// Returns a builder for a bundle, which by default has no content and
+    /// a time tag meaning "immediately".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::{OscBundle, OscMessage};
+    /// use std::time::Duration;
+    ///
+    /// let inner = OscBundle::builder()
+    ///     .add(OscMessage::from("/inner"))
+    ///     .build()
+    ///     .unwrap();
+    /// let bundle = OscBundle::builder()
+    ///     .timetag_after(Duration::from_millis(100))
+    ///     .add(OscMessage::from("/outer"))
+    ///     .add(inner)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(2, bundle.content.len());
+    /// ```
+    pub fn builder() -> OscBundleBuilder {
+        OscBundleBuilder {
+            timetag: Ok(OscTime::IMMEDIATE),
+            content: vec![],
+        }
+    }
+}
+
+/// Builds an [`OscBundle`], see [`OscBundle::builder`].
 #[derive(Clone, Debug, PartialEq)]
+pub struct OscBundleBuilder {
+    timetag: result::Result<OscTime, OscTimeError>,
+    content: Vec<OscPacket>,
+}
+
+impl OscBundleBuilder {
+    /// Sets the time tag to [`OscTime::IMMEDIATE`], which is the default.
+    pub fn timetag_immediate(mut self) -> OscBundleBuilder {
+        self.timetag = Ok(OscTime::IMMEDIATE);
+        self
+    }
+
+    /// Sets the time tag.
+    pub fn timetag<T: Into<OscTime>>(mut self, timetag: T) -> OscBundleBuilder {
+        self.timetag = Ok(timetag.into());
+        self
+    }
+
+    /// Sets the time tag to the current time.
+    #[cfg(feature = "std")]
+    pub fn timetag_now(mut self) -> OscBundleBuilder {
+        self.timetag = OscTime::now();
+        self
+    }
+
+    /// Sets the time tag to `delay` from now.
+    #[cfg(feature = "std")]
+    pub fn timetag_after(mut self, delay: Duration) -> OscBundleBuilder {
+        self.timetag = OscTime::from_duration_since_now(delay);
+        self
+    }
+
+    /// Sets the time tag to `time`.
+    #[cfg(feature = "std")]
+    pub fn timetag_at(mut self, time: SystemTime) -> OscBundleBuilder {
+        self.timetag = OscTime::try_from(time);
+        self
+    }
+
+    /// Appends a message or bundle.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<P: Into<OscPacket>>(mut self, packet: P) -> OscBundleBuilder {
+        self.content.push(packet.into());
+        self
+    }
+
+    /// Returns the bundle, or an `OscError::BadBundle` if the time tag
+    /// can't be represented as an `OscTime`.
+    pub fn build(self) -> Result<OscBundle> {
+        let timetag = self
+            .timetag
+            .map_err(|err| errors::OscError::BadBundle(format!("Bad time tag: {}", err)))?;
+        Ok(OscBundle {
+            timetag,
+            content: self.content,
+        })
+    }
+}
+
+/// An RGBA color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OscColor {
     pub red: u8,
     pub green: u8,
@@ -257,12 +1390,75 @@ pub struct OscColor {
     pub alpha: u8,
 }
 
+impl OscColor {
+    /// Parses a web style color string of the form `#RRGGBBAA` or
+    /// `#RRGGBB`, in which case the color is opaque. The leading `#` is
+    /// optional.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rosc::OscColor;
+    ///
+    /// let color = OscColor::from_hex("#ff800040").unwrap();
+    /// assert_eq!((255, 128, 0, 64), (color.red, color.green, color.blue, color.alpha));
+    /// assert_eq!("#ff800040", color.to_hex());
+    /// assert_eq!(0xff80_0040, color.to_rgba_u32());
+    ///
+    /// assert_eq!(255, OscColor::from_hex("ff8000").unwrap().alpha);
+    /// assert!(OscColor::from_hex("#ff80").is_none());
+    /// ```
+    pub fn from_hex(hex: &str) -> Option<OscColor> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        match hex.len() {
+            8 => u32::from_str_radix(hex, 16)
+                .ok()
+                .map(OscColor::from_rgba_u32),
+            6 => u32::from_str_radix(hex, 16)
+                .ok()
+                .map(|rgb| OscColor::from_rgba_u32(rgb << 8 | 0xff)),
+            _ => None,
+        }
+    }
+
+    /// Returns the color as a `#rrggbbaa` string.
+    pub fn to_hex(&self) -> String {
+        format!("#{:08x}", self.to_rgba_u32())
+    }
+
+    /// Creates a color from an integer laid out as `0xRRGGBBAA`.
+    pub fn from_rgba_u32(rgba: u32) -> OscColor {
+        let [red, green, blue, alpha] = rgba.to_be_bytes();
+        OscColor {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Returns the color as an integer laid out as `0xRRGGBBAA`.
+    pub fn to_rgba_u32(&self) -> u32 {
+        u32::from_be_bytes([self.red, self.green, self.blue, self.alpha])
+    }
+}
+
 /// An OscArray color.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OscArray {
     pub content: Vec<OscType>,
 }
 
+impl From<Vec<OscType>> for OscArray {
+    fn from(content: Vec<OscType>) -> OscArray {
+        OscArray { content }
+    }
+}
+
 impl<T: Into<OscType>> FromIterator<T> for OscArray {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> OscArray {
         OscArray {
@@ -273,6 +1469,17 @@ impl<T: Into<OscType>> FromIterator<T> for OscArray {
 
 pub type Result<T> = result::Result<T, errors::OscError>;
 
+impl From<OscMessage> for OscPacket {
+    fn from(msg: OscMessage) -> OscPacket {
+        OscPacket::Message(msg)
+    }
+}
+impl From<OscBundle> for OscPacket {
+    fn from(bundle: OscBundle) -> OscPacket {
+        OscPacket::Bundle(bundle)
+    }
+}
+
 impl From<String> for OscMessage {
     fn from(s: String) -> OscMessage {
         OscMessage {
@@ -281,7 +1488,7 @@ impl From<String> for OscMessage {
         }
     }
 }
-impl<'a> From<&'a str> for OscMessage {
+impl From<&str> for OscMessage {
     fn from(s: &str) -> OscMessage {
         OscMessage {
             addr: s.to_string(),
@@ -290,7 +1497,7 @@ impl<'a> From<&'a str> for OscMessage {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     use std::time::UNIX_EPOCH;
@@ -303,7 +1510,10 @@ mod tests {
         for time in times {
             for i in 0..1000 {
                 let time = time + Duration::from_nanos(1) * i;
-                assert_eq_system_times(time, SystemTime::from(OscTime::try_from(time).unwrap()));
+                assert_eq_system_times(
+                    time,
+                    SystemTime::try_from(OscTime::try_from(time).unwrap()).unwrap(),
+                );
             }
         }
     }
@@ -312,7 +1522,7 @@ mod tests {
     fn osc_times_can_be_converted_to_and_from_system_times() {
         let mut times = vec![];
         // Sweep across a few numbers to check for tolerance
-        for seconds in vec![
+        for seconds in [
             // We don't start at zero because times before the UNIX_EPOCH cannot be converted to
             // OscTime.
             OscTime::UNIX_OFFSET as u32,
@@ -332,16 +1542,94 @@ mod tests {
         for osc_time in times.into_iter().map(OscTime::from) {
             assert_eq_osc_times(
                 osc_time,
-                OscTime::try_from(SystemTime::from(osc_time)).unwrap(),
+                OscTime::try_from(SystemTime::try_from(osc_time).unwrap()).unwrap(),
             );
         }
     }
 
+    #[test]
+    fn durations_can_be_added_to_and_subtracted_from_osc_times() {
+        let time = OscTime::from((1000, 0));
+        assert_eq!(
+            OscTime::from((1001, 1 << 31)),
+            time + Duration::from_millis(1500)
+        );
+        assert_eq!(
+            OscTime::from((998, 1 << 31)),
+            time - Duration::from_millis(1500)
+        );
+
+        let mut time = time;
+        time += Duration::from_nanos(1);
+        assert_eq_osc_times(OscTime::from((1000, 4)), time);
+        time -= Duration::from_nanos(1);
+        assert_eq!(OscTime::from((1000, 0)), time);
+
+        let later = time + Duration::new(5, 123_456_789);
+        let elapsed = later - time;
+        assert_eq!(5, elapsed.as_secs());
+        assert!((elapsed.subsec_nanos() as i64 - 123_456_789).abs() <= 1);
+
+        assert_eq!(
+            None,
+            OscTime::from((u32::MAX, 0)).checked_add(Duration::from_secs(1))
+        );
+        assert_eq!(
+            None,
+            OscTime::from((0, 0)).checked_sub(Duration::from_nanos(1))
+        );
+        assert_eq!(None, time.checked_add(Duration::from_secs(u64::MAX)));
+    }
+
+    #[test]
+    fn osc_times_can_be_converted_to_and_from_durations() {
+        let duration = Duration::new(OscTime::UNIX_OFFSET, 500_000_000);
+        let time = OscTime::try_from(duration).unwrap();
+        assert_eq!(OscTime::from((OscTime::UNIX_OFFSET as u32, 1 << 31)), time);
+        assert_eq!(duration, time.to_duration());
+        assert_eq!(
+            UNIX_EPOCH + Duration::from_millis(500),
+            SystemTime::try_from(time).unwrap()
+        );
+
+        let last_second = OscTime::from((u32::MAX, 0));
+        assert_eq!(
+            last_second,
+            OscTime::try_from(last_second.to_duration()).unwrap()
+        );
+        assert!(OscTime::try_from(Duration::from_secs(1 << 32)).is_err());
+    }
+
+    #[test]
+    fn osc_time_now_is_close_to_system_time_now() {
+        let before = SystemTime::now();
+        let now = OscTime::now().unwrap();
+        let after = SystemTime::now();
+        assert_eq_system_times(before.min(SystemTime::try_from(now).unwrap()), before);
+        assert_eq_system_times(after.max(SystemTime::try_from(now).unwrap()), after);
+
+        let later = OscTime::from_duration_since_now(Duration::from_secs(60)).unwrap();
+        let delay = later.duration_since(now).unwrap();
+        assert!(delay >= Duration::from_secs(60) && delay < Duration::from_secs(61));
+    }
+
     #[test]
     fn osc_time_cannot_represent_times_before_1970_01_01() {
         assert!(OscTime::try_from(UNIX_EPOCH - Duration::from_secs(1)).is_err())
     }
 
+    #[test]
+    fn immediate_and_pre_1970_osc_times_are_not_system_times() {
+        assert!(SystemTime::try_from(OscTime::IMMEDIATE).is_err());
+        let bundle = OscBundle::builder().build().unwrap();
+        assert!(SystemTime::try_from(bundle.timetag).is_err());
+        assert!(SystemTime::try_from(OscTime::from((OscTime::UNIX_OFFSET as u32 - 1, 0))).is_err());
+        assert_eq!(
+            UNIX_EPOCH,
+            SystemTime::try_from(OscTime::from((OscTime::UNIX_OFFSET as u32, 0))).unwrap()
+        );
+    }
+
     fn assert_eq_system_times(a: SystemTime, b: SystemTime) {
         let difference = if a < b {
             b.duration_since(a).unwrap()
@@ -0,0 +1,1203 @@
+//! Sending and receiving OSC packets over UDP.
+//!
+//! Besides unicast, both the client and the server support multicast to
+//! reach many receivers at once.
+
+#[cfg(feature = "hmac")]
+use crate::auth::{self, HmacSigner};
+use crate::decoder::{self, DecoderOptions};
+use crate::dispatcher::Dispatcher;
+use crate::encoder::{self, Encoder};
+use crate::errors::OscError;
+use crate::filter::AddressFilter;
+use crate::fragment::{Fragmenter, Reassembler};
+use crate::metrics::{Metrics, OscMetricsSink};
+use crate::ratelimit::{RateLimit, RateLimiter};
+use crate::socket::SocketOptions;
+use crate::types::{OscPacket, Result};
+use socket2::SockRef;
+use std::fmt;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, ToSocketAddrs, UdpSocket};
+#[cfg(all(feature = "mio", unix))]
+use std::os::unix::io::AsRawFd;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Sends OSC packets to a single UDP target.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::udp::OscUdpClient;
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let mut client = OscUdpClient::connect("127.0.0.1:9000").unwrap();
+/// client
+///     .send(&OscPacket::Message(OscMessage {
+///         addr: "/synth/freq".to_string(),
+///         args: vec![440.0f32.into()],
+///     }))
+///     .unwrap();
+/// ```
+pub struct OscUdpClient {
+    socket: UdpSocket,
+    target: SocketAddr,
+    encoder: Encoder,
+    metrics: Metrics,
+    limiter: Option<RateLimiter>,
+    max_datagram_size: Option<usize>,
+    fragmenter: Option<Fragmenter>,
+    #[cfg(feature = "hmac")]
+    signer: Option<HmacSigner>,
+    connected: bool,
+    on_error: Option<SendErrorHook>,
+}
+
+/// A callback for packets that couldn't be sent.
+type SendErrorHook = Box<dyn FnMut(&OscError) + Send>;
+
+impl OscUdpClient {
+    /// Creates a client that sends to `target` from a socket bound to an
+    /// ephemeral port. Returns an `OscError::SocketError` if the target
+    /// can't be resolved or the socket can't be bound.
+    pub fn connect<A: ToSocketAddrs>(target: A) -> Result<OscUdpClient> {
+        let target = resolve(target)?;
+        let socket = UdpSocket::bind(unspecified_addr(target)).map_err(OscError::SocketError)?;
+        Ok(OscUdpClient::from_socket(socket, target))
+    }
+
+    /// Like [`connect`](OscUdpClient::connect), but creates the socket with
+    /// the given `options`.
+    pub fn connect_with_options<A: ToSocketAddrs>(
+        target: A,
+        options: &SocketOptions,
+    ) -> Result<OscUdpClient> {
+        let target = resolve(target)?;
+        let socket = options
+            .bind_udp(unspecified_addr(target))
+            .map_err(OscError::SocketError)?;
+        Ok(OscUdpClient::from_socket(socket, target))
+    }
+
+    /// Creates a client that sends to `target` from an existing socket.
+    pub fn from_socket(socket: UdpSocket, target: SocketAddr) -> OscUdpClient {
+        OscUdpClient {
+            socket,
+            target,
+            encoder: Encoder::new(),
+            metrics: None,
+            limiter: None,
+            max_datagram_size: None,
+            fragmenter: None,
+            #[cfg(feature = "hmac")]
+            signer: None,
+            connected: false,
+            on_error: None,
+        }
+    }
+
+    /// Connects the socket to the target, so that it only exchanges
+    /// datagrams with the target. Returns an `OscError::SocketError` if
+    /// connecting fails.
+    ///
+    /// Unlike datagrams sent from an unconnected socket, which vanish
+    /// silently if nothing is listening on the target, a connected socket
+    /// reports the ICMP errors of the target's host: once the host replied
+    /// that the port is unreachable, the next [`send`](OscUdpClient::send)
+    /// fails with an `OscError::WriteError` of the kind
+    /// `io::ErrorKind::ConnectionRefused`. Use
+    /// [`on_error`](OscUdpClient::on_error) to detect a receiver that went
+    /// away.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rosc::udp::OscUdpClient;
+    /// use rosc::OscError;
+    /// use std::io;
+    ///
+    /// let mut client = OscUdpClient::connect("192.168.1.20:9000").unwrap();
+    /// client.connect_socket().unwrap().on_error(|err| {
+    ///     if let OscError::WriteError(ref err) = *err {
+    ///         if err.kind() == io::ErrorKind::ConnectionRefused {
+    ///             eprintln!("the receiver isn't listening");
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn connect_socket(&mut self) -> Result<&mut OscUdpClient> {
+        self.socket
+            .connect(self.target)
+            .map_err(OscError::SocketError)?;
+        self.connected = true;
+        Ok(self)
+    }
+
+    /// Sets the hook that is called with each error
+    /// [`send`](OscUdpClient::send) returns, e.g. to detect an unreachable
+    /// receiver with a connected socket, see
+    /// [`connect_socket`](OscUdpClient::connect_socket).
+    pub fn on_error<F>(&mut self, hook: F) -> &mut OscUdpClient
+    where
+        F: FnMut(&OscError) + Send + 'static,
+    {
+        self.on_error = Some(Box::new(hook));
+        self
+    }
+
+    /// Reports the sent packets to `metrics`.
+    pub fn set_metrics(&mut self, metrics: Arc<dyn OscMetricsSink>) -> &mut OscUdpClient {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Limits the rate at which [`send`](OscUdpClient::send) sends packets, see
+    /// [`RateLimit`]. Packets exceeding the limit are delayed, or rejected
+    /// with an `OscError::RateLimited`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rate of `limit` isn't positive and finite.
+    pub fn set_rate_limit(&mut self, limit: RateLimit) -> &mut OscUdpClient {
+        self.limiter = Some(RateLimiter::new(limit));
+        self
+    }
+
+    /// Limits the size of the datagrams sent, e.g. to the MTU of the
+    /// network. Bundles exceeding it are split by
+    /// [`encoder::split_bundle`] and sent in multiple datagrams, other
+    /// packets exceeding it are rejected. By default the size is only
+    /// limited by the operating system.
+    pub fn set_max_datagram_size(&mut self, size: Option<usize>) -> &mut OscUdpClient {
+        self.max_datagram_size = size;
+        self
+    }
+
+    /// Splits packets larger than `size` bytes into fragments, which a
+    /// server reassembles with a [`Reassembler`], see
+    /// [`fragment`](crate::fragment). `None` disables fragmentation, which
+    /// is the default. Bundles exceeding the maximum datagram size are
+    /// split before fragmenting their parts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is too small to hold a fragment, see
+    /// [`Fragmenter::new`].
+    pub fn set_fragment_size(&mut self, size: Option<usize>) -> &mut OscUdpClient {
+        self.fragmenter = size.map(Fragmenter::new);
+        self
+    }
+
+    /// Signs each datagram with `signer`, see [`auth`](crate::auth). The
+    /// signature counts towards the maximum datagram size, but not towards
+    /// the fragment size.
+    #[cfg(feature = "hmac")]
+    pub fn set_signer(&mut self, signer: HmacSigner) -> &mut OscUdpClient {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Encodes `packet` and sends it to the target. Returns an
+    /// `OscError::WriteError` if sending fails, or an
+    /// `OscError::BadPacket` if the packet exceeds the maximum datagram
+    /// size and can't be split. Errors are passed to the error hook as
+    /// well.
+    pub fn send(&mut self, packet: &OscPacket) -> Result<()> {
+        let result = self.send_packet(packet);
+        if let (Err(ref err), Some(ref mut hook)) = (&result, &mut self.on_error) {
+            hook(err);
+        }
+        result
+    }
+
+    fn send_packet(&mut self, packet: &OscPacket) -> Result<()> {
+        if let (Some(max_size), OscPacket::Bundle(bundle)) = (self.max_datagram_size, packet) {
+            let max_size = max_size.saturating_sub(self.signature_overhead());
+            if encoder::encoded_size(packet) > max_size {
+                for part in encoder::split_bundle(bundle, max_size)? {
+                    self.send_datagram(&OscPacket::Bundle(part))?;
+                }
+                return Ok(());
+            }
+        }
+        self.send_datagram(packet)
+    }
+
+    fn send_datagram(&mut self, packet: &OscPacket) -> Result<()> {
+        let overhead = self.signature_overhead();
+        let OscUdpClient {
+            ref socket,
+            target,
+            ref mut encoder,
+            ref metrics,
+            ref mut limiter,
+            max_datagram_size,
+            ref mut fragmenter,
+            #[cfg(feature = "hmac")]
+            ref signer,
+            connected,
+            ..
+        } = *self;
+        let mut send = |bytes: &[u8]| {
+            #[cfg(feature = "hmac")]
+            let signed = signer.as_ref().map(|signer| signer.sign_encoded(bytes));
+            #[cfg(feature = "hmac")]
+            let bytes = signed.as_deref().unwrap_or(bytes);
+            if let Some(ref mut limiter) = limiter {
+                limiter.acquire(bytes.len())?;
+            }
+            let sent = if connected {
+                socket.send(bytes)
+            } else {
+                socket.send_to(bytes, target)
+            };
+            let len = sent.map_err(OscError::WriteError)?;
+            if let Some(ref metrics) = metrics {
+                metrics.packet_sent(len);
+            }
+            Ok(())
+        };
+
+        let bytes = encoder.encode(packet)?;
+        if let Some(ref mut fragmenter) = fragmenter {
+            if bytes.len() > fragmenter.max_size() {
+                return fragmenter.fragment(bytes).iter().try_for_each(|f| send(f));
+            }
+        }
+        if max_datagram_size.is_some_and(|max_size| bytes.len() + overhead > max_size) {
+            return Err(OscError::BadPacket(
+                "Packet exceeds the maximum datagram size.",
+            ));
+        }
+        send(bytes)
+    }
+
+    /// Returns the number of bytes signing adds to each datagram.
+    fn signature_overhead(&self) -> usize {
+        #[cfg(feature = "hmac")]
+        if self.signer.is_some() {
+            return auth::SIGNATURE_OVERHEAD;
+        }
+        0
+    }
+
+    /// Creates a client that multicasts to `group` through the network
+    /// interface with the address `interface`, with packets passing at most
+    /// `ttl` routers. Use `Ipv4Addr::UNSPECIFIED` to let the system choose
+    /// the interface.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rosc::udp::OscUdpClient;
+    /// use rosc::{OscMessage, OscPacket};
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let group = "239.255.10.1:9000".parse().unwrap();
+    /// let mut client = OscUdpClient::multicast_v4(group, Ipv4Addr::UNSPECIFIED, 1).unwrap();
+    /// client
+    ///     .send(&OscPacket::Message(OscMessage::from("/cue/go")))
+    ///     .unwrap();
+    /// ```
+    pub fn multicast_v4(
+        group: SocketAddrV4,
+        interface: Ipv4Addr,
+        ttl: u32,
+    ) -> Result<OscUdpClient> {
+        let client = OscUdpClient::connect(group)?;
+        client.set_multicast_interface_v4(interface)?;
+        client.set_multicast_ttl_v4(ttl)?;
+        Ok(client)
+    }
+
+    /// Sets the network interface multicast packets are sent through.
+    pub fn set_multicast_interface_v4(&self, interface: Ipv4Addr) -> Result<()> {
+        SockRef::from(&self.socket)
+            .set_multicast_if_v4(&interface)
+            .map_err(OscError::SocketError)
+    }
+
+    /// Sets the index of the network interface IPv6 multicast packets are
+    /// sent through, 0 lets the system choose.
+    pub fn set_multicast_interface_v6(&self, interface: u32) -> Result<()> {
+        SockRef::from(&self.socket)
+            .set_multicast_if_v6(interface)
+            .map_err(OscError::SocketError)
+    }
+
+    /// Sets the number of routers multicast packets may pass, the default
+    /// of 1 keeps them in the local network.
+    pub fn set_multicast_ttl_v4(&self, ttl: u32) -> Result<()> {
+        self.socket
+            .set_multicast_ttl_v4(ttl)
+            .map_err(OscError::SocketError)
+    }
+
+    /// Sets whether multicast packets are delivered to receivers on this
+    /// host as well, which is enabled by default.
+    pub fn set_multicast_loop_v4(&self, enabled: bool) -> Result<()> {
+        self.socket
+            .set_multicast_loop_v4(enabled)
+            .map_err(OscError::SocketError)
+    }
+
+    /// Returns the address packets are sent to.
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    /// Returns the underlying socket.
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+}
+
+impl fmt::Debug for OscUdpClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OscUdpClient")
+            .field("socket", &self.socket)
+            .field("target", &self.target)
+            .field("limiter", &self.limiter)
+            .field("max_datagram_size", &self.max_datagram_size)
+            .field("fragmenter", &self.fragmenter)
+            .field("connected", &self.connected)
+            .finish()
+    }
+}
+
+/// Sends every OSC packet to several UDP targets, e.g. to mirror the
+/// packets for a lighting desk to a recorder.
+///
+/// Each packet is encoded once and sent to all targets from the same
+/// socket. Failing to reach one target doesn't keep the packet from being
+/// sent to the others.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::udp::MultiSender;
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let mut sender = MultiSender::bind("0.0.0.0:0").unwrap();
+/// sender
+///     .add_target("192.168.1.20:8000")
+///     .unwrap()
+///     .add_target("192.168.1.30:9000")
+///     .unwrap();
+/// let packet = OscPacket::Message(OscMessage::from("/cue/go"));
+/// for (target, err) in sender.send(&packet).unwrap() {
+///     eprintln!("couldn't send to {}: {}", target, err);
+/// }
+/// ```
+pub struct MultiSender {
+    socket: UdpSocket,
+    targets: Vec<SocketAddr>,
+    encoder: Encoder,
+    metrics: Metrics,
+}
+
+impl MultiSender {
+    /// Creates a sender without targets that sends from a socket bound to
+    /// `addr`. Returns an `OscError::SocketError` if the socket can't be
+    /// bound.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<MultiSender> {
+        let socket = UdpSocket::bind(addr).map_err(OscError::SocketError)?;
+        Ok(MultiSender::from_socket(socket))
+    }
+
+    /// Creates a sender without targets that sends from an existing socket.
+    pub fn from_socket(socket: UdpSocket) -> MultiSender {
+        MultiSender {
+            socket,
+            targets: vec![],
+            encoder: Encoder::new(),
+            metrics: None,
+        }
+    }
+
+    /// Adds `target` to the targets packets are sent to, unless it was
+    /// added before. Returns an `OscError::SocketError` if it can't be
+    /// resolved.
+    pub fn add_target<A: ToSocketAddrs>(&mut self, target: A) -> Result<&mut MultiSender> {
+        let target = resolve(target)?;
+        if !self.targets.contains(&target) {
+            self.targets.push(target);
+        }
+        Ok(self)
+    }
+
+    /// Stops sending to `target`. Returns whether it was a target.
+    pub fn remove_target(&mut self, target: SocketAddr) -> bool {
+        let len = self.targets.len();
+        self.targets.retain(|t| *t != target);
+        self.targets.len() != len
+    }
+
+    /// Returns the targets in the order they were added.
+    pub fn targets(&self) -> &[SocketAddr] {
+        &self.targets
+    }
+
+    /// Reports the packets sent to each target to `metrics`.
+    pub fn set_metrics(&mut self, metrics: Arc<dyn OscMetricsSink>) -> &mut MultiSender {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Encodes `packet` and sends it to every target. Returns the targets
+    /// sending failed for together with their `OscError::WriteError`, which
+    /// is empty if the packet reached all of them, or the encoding error if
+    /// the packet couldn't be encoded.
+    pub fn send(&mut self, packet: &OscPacket) -> Result<Vec<(SocketAddr, OscError)>> {
+        let bytes = self.encoder.encode(packet)?;
+        let mut failed = vec![];
+        for &target in &self.targets {
+            match self.socket.send_to(bytes, target) {
+                Ok(len) => {
+                    if let Some(ref metrics) = self.metrics {
+                        metrics.packet_sent(len);
+                    }
+                }
+                Err(err) => failed.push((target, OscError::WriteError(err))),
+            }
+        }
+        Ok(failed)
+    }
+
+    /// Returns the underlying socket.
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+}
+
+impl fmt::Debug for MultiSender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MultiSender")
+            .field("socket", &self.socket)
+            .field("targets", &self.targets)
+            .finish()
+    }
+}
+
+/// Receives OSC packets on a UDP socket.
+///
+/// [`run`](OscUdpServer::run) decodes incoming datagrams in a loop and
+/// passes each packet to a callback. Datagrams that can't be decoded are
+/// passed to the error hook set with [`on_error`](OscUdpServer::on_error)
+/// and don't stop the loop.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::dispatcher::Dispatcher;
+/// use rosc::udp::OscUdpServer;
+///
+/// let mut dispatcher = Dispatcher::new();
+/// dispatcher
+///     .on("/synth/freq", |msg| println!("frequency: {:?}", msg.args))
+///     .unwrap();
+///
+/// let mut server = OscUdpServer::bind("0.0.0.0:9000").unwrap();
+/// server.on_error(|err, from| eprintln!("bad packet from {}: {}", from, err));
+/// server.run_dispatcher(&mut dispatcher).unwrap();
+/// ```
+pub struct OscUdpServer {
+    socket: UdpSocket,
+    buf: Vec<u8>,
+    options: DecoderOptions,
+    on_error: Option<ErrorHook>,
+    metrics: Metrics,
+    reassembler: Option<Reassembler>,
+    filter: Option<AddressFilter>,
+    source_filter: Option<SourceFilter>,
+    on_rejected: Option<RejectedHook>,
+    #[cfg(feature = "hmac")]
+    signer: Option<HmacSigner>,
+}
+
+/// A callback for packets that couldn't be decoded.
+type ErrorHook = Box<dyn FnMut(&OscError, SocketAddr) + Send>;
+
+/// A callback for datagrams from senders the source filter rejected.
+type RejectedHook = Box<dyn FnMut(SocketAddr) + Send>;
+
+impl OscUdpServer {
+    /// Creates a server listening on `addr`. Returns an
+    /// `OscError::SocketError` if the socket can't be bound.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<OscUdpServer> {
+        let socket = UdpSocket::bind(addr).map_err(OscError::SocketError)?;
+        Ok(OscUdpServer::from_socket(socket))
+    }
+
+    /// Like [`bind`](OscUdpServer::bind), but creates the socket with the
+    /// given `options`, see [`SocketOptions`].
+    pub fn bind_with_options<A: ToSocketAddrs>(
+        addr: A,
+        options: &SocketOptions,
+    ) -> Result<OscUdpServer> {
+        let socket = options
+            .bind_udp(resolve(addr)?)
+            .map_err(OscError::SocketError)?;
+        Ok(OscUdpServer::from_socket(socket))
+    }
+
+    /// Creates a server receiving on an existing socket.
+    pub fn from_socket(socket: UdpSocket) -> OscUdpServer {
+        OscUdpServer {
+            socket,
+            buf: vec![0; decoder::MAX_DATAGRAM_SIZE],
+            options: DecoderOptions::default(),
+            on_error: None,
+            metrics: None,
+            reassembler: None,
+            filter: None,
+            source_filter: None,
+            on_rejected: None,
+            #[cfg(feature = "hmac")]
+            signer: None,
+        }
+    }
+
+    /// Creates a server receiving the packets multicast to `group` on
+    /// `port`, which joins the group on the network interface with the
+    /// address `interface`. Use `Ipv4Addr::UNSPECIFIED` to let the system
+    /// choose the interface.
+    ///
+    /// The port may be shared with other receivers on the same host.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rosc::udp::OscUdpServer;
+    /// use std::net::Ipv4Addr;
+    ///
+    /// let group = "239.255.10.1".parse().unwrap();
+    /// let mut server = OscUdpServer::bind_multicast_v4(group, 9000, Ipv4Addr::UNSPECIFIED).unwrap();
+    /// let (packet, from) = server.recv().unwrap();
+    /// ```
+    pub fn bind_multicast_v4(
+        group: Ipv4Addr,
+        port: u16,
+        interface: Ipv4Addr,
+    ) -> Result<OscUdpServer> {
+        let options = SocketOptions::new().reuse_address(true);
+        let server = OscUdpServer::bind_with_options((Ipv4Addr::UNSPECIFIED, port), &options)?;
+        server.join_multicast_v4(group, interface)?;
+        Ok(server)
+    }
+
+    /// Joins the multicast `group` on the network interface with the
+    /// address `interface`.
+    pub fn join_multicast_v4(&self, group: Ipv4Addr, interface: Ipv4Addr) -> Result<()> {
+        self.socket
+            .join_multicast_v4(&group, &interface)
+            .map_err(OscError::SocketError)
+    }
+
+    /// Leaves a multicast group joined with
+    /// [`join_multicast_v4`](OscUdpServer::join_multicast_v4).
+    pub fn leave_multicast_v4(&self, group: Ipv4Addr, interface: Ipv4Addr) -> Result<()> {
+        self.socket
+            .leave_multicast_v4(&group, &interface)
+            .map_err(OscError::SocketError)
+    }
+
+    /// Joins the IPv6 multicast `group` on the network interface with the
+    /// index `interface`, 0 lets the system choose.
+    pub fn join_multicast_v6(&self, group: Ipv6Addr, interface: u32) -> Result<()> {
+        self.socket
+            .join_multicast_v6(&group, interface)
+            .map_err(OscError::SocketError)
+    }
+
+    /// Leaves a multicast group joined with
+    /// [`join_multicast_v6`](OscUdpServer::join_multicast_v6).
+    pub fn leave_multicast_v6(&self, group: Ipv6Addr, interface: u32) -> Result<()> {
+        self.socket
+            .leave_multicast_v6(&group, interface)
+            .map_err(OscError::SocketError)
+    }
+
+    /// Decodes received packets using the given `options`, which also
+    /// apply to the packets of the reassembler.
+    pub fn set_decoder_options(&mut self, options: DecoderOptions) -> &mut OscUdpServer {
+        if let Some(ref mut reassembler) = self.reassembler {
+            reassembler.set_decoder_options(options.clone());
+        }
+        self.options = options;
+        self
+    }
+
+    /// Sets the hook that [`run`](OscUdpServer::run) calls with the error
+    /// and sender of each datagram that can't be decoded. Without a hook
+    /// such datagrams are dropped silently.
+    pub fn on_error<F>(&mut self, hook: F) -> &mut OscUdpServer
+    where
+        F: FnMut(&OscError, SocketAddr) + Send + 'static,
+    {
+        self.on_error = Some(Box::new(hook));
+        self
+    }
+
+    /// Reports the received packets and decoding errors to `metrics`.
+    pub fn set_metrics(&mut self, metrics: Arc<dyn OscMetricsSink>) -> &mut OscUdpServer {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Passes received packets through `reassembler`, which collects
+    /// fragments sent by a client with a fragment size and returns the
+    /// reassembled packets, see [`fragment`](crate::fragment). The
+    /// reassembler decodes packets with the server's decoder options.
+    pub fn set_reassembler(&mut self, mut reassembler: Reassembler) -> &mut OscUdpServer {
+        reassembler.set_decoder_options(self.options.clone());
+        self.reassembler = Some(reassembler);
+        self
+    }
+
+    /// Drops the received messages that don't pass `filter`, see
+    /// [`filter`](crate::filter). Packets are only returned if messages
+    /// remain, dropped messages are reported to the metrics sink.
+    pub fn set_filter(&mut self, filter: AddressFilter) -> &mut OscUdpServer {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Drops the datagrams of senders that `filter` doesn't allow without
+    /// decoding them. They are reported to the metrics sink as dropped
+    /// packets and to the hook set with
+    /// [`on_rejected`](OscUdpServer::on_rejected).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rosc::udp::{OscUdpServer, SourceFilter};
+    ///
+    /// let mut filter = SourceFilter::new();
+    /// filter.allow("192.168.10.0/24").unwrap().allow("10.0.0.5").unwrap();
+    ///
+    /// let mut server = OscUdpServer::bind("0.0.0.0:9000").unwrap();
+    /// server
+    ///     .set_source_filter(filter)
+    ///     .on_rejected(|from| eprintln!("ignoring packet from {}", from));
+    /// ```
+    pub fn set_source_filter(&mut self, filter: SourceFilter) -> &mut OscUdpServer {
+        self.source_filter = Some(filter);
+        self
+    }
+
+    /// Sets the hook called with the sender of each datagram the source
+    /// filter rejected, e.g. to log them.
+    pub fn on_rejected<F>(&mut self, hook: F) -> &mut OscUdpServer
+    where
+        F: FnMut(SocketAddr) + Send + 'static,
+    {
+        self.on_rejected = Some(Box::new(hook));
+        self
+    }
+
+    /// Only accepts datagrams signed with the key of `signer`, see
+    /// [`auth`](crate::auth). Datagrams without a valid signature fail to
+    /// decode with an `OscError::BadSignature`.
+    #[cfg(feature = "hmac")]
+    pub fn set_signer(&mut self, signer: HmacSigner) -> &mut OscUdpServer {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Waits for the next datagram and returns its decoded packet together
+    /// with the sender. Returns an `OscError::ReadError` if receiving
+    /// fails, or the decoding error if the datagram is malformed. With a
+    /// reassembler this waits until a packet is complete.
+    pub fn recv(&mut self) -> Result<(OscPacket, SocketAddr)> {
+        loop {
+            let (len, from) = self.recv_datagram()?;
+            if let Some(packet) = self.process(len, from)? {
+                return Ok((packet, from));
+            }
+        }
+    }
+
+    /// Like [`recv`](OscUdpServer::recv), but gives up after `timeout`
+    /// with an `OscError::ReadError` of the kind `io::ErrorKind::TimedOut`.
+    /// The read timeout of the socket is restored afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rosc::udp::OscUdpServer;
+    /// use rosc::OscError;
+    /// use std::io;
+    /// use std::time::Duration;
+    ///
+    /// let mut server = OscUdpServer::bind("0.0.0.0:9000").unwrap();
+    /// match server.recv_timeout(Duration::from_secs(5)) {
+    ///     Ok((packet, from)) => println!("{}: {:?}", from, packet),
+    ///     Err(OscError::ReadError(ref err)) if err.kind() == io::ErrorKind::TimedOut => {
+    ///         eprintln!("the sender went silent")
+    ///     }
+    ///     Err(err) => eprintln!("receiving failed: {}", err),
+    /// }
+    /// ```
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<(OscPacket, SocketAddr)> {
+        let deadline = Instant::now() + timeout;
+        self.restoring_read_timeout(|server| loop {
+            server
+                .socket
+                .set_read_timeout(Some(time_left(deadline)?))
+                .map_err(OscError::SocketError)?;
+            let (len, from) = server.recv_datagram().map_err(timed_out)?;
+            if let Some(packet) = server.process(len, from)? {
+                return Ok((packet, from));
+            }
+        })
+    }
+
+    /// Calls `f` and restores the read timeout of the socket afterwards.
+    fn restoring_read_timeout<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut OscUdpServer) -> Result<T>,
+    {
+        let previous = self.socket.read_timeout().map_err(OscError::SocketError)?;
+        let result = f(self);
+        self.socket
+            .set_read_timeout(previous)
+            .map_err(OscError::SocketError)?;
+        result
+    }
+
+    /// Returns the next packet that was already received together with its
+    /// sender, or `None` if no complete packet is pending. Returns an
+    /// `OscError::ReadError` if receiving fails, or the decoding error if
+    /// the next datagram is malformed, after which the following ones can
+    /// still be received.
+    ///
+    /// This only returns immediately on a non-blocking socket, see
+    /// [`set_nonblocking`](OscUdpServer::set_nonblocking), otherwise it
+    /// waits like [`recv`](OscUdpServer::recv).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rosc::udp::OscUdpServer;
+    ///
+    /// let mut server = OscUdpServer::bind("0.0.0.0:9000").unwrap();
+    /// server.set_nonblocking(true).unwrap();
+    /// loop {
+    ///     // once per frame, handle all packets received since the last one
+    ///     loop {
+    ///         match server.try_recv() {
+    ///             Ok(Some((packet, from))) => println!("{}: {:?}", from, packet),
+    ///             Ok(None) => break,
+    ///             Err(err) => eprintln!("bad packet: {}", err),
+    ///         }
+    ///     }
+    ///     // update and render the frame
+    /// }
+    /// ```
+    pub fn try_recv(&mut self) -> Result<Option<(OscPacket, SocketAddr)>> {
+        loop {
+            let (len, from) = match self.recv_datagram() {
+                Ok(datagram) => datagram,
+                Err(ref err) if would_block(err) => return Ok(None),
+                Err(err) => return Err(err),
+            };
+            if let Some(packet) = self.process(len, from)? {
+                return Ok(Some((packet, from)));
+            }
+        }
+    }
+
+    /// Sets whether the socket is in non-blocking mode, in which
+    /// [`try_recv`](OscUdpServer::try_recv) returns immediately and
+    /// [`recv`](OscUdpServer::recv) fails with an `OscError::ReadError` of
+    /// the kind `io::ErrorKind::WouldBlock` if nothing was received.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        self.socket
+            .set_nonblocking(nonblocking)
+            .map_err(OscError::SocketError)
+    }
+
+    /// Receives packets and calls `handler` with each of them and their
+    /// sender. Malformed packets are passed to the error hook.
+    ///
+    /// Only returns if receiving from the socket fails.
+    pub fn run<F>(&mut self, mut handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        loop {
+            let (len, from) = self.recv_datagram()?;
+            self.handle(len, from, &mut handler);
+        }
+    }
+
+    /// Like [`run`](OscUdpServer::run), but returns once `deadline` has
+    /// passed, e.g. to check for a shutdown request regularly. The read
+    /// timeout of the socket is restored afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rosc::udp::OscUdpServer;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let shutdown = AtomicBool::new(false);
+    /// let mut server = OscUdpServer::bind("0.0.0.0:9000").unwrap();
+    /// while !shutdown.load(Ordering::Relaxed) {
+    ///     let deadline = Instant::now() + Duration::from_millis(500);
+    ///     server
+    ///         .run_until(deadline, |packet, from| println!("{}: {:?}", from, packet))
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn run_until<F>(&mut self, deadline: Instant, mut handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        self.restoring_read_timeout(|server| loop {
+            let timeout = match time_left(deadline) {
+                Ok(timeout) => timeout,
+                Err(_) => return Ok(()),
+            };
+            server
+                .socket
+                .set_read_timeout(Some(timeout))
+                .map_err(OscError::SocketError)?;
+            match server.recv_datagram().map_err(timed_out) {
+                Ok((len, from)) => server.handle(len, from, &mut handler),
+                Err(OscError::ReadError(ref err)) if err.kind() == io::ErrorKind::TimedOut => (),
+                Err(err) => return Err(err),
+            }
+        })
+    }
+
+    /// Handles the datagrams received since the socket became readable
+    /// with `handler`, until none are left. Malformed packets are passed to
+    /// the error hook. Returns an `OscError::ReadError` if receiving fails.
+    ///
+    /// Call this whenever a [`mio::Poll`] reports the registered server as
+    /// readable. Registering puts the socket into non-blocking mode. Only
+    /// available on Unix.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # extern crate mio;
+    /// # extern crate rosc;
+    /// use mio::{Events, Interest, Poll, Token};
+    /// use rosc::udp::OscUdpServer;
+    ///
+    /// const OSC: Token = Token(0);
+    ///
+    /// let mut poll = Poll::new().unwrap();
+    /// let mut server = OscUdpServer::bind("0.0.0.0:9000").unwrap();
+    /// poll.registry()
+    ///     .register(&mut server, OSC, Interest::READABLE)
+    ///     .unwrap();
+    ///
+    /// let mut events = Events::with_capacity(16);
+    /// loop {
+    ///     poll.poll(&mut events, None).unwrap();
+    ///     for event in events.iter() {
+    ///         if event.token() == OSC {
+    ///             server
+    ///                 .ready(|packet, from| println!("{}: {:?}", from, packet))
+    ///                 .unwrap();
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(all(feature = "mio", unix))]
+    pub fn ready<F>(&mut self, mut handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        loop {
+            let (len, from) = match self.recv_datagram() {
+                Ok(datagram) => datagram,
+                Err(ref err) if would_block(err) => return Ok(()),
+                Err(err) => return Err(err),
+            };
+            self.handle(len, from, &mut handler);
+        }
+    }
+
+    /// Processes the first `len` bytes of the buffer and passes the packet
+    /// to `handler`, or the error to the error hook.
+    fn handle<F>(&mut self, len: usize, from: SocketAddr, handler: &mut F)
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        match self.process(len, from) {
+            Ok(Some(packet)) => handler(packet, from),
+            Ok(None) => (),
+            Err(err) => {
+                if let Some(ref mut hook) = self.on_error {
+                    hook(&err, from);
+                }
+            }
+        }
+    }
+
+    /// Like [`run`](OscUdpServer::run), but passes each packet to
+    /// `dispatcher`.
+    pub fn run_dispatcher(&mut self, dispatcher: &mut Dispatcher) -> Result<()> {
+        self.run(|packet, _| {
+            dispatcher.dispatch(&packet);
+        })
+    }
+
+    /// Decodes the first `len` bytes of the buffer and passes the packet
+    /// to the reassembler and the filter. Returns `None` for fragments of
+    /// packets that aren't complete yet, for filtered messages and for
+    /// datagrams of rejected senders.
+    fn process(&mut self, len: usize, from: SocketAddr) -> Result<Option<OscPacket>> {
+        if let Some(ref filter) = self.source_filter {
+            if !filter.is_allowed(from.ip()) {
+                if let Some(ref metrics) = self.metrics {
+                    metrics.packet_dropped(len);
+                }
+                if let Some(ref mut hook) = self.on_rejected {
+                    hook(from);
+                }
+                return Ok(None);
+            }
+        }
+        let packet = self.decode(len)?;
+        let packet = match self.reassembler {
+            Some(ref mut reassembler) => reassembler.push(packet, from)?,
+            None => Some(packet),
+        };
+        Ok(packet.and_then(|packet| filter_packet(&self.filter, &self.metrics, packet)))
+    }
+
+    /// Verifies and decodes the first `len` bytes of the buffer and reports
+    /// the result to the metrics sink.
+    fn decode(&self, len: usize) -> Result<OscPacket> {
+        let decoded = self
+            .verify(&self.buf[..len])
+            .and_then(|data| decoder::decode_with_options(data, &self.options));
+        if let Some(ref metrics) = self.metrics {
+            match decoded {
+                Ok(_) => metrics.packet_received(len),
+                Err(ref err) => metrics.decode_error(err),
+            }
+        }
+        decoded
+    }
+
+    /// Checks the signature of `data` if the server has a signer and
+    /// returns the signed packet.
+    fn verify<'a>(&self, data: &'a [u8]) -> Result<&'a [u8]> {
+        #[cfg(feature = "hmac")]
+        if let Some(ref signer) = self.signer {
+            return signer.verify(data);
+        }
+        Ok(data)
+    }
+
+    /// Waits for the next datagram and returns its length and sender.
+    fn recv_datagram(&mut self) -> Result<(usize, SocketAddr)> {
+        self.socket
+            .recv_from(&mut self.buf)
+            .map_err(OscError::ReadError)
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.socket.local_addr().map_err(OscError::SocketError)
+    }
+
+    /// Returns the underlying socket.
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+}
+
+impl fmt::Debug for OscUdpServer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OscUdpServer")
+            .field("socket", &self.socket)
+            .field("options", &self.options)
+            .field("filter", &self.filter)
+            .field("source_filter", &self.source_filter)
+            .finish()
+    }
+}
+
+/// Lets a [`mio::Poll`] wait for the server to become readable, see
+/// [`ready`](OscUdpServer::ready).
+///
+/// Only the UDP server can be registered. The TCP and WebSocket servers
+/// read each connection on its own thread instead.
+#[cfg(all(feature = "mio", unix))]
+impl mio::event::Source for OscUdpServer {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        self.socket.set_nonblocking(true)?;
+        mio::unix::SourceFd(&self.socket.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        mio::unix::SourceFd(&self.socket.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        mio::unix::SourceFd(&self.socket.as_raw_fd()).deregister(registry)
+    }
+}
+
+/// The IP addresses and subnets a [`OscUdpServer`] accepts datagrams from,
+/// see [`set_source_filter`](OscUdpServer::set_source_filter).
+///
+/// OSC has no authentication, so on a shared network this at least keeps
+/// other hosts from controlling the receiver. Note that the sender address
+/// of a UDP datagram can be spoofed.
+///
+/// # Example
+///
+/// ```
+/// use rosc::udp::SourceFilter;
+///
+/// let mut filter = SourceFilter::new();
+/// filter.allow("192.168.10.0/24").unwrap().allow("::1").unwrap();
+///
+/// assert!(filter.is_allowed("192.168.10.42".parse().unwrap()));
+/// assert!(filter.is_allowed("::1".parse().unwrap()));
+/// assert!(!filter.is_allowed("192.168.11.1".parse().unwrap()));
+/// assert!(filter.allow("192.168.10.0/33").is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceFilter {
+    /// The allowed networks and the lengths of their prefixes.
+    subnets: Vec<(IpAddr, u8)>,
+}
+
+impl SourceFilter {
+    /// Creates a filter that doesn't allow any sender.
+    pub fn new() -> SourceFilter {
+        SourceFilter::default()
+    }
+
+    /// Allows the senders in `subnet`, an IP address optionally followed by
+    /// `/` and the length of the network prefix, e.g. `10.0.0.0/8`. Returns
+    /// an `OscError::BadAddress` if it can't be parsed.
+    pub fn allow(&mut self, subnet: &str) -> Result<&mut SourceFilter> {
+        let invalid = || OscError::BadAddress("Invalid subnet.");
+        let (ip, prefix_len) = match subnet.split_once('/') {
+            Some((ip, prefix_len)) => (ip, Some(prefix_len)),
+            None => (subnet, None),
+        };
+        let ip: IpAddr = ip.parse().map_err(|_| invalid())?;
+        let max_len = if ip.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(len) => len.parse().map_err(|_| invalid())?,
+            None => max_len,
+        };
+        if prefix_len > max_len {
+            return Err(invalid());
+        }
+        self.subnets.push((ip, prefix_len));
+        Ok(self)
+    }
+
+    /// Returns whether datagrams from `ip` are accepted. IPv4 addresses
+    /// mapped to IPv6, as dual-stack sockets report IPv4 senders, are
+    /// treated as IPv4 addresses.
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.subnets
+            .iter()
+            .any(|&(network, prefix_len)| match (network, ip) {
+                (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                    let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+                    u32::from(network) & mask == u32::from(ip) & mask
+                }
+                (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                    let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+                    u128::from(network) & mask == u128::from(ip) & mask
+                }
+                _ => false,
+            })
+    }
+}
+
+/// Passes `packet` through `filter`, if any, and reports the dropped
+/// messages to `metrics`.
+pub(crate) fn filter_packet(
+    filter: &Option<AddressFilter>,
+    metrics: &Metrics,
+    packet: OscPacket,
+) -> Option<OscPacket> {
+    match *filter {
+        Some(ref filter) => filter.filter_packet(packet, |msg| {
+            if let Some(ref metrics) = *metrics {
+                metrics.message_filtered(&msg.addr);
+            }
+        }),
+        None => Some(packet),
+    }
+}
+
+/// Returns the wildcard address of the same family as `addr` with an
+/// ephemeral port.
+pub(crate) fn unspecified_addr(addr: SocketAddr) -> SocketAddr {
+    match addr {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    }
+}
+
+/// Returns whether receiving failed only because nothing was received on a
+/// non-blocking socket.
+fn would_block(err: &OscError) -> bool {
+    match *err {
+        OscError::ReadError(ref err) => err.kind() == io::ErrorKind::WouldBlock,
+        _ => false,
+    }
+}
+
+/// Returns the time left until `deadline`, or an `OscError::ReadError` of
+/// the kind `io::ErrorKind::TimedOut` if it has passed.
+pub(crate) fn time_left(deadline: Instant) -> Result<Duration> {
+    match deadline.checked_duration_since(Instant::now()) {
+        Some(left) if !left.is_zero() => Ok(left),
+        _ => Err(OscError::ReadError(io::ErrorKind::TimedOut.into())),
+    }
+}
+
+/// Reports reads that exceeded the read timeout, which some platforms fail
+/// with `io::ErrorKind::WouldBlock`, as `io::ErrorKind::TimedOut`.
+pub(crate) fn timed_out(err: OscError) -> OscError {
+    match err {
+        OscError::ReadError(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+            OscError::ReadError(io::ErrorKind::TimedOut.into())
+        }
+        err => err,
+    }
+}
+
+/// Returns the first socket address `addr` resolves to.
+pub(crate) fn resolve<A: ToSocketAddrs>(addr: A) -> Result<SocketAddr> {
+    addr.to_socket_addrs()
+        .map_err(OscError::SocketError)?
+        .next()
+        .ok_or_else(|| {
+            OscError::SocketError(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "address resolved to nothing",
+            ))
+        })
+}
@@ -0,0 +1,328 @@
+//! Sending and receiving OSC packets over WebSocket connections.
+//!
+//! Every packet is sent as one binary frame, which is how browser based
+//! control surfaces usually speak OSC.
+
+use crate::decoder::{self, DecoderOptions};
+use crate::dispatcher::Dispatcher;
+use crate::encoder::Encoder;
+use crate::errors::{DecodeLimit, OscError};
+use crate::server::{Connection, ErrorHook, Received, Runner};
+use crate::tcp;
+use crate::types::{OscPacket, Result};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use tungstenite::handshake::{HandshakeError, HandshakeRole};
+use tungstenite::protocol::WebSocketConfig;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Bytes, Message, WebSocket};
+
+/// How long the server waits for a peer to complete the WebSocket
+/// handshake unless another timeout is set.
+pub const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends and receives OSC packets over a WebSocket connection.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::websocket;
+/// use rosc::{OscMessage, OscPacket};
+///
+/// let mut socket = websocket::connect("ws://127.0.0.1:8080").unwrap();
+/// socket
+///     .send(&OscPacket::Message(OscMessage::from("/status")))
+///     .unwrap();
+/// println!("reply: {:?}", socket.recv().unwrap());
+/// ```
+pub struct OscWebSocket<S> {
+    socket: WebSocket<S>,
+    encoder: Encoder,
+    options: DecoderOptions,
+}
+
+/// Connects to the WebSocket server at `url`, e.g. `ws://10.0.0.2:8080`.
+/// Returns an `OscError::SocketError` if the connection or the WebSocket
+/// handshake fails.
+pub fn connect(url: &str) -> Result<OscWebSocket<MaybeTlsStream<TcpStream>>> {
+    let (socket, _) =
+        tungstenite::connect(url).map_err(|err| OscError::SocketError(io_error(err)))?;
+    Ok(OscWebSocket::from_socket(socket))
+}
+
+impl<S: Read + Write> OscWebSocket<S> {
+    /// Creates an OSC connection from an established WebSocket.
+    pub fn from_socket(socket: WebSocket<S>) -> OscWebSocket<S> {
+        OscWebSocket {
+            socket,
+            encoder: Encoder::new(),
+            options: DecoderOptions::default(),
+        }
+    }
+
+    /// Decodes received packets using the given `options`.
+    pub fn set_decoder_options(&mut self, options: DecoderOptions) -> &mut OscWebSocket<S> {
+        self.options = options;
+        self
+    }
+
+    /// Encodes `packet` and sends it as a binary frame. Returns an
+    /// `OscError::WriteError` if sending fails.
+    pub fn send(&mut self, packet: &OscPacket) -> Result<()> {
+        let bytes = self.encoder.encode(packet)?;
+        self.socket
+            .send(Message::Binary(Bytes::copy_from_slice(bytes)))
+            .map_err(|err| OscError::WriteError(io_error(err)))
+    }
+
+    /// Waits for the next binary frame and decodes it. Returns an
+    /// `OscError::ReadError` if receiving fails or the connection was
+    /// closed, an `OscError::LimitExceeded` if the message exceeds the
+    /// maximum size of the WebSocket, an `OscError::BadPacket` for a text
+    /// frame, or the decoding error if the packet is malformed.
+    pub fn recv(&mut self) -> Result<OscPacket> {
+        let data = self.read_binary()?;
+        decoder::decode_with_options(&data, &self.options)
+    }
+
+    /// Waits for the next binary frame, skipping control frames.
+    fn read_binary(&mut self) -> Result<Bytes> {
+        loop {
+            match self.socket.read() {
+                Ok(Message::Binary(data)) => return Ok(data),
+                Ok(Message::Text(_)) => {
+                    return Err(OscError::BadPacket("Expected a binary frame, got text."))
+                }
+                Ok(_) => continue,
+                Err(tungstenite::Error::Capacity(_)) => {
+                    return Err(OscError::LimitExceeded(DecodeLimit::PacketSize))
+                }
+                Err(err) => return Err(OscError::ReadError(io_error(err))),
+            }
+        }
+    }
+
+    /// Closes the connection.
+    pub fn close(&mut self) -> Result<()> {
+        self.socket
+            .close(None)
+            .map_err(|err| OscError::WriteError(io_error(err)))
+    }
+
+    /// Returns the underlying WebSocket.
+    pub fn socket(&self) -> &WebSocket<S> {
+        &self.socket
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for OscWebSocket<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OscWebSocket")
+            .field("socket", &self.socket)
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+/// Accepts WebSocket connections and receives OSC packets from them.
+///
+/// Like [`OscTcpServer`](crate::tcp::OscTcpServer),
+/// [`run`](OscWsServer::run) serves any number of connections at once and
+/// passes the packets received from them to a callback on the calling
+/// thread.
+///
+/// # Example
+///
+/// ```no_run
+/// use rosc::dispatcher::Dispatcher;
+/// use rosc::websocket::OscWsServer;
+///
+/// let mut dispatcher = Dispatcher::new();
+/// dispatcher
+///     .on("/fader/*", |msg| println!("{}: {:?}", msg.addr, msg.args))
+///     .unwrap();
+///
+/// let mut server = OscWsServer::bind("0.0.0.0:8080").unwrap();
+/// server.run_dispatcher(&mut dispatcher).unwrap();
+/// ```
+pub struct OscWsServer {
+    listener: TcpListener,
+    options: DecoderOptions,
+    handshake_timeout: Duration,
+    on_error: Option<ErrorHook>,
+    runner: Runner,
+}
+
+impl OscWsServer {
+    /// Creates a server listening on `addr`. Returns an
+    /// `OscError::SocketError` if the socket can't be bound.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<OscWsServer> {
+        let listener = TcpListener::bind(addr).map_err(OscError::SocketError)?;
+        Ok(OscWsServer::from_listener(listener))
+    }
+
+    /// Creates a server accepting connections on an existing listener.
+    pub fn from_listener(listener: TcpListener) -> OscWsServer {
+        OscWsServer {
+            listener,
+            options: tcp::default_options(),
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+            on_error: None,
+            runner: Runner::default(),
+        }
+    }
+
+    /// Decodes received packets using the given `options`. Defaults to
+    /// [`DecoderOptions::default`] limited to packets of
+    /// [`DEFAULT_MAX_PACKET_SIZE`](crate::tcp::DEFAULT_MAX_PACKET_SIZE)
+    /// bytes. Connections receiving a message exceeding the maximum packet
+    /// size are closed.
+    pub fn set_decoder_options(&mut self, options: DecoderOptions) -> &mut OscWsServer {
+        self.options = options;
+        self
+    }
+
+    /// Closes connections whose peer doesn't complete the WebSocket
+    /// handshake within `timeout`. Defaults to
+    /// [`DEFAULT_HANDSHAKE_TIMEOUT`].
+    pub fn set_handshake_timeout(&mut self, timeout: Duration) -> &mut OscWsServer {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// Sets the hook that [`run`](OscWsServer::run) calls with the error
+    /// and sender of each packet that can't be decoded and of each
+    /// connection whose handshake failed. Without a hook such errors are
+    /// dropped silently.
+    pub fn on_error<F>(&mut self, hook: F) -> &mut OscWsServer
+    where
+        F: FnMut(&OscError, SocketAddr) + Send + 'static,
+    {
+        self.on_error = Some(Box::new(hook));
+        self
+    }
+
+    /// Waits for the next connection and performs the WebSocket handshake.
+    /// Returns an `OscError::SocketError` if accepting or the handshake
+    /// fails, or the handshake times out.
+    pub fn accept(&self) -> Result<(OscWebSocket<TcpStream>, SocketAddr)> {
+        let (stream, addr) = self.listener.accept().map_err(OscError::SocketError)?;
+        Ok((
+            handshake(stream, &self.options, self.handshake_timeout)?,
+            addr,
+        ))
+    }
+
+    /// Accepts connections and calls `handler` with each packet received
+    /// from them and their sender. Every connection is read on its own
+    /// thread until it is closed.
+    ///
+    /// Only returns if accepting a connection fails.
+    pub fn run<F>(&mut self, handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        self.serve(None, handler)
+    }
+
+    /// Like [`run`](OscWsServer::run), but returns once `deadline` has
+    /// passed, see [`OscTcpServer::run_until`](crate::tcp::OscTcpServer::run_until).
+    pub fn run_until<F>(&mut self, deadline: Instant, handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        self.serve(Some(deadline), handler)
+    }
+
+    fn serve<F>(&mut self, deadline: Option<Instant>, handler: F) -> Result<()>
+    where
+        F: FnMut(OscPacket, SocketAddr),
+    {
+        let options = self.options.clone();
+        let timeout = self.handshake_timeout;
+        let open = move |stream| handshake(stream, &options, timeout);
+        self.runner
+            .run(&self.listener, open, &mut self.on_error, deadline, handler)
+    }
+
+    /// Like [`run`](OscWsServer::run), but passes each packet to
+    /// `dispatcher`.
+    pub fn run_dispatcher(&mut self, dispatcher: &mut Dispatcher) -> Result<()> {
+        self.run(|packet, _| {
+            dispatcher.dispatch(&packet);
+        })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(OscError::SocketError)
+    }
+}
+
+impl fmt::Debug for OscWsServer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OscWsServer")
+            .field("listener", &self.listener)
+            .field("options", &self.options)
+            .field("handshake_timeout", &self.handshake_timeout)
+            .finish()
+    }
+}
+
+/// Performs the server side of the WebSocket handshake on `stream`, and
+/// limits the size of received messages to the maximum packet size.
+fn handshake(
+    stream: TcpStream,
+    options: &DecoderOptions,
+    timeout: Duration,
+) -> Result<OscWebSocket<TcpStream>> {
+    stream
+        .set_read_timeout(Some(timeout))
+        .map_err(OscError::SocketError)?;
+    let config = WebSocketConfig::default()
+        .max_message_size(Some(options.max_packet_size))
+        .max_frame_size(Some(options.max_packet_size));
+    let socket = tungstenite::accept_with_config(stream, Some(config))
+        .map_err(|err| OscError::SocketError(handshake_error(err)))?;
+    socket
+        .get_ref()
+        .set_read_timeout(None)
+        .map_err(OscError::SocketError)?;
+    let mut socket = OscWebSocket::from_socket(socket);
+    socket.set_decoder_options(options.clone());
+    Ok(socket)
+}
+
+impl Connection for OscWebSocket<TcpStream> {
+    fn read_packet(&mut self) -> Received {
+        match self.read_binary() {
+            Ok(data) => match decoder::decode_with_options(&data, &self.options) {
+                Ok(packet) => Received::Packet(packet),
+                Err(err) => Received::BadPacket(err),
+            },
+            Err(OscError::ReadError(_)) => Received::Closed(None),
+            // the WebSocket can't be read anymore
+            Err(err @ OscError::LimitExceeded(_)) => Received::Closed(Some(err)),
+            Err(err) => Received::BadPacket(err),
+        }
+    }
+}
+
+/// Converts a WebSocket error into an I/O error.
+fn io_error(err: tungstenite::Error) -> io::Error {
+    match err {
+        tungstenite::Error::Io(err) => err,
+        err => io::Error::other(err),
+    }
+}
+
+/// Converts a failed WebSocket handshake into an I/O error. The handshake
+/// is only interrupted if the peer didn't complete it in time.
+fn handshake_error<R: HandshakeRole>(err: HandshakeError<R>) -> io::Error {
+    match err {
+        HandshakeError::Failure(err) => io_error(err),
+        HandshakeError::Interrupted(_) => io::ErrorKind::TimedOut.into(),
+    }
+}
@@ -0,0 +1,73 @@
+extern crate rosc;
+
+use rosc::address;
+
+#[test]
+fn test_validate() {
+    for addr in &["/", "/synth", "/synth/1/freq", "/synth/*/{freq,amp}"] {
+        assert!(address::validate(addr).is_ok(), "{}", addr);
+    }
+    for addr in &["", "synth", "/synth freq", "/synth#1", "/synth\n"] {
+        assert!(address::validate(addr).is_err(), "{:?}", addr);
+    }
+}
+
+#[test]
+fn test_is_valid() {
+    let addrs = [
+        "",
+        "/",
+        "/synth",
+        "/synth/*/{freq,amp}",
+        "/synth freq",
+        "synth",
+        "/synth#1",
+        "/synth\n",
+        "/synth\u{7f}",
+        "/synth\u{85}",
+        "/synth\u{a0}",
+        "/dé/ü",
+    ];
+    for addr in &addrs {
+        assert_eq!(
+            address::validate(addr).is_ok(),
+            address::is_valid(addr),
+            "{:?}",
+            addr
+        );
+    }
+}
+
+#[test]
+fn test_validate_method() {
+    assert!(address::validate_method("/synth/1/freq").is_ok());
+    for addr in &[
+        "/",
+        "/synth/",
+        "/synth//freq",
+        "/synth/*",
+        "/synth/?",
+        "/synth/[12]",
+        "/synth/{a,b}",
+        "/synth freq",
+    ] {
+        assert!(address::validate_method(addr).is_err(), "{:?}", addr);
+    }
+}
+
+#[test]
+fn test_is_pattern() {
+    assert!(address::is_pattern("/synth/*"));
+    assert!(address::is_pattern("/synth/{a,b}"));
+    assert!(!address::is_pattern("/synth/1/freq"));
+}
+
+#[test]
+fn test_split() {
+    let parts = |addr| address::split(addr).collect::<Vec<_>>();
+    assert_eq!(Vec::<&str>::new(), parts("/"));
+    assert_eq!(vec!["synth"], parts("/synth"));
+    assert_eq!(vec!["synth", "1", "freq"], parts("/synth/1/freq"));
+    assert_eq!(vec!["synth", ""], parts("/synth/"));
+    assert_eq!(vec!["synth", "", "freq"], parts("/synth//freq"));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "arbitrary")]
+
+extern crate arbitrary;
+extern crate rosc;
+
+use arbitrary::{Arbitrary, Unstructured};
+use rosc::{decoder, encoder, OscPacket};
+
+/// Small xorshift generator to produce reproducible input for `Unstructured`.
+struct XorShift(u64);
+
+impl XorShift {
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len)
+            .map(|_| {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                self.0 as u8
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_arbitrary_packets_round_trip() {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    let mut bundles = 0;
+    for _ in 0..2_000 {
+        let data = rng.bytes(512);
+        let packet = OscPacket::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        if let OscPacket::Bundle(_) = packet {
+            bundles += 1;
+        }
+        let encoded = encoder::encode(&packet).unwrap();
+        assert_eq!(packet, decoder::decode(&encoded).unwrap());
+    }
+    assert!(bundles > 0);
+}
+
+#[test]
+fn test_arbitrary_packets_from_short_input() {
+    for len in 0..64 {
+        let data = vec![0xa5; len];
+        let packet = OscPacket::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        let encoded = encoder::encode(&packet).unwrap();
+        assert_eq!(packet, decoder::decode(&encoded).unwrap());
+    }
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "bumpalo")]
+
+extern crate bumpalo;
+extern crate rosc;
+
+use bumpalo::Bump;
+use rosc::{
+    decoder, encoder, OscArray, OscBundle, OscMessage, OscPacket, OscPacketArena, OscType,
+    OscTypeArena,
+};
+
+#[test]
+fn test_decode_in_matches_decode() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: (1, 2).into(),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/sensor/1".to_string(),
+                args: vec![
+                    "label".into(),
+                    vec![1u8, 2, 3].into(),
+                    OscType::Array(OscArray {
+                        content: vec![1i32.into(), 2.5f64.into()],
+                    }),
+                    OscType::Inf,
+                ],
+            }),
+            OscPacket::Message(OscMessage::from("/empty")),
+        ],
+    });
+    let buf = encoder::encode(&packet).unwrap();
+    let bump = Bump::new();
+    let decoded = decoder::decode_in(&buf, &bump).unwrap();
+    assert_eq!(packet, OscPacket::from(decoded));
+}
+
+#[test]
+fn test_decode_in_outlives_buffer() {
+    let bump = Bump::new();
+    let packet = {
+        let buf = encoder::encode(&OscPacket::Message(OscMessage {
+            addr: "/sensor/2".to_string(),
+            args: vec!["hot".into(), vec![9u8; 10].into()],
+        }))
+        .unwrap();
+        decoder::decode_in(&buf, &bump).unwrap()
+    };
+    match packet {
+        OscPacketArena::Message(msg) => {
+            assert_eq!("/sensor/2", msg.addr);
+            assert_eq!(
+                &[OscTypeArena::String("hot"), OscTypeArena::Blob(&[9u8; 10])],
+                msg.args
+            );
+        }
+        OscPacketArena::Bundle(_) => panic!("expected a message"),
+    }
+    assert!(bump.allocated_bytes() > 0);
+}
+
+#[test]
+fn test_decode_in_rejects_malformed_packets() {
+    let bump = Bump::new();
+    assert!(decoder::decode_in(b"/a\0\0,s\0\0", &bump).is_err());
+    assert!(decoder::decode_in(b"", &bump).is_err());
+}
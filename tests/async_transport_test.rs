@@ -0,0 +1,51 @@
+#![cfg(feature = "async")]
+
+extern crate futures_lite;
+extern crate rosc;
+
+use futures_lite::future::block_on;
+use rosc::async_transport::{AsyncOscSink, AsyncOscStream, AsyncOscUdpClient, AsyncOscUdpServer};
+use rosc::{OscMessage, OscPacket};
+use std::net::UdpSocket;
+
+#[test]
+fn test_async_udp_send_and_recv() {
+    let mut server = AsyncOscUdpServer::bind("127.0.0.1:0").unwrap();
+    let mut client = AsyncOscUdpClient::connect(server.local_addr().unwrap()).unwrap();
+    assert_eq!(server.local_addr().unwrap(), client.target());
+
+    let packets = vec![
+        OscPacket::Message(OscMessage::from("/first")),
+        OscPacket::Message(OscMessage::from("/second")),
+    ];
+    for packet in &packets {
+        block_on(client.send(packet)).unwrap();
+    }
+    for packet in packets {
+        let (received, from) = block_on(server.recv()).unwrap();
+        assert_eq!(packet, received);
+        assert_eq!(client.socket().local_addr().unwrap().port(), from.port());
+    }
+
+    // datagrams larger than the MTU aren't truncated
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/blob".to_string(),
+        args: vec![vec![7u8; 4096].into()],
+    });
+    block_on(client.send(&packet)).unwrap();
+    assert_eq!(packet, block_on(server.recv()).unwrap().0);
+}
+
+#[test]
+fn test_async_udp_recv_malformed() {
+    let mut server = AsyncOscUdpServer::bind("127.0.0.1:0").unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let target = server.local_addr().unwrap();
+    sender.send_to(b"garbage", target).unwrap();
+    assert!(block_on(server.recv()).is_err());
+
+    let mut client = AsyncOscUdpClient::from_socket(sender, target).unwrap();
+    let packet = OscPacket::Message(OscMessage::from("/ping"));
+    block_on(client.send(&packet)).unwrap();
+    assert_eq!(packet, block_on(server.recv()).unwrap().0);
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "hmac")]
+
+extern crate rosc;
+
+use rosc::auth::{HmacSigner, SIGNATURE_ADDR, SIGNATURE_OVERHEAD};
+use rosc::{decoder, encoder, OscBundle, OscError, OscMessage, OscPacket, OscTime, OscType};
+
+fn packet() -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: "/mixer/1/gain".to_string(),
+        args: vec![0.5f32.into(), "vocals".into()],
+    })
+}
+
+#[test]
+fn test_sign_and_verify() {
+    let signer = HmacSigner::new(b"0123456789abcdef0123456789abcdef");
+    let encoded = encoder::encode(&packet()).unwrap();
+    let signed = signer.sign(&packet()).unwrap();
+    assert_eq!(encoded.len() + SIGNATURE_OVERHEAD, signed.len());
+    assert_eq!(signed, signer.sign_encoded(&encoded));
+    assert_eq!(&encoded[..], signer.verify(&signed).unwrap());
+    assert_eq!(packet(), signer.verify_packet(&signed).unwrap());
+
+    // receivers without the key still see a valid bundle
+    match decoder::decode(&signed).unwrap() {
+        OscPacket::Bundle(OscBundle { timetag, content }) => {
+            assert_eq!(OscTime::IMMEDIATE, timetag);
+            assert_eq!(packet(), content[0]);
+            match content[1] {
+                OscPacket::Message(ref msg) => {
+                    assert_eq!(SIGNATURE_ADDR, msg.addr);
+                    match msg.args[..] {
+                        [OscType::Blob(ref mac)] => assert_eq!(32, mac.len()),
+                        ref args => panic!("unexpected signature arguments {:?}", args),
+                    }
+                }
+                ref other => panic!("expected the signature message, got {:?}", other),
+            }
+        }
+        other => panic!("expected a bundle, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_verify_rejects_bad_signatures() {
+    let signer = HmacSigner::new(b"shared secret");
+    let signed = signer.sign(&packet()).unwrap();
+    let encoded = encoder::encode(&packet()).unwrap();
+
+    let other_key = HmacSigner::new(b"other secret").sign(&packet()).unwrap();
+    let mut tampered = signed.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    let mut truncated = signed.clone();
+    truncated.pop();
+    let mut extended = signed.clone();
+    extended.extend_from_slice(&[0; 4]);
+
+    for data in [
+        &other_key[..],
+        &tampered,
+        &truncated,
+        &extended,
+        &encoded,
+        &signed[..16],
+        &[],
+    ] {
+        match signer.verify(data) {
+            Err(OscError::BadSignature) => (),
+            other => panic!("expected a bad signature, got {:?}", other),
+        }
+    }
+    assert!(!format!("{:?}", signer).contains("secret"));
+}
+
+#[test]
+fn test_udp_signed_packets() {
+    use rosc::udp::{OscUdpClient, OscUdpServer};
+    use std::net::UdpSocket;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let signer = HmacSigner::new(b"shared secret");
+    let (errors, received_errors) = mpsc::channel();
+    let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    server
+        .set_signer(signer.clone())
+        .on_error(move |err, _| errors.send(err.to_string()).unwrap());
+    server
+        .socket()
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let target = server.local_addr().unwrap();
+
+    let unsigned = UdpSocket::bind("127.0.0.1:0").unwrap();
+    unsigned
+        .send_to(&encoder::encode(&packet()).unwrap(), target)
+        .unwrap();
+    match server.recv() {
+        Err(OscError::BadSignature) => (),
+        other => panic!("expected a bad signature, got {:?}", other),
+    }
+
+    let mut client = OscUdpClient::connect(target).unwrap();
+    client.set_signer(signer);
+    client.send(&packet()).unwrap();
+    assert_eq!(packet(), server.recv().unwrap().0);
+
+    // the signature counts towards the maximum datagram size
+    let size = encoder::encoded_size(&packet());
+    client.set_max_datagram_size(Some(size + SIGNATURE_OVERHEAD - 1));
+    assert!(client.send(&packet()).is_err());
+    assert!(received_errors.try_recv().is_err());
+}
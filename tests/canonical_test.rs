@@ -0,0 +1,62 @@
+extern crate rosc;
+
+use rosc::{Canonical, CanonicalEq, OscBundle, OscMessage, OscPacket, OscType};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn message(args: Vec<OscType>) -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: "/level".to_string(),
+        args,
+    })
+}
+
+#[test]
+fn test_canonical_floats() {
+    let nan = message(vec![
+        f32::NAN.into(),
+        OscType::Array(vec![f64::NAN].into_iter().collect()),
+    ]);
+    let other_nan = message(vec![
+        f32::from_bits(0x7fc0_0001).into(),
+        OscType::Array(vec![-f64::NAN].into_iter().collect()),
+    ]);
+    assert_ne!(nan, other_nan);
+    assert_eq!(Canonical(&nan), Canonical(&other_nan));
+    assert_eq!(hash(&Canonical(&nan)), hash(&Canonical(&other_nan)));
+
+    // unlike numerically, zeros of different signs are different
+    let zero = message(vec![0.0f32.into()]);
+    let negative_zero = message(vec![(-0.0f32).into()]);
+    assert_eq!(zero, negative_zero);
+    assert!(!zero.canonical_eq(&negative_zero));
+
+    assert!(!message(vec![1.0f32.into()]).canonical_eq(&message(vec![1.0f64.into()])));
+    assert!(!message(vec![1.0f32.into()]).canonical_eq(&message(vec![])));
+}
+
+#[test]
+fn test_canonical_keys() {
+    let bundle = |x: f32| {
+        OscPacket::Bundle(OscBundle {
+            timetag: (1, 0).into(),
+            content: vec![message(vec![x.into(), "on".into()])],
+        })
+    };
+
+    let mut counts = HashMap::new();
+    for packet in [bundle(f32::NAN), bundle(0.5), bundle(f32::NAN)] {
+        *counts.entry(Canonical(packet)).or_insert(0) += 1;
+    }
+    assert_eq!(2, counts.len());
+    assert_eq!(Some(&2), counts.get(&Canonical(bundle(f32::NAN))));
+    assert_eq!(bundle(0.5), Canonical(bundle(0.5)).into_inner());
+}
@@ -0,0 +1,164 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::clock::{self, ClockEstimate, ClockOptions, ClockSample, MAX_DRIFT};
+use rosc::{decoder, encoder, OscMessage, OscPacket, OscTime, OscType};
+use std::net::UdpSocket;
+use std::thread;
+use std::time::Duration;
+
+fn time(secs: f64) -> OscTime {
+    OscTime::from((0, 0)) + Duration::from_secs_f64(secs)
+}
+
+fn sample(t1: f64, t2: f64, t3: f64, t4: f64) -> ClockSample {
+    ClockSample {
+        t1: time(t1),
+        t2: time(t2),
+        t3: time(t3),
+        t4: time(t4),
+    }
+}
+
+#[test]
+fn test_sample() {
+    // remote is 5 s ahead, 10 ms each way, 2 ms processing
+    let sample = sample(100.0, 105.010, 105.012, 100.022);
+    assert!((sample.offset() - 5.0).abs() < 1e-6);
+    assert!((sample.delay() - 0.020).abs() < 1e-6);
+}
+
+#[test]
+fn test_estimate_from_samples() {
+    assert_eq!(None, ClockEstimate::from_samples(&[]));
+
+    let samples = [
+        // asymmetric queuing distorts the offset of slow samples
+        sample(100.0, 105.050, 105.050, 100.060),
+        sample(101.0, 106.011, 106.011, 101.020),
+        sample(102.0, 107.0115, 107.0115, 102.0215),
+    ];
+    let estimate = ClockEstimate::from_samples(&samples).unwrap();
+    assert_eq!(3, estimate.samples);
+    assert_eq!(time(101.0), estimate.reference);
+    assert!((estimate.offset - 5.001).abs() < 1e-6);
+    assert!((estimate.delay - 0.020).abs() < 1e-6);
+
+    let remote = estimate.to_remote(time(200.0));
+    assert!((remote.to_duration().as_secs_f64() - (205.001 + 99.0 * estimate.drift)).abs() < 1e-6);
+    let local = estimate.to_local(remote);
+    assert!((local.to_duration().as_secs_f64() - 200.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_drift() {
+    // the remote clock runs 100 ppm fast
+    let samples: Vec<_> = (0..10)
+        .map(|i| {
+            let t = 1000.0 + 10.0 * i as f64;
+            let offset = 2.0 + 100e-6 * (t - 1000.0);
+            sample(t, t + 0.01 + offset, t + 0.01 + offset, t + 0.02)
+        })
+        .collect();
+    let estimate = ClockEstimate::from_samples(&samples).unwrap();
+    assert!((estimate.drift - 100e-6).abs() < 1e-9);
+    assert!((estimate.offset_at(time(1090.0)) - 2.009).abs() < 1e-6);
+}
+
+#[test]
+fn test_conversion_saturates() {
+    // the peer's clock was set forward by 4e9 s between two samples
+    let samples = [
+        sample(100.0, 100.01, 100.01, 100.02),
+        sample(100.1, 4e9 + 100.11, 4e9 + 100.11, 100.12),
+    ];
+    let estimate = ClockEstimate::from_samples(&samples).unwrap();
+    assert_eq!(MAX_DRIFT, estimate.drift);
+    let max = OscTime::from((u32::MAX, u32::MAX));
+    let remote = estimate.to_remote(OscTime::from((2_000_000_000, 0)));
+    assert_eq!(OscTime::from((2_000_000_000, 0)), estimate.to_local(remote));
+    assert_eq!(max, estimate.to_remote(max));
+
+    let mut estimate = ClockEstimate {
+        offset: -1e300,
+        reference: time(100.0),
+        drift: 0.0,
+        delay: 0.0,
+        samples: 1,
+    };
+    assert_eq!(OscTime::from((0, 0)), estimate.to_remote(time(100.0)));
+    estimate.offset = f64::NAN;
+    assert_eq!(time(100.0), estimate.to_remote(time(100.0)));
+}
+
+#[test]
+fn test_conversion_limits_drift() {
+    let mut estimate = ClockEstimate {
+        offset: 1.0,
+        reference: time(100.0),
+        drift: -1.0,
+        delay: 0.0,
+        samples: 2,
+    };
+    for drift in [-1.0, -2.0, f64::NEG_INFINITY, f64::NAN] {
+        estimate.drift = drift;
+        let remote = estimate.to_remote(time(200.0));
+        let local = estimate.to_local(remote);
+        assert!(
+            (local.to_duration().as_secs_f64() - 200.0).abs() < 1e-6,
+            "drift {}",
+            drift
+        );
+    }
+}
+
+#[test]
+fn test_reply() {
+    let t1 = time(100.0);
+    let received = time(200.0);
+    let request = OscMessage {
+        addr: "/clock".to_string(),
+        args: vec![OscType::Time(t1)],
+    };
+    let reply = clock::reply(&request, received).unwrap();
+    assert_eq!("/clock", reply.addr);
+    assert_eq!(OscType::Time(t1), reply.args[0]);
+    assert_eq!(OscType::Time(received), reply.args[1]);
+    assert!(reply.args[2].clone().time().unwrap() > received);
+
+    assert_eq!(None, clock::reply(&OscMessage::from("/clock"), received));
+}
+
+#[test]
+fn test_estimate() {
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let target = peer.local_addr().unwrap();
+    thread::spawn(move || {
+        let mut buf = [0u8; 1536];
+        while let Ok((len, from)) = peer.recv_from(&mut buf) {
+            let received = OscTime::now().unwrap() + Duration::from_secs(30);
+            let request = match decoder::decode(&buf[..len]).unwrap() {
+                OscPacket::Message(msg) => msg,
+                _ => continue,
+            };
+            let mut reply = clock::reply(&request, received).unwrap();
+            // the peer's clock is 30 s ahead
+            if let OscType::Time(ref mut t3) = reply.args[2] {
+                *t3 += Duration::from_secs(30);
+            }
+            let reply = encoder::encode(&OscPacket::Message(reply)).unwrap();
+            peer.send_to(&reply, from).unwrap();
+        }
+    });
+
+    let options = ClockOptions {
+        count: 4,
+        interval: Duration::from_millis(1),
+        ..Default::default()
+    };
+    let estimate = clock::estimate(target, &options).unwrap();
+    assert_eq!(4, estimate.samples);
+    assert!((estimate.offset - 30.0).abs() < 0.05, "{:?}", estimate);
+    assert!(estimate.delay < 0.1);
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "tokio")]
+
+extern crate bytes;
+extern crate rosc;
+extern crate tokio_util;
+
+use bytes::BytesMut;
+use rosc::codec::{DecodeError, EncodeError, Framing, OscCodec};
+use rosc::decoder::DecoderOptions;
+use rosc::{encoder, slip, OscError, OscMessage, OscPacket};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+fn packets() -> Vec<OscPacket> {
+    vec![
+        OscPacket::Message(OscMessage {
+            addr: "/first".to_string(),
+            args: vec![vec![0xc0u8, 0xdb].into()],
+        }),
+        OscPacket::Message(OscMessage::from("/second")),
+    ]
+}
+
+#[test]
+fn test_codec_encode() {
+    let packet = OscPacket::Message(OscMessage::from("/ping"));
+
+    let mut buf = BytesMut::new();
+    OscCodec::new().encode(&packet, &mut buf).unwrap();
+    assert_eq!(encoder::encode_framed(&packet).unwrap(), &buf[..]);
+
+    let mut buf = BytesMut::new();
+    OscCodec::slip().encode(packet.clone(), &mut buf).unwrap();
+    assert_eq!(slip::encode_packet(&packet).unwrap(), &buf[..]);
+}
+
+#[test]
+fn test_codec_round_trip() {
+    for framing in [Framing::LengthPrefix, Framing::Slip] {
+        let mut codec = OscCodec::with_framing(framing);
+        assert_eq!(framing, codec.framing());
+        let mut stream = BytesMut::new();
+        for packet in packets() {
+            codec.encode(packet, &mut stream).unwrap();
+        }
+
+        let mut decoded = vec![];
+        let mut src = BytesMut::new();
+        for byte in stream {
+            src.extend_from_slice(&[byte]);
+            while let Some(packet) = codec.decode(&mut src).unwrap() {
+                decoded.push(packet);
+            }
+        }
+        assert_eq!(packets(), decoded);
+        assert_eq!(None, codec.decode_eof(&mut src).unwrap());
+    }
+}
+
+#[test]
+fn test_codec_truncated_stream() {
+    for framing in [Framing::LengthPrefix, Framing::Slip] {
+        let mut codec = OscCodec::with_options(framing, DecoderOptions::default());
+        let mut stream = BytesMut::new();
+        codec.encode(&packets()[1], &mut stream).unwrap();
+        let mut src = stream.split_to(stream.len() - 1);
+        assert_eq!(None, codec.decode(&mut src).unwrap());
+        match codec.decode_eof(&mut src) {
+            Err(DecodeError(OscError::ReadError(ref err)))
+                if err.kind() == io::ErrorKind::UnexpectedEof => {}
+            other => panic!("Expected an unexpected EOF, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_codec_errors() {
+    // I/O errors of the stream keep their direction
+    match DecodeError::from(io::Error::from(io::ErrorKind::ConnectionReset)).into() {
+        OscError::ReadError(_) => (),
+        other => panic!("Expected a read error, got {:?}", other),
+    }
+    match EncodeError::from(io::Error::from(io::ErrorKind::BrokenPipe)).into() {
+        OscError::WriteError(_) => (),
+        other => panic!("Expected a write error, got {:?}", other),
+    }
+
+    let mut src = BytesMut::from(&[0, 0, 0, 4, b'x', 0, 0, 0][..]);
+    match OscCodec::new().decode(&mut src) {
+        Err(DecodeError(OscError::Context(_))) => (),
+        other => panic!("Expected a bad packet, got {:?}", other),
+    }
+}
@@ -0,0 +1,91 @@
+extern crate rosc;
+
+use rosc::{
+    decoder, encoder, OscArray, OscColor, OscMessage, OscMessageCow, OscPacket, OscPacketRef,
+    OscType, OscTypeCow,
+};
+
+use std::borrow::Cow;
+
+fn message() -> OscMessage {
+    OscMessage {
+        addr: "/mixer/channel/1".to_string(),
+        args: vec![
+            1i32.into(),
+            "label".into(),
+            OscType::Symbol("sym".to_string()),
+            vec![1u8, 2, 3, 4, 5].into(),
+            OscType::Array(OscArray {
+                content: vec![0.5f32.into(), "nested".into(), OscType::Nil],
+            }),
+            OscColor {
+                red: 1,
+                green: 2,
+                blue: 3,
+                alpha: 4,
+            }
+            .into(),
+            true.into(),
+        ],
+    }
+}
+
+#[test]
+fn test_encode_cow() {
+    let msg = message();
+    let expected = encoder::encode(&OscPacket::Message(msg.clone())).unwrap();
+
+    let borrowed = OscMessageCow::from(&msg);
+    assert_eq!(Cow::Borrowed(msg.addr.as_str()), borrowed.addr);
+    assert_eq!(expected, encoder::encode_cow(&borrowed).unwrap());
+
+    let owned = OscMessageCow::from(msg.clone());
+    assert_eq!(expected, encoder::encode_cow(&owned).unwrap());
+    assert_eq!(msg, owned.into_owned());
+
+    let mut buf = vec![0xff];
+    let len = encoder::encode_cow_into(&borrowed, &mut buf).unwrap();
+    assert_eq!(expected, &buf[1..]);
+    assert_eq!(expected.len(), len);
+}
+
+#[test]
+fn test_forward_decoded_message() {
+    let received = encoder::encode(&OscPacket::Message(message())).unwrap();
+    let msg = match decoder::decode_ref(&received).unwrap() {
+        OscPacketRef::Message(msg) => msg,
+        _ => panic!("Expected an OSC message!"),
+    };
+
+    let mut forwarded = OscMessageCow::from(msg);
+    match forwarded.args[1] {
+        OscTypeCow::String(Cow::Borrowed(s)) => assert_eq!("label", s),
+        ref other => panic!("expected a borrowed string, got {:?}", other),
+    }
+    forwarded.addr = "/forwarded".into();
+    forwarded.args.push(OscTypeCow::from(String::from("owned")));
+
+    let mut expected = message();
+    expected.addr = "/forwarded".to_string();
+    expected.args.push("owned".into());
+    assert_eq!(
+        OscPacket::Message(expected),
+        decoder::decode(&encoder::encode_cow(&forwarded).unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn test_encode_cow_errors() {
+    let mut buf = vec![1, 2, 3];
+    let bad_addr = OscMessageCow::new("no/slash");
+    assert!(encoder::encode_cow(&bad_addr).is_err());
+    assert!(encoder::encode_cow_into(&bad_addr, &mut buf).is_err());
+
+    let mut bad_arg = OscMessageCow::new("/custom");
+    bad_arg.args.push(OscTypeCow::Custom {
+        tag: 'i',
+        data: Cow::Borrowed(&[0, 0, 0, 1]),
+    });
+    assert!(encoder::encode_cow_into(&bad_arg, &mut buf).is_err());
+    assert_eq!(vec![1, 2, 3], buf);
+}
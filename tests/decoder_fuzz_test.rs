@@ -0,0 +1,171 @@
+extern crate rosc;
+
+use rosc::decoder::{self, DecodeMode, DecoderOptions};
+use rosc::{
+    encoder, OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime, OscType,
+};
+
+/// Small xorshift generator, good enough to produce reproducible garbage.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn byte(&mut self) -> u8 {
+        self.next() as u8
+    }
+}
+
+fn corpus() -> Vec<Vec<u8>> {
+    let msg = OscMessage {
+        addr: "/some/addr".to_string(),
+        args: vec![
+            1i32.into(),
+            2.0f32.into(),
+            "three".into(),
+            vec![4u8, 5, 6].into(),
+            7i64.into(),
+            8.0f64.into(),
+            'x'.into(),
+            (9, 10).into(),
+            true.into(),
+            OscType::Nil,
+            OscType::Inf,
+            OscMidiMessage {
+                port: 1,
+                status: 2,
+                data1: 3,
+                data2: 4,
+            }
+            .into(),
+            OscColor {
+                red: 1,
+                green: 2,
+                blue: 3,
+                alpha: 4,
+            }
+            .into(),
+            OscArray {
+                content: vec![
+                    1i32.into(),
+                    OscArray {
+                        content: vec!["nested".into()],
+                    }
+                    .into(),
+                ],
+            }
+            .into(),
+        ],
+    };
+    let packets = [
+        OscPacket::Message(msg.clone()),
+        OscPacket::Message("/".into()),
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((1, 2)),
+            content: vec![
+                OscPacket::Message(msg),
+                OscPacket::Bundle(OscBundle {
+                    timetag: OscTime::from((3, 4)),
+                    content: vec![OscPacket::Message("/deep".into())],
+                }),
+            ],
+        }),
+    ];
+    packets
+        .iter()
+        .map(|packet| encoder::encode(packet).unwrap())
+        .collect()
+}
+
+/// Runs every decoding entry point on `data`, none of them may panic.
+fn decode_everything(data: &[u8]) {
+    let strict = DecoderOptions {
+        mode: DecodeMode::Strict,
+        ..Default::default()
+    };
+    let _ = decoder::decode(data);
+    let _ = decoder::decode_with_options(data, &strict);
+    let _ = decoder::decode_with_len(data);
+    let _ = decoder::decode_all(data);
+    #[cfg(feature = "std")]
+    let _ = decoder::decode_from(&mut &data[..]);
+    #[cfg(feature = "std")]
+    let _ = decoder::decode_from_with_options(&mut &data[..], &strict);
+    if let Ok(packet) = decoder::decode_ref(data) {
+        let _ = OscPacket::from(packet);
+    }
+    if let Ok((_, args)) = decoder::decode_args(data) {
+        for arg in args {
+            let _ = arg.map(OscType::from);
+        }
+    }
+    let mut stream = decoder::StreamDecoder::new();
+    stream.feed(data);
+    while let Ok(Some(_)) = stream.decode() {}
+}
+
+#[test]
+fn test_decode_truncated_packets() {
+    for encoded in corpus() {
+        for len in 0..encoded.len() {
+            decode_everything(&encoded[..len]);
+        }
+    }
+}
+
+#[test]
+fn test_decode_mutated_packets() {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    for encoded in corpus() {
+        for pos in 0..encoded.len() {
+            for value in &[0u8, 1, 0x7f, 0x80, 0xff, b'[', b']', b',', rng.byte()] {
+                let mut mutated = encoded.clone();
+                mutated[pos] = *value;
+                decode_everything(&mutated);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_decode_random_packets() {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    for i in 0..20_000 {
+        let len = (rng.next() % 128) as usize;
+        let mut data: Vec<u8> = (0..len).map(|_| rng.byte()).collect();
+        // make most inputs look like a message or bundle to get past the first check
+        match (i % 3, data.first_mut()) {
+            (0, Some(first)) => *first = b'/',
+            (1, Some(first)) => *first = b'#',
+            _ => (),
+        }
+        decode_everything(&data);
+    }
+}
+
+#[test]
+fn test_decode_deeply_nested_arrays() {
+    let nested_msg = |depth: usize| {
+        let mut msg = encoder::encode_string("/deep");
+        let type_tags: String = std::iter::once(',')
+            .chain(std::iter::repeat_n('[', depth))
+            .chain(std::iter::repeat_n(']', depth))
+            .collect();
+        msg.extend(encoder::encode_string(type_tags));
+        msg
+    };
+
+    let msg = nested_msg(100_000);
+    decode_everything(&msg);
+    assert!(decoder::decode(&msg).is_err());
+    assert!(decoder::decode_ref(&msg).is_err());
+
+    let msg = nested_msg(64);
+    assert!(decoder::decode(&msg).is_ok());
+    assert!(decoder::decode_ref(&msg).is_ok());
+}
@@ -2,9 +2,15 @@ extern crate byteorder;
 extern crate rosc;
 
 use byteorder::{BigEndian, ByteOrder};
-use std::mem;
 
-use rosc::{decoder, encoder, OscBundle, OscPacket, OscTime, OscType};
+use rosc::decoder::{
+    DecodeMode, DecodeWarning, DecodeWarningKind, DecoderOptions, InvalidStrings, MissingTypeTags,
+    UnknownTypeTags,
+};
+use rosc::{
+    decoder, encoder, DecodeLimit, NonAsciiChars, OscArray, OscBundle, OscColor, OscError,
+    OscMessage, OscMidiMessage, OscPacket, OscPacketRef, OscTime, OscType, OscTypeRef,
+};
 
 #[test]
 fn test_decode_no_args() {
@@ -12,7 +18,7 @@ fn test_decode_no_args() {
     let raw_addr = "/some/valid/address/4";
     let addr = encoder::encode_string(raw_addr);
     let type_tags = encoder::encode_string(",");
-    let merged: Vec<u8> = addr.into_iter().chain(type_tags.into_iter()).collect();
+    let merged: Vec<u8> = addr.into_iter().chain(type_tags).collect();
     let osc_packet: Result<rosc::OscPacket, rosc::OscError> = decoder::decode(&merged);
     assert!(osc_packet.is_ok());
     match osc_packet {
@@ -21,7 +27,7 @@ fn test_decode_no_args() {
             assert!(msg.args.is_empty());
         }
         Ok(_) => panic!("Expected an OscMessage!"),
-        Err(e) => panic!(e),
+        Err(e) => panic!("{}", e),
     }
 }
 
@@ -37,7 +43,7 @@ fn test_decode_empty_bundle() {
             assert!(bundle.content.is_empty());
         }
         Ok(_) => panic!("Expected an OscBundle!"),
-        Err(e) => panic!(e),
+        Err(e) => panic!("{}", e),
     }
 }
 
@@ -47,23 +53,23 @@ fn test_decode_args() {
     // -1234567891011
     let addr = encoder::encode_string("/another/valid/address/123");
     // args
-    let f = 3.1415f32;
+    let f = 1.234_567f32;
     let mut f_bytes: [u8; 4] = [0u8; 4];
     BigEndian::write_f32(&mut f_bytes, f);
     assert_eq!(BigEndian::read_f32(&f_bytes), f);
 
-    let d = 3.14159265359f64;
+    let d = 1.234_567_890_12f64;
     let mut d_bytes: [u8; 8] = [0u8; 8];
     BigEndian::write_f64(&mut d_bytes, d);
     assert_eq!(BigEndian::read_f64(&d_bytes), d);
 
     let i = 12345678i32;
-    let i_bytes: [u8; 4] = unsafe { mem::transmute(i.to_be()) };
+    let i_bytes: [u8; 4] = i.to_be_bytes();
 
     let l = -1234567891011i64;
-    let h_bytes: [u8; 8] = unsafe { mem::transmute(l.to_be()) };
+    let h_bytes: [u8; 8] = l.to_be_bytes();
 
-    let blob_size: [u8; 4] = unsafe { mem::transmute(6u32.to_be()) };
+    let blob_size: [u8; 4] = 6u32.to_be_bytes();
     let blob: Vec<u8> = vec![1u8, 2u8, 3u8, 4u8, 5u8, 6u8];
 
     let s = "I am an osc test string.";
@@ -72,7 +78,7 @@ fn test_decode_args() {
     let s_bytes: Vec<u8> = encoder::encode_string(s);
 
     let c = '$';
-    let c_bytes: [u8; 4] = unsafe { mem::transmute((c as u32).to_be()) };
+    let c_bytes: [u8; 4] = (c as u32).to_be_bytes();
 
     let a = vec![OscType::Int(i), OscType::Float(f), OscType::Int(i)];
 
@@ -85,19 +91,19 @@ fn test_decode_args() {
         .chain(i_bytes.iter())
         .chain(blob_size.iter())
         .chain(blob.iter())
-        .chain(vec![0u8, 0u8].iter())
+        .chain([0u8, 0u8].iter())
         .chain(h_bytes.iter())
         .chain(c_bytes.iter())
         // array content
         .chain(i_bytes.iter())
         .chain(f_bytes.iter())
         .chain(i_bytes.iter())
-        .map(|x| *x)
+        .copied()
         .collect::<Vec<u8>>();
 
     let merged: Vec<u8> = addr
         .into_iter()
-        .chain(type_tags.into_iter())
+        .chain(type_tags)
         .chain(args)
         .collect::<Vec<u8>>();
 
@@ -127,3 +133,878 @@ fn test_decode_args() {
         _ => panic!("Expected an OSC message!"),
     }
 }
+
+#[test]
+fn test_decode_time_midi_color_args() {
+    let addr = encoder::encode_string("/some/addr");
+    let type_tags = encoder::encode_string(",tmr");
+    let args: Vec<u8> = vec![
+        0, 0, 0, 4, 0, 0, 0, 2, // time tag
+        1, 144, 60, 127, // midi message
+        255, 192, 42, 13, // color
+    ];
+    let merged: Vec<u8> = addr.into_iter().chain(type_tags).chain(args).collect();
+
+    match decoder::decode(&merged).unwrap() {
+        OscPacket::Message(msg) => assert_eq!(
+            vec![
+                OscType::Time(OscTime::from((4, 2))),
+                OscType::Midi(OscMidiMessage {
+                    port: 1,
+                    status: 144,
+                    data1: 60,
+                    data2: 127,
+                }),
+                OscType::Color(OscColor {
+                    red: 255,
+                    green: 192,
+                    blue: 42,
+                    alpha: 13,
+                }),
+            ],
+            msg.args
+        ),
+        _ => panic!("Expected an OSC message!"),
+    }
+}
+
+#[test]
+fn test_decode_type_tags_without_comma() {
+    let addr = encoder::encode_string("/some/addr");
+    let type_tags = encoder::encode_string("i");
+    let merged: Vec<u8> = addr
+        .into_iter()
+        .chain(type_tags)
+        .chain(vec![0u8, 0, 0, 1])
+        .collect();
+
+    assert!(decoder::decode(&merged).is_err());
+}
+
+#[test]
+fn test_decode_nested_bundles() {
+    let msg = OscPacket::Message(OscMessage {
+        addr: "/deep".to_string(),
+        args: vec![OscType::Int(3)],
+    });
+    let mut packet = msg.clone();
+    for i in 0..3 {
+        packet = OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((i, 1)),
+            content: vec![msg.clone(), packet],
+        });
+    }
+
+    let encoded = encoder::encode(&packet).unwrap();
+    assert_eq!(packet, decoder::decode(&encoded).unwrap());
+}
+
+#[test]
+fn test_decode_bundle_with_truncated_element() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((4, 2)),
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/some/addr".to_string(),
+            args: vec![OscType::Int(42)],
+        })],
+    });
+    let encoded = encoder::encode(&packet).unwrap();
+
+    assert!(decoder::decode(&encoded[..encoded.len() - 4]).is_err());
+}
+
+#[test]
+fn test_decode_ref_matches_decode() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((4, 2)),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/some/addr".to_string(),
+                args: vec![
+                    42i32.into(),
+                    "a string".into(),
+                    vec![1u8, 2, 3, 4, 5].into(),
+                    OscArray {
+                        content: vec![1i64.into(), OscArray { content: vec![] }.into()],
+                    }
+                    .into(),
+                    OscType::Nil,
+                    0.5f64.into(),
+                ],
+            }),
+            OscPacket::Bundle(OscBundle {
+                timetag: OscTime::from((1, 2)),
+                content: vec![OscPacket::Message("/nested".into())],
+            }),
+        ],
+    });
+    let encoded = encoder::encode(&packet).unwrap();
+
+    let borrowed = decoder::decode_ref(&encoded).unwrap();
+    assert_eq!(packet, OscPacket::from(borrowed));
+
+    let bundle = match borrowed {
+        OscPacketRef::Bundle(bundle) => bundle,
+        _ => panic!("Expected an OSC bundle!"),
+    };
+    match bundle.content().next() {
+        Some(OscPacketRef::Message(msg)) => {
+            assert_eq!("/some/addr", msg.addr);
+            assert_eq!("isb[h[]]Nd", msg.type_tags());
+            let args: Vec<OscTypeRef> = msg.args().take(3).collect();
+            assert_eq!(
+                vec![
+                    OscTypeRef::Int(42),
+                    OscTypeRef::String("a string"),
+                    OscTypeRef::Blob(&[1, 2, 3, 4, 5]),
+                ],
+                args
+            );
+        }
+        _ => panic!("Expected an OSC message!"),
+    }
+}
+
+#[test]
+fn test_decode_ref_rejects_truncated_args() {
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/some/addr".to_string(),
+        args: vec![42i32.into(), 1.5f64.into()],
+    });
+    let encoded = encoder::encode(&packet).unwrap();
+
+    assert!(decoder::decode_ref(&encoded[..encoded.len() - 4]).is_err());
+    assert!(decoder::decode_ref(&[]).is_err());
+}
+
+#[test]
+fn test_stream_decoder() {
+    let packets = vec![
+        OscPacket::Message(OscMessage {
+            addr: "/first".to_string(),
+            args: vec![1i32.into()],
+        }),
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((4, 2)),
+            content: vec![OscPacket::Message("/second".into())],
+        }),
+    ];
+    let mut stream: Vec<u8> = Vec::new();
+    for packet in &packets {
+        let encoded = encoder::encode(packet).unwrap();
+        stream.extend(&(encoded.len() as u32).to_be_bytes());
+        stream.extend(encoded);
+    }
+
+    let mut decoder = decoder::StreamDecoder::new();
+    let mut decoded = vec![];
+    let mut consumed = 0;
+    // feed the stream in chunks that don't line up with packet boundaries
+    for chunk in stream.chunks(7) {
+        decoder.feed(chunk);
+        while let Some((packet, size)) = decoder.decode().unwrap() {
+            decoded.push(packet);
+            consumed += size;
+        }
+    }
+
+    assert_eq!(packets, decoded);
+    assert_eq!(stream.len(), consumed);
+    assert_eq!(0, decoder.buffered());
+}
+
+#[test]
+fn test_stream_decoder_skips_malformed_packet() {
+    let mut decoder = decoder::StreamDecoder::new();
+    decoder.feed(&[0, 0, 0, 4, b'x', 0, 0, 0]);
+    let encoded = encoder::encode(&OscPacket::Message("/ok".into())).unwrap();
+    decoder.feed(&(encoded.len() as u32).to_be_bytes());
+    decoder.feed(&encoded);
+
+    assert!(decoder.decode().is_err());
+    assert_eq!(
+        Some(OscPacket::Message("/ok".into())),
+        decoder.decode().unwrap().map(|(packet, _)| packet)
+    );
+}
+
+#[test]
+fn test_decode_strict_accepts_encoded_packets() {
+    let options = DecoderOptions {
+        mode: DecodeMode::Strict,
+        ..Default::default()
+    };
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((4, 2)),
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/some/addr".to_string(),
+            args: vec![
+                "abc".into(),
+                vec![1u8, 2, 3].into(),
+                OscArray {
+                    content: vec![1i32.into()],
+                }
+                .into(),
+            ],
+        })],
+    });
+    let encoded = encoder::encode(&packet).unwrap();
+
+    assert_eq!(
+        packet,
+        decoder::decode_with_options(&encoded, &options).unwrap()
+    );
+}
+
+#[test]
+fn test_decode_strict_and_lenient_deviations() {
+    let strict = DecoderOptions {
+        mode: DecodeMode::Strict,
+        ..Default::default()
+    };
+    let lenient = DecoderOptions::default();
+
+    let addr = encoder::encode_string("/some/addr");
+    let type_tags = encoder::encode_string(",i");
+    let arg = [0u8, 0, 0, 42];
+    let cases: Vec<(Vec<u8>, Vec<OscType>, usize, DecodeWarningKind)> = vec![
+        // no type tag string
+        (addr.clone(), vec![], 12, DecodeWarningKind::MissingTypeTags),
+        // trailing bytes after the last argument
+        (
+            [&addr[..], &type_tags[..], &arg[..], &[0, 0, 0, 1][..]].concat(),
+            vec![42i32.into()],
+            20,
+            DecodeWarningKind::TrailingBytes(4),
+        ),
+        // non-zero padding after the type tag string
+        (
+            [&addr[..], &[b',', b'i', 0, 1][..], &arg[..]].concat(),
+            vec![42i32.into()],
+            15,
+            DecodeWarningKind::BadPadding,
+        ),
+        // missing padding at the end of the packet
+        (
+            [&addr[..], &encoder::encode_string(",s")[..], b"abcde\0"].concat(),
+            vec!["abcde".into()],
+            22,
+            DecodeWarningKind::BadPadding,
+        ),
+        // missing nul terminator of the last string
+        (
+            [&addr[..], &encoder::encode_string(",s")[..], b"abcd"].concat(),
+            vec!["abcd".into()],
+            20,
+            DecodeWarningKind::MissingNulTerminator,
+        ),
+        // missing ] at the end of an array
+        (
+            [&addr[..], &encoder::encode_string(",[i")[..], &arg[..]].concat(),
+            vec![OscArray {
+                content: vec![42i32.into()],
+            }
+            .into()],
+            20,
+            DecodeWarningKind::UnclosedArray,
+        ),
+    ];
+
+    for (msg, args, offset, kind) in cases {
+        assert!(decoder::decode_with_options(&msg, &strict).is_err());
+        let (packet, warnings) = decoder::decode_with_warnings(&msg, &lenient).unwrap();
+        match packet {
+            OscPacket::Message(decoded) => assert_eq!(args, decoded.args),
+            _ => panic!("Expected an OSC message!"),
+        }
+        assert_eq!(
+            vec![DecodeWarning {
+                offset,
+                addr: Some("/some/addr".to_string()),
+                kind,
+            }],
+            warnings
+        );
+    }
+
+    // well-formed packets decode without warnings
+    let msg = [&addr[..], &type_tags[..], &arg[..]].concat();
+    assert_eq!(
+        Vec::<DecodeWarning>::new(),
+        decoder::decode_with_warnings(&msg, &lenient).unwrap().1
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_decode_from_reader() {
+    let packets = vec![
+        OscPacket::Message(OscMessage {
+            addr: "/first".to_string(),
+            args: vec!["abc".into()],
+        }),
+        OscPacket::Message("/second".into()),
+    ];
+    let mut stream: Vec<u8> = Vec::new();
+    for packet in &packets {
+        let encoded = encoder::encode(packet).unwrap();
+        stream.extend(&(encoded.len() as u32).to_be_bytes());
+        stream.extend(encoded);
+    }
+
+    let mut reader = std::io::Cursor::new(&stream[..stream.len() - 1]);
+    assert_eq!(packets[0], decoder::decode_from(&mut reader).unwrap());
+    // the second packet is truncated
+    assert!(decoder::decode_from(&mut reader).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_decode_from_reader_with_options() {
+    let encoded = encoder::encode(&OscPacket::Message("/limited".into())).unwrap();
+    let mut stream = (encoded.len() as u32).to_be_bytes().to_vec();
+    stream.extend(&encoded);
+
+    let options = DecoderOptions {
+        max_packet_size: encoded.len(),
+        ..Default::default()
+    };
+    assert_eq!(
+        OscPacket::Message("/limited".into()),
+        decoder::decode_from_with_options(&mut stream.as_slice(), &options).unwrap()
+    );
+
+    let options = DecoderOptions {
+        max_packet_size: encoded.len() - 1,
+        ..Default::default()
+    };
+    let mut reader = stream.as_slice();
+    match decoder::decode_from_with_options(&mut reader, &options) {
+        Err(OscError::LimitExceeded(DecodeLimit::PacketSize)) => (),
+        other => panic!("Expected the packet size to be exceeded, got {:?}", other),
+    }
+    // only the size prefix was read
+    assert_eq!(&encoded[..], reader);
+}
+
+#[test]
+fn test_decode_nested_arrays() {
+    let addr = encoder::encode_string("/some/addr");
+    let type_tags = encoder::encode_string(",[i[]i[s]]T");
+    let args: Vec<u8> = [
+        &1i32.to_be_bytes()[..],
+        &2i32.to_be_bytes()[..],
+        &encoder::encode_string("abc")[..],
+    ]
+    .concat();
+    let merged: Vec<u8> = addr.into_iter().chain(type_tags).chain(args).collect();
+
+    match decoder::decode(&merged).unwrap() {
+        OscPacket::Message(msg) => assert_eq!(
+            vec![
+                OscType::Array(OscArray {
+                    content: vec![
+                        1i32.into(),
+                        OscArray { content: vec![] }.into(),
+                        2i32.into(),
+                        OscArray {
+                            content: vec!["abc".into()]
+                        }
+                        .into(),
+                    ]
+                }),
+                true.into(),
+            ],
+            msg.args
+        ),
+        _ => panic!("Expected an OSC message!"),
+    }
+}
+
+#[test]
+fn test_decode_array_end_outside_array() {
+    let addr = encoder::encode_string("/some/addr");
+    let type_tags = encoder::encode_string(",T]");
+    let merged: Vec<u8> = addr.into_iter().chain(type_tags).collect();
+
+    assert!(decoder::decode(&merged).is_err());
+    assert!(decoder::decode_ref(&merged).is_err());
+}
+
+#[test]
+fn test_decode_limits() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((4, 2)),
+        content: vec![OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((4, 2)),
+            content: vec![OscPacket::Message(OscMessage {
+                addr: "/some/addr".to_string(),
+                args: vec![
+                    vec![0u8; 16].into(),
+                    OscArray {
+                        content: vec![1i32.into(), 2i32.into()],
+                    }
+                    .into(),
+                ],
+            })],
+        })],
+    });
+    let encoded = encoder::encode(&packet).unwrap();
+
+    let limits = vec![
+        (
+            DecoderOptions {
+                max_packet_size: encoded.len() - 1,
+                ..Default::default()
+            },
+            DecodeLimit::PacketSize,
+        ),
+        (
+            DecoderOptions {
+                max_blob_size: 15,
+                ..Default::default()
+            },
+            DecodeLimit::BlobSize,
+        ),
+        (
+            DecoderOptions {
+                max_args: 2,
+                ..Default::default()
+            },
+            DecodeLimit::Args,
+        ),
+        (
+            DecoderOptions {
+                max_bundle_depth: 1,
+                ..Default::default()
+            },
+            DecodeLimit::BundleDepth,
+        ),
+    ];
+    for (options, limit) in limits {
+        match decoder::decode_with_options(&encoded, &options) {
+            Err(err) => match err.root() {
+                OscError::LimitExceeded(exceeded) => assert_eq!(limit, *exceeded),
+                other => panic!("Expected {:?} to be exceeded, got {:?}", limit, other),
+            },
+            Ok(_) => panic!("Expected {:?} to be exceeded", limit),
+        }
+    }
+
+    let options = DecoderOptions {
+        max_packet_size: encoded.len(),
+        max_blob_size: 16,
+        max_args: 3,
+        max_bundle_depth: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        packet,
+        decoder::decode_with_options(&encoded, &options).unwrap()
+    );
+}
+
+#[test]
+fn test_decode_deeply_nested_bundles() {
+    let nested = |depth: usize| {
+        (0..depth).fold(encoder::encode_string("/leaf"), |element, _| {
+            let mut bundle = encoder::encode_string("#bundle");
+            bundle.extend(&[0, 0, 0, 0, 0, 0, 0, 1]);
+            bundle.extend(&(element.len() as u32).to_be_bytes());
+            bundle.extend(element);
+            bundle
+        })
+    };
+
+    assert!(decoder::decode(&nested(32)).is_ok());
+    assert!(decoder::decode_ref(&nested(32)).is_ok());
+
+    // crafted input must not exhaust the stack
+    let too_deep = nested(10_000);
+    for result in [
+        decoder::decode(&too_deep).map(|_| ()),
+        decoder::decode_ref(&too_deep).map(|_| ()),
+    ] {
+        match result {
+            Err(err) => match err.root() {
+                OscError::LimitExceeded(DecodeLimit::BundleDepth) => (),
+                other => panic!("Expected the bundle depth to be exceeded, got {:?}", other),
+            },
+            Ok(_) => panic!("Expected the bundle depth to be exceeded"),
+        }
+    }
+}
+
+#[test]
+fn test_stream_decoder_discards_oversized_packets() {
+    let mut decoder = decoder::StreamDecoder::with_options(DecoderOptions {
+        max_packet_size: 64,
+        ..Default::default()
+    });
+    decoder.feed(&[0, 0, 1, 0]);
+    decoder.feed(&[0u8; 100]);
+    match decoder.decode() {
+        Err(OscError::LimitExceeded(DecodeLimit::PacketSize)) => (),
+        other => panic!("Expected the packet size to be exceeded, got {:?}", other),
+    }
+    decoder.feed(&[0u8; 156]);
+
+    let encoded = encoder::encode(&OscPacket::Message("/ok".into())).unwrap();
+    decoder.feed(&(encoded.len() as u32).to_be_bytes());
+    decoder.feed(&encoded);
+    assert_eq!(
+        Some(OscPacket::Message("/ok".into())),
+        decoder.decode().unwrap().map(|(packet, _)| packet)
+    );
+}
+
+#[test]
+fn test_decode_error_context() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((4, 2)),
+        content: vec![
+            OscPacket::Message("/first".into()),
+            OscPacket::Message(OscMessage {
+                addr: "/some/addr".to_string(),
+                args: vec![1i32.into(), 'x'.into()],
+            }),
+        ],
+    });
+    let mut encoded = encoder::encode(&packet).unwrap();
+    // replace the char argument with an invalid code point
+    let char_pos = encoded.len() - 4;
+    encoded[char_pos..].copy_from_slice(&0xD800u32.to_be_bytes());
+
+    let err = decoder::decode(&encoded).unwrap_err();
+    let ctx = err.context().expect("Expected a decoding context");
+    assert_eq!(char_pos, ctx.offset);
+    assert_eq!(Some('c'), ctx.type_tag);
+    assert_eq!(Some("/some/addr"), ctx.addr.as_deref());
+    match err.root() {
+        OscError::BadArg(_) => (),
+        other => panic!("Expected a bad argument error, got {:?}", other),
+    }
+    assert_eq!(
+        format!(
+            "bad OSC argument: Argument is not a char! at byte {} in message /some/addr while decoding type tag 'c'",
+            char_pos
+        ),
+        err.to_string()
+    );
+    // the message of the underlying error isn't repeated as the source
+    assert!(std::error::Error::source(&err).is_none());
+}
+
+#[test]
+fn test_decode_missing_type_tags() {
+    let addr = encoder::encode_string("/legacy");
+    let raw_args: Vec<u8> = vec![0, 0, 0, 42, 0x3f, 0x80, 0, 0];
+    let merged: Vec<u8> = addr.into_iter().chain(raw_args.clone()).collect();
+
+    assert!(decoder::decode(&merged).is_err());
+
+    let options = DecoderOptions {
+        missing_type_tags: MissingTypeTags::Blob,
+        ..Default::default()
+    };
+    assert_eq!(
+        OscPacket::Message(OscMessage {
+            addr: "/legacy".to_string(),
+            args: vec![OscType::Blob(raw_args)],
+        }),
+        decoder::decode_with_options(&merged, &options).unwrap()
+    );
+    assert_eq!(
+        vec![DecodeWarning {
+            offset: 8,
+            addr: Some("/legacy".to_string()),
+            kind: DecodeWarningKind::MissingTypeTags,
+        }],
+        decoder::decode_with_warnings(&merged, &options).unwrap().1
+    );
+
+    // messages with type tags are unaffected
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/modern".to_string(),
+        args: vec![42i32.into()],
+    });
+    let encoded = encoder::encode(&packet).unwrap();
+    assert_eq!(
+        packet,
+        decoder::decode_with_options(&encoded, &options).unwrap()
+    );
+}
+
+#[test]
+fn test_decode_unknown_type_tags() {
+    let msg = OscPacket::Message(OscMessage {
+        addr: "/vendor".to_string(),
+        args: vec![],
+    });
+    let mut element = encoder::encode_string("/vendor");
+    element.extend(encoder::encode_string(",i[Xi]i"));
+    element.extend(&1i32.to_be_bytes());
+    element.extend(&2i32.to_be_bytes());
+    element.extend(&3i32.to_be_bytes());
+    // wrap the message into a bundle to check the reported offsets
+    let mut bundle = encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((0, 1)),
+        content: vec![msg],
+    }))
+    .unwrap();
+    let header_len = 16;
+    bundle.truncate(header_len);
+    bundle.extend(&(element.len() as u32).to_be_bytes());
+    bundle.extend(&element);
+
+    assert!(decoder::decode(&bundle).is_err());
+
+    let cases = vec![
+        (
+            UnknownTypeTags::Skip,
+            vec![
+                1i32.into(),
+                OscType::Array(OscArray {
+                    content: vec![2i32.into()],
+                }),
+                3i32.into(),
+            ],
+        ),
+        (
+            UnknownTypeTags::Truncate,
+            vec![1i32.into(), OscType::Array(OscArray { content: vec![] })],
+        ),
+    ];
+    for (unknown_type_tags, args) in cases {
+        let options = DecoderOptions {
+            unknown_type_tags,
+            ..Default::default()
+        };
+        let (packet, warnings) = decoder::decode_with_warnings(&bundle, &options).unwrap();
+        match packet {
+            OscPacket::Bundle(bundle) => assert_eq!(
+                OscPacket::Message(OscMessage {
+                    addr: "/vendor".to_string(),
+                    args
+                }),
+                bundle.content[0]
+            ),
+            _ => panic!("Expected an OSC bundle!"),
+        }
+        assert_eq!(
+            vec![DecodeWarning {
+                // bundle header, element size, address, type tags and first argument
+                offset: header_len + 4 + 8 + 8 + 4,
+                addr: Some("/vendor".to_string()),
+                kind: DecodeWarningKind::UnknownTypeTag('X'),
+            }],
+            warnings
+        );
+    }
+}
+
+#[test]
+fn test_decode_invalid_strings() {
+    let mut msg = encoder::encode_string("/label");
+    msg.extend(encoder::encode_string(",si"));
+    // "Caf\xe9" in Latin-1
+    msg.extend(&[b'C', b'a', b'f', 0xe9, 0, 0, 0, 0]);
+    msg.extend(&7i32.to_be_bytes());
+
+    match decoder::decode(&msg) {
+        Err(err) => match err.root() {
+            OscError::StringError(_) => (),
+            other => panic!("expected StringError, got {:?}", other),
+        },
+        other => panic!("expected an error, got {:?}", other),
+    }
+
+    let cases = vec![
+        (InvalidStrings::Lossy, OscType::from("Caf\u{fffd}")),
+        (
+            InvalidStrings::Raw,
+            OscType::RawString(vec![b'C', b'a', b'f', 0xe9]),
+        ),
+    ];
+    for (invalid_strings, arg) in cases {
+        let options = DecoderOptions {
+            invalid_strings,
+            ..Default::default()
+        };
+        let (packet, warnings) = decoder::decode_with_warnings(&msg, &options).unwrap();
+        assert_eq!(
+            OscPacket::Message(OscMessage {
+                addr: "/label".to_string(),
+                args: vec![arg, 7i32.into()],
+            }),
+            packet
+        );
+        assert_eq!(
+            vec![DecodeWarning {
+                offset: 12,
+                addr: Some("/label".to_string()),
+                kind: DecodeWarningKind::InvalidString,
+            }],
+            warnings
+        );
+        if invalid_strings == InvalidStrings::Raw {
+            // raw strings are encoded unchanged
+            assert_eq!(msg, encoder::encode(&packet).unwrap());
+        }
+    }
+}
+
+#[test]
+fn test_decode_symbols() {
+    let mut msg = encoder::encode_string("/s_new");
+    msg.extend(encoder::encode_string(",Ssi"));
+    msg.extend(encoder::encode_string("default"));
+    msg.extend(encoder::encode_string("freq"));
+    msg.extend(&440i32.to_be_bytes());
+
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/s_new".to_string(),
+        args: vec![
+            OscType::Symbol("default".to_string()),
+            "freq".into(),
+            440i32.into(),
+        ],
+    });
+    assert_eq!(packet, decoder::decode(&msg).unwrap());
+    assert_eq!(msg, encoder::encode(&packet).unwrap());
+    match decoder::decode_ref(&msg).unwrap() {
+        OscPacketRef::Message(msg) => {
+            assert_eq!(Some(OscTypeRef::Symbol("default")), msg.args().next())
+        }
+        _ => panic!("Expected an OSC message!"),
+    }
+}
+
+#[test]
+fn test_decode_non_ascii_chars() {
+    let mut msg = encoder::encode_string("/key");
+    msg.extend(encoder::encode_string(",c"));
+    msg.extend(&('é' as u32).to_be_bytes());
+
+    match decoder::decode(&msg) {
+        Err(err) => match err.root() {
+            OscError::BadArg(_) => (),
+            other => panic!("expected BadArg, got {:?}", other),
+        },
+        other => panic!("expected an error, got {:?}", other),
+    }
+    assert!(decoder::decode_ref(&msg).is_err());
+
+    for (non_ascii_chars, c) in [(NonAsciiChars::Lossy, '?'), (NonAsciiChars::Allow, 'é')] {
+        let options = DecoderOptions {
+            non_ascii_chars,
+            ..Default::default()
+        };
+        let (packet, warnings) = decoder::decode_with_warnings(&msg, &options).unwrap();
+        assert_eq!(
+            OscPacket::Message(OscMessage {
+                addr: "/key".to_string(),
+                args: vec![c.into()],
+            }),
+            packet
+        );
+        assert_eq!(
+            vec![DecodeWarning {
+                offset: 12,
+                addr: Some("/key".to_string()),
+                kind: DecodeWarningKind::NonAsciiChar('é'),
+            }],
+            warnings
+        );
+    }
+}
+
+#[test]
+fn test_decode_args_lazily() {
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/some/addr".to_string(),
+        args: vec![
+            1i32.into(),
+            "abc".into(),
+            OscArray {
+                content: vec![2i32.into()],
+            }
+            .into(),
+            3i32.into(),
+        ],
+    });
+    let mut encoded = encoder::encode(&packet).unwrap();
+
+    let (addr, args) = decoder::decode_args(&encoded).unwrap();
+    assert_eq!("/some/addr", addr);
+    let args: Vec<OscType> = args.map(|arg| arg.unwrap().into()).collect();
+    match packet {
+        OscPacket::Message(msg) => assert_eq!(msg.args, args),
+        _ => unreachable!(),
+    }
+
+    // corrupt the last argument, earlier arguments still decode
+    encoded.truncate(encoded.len() - 2);
+    let (_, mut args) = decoder::decode_args(&encoded).unwrap();
+    assert_eq!(OscTypeRef::Int(1), args.next().unwrap().unwrap());
+    assert_eq!("s[i]i", args.type_tags());
+    assert_eq!(OscTypeRef::String("abc"), args.next().unwrap().unwrap());
+    assert!(args.next().unwrap().is_ok());
+    assert!(args.next().unwrap().is_err());
+    assert!(args.next().is_none());
+}
+
+#[test]
+fn test_decode_all() {
+    let packets = vec![
+        OscPacket::Message(OscMessage {
+            addr: "/first".to_string(),
+            args: vec![1i32.into()],
+        }),
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((4, 2)),
+            content: vec![OscPacket::Message("/second".into())],
+        }),
+        OscPacket::Message("/third".into()),
+    ];
+    let mut buf: Vec<u8> = Vec::new();
+    for packet in &packets {
+        let encoded = encoder::encode(packet).unwrap();
+        buf.extend(&(encoded.len() as u32).to_be_bytes());
+        buf.extend(encoded);
+    }
+
+    assert_eq!(packets, decoder::decode_all(&buf).unwrap());
+    assert!(decoder::decode_all(&[]).unwrap().is_empty());
+    assert!(decoder::decode_all(&buf[..buf.len() - 1]).is_err());
+}
+
+#[test]
+fn test_decode_with_len() {
+    let msg = OscPacket::Message(OscMessage {
+        addr: "/some/addr".to_string(),
+        args: vec!["abc".into(), 1i32.into()],
+    });
+    let encoded = encoder::encode(&msg).unwrap();
+    let mut buf = encoded.clone();
+    buf.extend(&encoded);
+
+    assert_eq!(
+        (msg.clone(), encoded.len()),
+        decoder::decode_with_len(&buf).unwrap()
+    );
+    assert_eq!(
+        (msg, encoded.len()),
+        decoder::decode_with_len(&encoded).unwrap()
+    );
+
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: OscTime::from((4, 2)),
+        content: vec![OscPacket::Message("/nested".into())],
+    });
+    let encoded = encoder::encode(&bundle).unwrap();
+    assert_eq!(
+        (bundle, encoded.len()),
+        decoder::decode_with_len(&encoded).unwrap()
+    );
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "derive")]
+extern crate rosc;
+
+use rosc::{FromOsc, OscError, OscMessage, OscType, ToOsc};
+
+#[derive(Debug, PartialEq, ToOsc, FromOsc)]
+#[osc(addr = "/synth/voice")]
+struct SynthVoice {
+    freq: f32,
+    amp: f32,
+}
+
+#[derive(Debug, PartialEq, ToOsc, FromOsc)]
+#[osc(addr = "/mixer/channel")]
+struct Channel(i32, String, bool);
+
+#[derive(Debug, PartialEq, ToOsc, FromOsc)]
+#[osc(addr = "/transport/stop")]
+struct Stop;
+
+#[test]
+fn test_derive_named_fields() {
+    let voice = SynthVoice {
+        freq: 440.0,
+        amp: 0.5,
+    };
+    let msg = voice.to_osc();
+    assert_eq!(OscMessage::new("/synth/voice", (440.0f32, 0.5f32)), msg);
+    assert_eq!("ff", msg.type_tags());
+    assert_eq!(voice, SynthVoice::from_osc(&msg).unwrap());
+}
+
+#[test]
+fn test_derive_tuple_and_unit_structs() {
+    let channel = Channel(3, "vocals".to_string(), true);
+    let msg = channel.to_osc();
+    assert_eq!("/mixer/channel", msg.addr);
+    assert_eq!("isT", msg.type_tags());
+    assert_eq!(channel, Channel::from_osc(&msg).unwrap());
+
+    let msg = Stop.to_osc();
+    assert_eq!(OscMessage::from("/transport/stop"), msg);
+    assert_eq!(Stop, Stop::from_osc(&msg).unwrap());
+}
+
+#[test]
+fn test_derive_type_checks() {
+    let mut msg = OscMessage::new("/synth/voice", (440.0f32, 0.5f32));
+    msg.addr = "/synth/other".to_string();
+    match SynthVoice::from_osc(&msg) {
+        Err(OscError::BadAddress(_)) => (),
+        other => panic!("expected a bad address error, got {:?}", other),
+    }
+
+    let msg = OscMessage::new("/synth/voice", (440.0f32,));
+    match SynthVoice::from_osc(&msg) {
+        Err(OscError::BadArg(err)) => assert_eq!("expected 2 arguments, found 1", err),
+        other => panic!("expected a bad argument error, got {:?}", other),
+    }
+
+    let msg = OscMessage::new("/synth/voice", (440.0f32, OscType::Int(1)));
+    match SynthVoice::from_osc(&msg) {
+        Err(OscError::BadArg(err)) => assert!(err.starts_with("argument 1:"), "{}", err),
+        other => panic!("expected a bad argument error, got {:?}", other),
+    }
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "mdns")]
+
+extern crate rosc;
+
+use rosc::discovery::{Discovery, UDP_SERVICE};
+use std::time::Duration;
+
+#[test]
+fn test_advertise_and_browse() {
+    let discovery = match Discovery::new() {
+        Ok(discovery) => discovery,
+        Err(err) => {
+            eprintln!("skipping discovery test: {}", err);
+            return;
+        }
+    };
+    discovery
+        .advertise("rosc discovery test", UDP_SERVICE, 9123)
+        .unwrap();
+    let peers = discovery
+        .browse(UDP_SERVICE, Duration::from_secs(3))
+        .unwrap();
+    if let Some(peer) = peers.iter().find(|peer| peer.name == "rosc discovery test") {
+        assert!(peer.addrs.iter().all(|addr| addr.port() == 9123));
+    } else {
+        // multicast may not work in the test environment
+        eprintln!("advertised service not found in {:?}", peers);
+    }
+}
@@ -0,0 +1,238 @@
+extern crate rosc;
+
+use rosc::dispatcher::{Context, Dispatcher, Flow};
+use rosc::rewrite::Rewriter;
+use rosc::{OscBundle, OscError, OscMessage, OscPacket};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::rc::Rc;
+
+fn bundle(content: Vec<OscPacket>) -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: (0, 1).into(),
+        content,
+    })
+}
+
+fn msg(addr: &str) -> OscPacket {
+    OscPacket::Message(OscMessage::from(addr))
+}
+
+#[test]
+fn test_dispatch_to_matching_handlers() {
+    let calls = Rc::new(RefCell::new(vec![]));
+    let mut dispatcher = Dispatcher::new();
+    for pattern in &["/synth/*/freq", "/synth/1/*", "/fx/{delay,reverb}"] {
+        let calls = calls.clone();
+        let pattern = pattern.to_string();
+        dispatcher
+            .on(&pattern.clone(), move |msg| {
+                calls.borrow_mut().push((pattern.clone(), msg.addr.clone()))
+            })
+            .unwrap();
+    }
+
+    let packet = bundle(vec![
+        msg("/synth/1/freq"),
+        bundle(vec![msg("/synth/2/freq"), msg("/fx/reverb")]),
+        msg("/unknown"),
+        OscPacket::Raw(vec![]),
+    ]);
+    assert_eq!(4, dispatcher.dispatch(&packet));
+    assert_eq!(
+        vec![
+            ("/synth/*/freq".to_string(), "/synth/1/freq".to_string()),
+            ("/synth/1/*".to_string(), "/synth/1/freq".to_string()),
+            ("/synth/*/freq".to_string(), "/synth/2/freq".to_string()),
+            ("/fx/{delay,reverb}".to_string(), "/fx/reverb".to_string()),
+        ],
+        *calls.borrow()
+    );
+
+    assert_eq!(0, dispatcher.dispatch(&msg("/synth/1")));
+}
+
+#[test]
+fn test_dispatch_rejects_malformed_patterns() {
+    let mut dispatcher = Dispatcher::new();
+    match dispatcher.on("/synth/[1-2", |_| ()) {
+        Err(OscError::BadAddress(_)) => (),
+        other => panic!("expected BadAddress, got {:?}", other),
+    }
+    assert_eq!(0, dispatcher.dispatch(&msg("/synth/1")));
+}
+
+#[test]
+fn test_dispatch_rewritten_messages() {
+    let calls = Rc::new(RefCell::new(vec![]));
+    let mut rewriter = Rewriter::new();
+    rewriter
+        .template("/1/fader{n}", "/mixer/channel/{n}/gain")
+        .unwrap();
+    let mut dispatcher = Dispatcher::new();
+    let received = calls.clone();
+    dispatcher
+        .set_rewriter(rewriter)
+        .on("/mixer/channel/*/gain", move |msg| {
+            received.borrow_mut().push(msg.addr.clone())
+        })
+        .unwrap();
+
+    assert_eq!(1, dispatcher.dispatch(&msg("/1/fader3")));
+    assert_eq!(1, dispatcher.dispatch(&msg("/mixer/channel/4/gain")));
+    assert_eq!(0, dispatcher.dispatch(&msg("/1/fader")));
+    assert_eq!(
+        vec![
+            "/mixer/channel/3/gain".to_string(),
+            "/mixer/channel/4/gain".to_string()
+        ],
+        *calls.borrow()
+    );
+}
+
+#[test]
+fn test_middleware_chain() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let mut dispatcher = Dispatcher::new();
+    let first = log.clone();
+    let second = log.clone();
+    let handler = log.clone();
+    dispatcher
+        .add_middleware(move |ctx: &mut Context| {
+            first.borrow_mut().push("first".to_string());
+            match ctx.packet {
+                OscPacket::Message(ref msg) if msg.addr == "/private" => Flow::Drop,
+                OscPacket::Message(ref msg) if msg.addr == "/urgent" => Flow::Dispatch,
+                _ => Flow::Continue,
+            }
+        })
+        .add_middleware(move |ctx: &mut Context| {
+            second.borrow_mut().push("second".to_string());
+            if let OscPacket::Message(ref mut msg) = ctx.packet {
+                msg.addr.push_str("/changed");
+            }
+            Flow::Continue
+        })
+        .on("/*/*", move |msg| {
+            handler.borrow_mut().push(msg.addr.clone())
+        })
+        .unwrap()
+        .on("/urgent", |_| ())
+        .unwrap();
+
+    assert_eq!(1, dispatcher.dispatch(&msg("/public")));
+    assert_eq!(0, dispatcher.dispatch(&msg("/private")));
+    assert_eq!(1, dispatcher.dispatch_message(&OscMessage::from("/urgent")));
+    assert_eq!(
+        vec!["first", "second", "/public/changed", "first", "first",],
+        *log.borrow()
+    );
+}
+
+#[test]
+fn test_middleware_context() {
+    let source: SocketAddr = "192.168.1.20:9000".parse().unwrap();
+    let mut seen = HashSet::new();
+    let mut dispatcher = Dispatcher::new();
+    dispatcher
+        // drops repeated packets per sender
+        .add_middleware(move |ctx: &mut Context| {
+            if seen.insert((ctx.source, format!("{:?}", ctx.packet))) {
+                Flow::Continue
+            } else {
+                Flow::Drop
+            }
+        })
+        .add_middleware(|ctx: &mut Context| {
+            if ctx.source.is_some() {
+                ctx.values.insert("remote".to_string(), "yes".to_string());
+            }
+            Flow::Continue
+        })
+        .add_middleware(|ctx: &mut Context| match ctx.values.get("remote") {
+            Some(_) => Flow::Continue,
+            None => Flow::Drop,
+        })
+        .on("/go", |_| ())
+        .unwrap();
+
+    let packet = bundle(vec![msg("/go"), msg("/go")]);
+    assert_eq!(0, dispatcher.dispatch(&packet));
+    let mut ctx = Context::new(packet);
+    ctx.source = Some(source);
+    assert_eq!(2, dispatcher.dispatch_context(ctx.clone()));
+    assert_eq!(0, dispatcher.dispatch_context(ctx));
+}
+
+#[test]
+fn test_dispatch_incoming_patterns() {
+    let calls = Rc::new(RefCell::new(vec![]));
+    let mut dispatcher = Dispatcher::new();
+    for addr in &[
+        "/synth/1/freq",
+        "/synth/2/freq",
+        "/synth/1/gain",
+        "/synth/*/pan",
+    ] {
+        let calls = calls.clone();
+        let addr = addr.to_string();
+        dispatcher
+            .on(&addr.clone(), move |msg| {
+                calls.borrow_mut().push((addr.clone(), msg.addr.clone()))
+            })
+            .unwrap();
+    }
+
+    assert_eq!(2, dispatcher.dispatch(&msg("/synth/*/freq")));
+    assert_eq!(2, dispatcher.dispatch(&msg("/synth/1/{freq,gain,pan}")));
+    // registered patterns don't match incoming ones
+    assert_eq!(0, dispatcher.dispatch(&msg("/synth/?/pan")));
+    // malformed patterns match nothing
+    assert_eq!(0, dispatcher.dispatch(&msg("/synth/[1/freq")));
+    assert_eq!(
+        vec![
+            ("/synth/1/freq".to_string(), "/synth/*/freq".to_string()),
+            ("/synth/2/freq".to_string(), "/synth/*/freq".to_string()),
+            (
+                "/synth/1/freq".to_string(),
+                "/synth/1/{freq,gain,pan}".to_string()
+            ),
+            (
+                "/synth/1/gain".to_string(),
+                "/synth/1/{freq,gain,pan}".to_string()
+            ),
+        ],
+        *calls.borrow()
+    );
+}
+
+#[test]
+fn test_bundle_transactions() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let mut dispatcher = Dispatcher::new();
+    let handler = log.clone();
+    let transaction = log.clone();
+    dispatcher
+        .on("/*", move |msg| handler.borrow_mut().push(msg.addr.clone()))
+        .unwrap()
+        .set_transaction(move |bundle, run| {
+            if bundle.content.is_empty() {
+                return;
+            }
+            transaction.borrow_mut().push("begin".to_string());
+            run();
+            transaction.borrow_mut().push("commit".to_string());
+        });
+
+    let packet = bundle(vec![msg("/a"), bundle(vec![msg("/b")]), msg("/c")]);
+    assert_eq!(3, dispatcher.dispatch(&packet));
+    assert_eq!(1, dispatcher.dispatch(&msg("/d")));
+    // the transaction decides not to run the handlers
+    assert_eq!(0, dispatcher.dispatch(&bundle(vec![])));
+    assert_eq!(2, dispatcher.dispatch(&bundle(vec![msg("/e"), msg("/f")])));
+    assert_eq!(
+        vec!["begin", "/a", "/b", "/c", "commit", "/d", "begin", "/e", "/f", "commit"],
+        *log.borrow()
+    );
+}
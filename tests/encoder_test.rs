@@ -1,7 +1,11 @@
 extern crate rosc;
 
+use rosc::encoder::{Encoder, EncoderOptions, EncoderProfile};
 use rosc::{decoder, encoder};
-use rosc::{OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscType};
+use rosc::{
+    DecodeLimit, NonAsciiChars, OscArray, OscBundle, OscColor, OscError, OscMessage,
+    OscMidiMessage, OscPacket, OscType,
+};
 
 #[test]
 fn test_encode_message_wo_args() {
@@ -26,6 +30,15 @@ fn test_encode_message_wo_args() {
     assert_eq!(*msg, dec_msg)
 }
 
+#[test]
+fn test_encode_message_wo_args_wire_format() {
+    // a message without arguments still has a type tag string with just the comma
+    let msg_packet = OscPacket::Message(OscMessage::from("/a"));
+    let enc_msg = encoder::encode(&msg_packet).unwrap();
+    assert_eq!(enc_msg, b"/a\0\0,\0\0\0");
+    assert_eq!(msg_packet, decoder::decode(&enc_msg).unwrap());
+}
+
 #[test]
 fn test_encode_empty_bundle() {
     let bundle_packet = OscPacket::Bundle(OscBundle {
@@ -57,8 +70,8 @@ fn test_encode_message_with_args() {
         args: vec![
             4i32.into(),
             42i64.into(),
-            3.1415926f32.into(),
-            3.14159265359f64.into(),
+            1.234_567_8f32.into(),
+            1.234_567_890_12f64.into(),
             "This is a string.".to_string().into(),
             "This is a string too.".into(),
             vec![1u8, 2u8, 3u8].into(),
@@ -154,3 +167,452 @@ fn test_encode_bundle() {
     let dec_bundle = decoder::decode(&enc_bundle).unwrap();
     assert_eq!(root_bundle, dec_bundle);
 }
+
+fn nested_bundle() -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: (1, 2).into(),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/a".to_string(),
+                args: vec![1i32.into(), "two".into(), vec![3u8, 4, 5].into()],
+            }),
+            OscPacket::Bundle(OscBundle {
+                timetag: (3, 4).into(),
+                content: vec![OscPacket::Message("/b".into())],
+            }),
+        ],
+    })
+}
+
+#[test]
+fn test_encode_into_vec() {
+    let packet = nested_bundle();
+    let expected = encoder::encode(&packet).unwrap();
+
+    let mut buf = vec![0xaa, 0xbb];
+    let len = encoder::encode_into(&packet, &mut buf).unwrap();
+    assert_eq!(len, expected.len());
+    assert_eq!(&buf[..2], &[0xaa, 0xbb]);
+    assert_eq!(&buf[2..], &expected[..]);
+
+    // reusing the buffer doesn't reallocate
+    let capacity = buf.capacity();
+    buf.clear();
+    encoder::encode_into(&packet, &mut buf).unwrap();
+    assert_eq!(buf, expected);
+    assert_eq!(buf.capacity(), capacity);
+}
+
+#[test]
+fn test_encode_into_slice() {
+    let packet = nested_bundle();
+    let expected = encoder::encode(&packet).unwrap();
+
+    let mut buf = [0u8; 256];
+    let len = encoder::encode_into_slice(&packet, &mut buf).unwrap();
+    assert_eq!(&buf[..len], &expected[..]);
+
+    let mut exact = vec![0u8; expected.len()];
+    assert_eq!(
+        encoder::encode_into_slice(&packet, &mut exact).unwrap(),
+        expected.len()
+    );
+
+    for len in 0..expected.len() {
+        let mut short = vec![0u8; len];
+        match encoder::encode_into_slice(&packet, &mut short) {
+            Err(OscError::BufferTooSmall) => (),
+            other => panic!("expected BufferTooSmall, got {:?}", other),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_encode_to_writer() {
+    let packet = nested_bundle();
+    let expected = encoder::encode(&packet).unwrap();
+
+    let mut out = std::io::Cursor::new(Vec::new());
+    let len = encoder::encode_to(&packet, &mut out).unwrap();
+    assert_eq!(len, expected.len());
+    assert_eq!(out.into_inner(), expected);
+
+    let mut short = [0u8; 16];
+    match encoder::encode_to(&packet, &mut &mut short[..]) {
+        Err(OscError::WriteError(_)) => (),
+        other => panic!("expected WriteError, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_encoded_size() {
+    let packets = vec![
+        OscPacket::Message("/".into()),
+        OscPacket::Message("/abc".into()),
+        OscPacket::Message(OscMessage {
+            addr: "/all/the/types".to_string(),
+            args: vec![
+                1i32.into(),
+                2.0f32.into(),
+                "".into(),
+                "four".into(),
+                vec![].into(),
+                vec![1u8, 2, 3, 4, 5].into(),
+                7i64.into(),
+                8.0f64.into(),
+                'x'.into(),
+                (9, 10).into(),
+                false.into(),
+                OscType::Nil,
+                OscType::Inf,
+                OscMidiMessage {
+                    port: 1,
+                    status: 2,
+                    data1: 3,
+                    data2: 4,
+                }
+                .into(),
+                OscColor {
+                    red: 1,
+                    green: 2,
+                    blue: 3,
+                    alpha: 4,
+                }
+                .into(),
+                OscArray {
+                    content: vec![
+                        1i32.into(),
+                        OscArray {
+                            content: vec!["nested".into()],
+                        }
+                        .into(),
+                    ],
+                }
+                .into(),
+            ],
+        }),
+        nested_bundle(),
+        OscPacket::Bundle(OscBundle {
+            timetag: (0, 1).into(),
+            content: vec![],
+        }),
+    ];
+
+    for packet in &packets {
+        assert_eq!(
+            encoder::encoded_size(packet),
+            encoder::encode(packet).unwrap().len(),
+            "{:?}",
+            packet
+        );
+    }
+}
+
+#[test]
+fn test_encode_validates_addresses() {
+    let invalid = ["", "foo", "/with space", "/#hash", "/nul\0", "/tab\t"];
+    for addr in &invalid {
+        let packet = OscPacket::Message(OscMessage::from(*addr));
+        match encoder::encode(&packet) {
+            Err(OscError::BadAddress(_)) => (),
+            other => panic!("expected BadAddress for {:?}, got {:?}", addr, other),
+        }
+        let options = EncoderOptions {
+            validate_addresses: false,
+            ..Default::default()
+        };
+        assert!(encoder::encode_with_options(&packet, &options).is_ok());
+        assert!(encoder::encode_into(&packet, &mut vec![]).is_err());
+        assert!(encoder::encode_into_with_options(&packet, &mut vec![], &options).is_ok());
+        assert!(encoder::encode_into_slice(&packet, &mut [0; 64]).is_err());
+        assert!(encoder::encode_into_slice_with_options(&packet, &mut [0; 64], &options).is_ok());
+        #[cfg(feature = "std")]
+        {
+            assert!(encoder::encode_to(&packet, &mut vec![]).is_err());
+            assert!(encoder::encode_to_with_options(&packet, &mut vec![], &options).is_ok());
+        }
+    }
+
+    // patterns and non-ASCII characters are fine
+    for addr in &["/", "/a/*/b?/[1-3]/{x,y}", "/ümlaut"] {
+        let packet = OscPacket::Message(OscMessage::from(*addr));
+        assert!(encoder::encode(&packet).is_ok(), "{}", addr);
+    }
+
+    // messages nested in bundles are validated too
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: (0, 1).into(),
+        content: vec![OscPacket::Message("bad".into())],
+    });
+    assert!(encoder::encode(&bundle).is_err());
+    assert!(encoder::encode_into(&bundle, &mut vec![]).is_err());
+}
+
+#[test]
+fn test_encode_non_ascii_chars() {
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/key".to_string(),
+        args: vec!['é'.into()],
+    });
+    match encoder::encode(&packet) {
+        Err(OscError::BadArg(_)) => (),
+        other => panic!("expected BadArg, got {:?}", other),
+    }
+
+    for (non_ascii_chars, c) in [(NonAsciiChars::Lossy, '?'), (NonAsciiChars::Allow, 'é')] {
+        let options = EncoderOptions {
+            non_ascii_chars,
+            ..Default::default()
+        };
+        let enc = encoder::encode_with_options(&packet, &options).unwrap();
+        assert_eq!(&(c as u32).to_be_bytes(), &enc[enc.len() - 4..]);
+    }
+}
+
+#[test]
+fn test_encode_osc10_profiles() {
+    let strict = EncoderOptions {
+        profile: EncoderProfile::Osc10,
+        ..Default::default()
+    };
+    let downgrade = EncoderOptions {
+        profile: EncoderProfile::Osc10Downgrade,
+        ..Default::default()
+    };
+    let encode_arg = |arg: OscType, options: &EncoderOptions| {
+        let packet = OscPacket::Message(OscMessage {
+            addr: "/a".to_string(),
+            args: vec![arg],
+        });
+        encoder::encode_with_options(&packet, options).map(|enc| match decoder::decode(&enc) {
+            Ok(OscPacket::Message(mut msg)) => msg.args.remove(0),
+            other => panic!("unexpected {:?}", other),
+        })
+    };
+
+    let core: Vec<OscType> = vec![1i32.into(), 2.0f32.into(), "s".into(), vec![1u8].into()];
+    for arg in core {
+        assert_eq!(arg, encode_arg(arg.clone(), &strict).unwrap());
+        assert_eq!(arg, encode_arg(arg.clone(), &downgrade).unwrap());
+    }
+
+    let converted: Vec<(OscType, OscType)> = vec![
+        (5i64.into(), 5i32.into()),
+        (0.5f64.into(), 0.5f32.into()),
+        ('x'.into(), "x".into()),
+        (OscType::Symbol("sym".to_string()), "sym".into()),
+        (true.into(), 1i32.into()),
+        (false.into(), 0i32.into()),
+        (OscType::Inf, f32::INFINITY.into()),
+        (
+            OscColor {
+                red: 1,
+                green: 2,
+                blue: 3,
+                alpha: 4,
+            }
+            .into(),
+            0x0102_0304i32.into(),
+        ),
+    ];
+    for (arg, expected) in converted {
+        assert!(encode_arg(arg.clone(), &strict).is_err(), "{:?}", arg);
+        assert_eq!(expected, encode_arg(arg, &downgrade).unwrap());
+    }
+
+    let unconvertible: Vec<OscType> = vec![
+        i64::MAX.into(),
+        (1, 2).into(),
+        OscType::Nil,
+        OscArray {
+            content: vec![1i32.into()],
+        }
+        .into(),
+    ];
+    for arg in unconvertible {
+        match encode_arg(arg.clone(), &downgrade) {
+            Err(OscError::BadArg(_)) => (),
+            other => panic!("expected BadArg for {:?}, got {:?}", arg, other),
+        }
+    }
+}
+
+#[test]
+fn test_encode_raw_bundle_element() {
+    let msg = OscPacket::Message(OscMessage {
+        addr: "/forwarded".to_string(),
+        args: vec![1i32.into(), "two".into()],
+    });
+    let raw = encoder::encode(&msg).unwrap();
+    assert_eq!(raw, encoder::encode(&OscPacket::Raw(raw.clone())).unwrap());
+
+    let bundle = |content| {
+        OscPacket::Bundle(OscBundle {
+            timetag: (1, 2).into(),
+            content: vec![OscPacket::Message("/local".into()), content],
+        })
+    };
+    let enc = encoder::encode(&bundle(OscPacket::Raw(raw.clone()))).unwrap();
+    assert_eq!(enc, encoder::encode(&bundle(msg.clone())).unwrap());
+    assert_eq!(
+        enc.len(),
+        encoder::encoded_size(&bundle(OscPacket::Raw(raw)))
+    );
+    assert_eq!(bundle(msg), decoder::decode(&enc).unwrap());
+}
+
+#[test]
+fn test_encode_bundle_depth() {
+    let nested = |depth: usize| {
+        (0..depth).fold(OscPacket::Message("/leaf".into()), |packet, _| {
+            OscPacket::Bundle(OscBundle {
+                timetag: (0, 1).into(),
+                content: vec![packet],
+            })
+        })
+    };
+
+    let packet = nested(32);
+    let encoded = encoder::encode(&packet).unwrap();
+    assert_eq!(packet, decoder::decode(&encoded).unwrap());
+
+    let too_deep = nested(33);
+    let mut buf = vec![0u8; 1024];
+    let results = vec![
+        encoder::encode(&too_deep).map(|_| ()),
+        encoder::encode_into_slice(&too_deep, &mut buf).map(|_| ()),
+    ];
+    for result in results {
+        match result {
+            Err(OscError::LimitExceeded(DecodeLimit::BundleDepth)) => (),
+            other => panic!("Expected the bundle depth to be exceeded, got {:?}", other),
+        }
+    }
+
+    let options = EncoderOptions {
+        max_bundle_depth: 33,
+        ..Default::default()
+    };
+    assert!(encoder::encode_with_options(&too_deep, &options).is_ok());
+}
+
+#[test]
+fn test_encoder_reuses_buffer() {
+    let packet = nested_bundle();
+    let expected = encoder::encode(&packet).unwrap();
+
+    let mut enc = Encoder::with_capacity(expected.len());
+    assert_eq!(enc.encode(&packet).unwrap(), &expected[..]);
+    assert_eq!(
+        enc.encode(&OscPacket::Message("/short".into())).unwrap(),
+        &encoder::encode(&OscPacket::Message("/short".into())).unwrap()[..]
+    );
+    assert!(enc.encode(&OscPacket::Message("bad".into())).is_err());
+    assert_eq!(enc.encode(&packet).unwrap(), &expected[..]);
+
+    let mut enc = Encoder::with_options(EncoderOptions {
+        validate_addresses: false,
+        ..Default::default()
+    });
+    assert!(enc.encode(&OscPacket::Message("bad".into())).is_ok());
+}
+
+#[test]
+fn test_encode_custom_type_tags() {
+    let msg = OscPacket::Message(OscMessage {
+        addr: "/vendor".to_string(),
+        args: vec![
+            OscType::Custom {
+                tag: 'X',
+                data: vec![1, 2, 3, 4, 5],
+            },
+            1i32.into(),
+            OscType::Custom {
+                tag: 'Y',
+                data: vec![],
+            },
+        ],
+    });
+    let enc = encoder::encode(&msg).unwrap();
+    assert_eq!(enc.len(), encoder::encoded_size(&msg));
+    assert_eq!(&enc[8..20], b",XiY\0\0\0\0\x01\x02\x03\x04");
+    assert_eq!(&enc[20..], b"\x05\0\0\0\0\0\0\x01");
+
+    // unknown to the default decoder
+    assert!(decoder::decode(&enc).is_err());
+    let options = decoder::DecoderOptions {
+        custom_type_tags: vec![('X', 5), ('Y', 0)],
+        ..Default::default()
+    };
+    assert_eq!(msg, decoder::decode_with_options(&enc, &options).unwrap());
+
+    for tag in &['i', '[', ',', ' ', 'ü', '\0'] {
+        let msg = OscPacket::Message(OscMessage {
+            addr: "/vendor".to_string(),
+            args: vec![OscType::Custom {
+                tag: *tag,
+                data: vec![],
+            }],
+        });
+        match encoder::encode(&msg) {
+            Err(OscError::BadArg(_)) => (),
+            other => panic!("expected BadArg for {:?}, got {:?}", tag, other),
+        }
+    }
+}
+
+#[test]
+fn test_split_bundle() {
+    let timetag = (1, 2).into();
+    // every message takes 20 bytes as bundle element
+    let msg = |i: i32| {
+        OscPacket::Message(OscMessage {
+            addr: "/fader".to_string(),
+            args: vec![i.into()],
+        })
+    };
+    let nested = OscBundle {
+        timetag: (3, 4).into(),
+        content: (3..7).map(msg).collect(),
+    };
+    let bundle = OscBundle {
+        timetag,
+        content: vec![msg(0), msg(1), msg(2), OscPacket::Bundle(nested), msg(7)],
+    };
+
+    let parts = encoder::split_bundle(&bundle, 76).unwrap();
+    let sizes: Vec<usize> = parts
+        .iter()
+        .map(|part| encoder::encoded_size(&OscPacket::Bundle(part.clone())))
+        .collect();
+    assert!(sizes.iter().all(|size| *size <= 76), "{:?}", sizes);
+    assert!(parts.iter().all(|part| part.timetag == timetag));
+    let flattened: Vec<_> = parts
+        .iter()
+        .flat_map(|part| {
+            OscPacket::Bundle(part.clone())
+                .iter_messages()
+                .map(|(msg, time)| (msg.args[0].clone().int().unwrap(), *time.unwrap()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let expected: Vec<_> = (0..8)
+        .map(|i| {
+            let time = if (3..7).contains(&i) { (3, 4) } else { (1, 2) };
+            (i, time.into())
+        })
+        .collect();
+    assert_eq!(expected, flattened);
+
+    // fits as is
+    assert_eq!(
+        vec![bundle.clone()],
+        encoder::split_bundle(&bundle, 1000).unwrap()
+    );
+
+    match encoder::split_bundle(&bundle, 30) {
+        Err(OscError::BadPacket(_)) => (),
+        other => panic!("expected BadPacket, got {:?}", other),
+    }
+}
@@ -0,0 +1,96 @@
+extern crate rosc;
+
+use rosc::filter::AddressFilter;
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+
+#[test]
+fn test_address_filter() {
+    let mut filter = AddressFilter::new();
+    assert!(filter.is_allowed("/anything"));
+    assert!(!filter.is_allowed("/any/*"));
+
+    filter
+        .allow("/public/**")
+        .unwrap()
+        .allow("/status")
+        .unwrap()
+        .deny("/public/{admin,debug}/**")
+        .unwrap();
+    for addr in [
+        "/public",
+        "/public/mixer",
+        "/public/mixer/1/gain",
+        "/status",
+    ] {
+        assert!(filter.is_allowed(addr), "{}", addr);
+    }
+    for addr in [
+        "/publicity",
+        "/status/detail",
+        "/admin/shutdown",
+        "/public/admin",
+        "/public/debug/level",
+        "/public/*",
+    ] {
+        assert!(!filter.is_allowed(addr), "{}", addr);
+    }
+
+    filter.allow_patterns(true);
+    assert!(filter.is_allowed("/public/*"));
+    assert!(!filter.is_allowed("/public/admin/*"));
+
+    assert!(AddressFilter::new().allow("no/slash").is_err());
+    assert!(AddressFilter::new().deny("/unclosed/[").is_err());
+}
+
+#[test]
+fn test_filter_packet() {
+    let mut filter = AddressFilter::new();
+    filter.deny("/**").unwrap();
+    let msg = OscPacket::Message(OscMessage::from("/mixer/1/gain"));
+    let mut rejected = 0;
+    assert_eq!(None, filter.filter_packet(msg.clone(), |_| rejected += 1));
+    assert_eq!(1, rejected);
+
+    let bundle = |content| {
+        OscPacket::Bundle(OscBundle {
+            timetag: OscTime::IMMEDIATE,
+            content,
+        })
+    };
+    let mut filter = AddressFilter::new();
+    filter.allow("/mixer/**").unwrap();
+    let packet = bundle(vec![
+        msg.clone(),
+        bundle(vec![OscPacket::Message(OscMessage::from("/admin/reset"))]),
+    ]);
+    assert_eq!(
+        Some(bundle(vec![msg, bundle(vec![])])),
+        filter.filter_packet(packet, |msg| assert_eq!("/admin/reset", msg.addr))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_udp_server_filter() {
+    use rosc::metrics::OscMetrics;
+    use rosc::udp::{OscUdpClient, OscUdpServer};
+    use std::sync::Arc;
+
+    let metrics = Arc::new(OscMetrics::new());
+    let mut filter = AddressFilter::new();
+    filter.allow("/public/**").unwrap();
+    let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    server.set_filter(filter).set_metrics(metrics.clone());
+
+    let mut client = OscUdpClient::connect(server.local_addr().unwrap()).unwrap();
+    let allowed = OscPacket::Message(OscMessage::from("/public/ping"));
+    client
+        .send(&OscPacket::Message(OscMessage::from("/admin/shutdown")))
+        .unwrap();
+    client.send(&allowed).unwrap();
+
+    assert_eq!(allowed, server.recv().unwrap().0);
+    assert_eq!(2, metrics.packets_received());
+    assert_eq!(1, metrics.messages_filtered());
+}
@@ -0,0 +1,209 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::decoder::DecoderOptions;
+use rosc::fragment::{Fragmenter, Reassembler, ReassemblerOptions, FRAGMENT_ADDR};
+use rosc::udp::{OscUdpClient, OscUdpServer};
+use rosc::{decoder, encoder, DecodeLimit, OscError, OscMessage, OscPacket, OscType};
+use std::net::SocketAddr;
+use std::thread;
+use std::time::Duration;
+
+fn waveform(len: usize) -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: "/waveform".to_string(),
+        args: vec![(0..len).map(|i| i as u8).collect::<Vec<u8>>().into()],
+    })
+}
+
+fn fragments(fragmenter: &mut Fragmenter, packet: &OscPacket) -> Vec<OscPacket> {
+    fragmenter
+        .fragment(&encoder::encode(packet).unwrap())
+        .iter()
+        .map(|datagram| {
+            assert!(datagram.len() <= fragmenter.max_size());
+            decoder::decode(datagram).unwrap()
+        })
+        .collect()
+}
+
+fn addr(port: u16) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], port))
+}
+
+#[test]
+fn test_small_packets_pass_through() {
+    let mut fragmenter = Fragmenter::new(100);
+    let packet = waveform(10);
+    assert_eq!(vec![packet.clone()], fragments(&mut fragmenter, &packet));
+
+    let mut reassembler = Reassembler::new();
+    assert_eq!(
+        Some(packet.clone()),
+        reassembler.push(packet, addr(1)).unwrap()
+    );
+}
+
+#[test]
+fn test_reassemble_out_of_order() {
+    let mut fragmenter = Fragmenter::new(100);
+    let packet = waveform(1000);
+    let mut parts = fragments(&mut fragmenter, &packet);
+    assert_eq!(17, parts.len());
+    match parts[0] {
+        OscPacket::Message(ref msg) => {
+            assert_eq!(FRAGMENT_ADDR, msg.addr);
+            assert_eq!(OscType::Int(17), msg.args[2]);
+        }
+        _ => panic!("expected a message"),
+    }
+    parts.reverse();
+    // a duplicate is ignored
+    parts.insert(3, parts[2].clone());
+
+    let mut reassembler = Reassembler::new();
+    let last = parts.pop().unwrap();
+    for part in parts {
+        assert_eq!(None, reassembler.push(part, addr(1)).unwrap());
+    }
+    assert_eq!(1, reassembler.pending());
+    assert_eq!(Some(packet), reassembler.push(last, addr(1)).unwrap());
+    assert_eq!(0, reassembler.pending());
+}
+
+#[test]
+fn test_reassemble_interleaved() {
+    let packet = waveform(300);
+    let mut a = Fragmenter::new(100);
+    let mut b = Fragmenter::new(100);
+    // both senders use the same ids
+    let parts_a = fragments(&mut a, &packet);
+    let parts_b = fragments(&mut b, &packet);
+
+    let mut reassembler = Reassembler::new();
+    let mut reassembled = vec![];
+    for (part_a, part_b) in parts_a.into_iter().zip(parts_b) {
+        reassembled.extend(reassembler.push(part_a, addr(1)).unwrap());
+        reassembled.extend(reassembler.push(part_b, addr(2)).unwrap());
+    }
+    assert_eq!(vec![packet.clone(), packet], reassembled);
+}
+
+#[test]
+fn test_reassembler_limits() {
+    let mut fragmenter = Fragmenter::new(100);
+    let mut reassembler = Reassembler::with_options(ReassemblerOptions {
+        timeout: Duration::from_millis(50),
+        max_packet_size: 500,
+        max_pending: 1,
+    });
+
+    let parts = fragments(&mut fragmenter, &waveform(1000));
+    let mut result = Ok(None);
+    for part in parts {
+        result = reassembler.push(part, addr(1));
+        if result.is_err() {
+            break;
+        }
+    }
+    match result {
+        Err(OscError::LimitExceeded(_)) => (),
+        other => panic!("expected LimitExceeded, got {:?}", other),
+    }
+    assert_eq!(0, reassembler.pending());
+
+    // more fragments than chunks of this size fit into the maximum size
+    let fragment = OscPacket::Message(OscMessage {
+        addr: FRAGMENT_ADDR.to_string(),
+        args: vec![1.into(), 0.into(), i32::MAX.into(), vec![0u8; 4].into()],
+    });
+    match reassembler.push(fragment, addr(1)) {
+        Err(OscError::LimitExceeded(_)) => (),
+        other => panic!("expected LimitExceeded, got {:?}", other),
+    }
+    assert_eq!(0, reassembler.pending());
+
+    let first = fragments(&mut fragmenter, &waveform(200));
+    let second = fragments(&mut fragmenter, &waveform(200));
+    assert_eq!(None, reassembler.push(first[0].clone(), addr(1)).unwrap());
+    match reassembler.push(second[0].clone(), addr(1)) {
+        Err(OscError::BadPacket(_)) => (),
+        other => panic!("expected BadPacket, got {:?}", other),
+    }
+    // the limit applies per sender
+    assert_eq!(None, reassembler.push(second[0].clone(), addr(2)).unwrap());
+    assert_eq!(2, reassembler.pending());
+
+    // the first packet expires
+    thread::sleep(Duration::from_millis(60));
+    for part in &second[..second.len() - 1] {
+        assert_eq!(None, reassembler.push(part.clone(), addr(1)).unwrap());
+    }
+    assert_eq!(1, reassembler.pending());
+    assert!(reassembler
+        .push(second.last().unwrap().clone(), addr(1))
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn test_malformed_fragment() {
+    let mut reassembler = Reassembler::new();
+    let fragment = OscPacket::Message(OscMessage {
+        addr: FRAGMENT_ADDR.to_string(),
+        args: vec![1.into(), 2.into(), 2.into(), vec![0u8].into()],
+    });
+    match reassembler.push(fragment, addr(1)) {
+        Err(OscError::BadPacket(_)) => (),
+        other => panic!("expected BadPacket, got {:?}", other),
+    }
+
+    let empty = OscPacket::Message(OscMessage {
+        addr: FRAGMENT_ADDR.to_string(),
+        args: vec![1.into(), 0.into(), 2.into(), vec![].into()],
+    });
+    match reassembler.push(empty, addr(1)) {
+        Err(OscError::BadPacket(_)) => (),
+        other => panic!("expected BadPacket, got {:?}", other),
+    }
+    assert_eq!(0, reassembler.pending());
+}
+
+#[test]
+#[should_panic(expected = "fragment size must be at least 44 bytes")]
+fn test_fragment_size_too_small() {
+    Fragmenter::new(43);
+}
+
+#[test]
+fn test_udp_fragmentation() {
+    let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    server.set_reassembler(Reassembler::new());
+    server
+        .socket()
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut client = OscUdpClient::connect(server.local_addr().unwrap()).unwrap();
+    client.set_fragment_size(Some(512));
+
+    let packet = waveform(5000);
+    client.send(&packet).unwrap();
+    let (received, _) = server.recv().unwrap();
+    assert_eq!(packet, received);
+
+    let small = waveform(10);
+    client.send(&small).unwrap();
+    assert_eq!(small, server.recv().unwrap().0);
+
+    // reassembled packets are decoded with the server's options
+    server.set_decoder_options(DecoderOptions {
+        max_blob_size: 1000,
+        ..DecoderOptions::default()
+    });
+    client.send(&packet).unwrap();
+    match server.recv() {
+        Err(ref err) if matches!(err.root(), OscError::LimitExceeded(DecodeLimit::BlobSize)) => (),
+        other => panic!("expected LimitExceeded, got {:?}", other),
+    }
+}
@@ -0,0 +1,91 @@
+extern crate rosc;
+
+use rosc::{encoder, hexdump, OscArray, OscBundle, OscMessage, OscPacket, OscTime};
+
+fn bundle() -> Vec<u8> {
+    encoder::encode(&OscPacket::Bundle(OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/mix".to_string(),
+            args: vec![
+                "a long string argument".into(),
+                vec![1u8, 2, 3].into(),
+                OscArray {
+                    content: vec![1.into(), true.into()],
+                }
+                .into(),
+                7i64.into(),
+            ],
+        })],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_hexdump_bundle() {
+    let expected = "\
+0000  23 62 75 6e 64 6c 65 00                          bundle
+0008  00 00 00 00 00 00 00 01                          time tag immediately
+0010  00 00 00 40                                      element of 64 bytes
+0014  2f 6d 69 78 00 00 00 00                          address \"/mix\"
+001c  2c 73 62 5b 69 54 5d 68 00 00 00 00              type tags \",sb[iT]h\"
+0028  61 20 6c 6f 6e 67 20 73 74 72 69 6e 67 20 61 72  s:\"a long string argument\"
+0038  67 75 6d 65 6e 74 00 00
+0040  00 00 00 03 01 02 03 00                          b:<01 02 03>
+0048                                                   [
+0048  00 00 00 01                                      i:1
+004c                                                   T
+004c                                                   ]
+004c  00 00 00 00 00 00 00 07                          h:7
+";
+    assert_eq!(expected, hexdump::hexdump(&bundle()));
+}
+
+#[test]
+fn test_hexdump_deeply_nested_bundles() {
+    // fills a 64 KB datagram, dumping it recursively would overflow the stack
+    let mut packet = encoder::encode(&OscPacket::Message(OscMessage::from("/deep"))).unwrap();
+    for _ in 0..3270 {
+        let mut bundle = encoder::encode_string("#bundle");
+        bundle.extend(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        bundle.extend(&(packet.len() as u32).to_be_bytes());
+        bundle.extend(packet);
+        packet = bundle;
+    }
+    let dump = hexdump::hexdump(&packet);
+    assert_eq!(1, dump.matches("bundle nested too deeply").count());
+    assert_eq!(32, dump.matches("time tag").count());
+    assert!(!dump.contains("address"));
+}
+
+#[test]
+fn test_hexdump_malformed_packets() {
+    let mut msg = encoder::encode_string("/vendor");
+    msg.extend(encoder::encode_string(",iX"));
+    msg.extend(&[0, 0, 0, 1, 0xde, 0xad]);
+    assert_eq!(
+        "\
+0000  2f 76 65 6e 64 6f 72 00                          address \"/vendor\"
+0008  2c 69 58 00                                      type tags \",iX\"
+000c  00 00 00 01                                      i:1
+0010  de ad                                            unknown type tag 'X'
+",
+        hexdump::hexdump(&msg)
+    );
+
+    assert_eq!(
+        "0000  78 79                                            unknown packet format\n",
+        hexdump::hexdump(b"xy")
+    );
+    assert_eq!(
+        "0000                                                   empty packet\n",
+        hexdump::hexdump(&[])
+    );
+
+    // dumping never panics, however the packet is cut off
+    let encoded = bundle();
+    for len in 0..encoded.len() {
+        let dump = hexdump::hexdump(&encoded[..len]);
+        assert!(!dump.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+extern crate rosc;
+
+use rosc::{
+    json, OscArray, OscBundle, OscColor, OscError, OscMessage, OscMidiMessage, OscPacket, OscType,
+};
+
+#[test]
+fn test_json_round_trip() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: (1, 2).into(),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/scene/\"1\"".to_string(),
+                args: vec![
+                    1i32.into(),
+                    2.5f32.into(),
+                    "thr\u{e9}e\n\u{1f3b5}".into(),
+                    OscType::Symbol("sym".to_string()),
+                    vec![0u8, 0xab, 0xff].into(),
+                    (5, 6).into(),
+                    i64::MAX.into(),
+                    0.1f64.into(),
+                    'x'.into(),
+                    OscColor {
+                        red: 1,
+                        green: 2,
+                        blue: 3,
+                        alpha: 4,
+                    }
+                    .into(),
+                    OscMidiMessage {
+                        port: 1,
+                        status: 0x90,
+                        data1: 60,
+                        data2: 127,
+                    }
+                    .into(),
+                    true.into(),
+                    false.into(),
+                    OscType::Nil,
+                    OscType::Inf,
+                    f32::INFINITY.into(),
+                    f64::NEG_INFINITY.into(),
+                    OscArray {
+                        content: vec![1i32.into(), OscArray { content: vec![] }.into()],
+                    }
+                    .into(),
+                    OscType::Custom {
+                        tag: 'X',
+                        data: vec![1, 2],
+                    },
+                ],
+            }),
+            OscPacket::Message("/empty".into()),
+            OscPacket::Raw(vec![1, 2, 3, 4]),
+        ],
+    });
+
+    let json = json::to_json(&packet);
+    assert_eq!(packet, json::from_json(&json).unwrap());
+}
+
+#[test]
+fn test_json_format() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: (1, 0).into(),
+        content: vec![OscPacket::Message(OscMessage {
+            addr: "/a".to_string(),
+            args: vec![
+                1.0f32.into(),
+                "b".into(),
+                vec![0xcau8, 0xfe].into(),
+                true.into(),
+                f64::NAN.into(),
+            ],
+        })],
+    });
+    assert_eq!(
+        concat!(
+            r#"{"timetag":{"seconds":1,"fractional":0},"elements":["#,
+            r#"{"address":"/a","args":[{"type":"f","value":1.0},{"type":"s","value":"b"},"#,
+            r#"{"type":"b","value":"cafe"},{"type":"T"},{"type":"d","value":"NaN"}]}]}"#
+        ),
+        json::to_json(&packet)
+    );
+}
+
+#[test]
+fn test_json_parse() {
+    let json = r#"
+        {
+            "address": "/synth/freq",
+            "comment": ["ignored", {"nested": null}],
+            "args": [
+                {"type": "i", "value": -3},
+                {"type": "f", "value": 4.4e2},
+                {"type": "c", "value": "🎵"}
+            ]
+        }
+    "#;
+    assert_eq!(
+        OscPacket::Message(OscMessage {
+            addr: "/synth/freq".to_string(),
+            args: vec![(-3).into(), 440.0f32.into(), '\u{1f3b5}'.into()],
+        }),
+        json::from_json(json).unwrap()
+    );
+
+    assert_eq!(
+        OscPacket::Message("/no/args".into()),
+        json::from_json(r#"{"address": "/no/args"}"#).unwrap()
+    );
+}
+
+#[test]
+fn test_json_errors() {
+    for json in &[
+        "",
+        "{",
+        r#"{"address": "/a"} trailing"#,
+        r#"{"address": 1}"#,
+        r#"{"something": "else"}"#,
+        r#"{"timetag": {"seconds": 1}}"#,
+        r#"{"raw": "abc"}"#,
+        r#"{"address": "/a", "args": [{"type": "s", "value": "\x"}]}"#,
+    ] {
+        match json::from_json(json) {
+            Err(OscError::BadPacket(_)) => (),
+            other => panic!("expected BadPacket for {}, got {:?}", json, other),
+        }
+    }
+
+    for json in &[
+        r#"{"address": "/a", "args": [{"value": 1}]}"#,
+        r#"{"address": "/a", "args": [{"type": "i", "value": 1.5}]}"#,
+        r#"{"address": "/a", "args": [{"type": "i", "value": 3000000000}]}"#,
+        r#"{"address": "/a", "args": [{"type": "c", "value": "ab"}]}"#,
+        r#"{"address": "/a", "args": [{"type": "m", "value": [1, 2, 3]}]}"#,
+        r#"{"address": "/a", "args": [{"type": "if", "value": 1}]}"#,
+    ] {
+        match json::from_json(json) {
+            Err(OscError::BadArg(_)) => (),
+            other => panic!("expected BadArg for {}, got {:?}", json, other),
+        }
+    }
+
+    let deep = "[".repeat(100_000);
+    assert!(json::from_json(&deep).is_err());
+}
@@ -0,0 +1,35 @@
+#[macro_use]
+extern crate rosc;
+
+use rosc::{encoder, OscMessage, OscPacket, OscType};
+
+#[test]
+fn test_osc_macro() {
+    assert_eq!(OscPacket::Message("/ping".into()), osc!("/ping"));
+    assert_eq!(
+        OscPacket::Message(OscMessage {
+            addr: "/synth/freq".to_string(),
+            args: vec![
+                440.0f32.into(),
+                "sine".into(),
+                true.into(),
+                OscType::Double(0.5),
+                OscType::Int(7),
+            ],
+        }),
+        osc!("/synth/freq", 440.0f32, "sine", true, 0.5, 7,)
+    );
+
+    let voice = 2;
+    let addr = format!("/voice/{}/gate", voice);
+    let packet = osc!(addr.as_str(), OscType::Nil);
+    assert!(encoder::encode(&packet).is_ok());
+    assert_eq!(packet, osc!(addr, OscType::Nil));
+}
+
+#[test]
+#[should_panic(expected = "invalid OSC address")]
+fn test_osc_macro_invalid_address() {
+    let addr = "no/slash";
+    osc!(addr, 1);
+}
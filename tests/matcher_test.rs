@@ -0,0 +1,119 @@
+extern crate rosc;
+
+use rosc::matcher::matches;
+
+#[test]
+fn test_match_literal() {
+    assert!(matches("/", "/"));
+    assert!(matches("/a/b", "/a/b"));
+    assert!(!matches("/a/b", "/a/c"));
+    assert!(!matches("/a/b", "/a/bc"));
+    assert!(!matches("/a/b", "/a"));
+    assert!(!matches("/a", "/a/b"));
+    assert!(matches("/ümlaut", "/ümlaut"));
+}
+
+#[test]
+fn test_match_wildcards() {
+    assert!(matches("/a/?", "/a/b"));
+    assert!(!matches("/a/?", "/a/bc"));
+    assert!(!matches("/a/?", "/a/"));
+    assert!(matches("/?/b", "/ü/b"));
+
+    assert!(matches("/a/*", "/a/"));
+    assert!(matches("/a/*", "/a/anything"));
+    assert!(matches("/*/*", "/a/b"));
+    assert!(matches("/a/*x*y", "/a/0x1y"));
+    assert!(matches("/a/**b", "/a/b"));
+    assert!(!matches("/a/*x", "/a/xy"));
+    // wildcards don't cross part boundaries
+    assert!(!matches("/*", "/a/b"));
+    assert!(!matches("/a*b", "/a/b"));
+}
+
+#[test]
+fn test_match_character_classes() {
+    assert!(matches("/osc[123]", "/osc2"));
+    assert!(!matches("/osc[123]", "/osc4"));
+    assert!(matches("/osc[1-3]", "/osc3"));
+    assert!(!matches("/osc[1-3]", "/osc0"));
+    assert!(matches("/[a-cx-z]", "/y"));
+    assert!(matches("/osc[!1-3]", "/osc4"));
+    assert!(!matches("/osc[!1-3]", "/osc2"));
+    // '-' at the edges is literal
+    assert!(matches("/[-a]", "/-"));
+    assert!(matches("/[a-]", "/-"));
+    assert!(matches("/[!-]", "/a"));
+    assert!(!matches("/[!-]", "/-"));
+}
+
+#[test]
+fn test_match_alternatives() {
+    assert!(matches("/{foo,bar}", "/foo"));
+    assert!(matches("/{foo,bar}", "/bar"));
+    assert!(!matches("/{foo,bar}", "/baz"));
+    assert!(!matches("/{foo,bar}", "/foobar"));
+    assert!(matches("/{foo,foobar}", "/foobar"));
+    assert!(matches("/x{,y}z", "/xz"));
+    assert!(matches("/x{,y}z", "/xyz"));
+    assert!(matches("/{a,b}{c,d}", "/bc"));
+    assert!(matches("/synth/{osc,lfo}[1-4]/*", "/synth/osc4/freq"));
+}
+
+#[test]
+fn test_match_malformed_patterns() {
+    assert!(!matches("/[abc", "/a"));
+    assert!(!matches("/{abc", "/abc"));
+    assert!(!matches("/[abc", "/[abc"));
+}
+
+#[test]
+fn test_match_pathological_patterns() {
+    let pattern = format!("/{}b", "*a".repeat(1000));
+    let address = format!("/{}", "a".repeat(2000));
+    assert!(!matches(&pattern, &address));
+
+    let long = format!("/{}", "x".repeat(1_000_000));
+    assert!(matches("/*", &long));
+}
+
+#[test]
+fn test_compiled_matcher() {
+    use rosc::matcher::Matcher;
+    use rosc::OscError;
+
+    let matcher = Matcher::new("/synth/*/{freq,gain}").unwrap();
+    assert_eq!("/synth/*/{freq,gain}", matcher.pattern());
+    assert!(matcher.is_match("/synth/1/freq"));
+    assert!(matcher.is_match("/synth//gain"));
+    assert!(!matcher.is_match("/synth/1/pan"));
+    assert!(!matcher.is_match("/synth/1"));
+    assert!(!matcher.is_match("/synth/1/freq/x"));
+
+    for pattern in &["/[abc", "/{abc", "/ok/[{a,b}"] {
+        match Matcher::new(pattern) {
+            Err(OscError::BadAddress(_)) => (),
+            other => panic!("expected BadAddress for {}, got {:?}", pattern, other),
+        }
+    }
+
+    // a compiled matcher agrees with `matches`
+    let patterns = [
+        "/a/b", "/a/?", "/a/*x*", "/[!a-c]?", "/{x,y,}z", "/*{a,b}*", "/a*/*b",
+    ];
+    let addresses = [
+        "/a/b", "/a/x", "/a/yxz", "/dz", "/z", "/yz", "/cab", "/ab/cb", "/a",
+    ];
+    for pattern in &patterns {
+        let matcher = Matcher::new(pattern).unwrap();
+        for address in &addresses {
+            assert_eq!(
+                matches(pattern, address),
+                matcher.is_match(address),
+                "{} {}",
+                pattern,
+                address
+            );
+        }
+    }
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::metrics::OscMetrics;
+use rosc::tcp::OscTcpServer;
+use rosc::udp::{OscUdpClient, OscUdpServer};
+use rosc::{encoder, OscMessage, OscPacket};
+use std::io::Write;
+use std::net::{TcpStream, UdpSocket};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn packet() -> OscPacket {
+    OscPacket::Message(OscMessage::from("/ping"))
+}
+
+#[test]
+fn test_udp_metrics() {
+    let metrics = Arc::new(OscMetrics::new());
+    let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    server.set_metrics(metrics.clone());
+    let mut client = OscUdpClient::connect(server.local_addr().unwrap()).unwrap();
+    client.set_metrics(metrics.clone());
+
+    client.send(&packet()).unwrap();
+    server.recv().unwrap();
+    let len = encoder::encode(&packet()).unwrap().len() as u64;
+    assert_eq!(1, metrics.packets_sent());
+    assert_eq!(len, metrics.bytes_sent());
+    assert_eq!(1, metrics.packets_received());
+    assert_eq!(len, metrics.bytes_received());
+
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .send_to(b"garbage", server.local_addr().unwrap())
+        .unwrap();
+    assert!(server.recv().is_err());
+    assert_eq!(1, metrics.decode_errors());
+    assert_eq!(1, metrics.packets_received());
+    assert_eq!(0, metrics.packets_dropped());
+}
+
+#[test]
+fn test_tcp_metrics() {
+    let metrics = Arc::new(OscMetrics::new());
+    let mut server = OscTcpServer::bind("127.0.0.1:0").unwrap();
+    server.set_metrics(metrics.clone());
+    let addr = server.local_addr().unwrap();
+    let (packets, received) = mpsc::channel();
+    thread::spawn(move || server.run(|packet, _| packets.send(packet).unwrap()));
+
+    let framed = encoder::encode_framed(&packet()).unwrap();
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(&framed).unwrap();
+    // the size prefix and half of the next packet
+    stream.write_all(&framed[..6]).unwrap();
+    received.recv_timeout(Duration::from_secs(5)).unwrap();
+    drop(stream);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while metrics.packets_dropped() == 0 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(1, metrics.packets_received());
+    assert_eq!(framed.len() as u64, metrics.bytes_received());
+    assert_eq!(1, metrics.packets_dropped());
+    assert_eq!(0, metrics.decode_errors());
+}
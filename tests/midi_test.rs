@@ -0,0 +1,162 @@
+#![cfg(feature = "midi")]
+extern crate rosc;
+
+use rosc::midi::{MidiBridge, MidiMapping, MidiSource, ValueFormat};
+use rosc::{OscError, OscMessage, OscMidiMessage, OscType};
+
+fn msg(addr: &str, arg: OscType) -> OscMessage {
+    OscMessage {
+        addr: addr.to_string(),
+        args: vec![arg],
+    }
+}
+
+#[test]
+fn test_notes() {
+    let mut bridge = MidiBridge::new();
+    bridge
+        .map(
+            MidiSource::Note {
+                channel: 1,
+                note: 60,
+            },
+            "/pad/1",
+        )
+        .unwrap();
+
+    let on = OscMidiMessage::note_on(0, 1, 60, 127);
+    assert_eq!(Some(msg("/pad/1", OscType::Float(1.0))), bridge.to_osc(&on));
+    let off = OscMidiMessage::note_off(0, 1, 60, 64);
+    assert_eq!(
+        Some(msg("/pad/1", OscType::Float(0.0))),
+        bridge.to_osc(&off)
+    );
+    let on_zero = OscMidiMessage::note_on(0, 1, 60, 0);
+    assert_eq!(
+        Some(msg("/pad/1", OscType::Float(0.0))),
+        bridge.to_osc(&on_zero)
+    );
+
+    // other notes and channels aren't mapped
+    assert_eq!(None, bridge.to_osc(&OscMidiMessage::note_on(0, 1, 61, 127)));
+    assert_eq!(None, bridge.to_osc(&OscMidiMessage::note_on(0, 2, 60, 127)));
+
+    assert_eq!(
+        Some(OscMidiMessage::note_on(0, 1, 60, 64)),
+        bridge.to_midi(&msg("/pad/1", OscType::Float(0.5)))
+    );
+    assert_eq!(
+        Some(OscMidiMessage::note_off(0, 1, 60, 0)),
+        bridge.to_midi(&msg("/pad/1", OscType::Float(0.0)))
+    );
+    assert_eq!(
+        Some(OscMidiMessage::note_on(0, 1, 60, 127)),
+        bridge.to_midi(&msg("/pad/1", OscType::Bool(true)))
+    );
+}
+
+#[test]
+fn test_control_change() {
+    let mut bridge = MidiBridge::new();
+    bridge
+        .add(MidiMapping {
+            source: MidiSource::ControlChange {
+                channel: 0,
+                controller: 1,
+            },
+            addr: "/mod".to_string(),
+            format: ValueFormat::Raw,
+        })
+        .unwrap();
+    bridge.set_port(3);
+
+    let cc = OscMidiMessage::control_change(3, 0, 1, 42);
+    assert_eq!(Some(msg("/mod", OscType::Int(42))), bridge.to_osc(&cc));
+    assert_eq!(Some(cc), bridge.to_midi(&msg("/mod", OscType::Int(42))));
+
+    // out of range values are clamped
+    assert_eq!(
+        Some(OscMidiMessage::control_change(3, 0, 1, 127)),
+        bridge.to_midi(&msg("/mod", OscType::Long(1000)))
+    );
+    assert_eq!(
+        Some(OscMidiMessage::control_change(3, 0, 1, 0)),
+        bridge.to_midi(&msg("/mod", OscType::Double(-1.0)))
+    );
+}
+
+#[test]
+fn test_pitch_bend() {
+    let mut bridge = MidiBridge::new();
+    bridge
+        .map(MidiSource::PitchBend { channel: 0 }, "/bend")
+        .unwrap();
+
+    let center = OscMidiMessage::pitch_bend(0, 0, 0x2000);
+    assert_eq!(0x00, center.data1);
+    assert_eq!(0x40, center.data2);
+    assert_eq!(
+        Some(msg("/bend", OscType::Float(0.0))),
+        bridge.to_osc(&center)
+    );
+    let down = OscMidiMessage::pitch_bend(0, 0, 0);
+    assert_eq!(
+        Some(msg("/bend", OscType::Float(-1.0))),
+        bridge.to_osc(&down)
+    );
+
+    assert_eq!(
+        Some(center),
+        bridge.to_midi(&msg("/bend", OscType::Float(0.0)))
+    );
+    assert_eq!(
+        Some(OscMidiMessage::pitch_bend(0, 0, 0x3fff)),
+        bridge.to_midi(&msg("/bend", OscType::Float(1.0)))
+    );
+    assert_eq!(
+        Some(OscMidiMessage::pitch_bend(0, 0, 0)),
+        bridge.to_midi(&msg("/bend", OscType::Float(-1.0)))
+    );
+}
+
+#[test]
+fn test_unmapped_messages() {
+    let mut bridge = MidiBridge::new();
+    bridge
+        .map(
+            MidiSource::Note {
+                channel: 0,
+                note: 60,
+            },
+            "/pad/1",
+        )
+        .unwrap();
+
+    // program change
+    let midi = OscMidiMessage::from_bytes(0, &[0xc0, 5]).unwrap();
+    assert_eq!(None, bridge.to_osc(&midi));
+
+    assert_eq!(None, bridge.to_midi(&msg("/pad/2", OscType::Float(1.0))));
+    assert_eq!(None, bridge.to_midi(&msg("/pad/1", "on".into())));
+    assert_eq!(
+        None,
+        bridge.to_midi(&msg("/pad/1", OscType::Float(f32::NAN)))
+    );
+    assert_eq!(
+        None,
+        bridge.to_midi(&OscMessage {
+            addr: "/pad/1".to_string(),
+            args: vec![],
+        })
+    );
+}
+
+#[test]
+fn test_invalid_address() {
+    let mut bridge = MidiBridge::new();
+    match bridge.map(MidiSource::PitchBend { channel: 0 }, "/pad/*") {
+        Err(OscError::BadAddress(_)) => (),
+        other => panic!("expected BadAddress, got {:?}", other),
+    }
+    assert!(bridge.mappings().is_empty());
+}
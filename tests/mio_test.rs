@@ -0,0 +1,62 @@
+#![cfg(all(feature = "mio", unix))]
+
+extern crate mio;
+extern crate rosc;
+
+use mio::{Events, Interest, Poll, Token};
+use rosc::udp::{OscUdpClient, OscUdpServer};
+use rosc::{OscMessage, OscPacket};
+use std::net::UdpSocket;
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[test]
+fn test_udp_server_mio() {
+    const OSC: Token = Token(7);
+
+    let mut poll = Poll::new().unwrap();
+    let (errors, received_errors) = mpsc::channel();
+    let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    server.on_error(move |err, _| errors.send(err.to_string()).unwrap());
+    poll.registry()
+        .register(&mut server, OSC, Interest::READABLE)
+        .unwrap();
+    let target = server.local_addr().unwrap();
+
+    let mut client = OscUdpClient::connect(target).unwrap();
+    let packets: Vec<_> = (0..3)
+        .map(|i| {
+            OscPacket::Message(OscMessage {
+                addr: "/frame".to_string(),
+                args: vec![i.into()],
+            })
+        })
+        .collect();
+    for packet in &packets {
+        client.send(packet).unwrap();
+    }
+    UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .send_to(b"garbage", target)
+        .unwrap();
+
+    let mut events = Events::with_capacity(4);
+    let mut received = vec![];
+    while received.len() < packets.len() {
+        poll.poll(&mut events, Some(Duration::from_secs(5)))
+            .unwrap();
+        assert!(!events.is_empty(), "timed out waiting for packets");
+        for event in events.iter() {
+            assert_eq!(OSC, event.token());
+            server.ready(|packet, _| received.push(packet)).unwrap();
+        }
+    }
+    assert_eq!(packets, received);
+    assert!(received_errors.try_recv().is_ok());
+
+    // nothing is left, so the server doesn't block
+    server
+        .ready(|packet, _| panic!("unexpected {:?}", packet))
+        .unwrap();
+    poll.registry().deregister(&mut server).unwrap();
+}
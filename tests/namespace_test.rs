@@ -0,0 +1,95 @@
+extern crate rosc;
+
+use rosc::namespace::Namespace;
+use rosc::OscError;
+
+fn children(namespace: &Namespace<i32>, addr: &str) -> Option<Vec<String>> {
+    namespace
+        .children(addr)
+        .map(|children| children.map(String::from).collect())
+}
+
+#[test]
+fn test_namespace_insert_and_enumerate() {
+    let mut namespace = Namespace::new();
+    assert!(namespace.is_empty());
+    assert_eq!(Some(vec![]), children(&namespace, "/"));
+
+    assert_eq!(None, namespace.insert("/synth/2/freq", 1).unwrap());
+    assert_eq!(None, namespace.insert("/synth/1/freq", 2).unwrap());
+    assert_eq!(None, namespace.insert("/synth/1/gain", 3).unwrap());
+    assert_eq!(None, namespace.insert("/volume", 4).unwrap());
+    assert_eq!(Some(4), namespace.insert("/volume", 5).unwrap());
+    assert!(!namespace.is_empty());
+
+    assert_eq!(
+        Some(vec!["synth".into(), "volume".into()]),
+        children(&namespace, "/")
+    );
+    assert_eq!(
+        Some(vec!["1".into(), "2".into()]),
+        children(&namespace, "/synth")
+    );
+    assert_eq!(
+        Some(vec!["freq".into(), "gain".into()]),
+        children(&namespace, "/synth/1")
+    );
+    // methods and unknown addresses have no children
+    assert_eq!(None, children(&namespace, "/volume"));
+    assert_eq!(None, children(&namespace, "/unknown"));
+
+    assert!(namespace.is_container("/synth"));
+    assert!(!namespace.is_container("/synth/1/freq"));
+    assert_eq!(Some(&2), namespace.get("/synth/1/freq"));
+    assert_eq!(None, namespace.get("/synth/1"));
+    *namespace.get_mut("/synth/1/freq").unwrap() += 10;
+
+    assert_eq!(
+        vec![
+            ("/synth/1/freq".to_string(), &12),
+            ("/synth/1/gain".to_string(), &3),
+            ("/synth/2/freq".to_string(), &1),
+            ("/volume".to_string(), &5),
+        ],
+        namespace.methods()
+    );
+}
+
+#[test]
+fn test_namespace_remove() {
+    let mut namespace = Namespace::new();
+    namespace.insert("/a/b/c", 1).unwrap();
+    namespace.insert("/a/d", 2).unwrap();
+
+    assert_eq!(None, namespace.remove("/a/b"));
+    assert_eq!(None, namespace.remove("/x"));
+    assert_eq!(Some(1), namespace.remove("/a/b/c"));
+    // the emptied container /a/b is gone
+    assert_eq!(Some(vec!["d".to_string()]), children(&namespace, "/a"));
+    assert_eq!(Some(2), namespace.remove("/a/d"));
+    assert!(namespace.is_empty());
+}
+
+#[test]
+fn test_namespace_rejects_bad_addresses() {
+    let mut namespace = Namespace::new();
+    namespace.insert("/method", 1).unwrap();
+    namespace.insert("/container/method", 2).unwrap();
+    for addr in &[
+        "",
+        "/",
+        "no/slash",
+        "/a//b",
+        "/trailing/",
+        "/pattern/*",
+        "/pattern/{a,b}",
+        "/method/child",
+        "/container",
+    ] {
+        match namespace.insert(addr, 3) {
+            Err(OscError::BadAddress(_)) => (),
+            other => panic!("expected BadAddress for {:?}, got {:?}", addr, other),
+        }
+    }
+    assert_eq!(2, namespace.methods().len());
+}
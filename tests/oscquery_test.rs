@@ -0,0 +1,142 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::namespace::Namespace;
+use rosc::oscquery::{self, Access, HostInfo, MethodInfo, OscQueryServer};
+use rosc::OscType;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn namespace() -> Namespace<MethodInfo> {
+    let mut namespace = Namespace::new();
+    let mut freq = MethodInfo::new("f");
+    freq.value = vec![OscType::Float(440.0)];
+    freq.description = Some("Frequency in \"Hz\"".to_string());
+    namespace.insert("/synth/freq", freq).unwrap();
+    let mut name = MethodInfo::new("s");
+    name.value = vec!["sine".into()];
+    name.access = Access::Read;
+    namespace.insert("/synth/name", name).unwrap();
+    let mut trigger = MethodInfo::new("");
+    trigger.access = Access::Write;
+    namespace.insert("/trigger", trigger).unwrap();
+    namespace
+}
+
+#[test]
+fn test_describe() {
+    let namespace = namespace();
+    assert_eq!(
+        Some(concat!(
+            r#"{"FULL_PATH":"/","CONTENTS":{"#,
+            r#""synth":{"FULL_PATH":"/synth","CONTENTS":{"#,
+            r#""freq":{"FULL_PATH":"/synth/freq","TYPE":"f","VALUE":[440.0],"ACCESS":3,"DESCRIPTION":"Frequency in \"Hz\""},"#,
+            r#""name":{"FULL_PATH":"/synth/name","TYPE":"s","VALUE":["sine"],"ACCESS":1}}},"#,
+            r#""trigger":{"FULL_PATH":"/trigger","TYPE":"","ACCESS":2}}}"#
+        )
+        .to_string()),
+        oscquery::describe(&namespace, "/")
+    );
+    assert_eq!(
+        oscquery::describe(&namespace, "/synth"),
+        oscquery::describe(&namespace, "/synth/")
+    );
+    assert_eq!(None, oscquery::describe(&namespace, "/drums"));
+}
+
+#[test]
+fn test_describe_attribute() {
+    let namespace = namespace();
+    assert_eq!(
+        Some(r#"{"VALUE":[440.0]}"#.to_string()),
+        oscquery::describe_attribute(&namespace, "/synth/freq", "VALUE")
+    );
+    assert_eq!(
+        Some(r#"{"FULL_PATH":"/synth"}"#.to_string()),
+        oscquery::describe_attribute(&namespace, "/synth", "FULL_PATH")
+    );
+    assert_eq!(
+        None,
+        oscquery::describe_attribute(&namespace, "/trigger", "VALUE")
+    );
+    assert_eq!(
+        None,
+        oscquery::describe_attribute(&namespace, "/synth", "TYPE")
+    );
+}
+
+fn get(addr: std::net::SocketAddr, target: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn test_oscquery_server() {
+    let namespace = Arc::new(RwLock::new(namespace()));
+    let mut server = OscQueryServer::bind("127.0.0.1:0", namespace.clone()).unwrap();
+    server.set_host_info(HostInfo {
+        name: "rosc test".to_string(),
+        osc_port: 9000,
+        osc_transport: "UDP".to_string(),
+    });
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+
+    let response = get(addr, "/synth/freq?VALUE");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.ends_with("\r\n\r\n{\"VALUE\":[440.0]}"));
+
+    namespace
+        .write()
+        .unwrap()
+        .get_mut("/synth/freq")
+        .unwrap()
+        .value = vec![OscType::Float(220.0)];
+    assert!(get(addr, "/synth/freq?VALUE").ends_with("{\"VALUE\":[220.0]}"));
+
+    let response = get(addr, "/?HOST_INFO");
+    assert!(response.contains("\"NAME\":\"rosc test\",\"OSC_PORT\":9000"));
+
+    assert!(get(addr, "/drums").starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+    // requests are limited in size
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let target = format!("/{}", "a".repeat(10_000));
+    write!(stream, "GET {} HTTP/1.1\r\n\r\n", target).unwrap();
+    let mut response = [0u8; 12];
+    stream.read_exact(&mut response).unwrap();
+    assert_eq!(b"HTTP/1.1 431", &response);
+}
+
+#[test]
+fn test_oscquery_server_request_timeout() {
+    let namespace = Arc::new(RwLock::new(namespace()));
+    let mut server = OscQueryServer::bind("127.0.0.1:0", namespace).unwrap();
+    server.set_request_timeout(Duration::from_millis(200));
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+
+    // trickles its request, which would restart a timeout per read
+    let mut slow = TcpStream::connect(addr).unwrap();
+    let trickle = thread::spawn(move || {
+        for byte in b"GET /synth HTTP/1.1\r\n".iter() {
+            if slow.write_all(&[*byte]).is_err() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    });
+
+    thread::sleep(Duration::from_millis(10));
+    let start = Instant::now();
+    assert!(get(addr, "/synth/freq?VALUE").starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(start.elapsed() < Duration::from_millis(800));
+    trickle.join().unwrap();
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::ping::{self, PingOptions, PingStats};
+use std::net::UdpSocket;
+use std::thread;
+use std::time::Duration;
+
+/// Echoes every datagram back to its sender, optionally dropping every
+/// third one.
+fn spawn_echo(drop_every_third: bool) -> UdpSocket {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let echo = socket.try_clone().unwrap();
+    thread::spawn(move || {
+        let mut buf = [0u8; 1536];
+        for i in 0.. {
+            let (len, from) = match echo.recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(_) => return,
+            };
+            if drop_every_third && i % 3 == 2 {
+                continue;
+            }
+            // a stray message that must not count as a reply
+            echo.send_to(b"/other\0\0,h\0\0\0\0\0\0\0\0\0\0", from)
+                .unwrap();
+            echo.send_to(&buf[..len], from).unwrap();
+        }
+    });
+    socket
+}
+
+#[test]
+fn test_ping() {
+    let echo = spawn_echo(false);
+    let options = PingOptions {
+        count: 5,
+        interval: Duration::from_millis(1),
+        ..Default::default()
+    };
+    let stats = ping::ping(echo.local_addr().unwrap(), &options).unwrap();
+    assert_eq!(5, stats.sent);
+    assert_eq!(5, stats.received);
+    assert_eq!(5, stats.rtts.len());
+    assert!(stats.min <= stats.avg && stats.avg <= stats.max);
+    assert!(stats.max < Duration::from_secs(1));
+    assert_eq!(0.0, stats.loss());
+}
+
+#[test]
+fn test_ping_loss() {
+    let echo = spawn_echo(true);
+    let options = PingOptions {
+        count: 3,
+        interval: Duration::from_millis(1),
+        timeout: Duration::from_millis(100),
+        ..Default::default()
+    };
+    let stats = ping::ping(echo.local_addr().unwrap(), &options).unwrap();
+    assert_eq!(3, stats.sent);
+    assert_eq!(2, stats.received);
+    assert!((stats.loss() - 1.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_ping_stats() {
+    let ms = Duration::from_millis;
+    let stats = PingStats::new(4, vec![ms(10), ms(30), ms(20)]);
+    assert_eq!(3, stats.received);
+    assert_eq!(ms(10), stats.min);
+    assert_eq!(ms(20), stats.avg);
+    assert_eq!(ms(30), stats.max);
+    assert_eq!(ms(15), stats.jitter);
+    assert_eq!(0.25, stats.loss());
+
+    let stats = PingStats::new(2, vec![]);
+    assert_eq!(Duration::ZERO, stats.avg);
+    assert_eq!(1.0, stats.loss());
+}
@@ -0,0 +1,19 @@
+#[macro_use]
+extern crate rosc;
+
+use rosc::prelude::*;
+
+#[test]
+fn test_prelude() {
+    let packet = osc!("/mixer/gain", 1i32, 0.5f32);
+    let msg = match decode(&encode(&packet).unwrap()).unwrap() {
+        OscPacket::Message(msg) => msg,
+        _ => panic!("Expected an OSC message!"),
+    };
+    let (channel, gain): (i32, f32) = msg.parse_args().unwrap();
+    assert_eq!((1, 0.5), (channel, gain));
+
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.on("/mixer/gain", |_: &OscMessage| ()).unwrap();
+    assert_eq!(1, dispatcher.dispatch(&packet));
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::ratelimit::{RateLimit, RateLimitAction, RateLimiter};
+use rosc::udp::{OscUdpClient, OscUdpServer};
+use rosc::{OscError, OscMessage, OscPacket};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_packet_burst_and_rate() {
+    let mut limiter = RateLimiter::new(RateLimit {
+        packets_per_sec: Some(20.0),
+        packet_burst: 3,
+        ..Default::default()
+    });
+    let start = Instant::now();
+    for _ in 0..3 {
+        limiter.acquire(100).unwrap();
+    }
+    assert!(start.elapsed() < Duration::from_millis(40));
+    // the next two packets wait 50 ms each
+    limiter.acquire(100).unwrap();
+    limiter.acquire(100).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(95));
+}
+
+#[test]
+fn test_byte_rate() {
+    let mut limiter = RateLimiter::new(RateLimit {
+        bytes_per_sec: Some(10_000.0),
+        byte_burst: 500,
+        ..Default::default()
+    });
+    let start = Instant::now();
+    limiter.acquire(400).unwrap();
+    // larger than the burst, sent once the bucket is full again
+    limiter.acquire(1000).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(35));
+    // waits for the 500 bytes it exceeded the full bucket by
+    limiter.acquire(1).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(85));
+}
+
+#[test]
+fn test_reject() {
+    let mut limiter = RateLimiter::new(RateLimit {
+        packets_per_sec: Some(1.0),
+        action: RateLimitAction::Reject,
+        ..Default::default()
+    });
+    limiter.acquire(10).unwrap();
+    match limiter.acquire(10) {
+        Err(OscError::RateLimited) => (),
+        other => panic!("expected RateLimited, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_no_limit() {
+    let mut limiter = RateLimiter::new(RateLimit::default());
+    let start = Instant::now();
+    for _ in 0..10_000 {
+        limiter.acquire(1000).unwrap();
+    }
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+#[should_panic(expected = "rate must be positive and finite")]
+fn test_invalid_rate() {
+    RateLimiter::new(RateLimit {
+        bytes_per_sec: Some(0.0),
+        ..Default::default()
+    });
+}
+
+#[test]
+fn test_udp_client_rate_limit() {
+    let server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    let mut client = OscUdpClient::connect(server.local_addr().unwrap()).unwrap();
+    client.set_rate_limit(RateLimit {
+        packets_per_sec: Some(1.0),
+        action: RateLimitAction::Reject,
+        ..Default::default()
+    });
+    let packet = OscPacket::Message(OscMessage::from("/ping"));
+    client.send(&packet).unwrap();
+    match client.send(&packet) {
+        Err(OscError::RateLimited) => (),
+        other => panic!("expected RateLimited, got {:?}", other),
+    }
+}
@@ -0,0 +1,83 @@
+extern crate rosc;
+
+use rosc::{encoder, OscBundle, OscError, OscMessage, OscPacket, OscType};
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts the allocations of the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn test_encode_into_slice_without_allocating() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: (1, 0).into(),
+        content: vec![
+            OscPacket::Message(OscMessage::new(
+                "/voice/1",
+                (440.0f32, "sine", vec![1u8, 2]),
+            )),
+            OscPacket::Message(OscMessage::new("/voice/2", (1i32, true))),
+        ],
+    });
+    let expected = encoder::encode(&packet).unwrap();
+
+    let mut buf = [0u8; 256];
+    let (len, count) = allocations(|| encoder::encode_into_slice(&packet, &mut buf).unwrap());
+    assert_eq!(0, count);
+    assert_eq!(expected, &buf[..len]);
+
+    let mut small = [0u8; 32];
+    let (result, count) = allocations(|| encoder::encode_into_slice(&packet, &mut small));
+    assert_eq!(0, count);
+    match result {
+        Err(OscError::BufferTooSmall) => (),
+        other => panic!("expected BufferTooSmall, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_encode_message_into_slice_without_allocating() {
+    let mut buf = [0u8; 64];
+    let (len, count) = allocations(|| {
+        let args = [OscType::Float(0.5), OscType::Int(3), OscType::Nil];
+        encoder::encode_message_into_slice("/voice/gain", &args, &mut buf).unwrap()
+    });
+    assert_eq!(0, count);
+    let expected = encoder::encode(&OscPacket::Message(OscMessage::new(
+        "/voice/gain",
+        (0.5f32, 3i32, OscType::Nil),
+    )))
+    .unwrap();
+    assert_eq!(expected, &buf[..len]);
+
+    assert!(encoder::encode_message_into_slice("no/slash", &[], &mut buf).is_err());
+    match encoder::encode_message_into_slice("/voice/gain", &[], &mut [0u8; 8]) {
+        Err(OscError::BufferTooSmall) => (),
+        other => panic!("expected BufferTooSmall, got {:?}", other),
+    }
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::dispatcher::Dispatcher;
+use rosc::recording::{Player, Recorder, MAGIC};
+use rosc::{decoder, encoder, OscMessage, OscPacket, OscType};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[test]
+fn test_recorder_writes_entries() {
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/light/1".to_string(),
+        args: vec![0.5f32.into()],
+    });
+    let encoded = encoder::encode(&packet).unwrap();
+
+    let mut recorder = Recorder::new(Vec::new()).unwrap();
+    let before = SystemTime::now();
+    recorder.record(&packet).unwrap();
+    recorder
+        .record_bytes(&encoded, UNIX_EPOCH + Duration::from_micros(1_500))
+        .unwrap();
+    let log = recorder.into_inner();
+
+    assert_eq!(&log[..8], MAGIC);
+    let mut rest = &log[8..];
+    let mut entries = vec![];
+    while !rest.is_empty() {
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let mut micros = [0u8; 8];
+        micros.copy_from_slice(&rest[4..12]);
+        let time = UNIX_EPOCH + Duration::from_micros(u64::from_be_bytes(micros));
+        entries.push((time, decoder::decode(&rest[12..12 + len]).unwrap()));
+        rest = &rest[12 + len..];
+    }
+
+    assert_eq!(2, entries.len());
+    assert!(entries[0].0 >= before - Duration::from_micros(1));
+    assert_eq!(packet, entries[0].1);
+    assert_eq!(UNIX_EPOCH + Duration::from_micros(1_500), entries[1].0);
+    assert_eq!(packet, entries[1].1);
+}
+
+#[test]
+fn test_recorder_create_file() {
+    let path = std::env::temp_dir().join(format!("rosc-recording-{}.log", std::process::id()));
+    let mut recorder = Recorder::create(&path).unwrap();
+    recorder
+        .record(&OscPacket::Message(OscMessage::from("/ping")))
+        .unwrap();
+    drop(recorder);
+
+    let log = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(&log[..8], MAGIC);
+    assert_eq!(8 + 12 + 12, log.len());
+}
+
+fn recorded_log() -> Vec<u8> {
+    let mut recorder = Recorder::new(Vec::new()).unwrap();
+    for (i, millis) in [0u64, 100, 300].iter().enumerate() {
+        let packet = OscPacket::Message(OscMessage {
+            addr: "/cue".to_string(),
+            args: vec![(i as i32).into()],
+        });
+        let encoded = encoder::encode(&packet).unwrap();
+        recorder
+            .record_bytes(&encoded, UNIX_EPOCH + Duration::from_millis(1_000 + millis))
+            .unwrap();
+    }
+    recorder.into_inner()
+}
+
+#[test]
+fn test_player_reads_entries() {
+    let log = recorded_log();
+    let mut player = Player::new(&log[..]).unwrap();
+    let entry = player.next_entry().unwrap().unwrap();
+    assert_eq!(UNIX_EPOCH + Duration::from_millis(1_000), entry.time);
+    assert_eq!(
+        OscPacket::Message(OscMessage {
+            addr: "/cue".to_string(),
+            args: vec![0.into()],
+        }),
+        entry.packet().unwrap()
+    );
+    assert!(player.next_entry().unwrap().is_some());
+    assert!(player.next_entry().unwrap().is_some());
+    assert!(player.next_entry().unwrap().is_none());
+
+    let mut truncated = Player::new(&log[..log.len() - 1]).unwrap();
+    truncated.next_entry().unwrap();
+    truncated.next_entry().unwrap();
+    assert!(truncated.next_entry().is_err());
+
+    assert!(Player::new(&b"not a log"[..]).is_err());
+}
+
+#[test]
+fn test_player_keeps_timing() {
+    let log = recorded_log();
+    let mut player = Player::new(&log[..]).unwrap();
+    let start = Instant::now();
+    let mut received = vec![];
+    player
+        .set_speed(2.0)
+        .play(|packet| received.push((start.elapsed(), packet)))
+        .unwrap();
+
+    assert_eq!(3, received.len());
+    assert!(received[0].0 < Duration::from_millis(50));
+    assert!(received[1].0 >= Duration::from_millis(50));
+    assert!(received[2].0 >= Duration::from_millis(150));
+    assert!(received[2].0 < Duration::from_secs(1));
+}
+
+#[test]
+fn test_player_dispatches_packets() {
+    let log = recorded_log();
+    let cues = Rc::new(RefCell::new(vec![]));
+    let mut dispatcher = Dispatcher::new();
+    let handler_cues = cues.clone();
+    dispatcher
+        .on("/cue", move |msg| {
+            handler_cues.borrow_mut().push(msg.args[0].clone())
+        })
+        .unwrap();
+    Player::new(&log[..])
+        .unwrap()
+        .set_speed(100.0)
+        .play_dispatcher(&mut dispatcher)
+        .unwrap();
+    assert_eq!(
+        vec![OscType::Int(0), OscType::Int(1), OscType::Int(2)],
+        *cues.borrow()
+    );
+}
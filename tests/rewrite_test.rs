@@ -0,0 +1,96 @@
+extern crate rosc;
+
+use rosc::rewrite::Rewriter;
+use rosc::{OscBundle, OscError, OscMessage, OscPacket};
+
+#[test]
+fn test_prefix_rules() {
+    let mut rewriter = Rewriter::new();
+    rewriter.prefix("/1", "/mixer").unwrap();
+    assert_eq!(Some("/mixer".to_string()), rewriter.rewrite("/1"));
+    assert_eq!(
+        Some("/mixer/fader/3".to_string()),
+        rewriter.rewrite("/1/fader/3")
+    );
+    assert_eq!(None, rewriter.rewrite("/10/fader/3"));
+    assert_eq!(None, rewriter.rewrite("/2/1"));
+}
+
+#[test]
+fn test_template_rules() {
+    let mut rewriter = Rewriter::new();
+    rewriter
+        .template("/{page}/fader{n}", "/mixer/channel/{n}/gain/{page}")
+        .unwrap()
+        .template("/xy{pad}/{axis}", "/pan/{axis}{pad}")
+        .unwrap();
+    assert_eq!(
+        Some("/mixer/channel/12/gain/1".to_string()),
+        rewriter.rewrite("/1/fader12")
+    );
+    assert_eq!(Some("/pan/x2".to_string()), rewriter.rewrite("/xy2/x"));
+    // captures match at least one character and never a '/'
+    assert_eq!(None, rewriter.rewrite("/1/fader"));
+    assert_eq!(None, rewriter.rewrite("/1/2/fader3"));
+    assert_eq!(None, rewriter.rewrite("/1/fader3/x"));
+}
+
+#[test]
+fn test_first_matching_rule_wins() {
+    let mut rewriter = Rewriter::new();
+    rewriter
+        .template("/1/fader{n}", "/mixer/{n}")
+        .unwrap()
+        .prefix("/1", "/controller")
+        .unwrap();
+    assert_eq!(Some("/mixer/1".to_string()), rewriter.rewrite("/1/fader1"));
+    assert_eq!(
+        Some("/controller/toggle1".to_string()),
+        rewriter.rewrite("/1/toggle1")
+    );
+}
+
+#[test]
+fn test_rewrite_packet() {
+    let mut rewriter = Rewriter::new();
+    rewriter.prefix("/a", "/b").unwrap();
+    let mut packet = OscPacket::Bundle(OscBundle {
+        timetag: (0, 1).into(),
+        content: vec![
+            OscPacket::Message(OscMessage::from("/a/1")),
+            OscPacket::Message(OscMessage::from("/c")),
+            OscPacket::Bundle(OscBundle {
+                timetag: (0, 1).into(),
+                content: vec![OscPacket::Message(OscMessage::from("/a"))],
+            }),
+        ],
+    });
+    assert_eq!(2, rewriter.rewrite_packet(&mut packet));
+    let addrs: Vec<&str> = packet
+        .iter_messages()
+        .map(|(msg, _)| msg.addr.as_str())
+        .collect();
+    assert_eq!(vec!["/b/1", "/c", "/b"], addrs);
+}
+
+#[test]
+fn test_malformed_rules() {
+    let mut rewriter = Rewriter::new();
+    for (from, to) in &[
+        ("/a/{n", "/b"),
+        ("/a/{}", "/b"),
+        ("/a/{n-1}", "/b"),
+        ("/a/{n}/{n}", "/b/{n}"),
+        ("/a/{n}", "/b/{m}"),
+        ("/a/*{n}", "/b/{n}"),
+        ("a/{n}", "/b/{n}"),
+    ] {
+        match rewriter.template(from, to) {
+            Err(OscError::BadAddress(_)) => (),
+            other => panic!("expected BadAddress for {}, got {:?}", from, other),
+        }
+    }
+    assert!(rewriter.prefix("/a/*", "/b").is_err());
+    assert!(rewriter.prefix("/a", "/").is_err());
+    assert!(rewriter.is_empty());
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "serde")]
+
+extern crate rosc;
+extern crate serde_json;
+
+use rosc::{OscArray, OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscType};
+
+#[test]
+fn test_serde_round_trip() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: (1, 2).into(),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/scene/1".to_string(),
+                args: vec![
+                    1i32.into(),
+                    2.5f32.into(),
+                    "three".into(),
+                    vec![4u8].into(),
+                    (5, 6).into(),
+                    7i64.into(),
+                    8.5f64.into(),
+                    'x'.into(),
+                    OscColor {
+                        red: 1,
+                        green: 2,
+                        blue: 3,
+                        alpha: 4,
+                    }
+                    .into(),
+                    OscMidiMessage {
+                        port: 0,
+                        status: 0x90,
+                        data1: 60,
+                        data2: 127,
+                    }
+                    .into(),
+                    true.into(),
+                    OscArray::from(vec![OscType::Nil, OscType::Inf]).into(),
+                ],
+            }),
+            OscPacket::Raw(vec![1, 2, 3, 4]),
+        ],
+    });
+
+    let json = serde_json::to_string(&packet).unwrap();
+    assert_eq!(packet, serde_json::from_str(&json).unwrap());
+}
+
+#[test]
+fn test_serde_representation() {
+    let msg = OscMessage {
+        addr: "/a".to_string(),
+        args: vec![1i32.into(), OscType::Nil],
+    };
+    assert_eq!(
+        serde_json::json!({
+            "addr": "/a",
+            "args": [{"type": "Int", "value": 1}, {"type": "Nil"}],
+        }),
+        serde_json::to_value(&msg).unwrap()
+    );
+}
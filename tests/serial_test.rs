@@ -0,0 +1,63 @@
+#![cfg(feature = "serial")]
+
+extern crate rosc;
+
+use rosc::serial::OscSerialPort;
+use rosc::{slip, OscMessage, OscPacket};
+use std::io::{self, Cursor, Read, Write};
+
+/// A port that reads from a fixed buffer and records what was written.
+struct FakePort {
+    input: Cursor<Vec<u8>>,
+    output: Vec<u8>,
+}
+
+impl Read for FakePort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // deliver a few bytes at a time like a slow serial line
+        let len = buf.len().min(3);
+        self.input.read(&mut buf[..len])
+    }
+}
+
+impl Write for FakePort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_serial_send_and_recv() {
+    let packets = vec![
+        OscPacket::Message(OscMessage {
+            addr: "/analog/0".to_string(),
+            args: vec![512i32.into()],
+        }),
+        OscPacket::Message(OscMessage::from("/button/1")),
+    ];
+    let mut input = vec![0x01, 0x02, slip::END];
+    for packet in &packets {
+        input.extend(slip::encode_packet(packet).unwrap());
+    }
+    let mut port = OscSerialPort::from_port(FakePort {
+        input: Cursor::new(input),
+        output: vec![],
+    });
+
+    // the garbage before the first complete frame is reported
+    assert!(port.recv().is_err());
+    for packet in &packets {
+        assert_eq!(*packet, port.recv().unwrap());
+    }
+    assert!(port.recv().is_err());
+
+    port.send(&packets[0]).unwrap();
+    assert_eq!(
+        slip::encode_packet(&packets[0]).unwrap(),
+        port.port().output
+    );
+}
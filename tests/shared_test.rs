@@ -0,0 +1,67 @@
+#![cfg(feature = "bytes")]
+
+extern crate bytes;
+extern crate rosc;
+
+use bytes::Bytes;
+use rosc::{
+    decoder, encoder, OscArray, OscBundle, OscMessage, OscPacket, OscPacketShared, OscType,
+    OscTypeShared,
+};
+
+#[test]
+fn test_decode_bytes_matches_decode() {
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: (1, 2).into(),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/sample".to_string(),
+                args: vec![
+                    "kick".into(),
+                    vec![1u8, 2, 3, 4, 5].into(),
+                    OscType::Array(OscArray {
+                        content: vec![1i32.into(), "nested".into()],
+                    }),
+                    OscType::Nil,
+                ],
+            }),
+            OscPacket::Message(OscMessage::from("/empty")),
+        ],
+    });
+    let buf = Bytes::from(encoder::encode(&packet).unwrap());
+    let shared = decoder::decode_bytes(&buf).unwrap();
+    assert_eq!(packet, OscPacket::from(shared));
+}
+
+#[test]
+fn test_decode_bytes_shares_buffer() {
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/sample".to_string(),
+        args: vec!["kick".into(), vec![7u8; 100].into()],
+    });
+    let buf = Bytes::from(encoder::encode(&packet).unwrap());
+    let range = buf.as_ptr() as usize..buf.as_ptr() as usize + buf.len();
+    let msg = match decoder::decode_bytes(&buf).unwrap() {
+        OscPacketShared::Message(msg) => msg,
+        OscPacketShared::Bundle(_) => panic!("expected a message"),
+    };
+    drop(buf);
+
+    assert_eq!("/sample", msg.addr);
+    assert!(range.contains(&(msg.addr.as_ptr() as usize)));
+    match msg.args[..] {
+        [OscTypeShared::String(ref name), OscTypeShared::Blob(ref blob)] => {
+            assert_eq!("kick", *name);
+            assert_eq!(&[7u8; 100][..], &blob[..]);
+            assert!(range.contains(&(name.as_ptr() as usize)));
+            assert!(range.contains(&(blob.as_ptr() as usize)));
+        }
+        ref args => panic!("unexpected arguments {:?}", args),
+    }
+}
+
+#[test]
+fn test_decode_bytes_rejects_malformed_packets() {
+    assert!(decoder::decode_bytes(&Bytes::from_static(b"/a\0\0,i\0\0")).is_err());
+    assert!(decoder::decode_bytes(&Bytes::new()).is_err());
+}
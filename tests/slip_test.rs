@@ -0,0 +1,73 @@
+extern crate rosc;
+
+use rosc::decoder::DecoderOptions;
+use rosc::slip::{self, SlipDecoder, END, ESC, ESC_END, ESC_ESC};
+use rosc::{encoder, DecodeLimit, OscError, OscMessage, OscPacket};
+
+#[test]
+fn test_slip_encode() {
+    assert_eq!(vec![END, END], slip::encode(&[]));
+    assert_eq!(
+        vec![END, 1, ESC, ESC_END, 2, ESC, ESC_ESC, END],
+        slip::encode(&[1, END, 2, ESC])
+    );
+
+    let mut buf = vec![42];
+    slip::encode_into(&[END], &mut buf);
+    assert_eq!(vec![42, END, ESC, ESC_END, END], buf);
+}
+
+#[test]
+fn test_slip_round_trip() {
+    let packets = vec![
+        OscPacket::Message(OscMessage {
+            addr: "/blob".to_string(),
+            args: vec![vec![END, ESC, ESC_END, ESC_ESC, 0].into()],
+        }),
+        OscPacket::Message(OscMessage::from("/ping")),
+    ];
+    let mut stream = vec![];
+    for packet in &packets {
+        stream.extend(slip::encode_packet(packet).unwrap());
+    }
+
+    // feeding byte by byte must yield the same packets
+    let mut decoder = SlipDecoder::new();
+    let mut decoded = vec![];
+    for byte in &stream {
+        decoder.feed(&[*byte]);
+        while let Some(packet) = decoder.decode().unwrap() {
+            decoded.push(packet);
+        }
+    }
+    assert_eq!(packets, decoded);
+}
+
+#[test]
+fn test_slip_decoder_resynchronizes() {
+    let packet = OscPacket::Message(OscMessage::from("/ping"));
+    let encoded = encoder::encode(&packet).unwrap();
+
+    let mut decoder = SlipDecoder::new();
+    // the tail of a frame whose start was lost
+    decoder.feed(&[1, 2, 3, END]);
+    decoder.feed(&slip::encode(&encoded));
+    assert!(decoder.decode().is_err());
+    assert_eq!(Some(packet), decoder.decode().unwrap());
+    assert_eq!(None, decoder.decode().unwrap());
+}
+
+#[test]
+fn test_slip_decoder_limits_frame_size() {
+    let mut decoder = SlipDecoder::with_options(DecoderOptions {
+        max_packet_size: 4,
+        ..Default::default()
+    });
+    decoder.feed(&slip::encode(&[1, 2, 3, 4, 5]));
+    decoder.feed(&slip::encode(&[1, 2, 3, 4]));
+    match decoder.next_frame() {
+        Err(OscError::LimitExceeded(DecodeLimit::PacketSize)) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(Some(vec![1, 2, 3, 4]), decoder.next_frame().unwrap());
+}
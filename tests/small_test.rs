@@ -0,0 +1,64 @@
+#![cfg(feature = "smallvec")]
+
+extern crate rosc;
+
+use rosc::{
+    decoder, encoder, OscBundle, OscMessage, OscMessageSmall, OscPacket, OscPacketSmall, OscType,
+};
+
+fn bundle() -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: (1, 2).into(),
+        content: vec![
+            OscPacket::Message(OscMessage {
+                addr: "/fader".to_string(),
+                args: vec![1i32.into(), 0.5f32.into()],
+            }),
+            OscPacket::Bundle(OscBundle {
+                timetag: (1, 2).into(),
+                content: vec![OscPacket::Message(OscMessage {
+                    addr: "/many".to_string(),
+                    args: (0..8).map(OscType::from).collect(),
+                })],
+            }),
+        ],
+    })
+}
+
+#[test]
+fn test_decode_small() {
+    let packet = bundle();
+    let buf = encoder::encode(&packet).unwrap();
+    let small = decoder::decode_small(&buf).unwrap();
+
+    match small {
+        OscPacketSmall::Bundle(ref bundle) => match bundle.content[0] {
+            OscPacketSmall::Message(ref msg) => {
+                assert_eq!(2, msg.args.len());
+                assert!(!msg.args.spilled());
+            }
+            _ => panic!("Expected an OSC message!"),
+        },
+        _ => panic!("Expected an OSC bundle!"),
+    }
+    assert_eq!(packet, OscPacket::from(small.clone()));
+
+    assert_eq!(buf, encoder::encode_small(&small).unwrap());
+    let mut out = vec![0xff];
+    assert_eq!(
+        buf.len(),
+        encoder::encode_small_into(&small, &mut out).unwrap()
+    );
+    assert_eq!(buf, &out[1..]);
+
+    assert!(decoder::decode_small(&buf[..buf.len() - 1]).is_err());
+}
+
+#[test]
+fn test_encode_small_errors() {
+    let packet = OscPacketSmall::from(OscMessageSmall::from(OscMessage::from("bad")));
+    let mut out = vec![1, 2, 3];
+    assert!(encoder::encode_small(&packet).is_err());
+    assert!(encoder::encode_small_into(&packet, &mut out).is_err());
+    assert_eq!(vec![1, 2, 3], out);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+extern crate socket2;
+
+use rosc::socket::SocketOptions;
+use rosc::tcp::{OscTcpClient, OscTcpServer};
+use rosc::udp::{OscUdpClient, OscUdpServer};
+use rosc::{OscMessage, OscPacket};
+use socket2::SockRef;
+
+#[test]
+fn test_udp_socket_options() {
+    let options = SocketOptions::new()
+        .reuse_address(true)
+        .recv_buffer_size(1 << 16)
+        .send_buffer_size(1 << 16)
+        .ttl(7)
+        .dscp(46);
+    assert_eq!(SocketOptions::new().tos(184), SocketOptions::new().dscp(46));
+
+    let mut server = OscUdpServer::bind_with_options("127.0.0.1:0", &options).unwrap();
+    let socket = SockRef::from(server.socket());
+    assert!(socket.reuse_address().unwrap());
+    assert!(socket.recv_buffer_size().unwrap() >= 1 << 16);
+    assert!(socket.send_buffer_size().unwrap() >= 1 << 16);
+    assert_eq!(7, socket.ttl_v4().unwrap());
+    assert_eq!(184, socket.tos_v4().unwrap());
+
+    let mut client =
+        OscUdpClient::connect_with_options(server.local_addr().unwrap(), &options).unwrap();
+    assert_eq!(7, client.socket().ttl().unwrap());
+    let packet = OscPacket::Message(OscMessage::from("/qos"));
+    client.send(&packet).unwrap();
+    assert_eq!(packet, server.recv().unwrap().0);
+
+    // unset options keep the defaults
+    let server = OscUdpServer::bind_with_options("127.0.0.1:0", &SocketOptions::new()).unwrap();
+    assert!(!SockRef::from(server.socket()).reuse_address().unwrap());
+}
+
+#[test]
+fn test_tcp_socket_options() {
+    let options = SocketOptions::new().reuse_address(true).ttl(9);
+    let server = OscTcpServer::bind_with_options("127.0.0.1:0", &options).unwrap();
+    let target = server.local_addr().unwrap();
+
+    let mut client = OscTcpClient::connect_with_options(target, &options).unwrap();
+    assert_eq!(9, client.stream().ttl().unwrap());
+    let (mut connection, _) = server.accept().unwrap();
+    let packet = OscPacket::Message(OscMessage::from("/hello"));
+    client.send(&packet).unwrap();
+    assert_eq!(packet, connection.recv().unwrap());
+}
@@ -0,0 +1,193 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::tcp::{OscTcpClient, OscTcpServer};
+use rosc::{decoder, encoder, OscMessage, OscPacket};
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_encode_framed() {
+    let packet = OscPacket::Message(OscMessage::from("/ping"));
+    let encoded = encoder::encode(&packet).unwrap();
+    let frame = encoder::encode_framed(&packet).unwrap();
+    assert_eq!((encoded.len() as u32).to_be_bytes(), frame[..4]);
+    assert_eq!(encoded, frame[4..]);
+
+    let mut buf = vec![1, 2, 3];
+    let len = encoder::encode_framed_into(&packet, &mut buf).unwrap();
+    assert_eq!(frame.len(), len);
+    assert_eq!(frame, buf[3..]);
+
+    let bad = OscPacket::Message(OscMessage::from("no slash"));
+    assert!(encoder::encode_framed_into(&bad, &mut buf).is_err());
+    assert_eq!(3 + len, buf.len());
+
+    let mut stream = frame.clone();
+    stream.extend(&frame);
+    assert_eq!(
+        vec![packet.clone(), packet],
+        decoder::decode_all(&stream).unwrap()
+    );
+}
+
+#[test]
+fn test_tcp_client_and_accept() {
+    let server = OscTcpServer::bind("127.0.0.1:0").unwrap();
+    let target = server.local_addr().unwrap();
+    let echo = thread::spawn(move || {
+        let (mut connection, _) = server.accept().unwrap();
+        let packet = connection.recv().unwrap();
+        connection.send(&packet).unwrap();
+    });
+
+    let mut client = OscTcpClient::connect(target).unwrap();
+    assert_eq!(target, client.peer_addr().unwrap());
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/echo".to_string(),
+        args: vec!["hello".into()],
+    });
+    client.send(&packet).unwrap();
+    assert_eq!(packet, client.recv().unwrap());
+    echo.join().unwrap();
+    assert!(client.recv().is_err());
+}
+
+#[test]
+fn test_tcp_client_recv_timeout() {
+    use rosc::OscError;
+    use std::io;
+
+    let server = OscTcpServer::bind("127.0.0.1:0").unwrap();
+    let mut client = OscTcpClient::connect(server.local_addr().unwrap()).unwrap();
+    let (mut connection, _) = server.accept().unwrap();
+    match client.recv_timeout(Duration::from_millis(50)) {
+        Err(OscError::ReadError(ref err)) if err.kind() == io::ErrorKind::TimedOut => (),
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+    assert_eq!(None, client.stream().read_timeout().unwrap());
+
+    let packet = OscPacket::Message(OscMessage::from("/late"));
+    connection.send(&packet).unwrap();
+    assert_eq!(packet, client.recv_timeout(Duration::from_secs(5)).unwrap());
+}
+
+#[test]
+fn test_tcp_server_closes_oversized_packets() {
+    use rosc::decoder::DecoderOptions;
+    use rosc::tcp::DEFAULT_MAX_PACKET_SIZE;
+    use std::io::Read;
+
+    let (tx, rx) = mpsc::channel();
+    let mut server = OscTcpServer::bind("127.0.0.1:0").unwrap();
+    let target = server.local_addr().unwrap();
+    server.on_error(move |err, _| tx.send(err.to_string()).unwrap());
+    thread::spawn(move || server.run(|_, _| ()));
+
+    let mut raw = TcpStream::connect(target).unwrap();
+    raw.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    raw.write_all(&[0xff, 0xff, 0xff, 0xf0]).unwrap();
+    assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+    // the server closed the connection instead of buffering the packet
+    let mut buf = [0u8; 4];
+    match raw.read(&mut buf) {
+        Ok(0) => (),
+        Err(ref err) if err.kind() == std::io::ErrorKind::ConnectionReset => (),
+        other => panic!("Expected the connection to be closed, got {:?}", other),
+    }
+
+    // clients are limited as well unless the limit is raised
+    let server = OscTcpServer::bind("127.0.0.1:0").unwrap();
+    let mut client = OscTcpClient::connect(server.local_addr().unwrap()).unwrap();
+    let (mut connection, _) = server.accept().unwrap();
+    let big = OscPacket::Message(OscMessage {
+        addr: "/big".to_string(),
+        args: vec![vec![0u8; DEFAULT_MAX_PACKET_SIZE].into()],
+    });
+    connection.send(&big).unwrap();
+    assert!(client.recv().is_err());
+    client.set_decoder_options(DecoderOptions::default());
+    connection.send(&big).unwrap();
+    assert_eq!(big, client.recv().unwrap());
+}
+
+#[test]
+fn test_tcp_server_run() {
+    let (tx, rx) = mpsc::channel();
+    let server = OscTcpServer::bind("127.0.0.1:0").unwrap();
+    let target = server.local_addr().unwrap();
+    thread::spawn(move || {
+        let mut server = server;
+        let errors = tx.clone();
+        server.on_error(move |err, _| errors.send(Err(err.to_string())).unwrap());
+        server.run(move |packet, _| tx.send(Ok(packet)).unwrap())
+    });
+
+    let mut first = OscTcpClient::connect(target).unwrap();
+    let mut second = OscTcpClient::connect(target).unwrap();
+    let timeout = Duration::from_secs(5);
+
+    let packet = OscPacket::Message(OscMessage::from("/first"));
+    first.send(&packet).unwrap();
+    assert_eq!(Ok(packet), rx.recv_timeout(timeout).unwrap());
+
+    let mut raw = TcpStream::connect(target).unwrap();
+    raw.write_all(&[0, 0, 0, 8]).unwrap();
+    raw.write_all(b"garbage!").unwrap();
+    assert!(rx.recv_timeout(timeout).unwrap().is_err());
+
+    let packet = OscPacket::Message(OscMessage::from("/second"));
+    second.send(&packet).unwrap();
+    assert_eq!(Ok(packet), rx.recv_timeout(timeout).unwrap());
+}
+
+#[test]
+fn test_tcp_server_run_until() {
+    let mut server = OscTcpServer::bind("127.0.0.1:0").unwrap();
+    let start = Instant::now();
+    server
+        .run_until(start + Duration::from_millis(50), |packet, _| {
+            panic!("unexpected {:?}", packet)
+        })
+        .unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    // the connection stays open between the calls
+    let mut client = OscTcpClient::connect(server.local_addr().unwrap()).unwrap();
+    let mut received = vec![];
+    for i in 0..2 {
+        let packet = OscPacket::Message(OscMessage {
+            addr: "/count".to_string(),
+            args: vec![i.into()],
+        });
+        client.send(&packet).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while received.len() <= i as usize && Instant::now() < deadline {
+            let deadline = Instant::now() + Duration::from_millis(100);
+            server
+                .run_until(deadline, |packet, _| received.push(packet))
+                .unwrap();
+        }
+        assert_eq!(packet, received[i as usize]);
+    }
+}
+
+#[test]
+fn test_tcp_server_drop_closes_listener() {
+    let mut server = OscTcpServer::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    server
+        .run_until(Instant::now() + Duration::from_millis(10), |_, _| ())
+        .unwrap();
+    drop(server);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while let Err(err) = TcpListener::bind(addr) {
+        assert!(Instant::now() < deadline, "{}", err);
+        thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,96 @@
+extern crate rosc;
+
+use rosc::{text, OscColor, OscError, OscMessage, OscType};
+
+#[test]
+fn test_parse_typed_message() {
+    let msg = text::parse_message("/synth/freq if 440 0.5").unwrap();
+    assert_eq!("/synth/freq", msg.addr);
+    assert_eq!(vec![OscType::Int(440), OscType::Float(0.5)], msg.args);
+
+    let msg = text::parse_message(r#"/all ,hdsScTFNIbr 1 2.5 "a b" sym x 0aff #ff000080"#).unwrap();
+    assert_eq!(
+        vec![
+            OscType::Long(1),
+            OscType::Double(2.5),
+            "a b".into(),
+            OscType::Symbol("sym".to_string()),
+            OscType::Char('x'),
+            OscType::Bool(true),
+            OscType::Bool(false),
+            OscType::Nil,
+            OscType::Inf,
+            OscType::Blob(vec![0x0a, 0xff]),
+            OscType::Color(OscColor {
+                red: 255,
+                green: 0,
+                blue: 0,
+                alpha: 128,
+            }),
+        ],
+        msg.args
+    );
+
+    // with a comma the type tag string may describe no arguments at all
+    assert!(text::parse_message("/ping ,").unwrap().args.is_empty());
+}
+
+#[test]
+fn test_parse_inferred_message() {
+    let msg: OscMessage = "/mix  1 -2 5000000000 0.5 1e3 true false nil inf hello \"42\""
+        .parse()
+        .unwrap();
+    assert_eq!(
+        vec![
+            OscType::Int(1),
+            OscType::Int(-2),
+            OscType::Long(5_000_000_000),
+            OscType::Float(0.5),
+            OscType::Float(1000.0),
+            OscType::Bool(true),
+            OscType::Bool(false),
+            OscType::Nil,
+            "inf".into(),
+            "hello".into(),
+            "42".into(),
+        ],
+        msg.args
+    );
+
+    // `s` would need a value, so it's taken as argument
+    let msg = text::parse_message("/label s").unwrap();
+    assert_eq!(vec![OscType::from("s")], msg.args);
+
+    let msg = text::parse_message(r#"/say "tab\there \"quoted\" back\\slash""#).unwrap();
+    assert_eq!(
+        vec![OscType::from("tab\there \"quoted\" back\\slash")],
+        msg.args
+    );
+
+    assert!(text::parse_message("/empty").unwrap().args.is_empty());
+}
+
+#[test]
+fn test_parse_message_errors() {
+    for (input, expected) in &[
+        ("", "BadMessage"),
+        ("\"/quoted\"", "BadMessage"),
+        ("/a \"unterminated", "BadMessage"),
+        ("/a \"bad \\q escape\"", "BadMessage"),
+        ("no/slash 1", "BadAddress"),
+        ("/a ,i x", "BadArg"),
+        ("/a ,i", "BadArg"),
+        ("/a ,i 1 2", "BadArg"),
+        ("/a ,c ab", "BadArg"),
+        ("/a ,m 1", "BadArg"),
+        ("/a ,b abc", "BadArg"),
+    ] {
+        let kind = match text::parse_message(input) {
+            Err(OscError::BadMessage(_)) => "BadMessage",
+            Err(OscError::BadAddress(_)) => "BadAddress",
+            Err(OscError::BadArg(_)) => "BadArg",
+            other => panic!("unexpected result for {:?}: {:?}", input, other),
+        };
+        assert_eq!(expected, &kind, "for {:?}", input);
+    }
+}
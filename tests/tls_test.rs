@@ -0,0 +1,114 @@
+#![cfg(feature = "tls")]
+
+extern crate rcgen;
+extern crate rosc;
+extern crate rustls;
+
+use rosc::tcp::{OscTcpClient, OscTcpServer};
+use rosc::{OscError, OscMessage, OscPacket};
+use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::{ClientConfig, RootCertStore, ServerConfig};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+/// Returns a server config with a self-signed certificate for `localhost`
+/// and a client config trusting it.
+fn configs() -> (Arc<ServerConfig>, Arc<ClientConfig>) {
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let cert = certified.cert.der().clone();
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+    let server = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert.clone()], key)
+        .unwrap();
+    let mut roots = RootCertStore::empty();
+    roots.add(cert).unwrap();
+    let client = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    (Arc::new(server), Arc::new(client))
+}
+
+#[test]
+fn test_tls_client_and_accept() {
+    let (server_config, client_config) = configs();
+    let server = OscTcpServer::bind_tls("127.0.0.1:0", server_config).unwrap();
+    let target = server.local_addr().unwrap();
+    let echo = thread::spawn(move || {
+        let (mut connection, _) = server.accept().unwrap();
+        assert!(connection.is_tls());
+        let packet = connection.recv().unwrap();
+        connection.send(&packet).unwrap();
+    });
+
+    let mut client = OscTcpClient::connect_tls(target, "localhost", client_config).unwrap();
+    assert!(client.is_tls());
+    assert_eq!(target, client.peer_addr().unwrap());
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/echo".to_string(),
+        args: vec!["hello".into(), 42i32.into()],
+    });
+    client.send(&packet).unwrap();
+    assert_eq!(packet, client.recv().unwrap());
+    echo.join().unwrap();
+}
+
+#[test]
+fn test_tls_server_run() {
+    let (server_config, client_config) = configs();
+    let mut server = OscTcpServer::bind_tls("127.0.0.1:0", server_config).unwrap();
+    let target = server.local_addr().unwrap();
+    let (received, packets) = mpsc::channel();
+    thread::spawn(move || server.run(|packet, _| received.send(packet).unwrap()));
+
+    let mut client = OscTcpClient::connect_tls(target, "localhost", client_config).unwrap();
+    for i in 0..3 {
+        client
+            .send(&OscPacket::Message(OscMessage {
+                addr: "/count".to_string(),
+                args: vec![i.into()],
+            }))
+            .unwrap();
+    }
+    for i in 0..3 {
+        let expected = OscPacket::Message(OscMessage {
+            addr: "/count".to_string(),
+            args: vec![i.into()],
+        });
+        assert_eq!(
+            expected,
+            packets.recv_timeout(Duration::from_secs(5)).unwrap()
+        );
+    }
+
+    // the plain protocol isn't accepted on a TLS server
+    let mut plain = OscTcpClient::connect(target).unwrap();
+    plain
+        .send(&OscPacket::Message(OscMessage::from("/plain")))
+        .unwrap();
+    assert!(packets.recv_timeout(Duration::from_millis(200)).is_err());
+}
+
+#[test]
+fn test_tls_untrusted_server() {
+    let (server_config, _) = configs();
+    let (_, other_client_config) = configs();
+    let server = OscTcpServer::bind_tls("127.0.0.1:0", server_config).unwrap();
+    let target = server.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut connection, _) = server.accept().unwrap();
+        let _ = connection.recv();
+    });
+
+    let mut client = OscTcpClient::connect_tls(target, "localhost", other_client_config).unwrap();
+    match client.send(&OscPacket::Message(OscMessage::from("/secret"))) {
+        Err(OscError::WriteError(_)) => (),
+        other => panic!("expected a failed handshake, got {:?}", other),
+    }
+
+    match OscTcpClient::connect_tls(target, "not a name", configs().1) {
+        Err(OscError::SocketError(_)) => (),
+        other => panic!("expected an invalid server name, got {:?}", other),
+    }
+}
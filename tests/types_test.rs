@@ -5,7 +5,7 @@ use rosc::{OscArray, OscType};
 #[test]
 fn test_osc_array_from_iter() {
     use std::iter::FromIterator;
-    let iter = (0..3).map(|i| OscType::Int(i));
+    let iter = (0..3).map(OscType::Int);
     let osc_arr = OscArray::from_iter(iter);
     assert_eq!(
         osc_arr,
@@ -14,3 +14,590 @@ fn test_osc_array_from_iter() {
         }
     );
 }
+
+#[test]
+fn test_hash_non_float_types() {
+    use rosc::{OscColor, OscMidiMessage, OscTime};
+    use std::collections::HashSet;
+
+    let colors: HashSet<OscColor> = vec![
+        OscColor {
+            red: 1,
+            green: 2,
+            blue: 3,
+            alpha: 4,
+        },
+        OscColor {
+            red: 1,
+            green: 2,
+            blue: 3,
+            alpha: 4,
+        },
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(colors.len(), 1);
+
+    let midi = OscMidiMessage {
+        port: 0,
+        status: 0x90,
+        data1: 60,
+        data2: 127,
+    };
+    let copy = midi;
+    assert_eq!(
+        vec![midi, copy].into_iter().collect::<HashSet<_>>().len(),
+        1
+    );
+
+    let times: HashSet<OscTime> = vec![(1, 2).into(), (1, 2).into(), (2, 1).into()]
+        .into_iter()
+        .collect();
+    assert_eq!(times.len(), 2);
+}
+
+#[test]
+fn test_hash_borrowed_packets() {
+    use rosc::{decoder, encoder, OscMessage, OscPacket};
+    use std::collections::HashSet;
+
+    let encoded: Vec<Vec<u8>> = vec!["/a", "/b", "/a"]
+        .into_iter()
+        .map(|addr| encoder::encode(&OscPacket::Message(OscMessage::from(addr))).unwrap())
+        .collect();
+    let unique: HashSet<_> = encoded
+        .iter()
+        .map(|msg| decoder::decode_ref(msg).unwrap())
+        .collect();
+    assert_eq!(unique.len(), 2);
+}
+
+#[test]
+fn test_osc_type_from_primitives() {
+    use rosc::OscTime;
+
+    let args: Vec<OscType> = vec![
+        1.into(),
+        2u8.into(),
+        (-3i16).into(),
+        4u32.into(),
+        5i64.into(),
+        6.0f32.into(),
+        7.0f64.into(),
+        "eight".into(),
+        String::from("nine").into(),
+        true.into(),
+        'x'.into(),
+        vec![1u8, 2].into(),
+        (&[3u8, 4][..]).into(),
+        OscTime::from((1, 2)).into(),
+        Some(10).into(),
+        None::<i32>.into(),
+        OscArray::from(vec![OscType::Int(1), "two".into()]).into(),
+    ];
+    assert_eq!(
+        args,
+        vec![
+            OscType::Int(1),
+            OscType::Int(2),
+            OscType::Int(-3),
+            OscType::Long(4),
+            OscType::Long(5),
+            OscType::Float(6.0),
+            OscType::Double(7.0),
+            OscType::String("eight".to_string()),
+            OscType::String("nine".to_string()),
+            OscType::Bool(true),
+            OscType::Char('x'),
+            OscType::Blob(vec![1, 2]),
+            OscType::Blob(vec![3, 4]),
+            OscType::Time(OscTime::from((1, 2))),
+            OscType::Int(10),
+            OscType::Nil,
+            OscType::Array(OscArray {
+                content: vec![OscType::Int(1), OscType::String("two".to_string())]
+            }),
+        ]
+    );
+}
+
+#[test]
+fn test_osc_type_accessors() {
+    use rosc::OscTime;
+
+    assert_eq!(Some(1), OscType::Int(1).as_int());
+    assert_eq!(None, OscType::Long(1).as_int());
+    assert_eq!(None, OscType::Int(1).as_float());
+    assert_eq!(Some(2.0), OscType::Float(2.0).as_float());
+    assert_eq!(Some(3), OscType::Long(3).as_long());
+    assert_eq!(Some(4.0), OscType::Double(4.0).as_double());
+    assert_eq!(Some('c'), OscType::Char('c').as_char());
+    assert_eq!(Some(true), OscType::Bool(true).as_bool());
+    assert_eq!(
+        Some(OscTime::from((1, 2))),
+        OscType::Time((1, 2).into()).as_time()
+    );
+
+    let s = OscType::from("str");
+    assert_eq!(Some("str"), s.as_str());
+    assert_eq!(None, s.as_blob());
+    let blob = OscType::from(vec![1u8, 2]);
+    assert_eq!(Some(&[1u8, 2][..]), blob.as_blob());
+    assert_eq!(None, blob.as_str());
+    let array = OscType::from(OscArray::from(vec![OscType::Nil]));
+    assert_eq!(
+        Some(&vec![OscType::Nil]),
+        array.as_array().map(|a| &a.content)
+    );
+}
+
+#[test]
+fn test_osc_type_try_from() {
+    use std::convert::{TryFrom, TryInto};
+
+    assert_eq!(Ok(1), i32::try_from(OscType::Int(1)));
+    assert_eq!(Ok(2.5), f64::try_from(OscType::Double(2.5)));
+    assert_eq!(
+        Ok("s".to_string()),
+        String::try_from(OscType::String("s".to_string()))
+    );
+    let blob: Result<Vec<u8>, _> = OscType::Blob(vec![1]).try_into();
+    assert_eq!(Ok(vec![1]), blob);
+
+    // no coercion between numeric types
+    let err = i32::try_from(OscType::Long(1)).unwrap_err();
+    assert_eq!("expected int argument, found Long(1)", err.to_string());
+    assert_eq!(OscType::Long(1), err.into_inner());
+    assert!(f32::try_from(OscType::Int(1)).is_err());
+    assert!(bool::try_from(OscType::Nil).is_err());
+}
+
+#[test]
+fn test_osc_message_builder() {
+    use rosc::{OscError, OscMessage};
+
+    let msg = OscMessage::builder("/mixer/1")
+        .arg(1)
+        .args(vec![2.0f32, 3.0])
+        .arg("four")
+        .build()
+        .unwrap();
+    assert_eq!(
+        OscMessage {
+            addr: "/mixer/1".to_string(),
+            args: vec![
+                OscType::Int(1),
+                OscType::Float(2.0),
+                OscType::Float(3.0),
+                OscType::String("four".to_string()),
+            ],
+        },
+        msg
+    );
+
+    assert_eq!(
+        OscMessage::from("/empty"),
+        OscMessage::builder(String::from("/empty")).build().unwrap()
+    );
+
+    for addr in &["", "missing/slash", "/with space"] {
+        match OscMessage::builder(*addr).arg(1).build() {
+            Err(OscError::BadAddress(_)) => (),
+            other => panic!("expected BadAddress for {:?}, got {:?}", addr, other),
+        }
+    }
+}
+
+#[test]
+fn test_osc_bundle_builder() {
+    use rosc::{OscBundle, OscMessage, OscPacket};
+
+    let empty = OscBundle::builder().build().unwrap();
+    assert!(empty.timetag.is_immediate());
+    assert!(empty.content.is_empty());
+
+    let inner = OscBundle::builder()
+        .timetag((5, 6))
+        .add(OscMessage::from("/b"))
+        .build()
+        .unwrap();
+    let bundle = OscBundle::builder()
+        .timetag((1, 2))
+        .add(OscMessage::from("/a"))
+        .add(inner.clone())
+        .add(OscPacket::Message("/c".into()))
+        .build()
+        .unwrap();
+    assert_eq!(
+        OscBundle {
+            timetag: (1, 2).into(),
+            content: vec![
+                OscPacket::Message("/a".into()),
+                OscPacket::Bundle(inner),
+                OscPacket::Message("/c".into()),
+            ],
+        },
+        bundle
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_osc_bundle_builder_timetags() {
+    use rosc::{OscBundle, OscError, OscTime};
+    use std::convert::TryFrom;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    let before = OscTime::try_from(SystemTime::now()).unwrap();
+    let now = OscBundle::builder().timetag_now().build().unwrap().timetag;
+    let later = OscBundle::builder()
+        .timetag_after(Duration::from_secs(10))
+        .build()
+        .unwrap()
+        .timetag;
+    assert!(before <= now);
+    assert!(later.duration_since(now).unwrap() >= Duration::from_secs(10));
+
+    let reset = OscBundle::builder()
+        .timetag_now()
+        .timetag_immediate()
+        .build()
+        .unwrap();
+    assert_eq!(OscTime::IMMEDIATE, reset.timetag);
+
+    match OscBundle::builder()
+        .timetag_at(UNIX_EPOCH - Duration::from_secs(1))
+        .build()
+    {
+        Err(OscError::BadBundle(_)) => (),
+        other => panic!("expected BadBundle, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_display_packets() {
+    use rosc::{OscBundle, OscColor, OscMessage, OscMidiMessage, OscPacket, OscTime};
+
+    let msg = OscMessage {
+        addr: "/synth/freq".to_string(),
+        args: vec![
+            440.0f32.into(),
+            "sine".into(),
+            1.into(),
+            2i64.into(),
+            0.5f64.into(),
+            'x'.into(),
+            vec![1u8, 0xab].into(),
+            true.into(),
+            false.into(),
+            OscType::Nil,
+            OscType::Inf,
+            OscColor {
+                red: 255,
+                green: 0,
+                blue: 16,
+                alpha: 128,
+            }
+            .into(),
+            OscMidiMessage {
+                port: 0,
+                status: 0x90,
+                data1: 60,
+                data2: 127,
+            }
+            .into(),
+            OscArray::from(vec![1.into(), "a\"b".into()]).into(),
+        ],
+    };
+    assert_eq!(
+        r#"/synth/freq f:440.0 s:"sine" i:1 h:2 d:0.5 c:'x' b:<01 ab> T F N I r:#ff001080 m:<00 90 3c 7f> [i:1 s:"a\"b"]"#,
+        msg.to_string()
+    );
+
+    let bundle = OscPacket::Bundle(OscBundle {
+        timetag: (0, 1).into(),
+        content: vec![
+            OscPacket::Message(OscMessage::from("/a")),
+            OscPacket::Bundle(OscBundle {
+                // 2000-02-29 12:34:56.5 UTC
+                timetag: OscTime::from((3_160_816_496, 1 << 31)),
+                content: vec![OscPacket::Message(OscMessage::from("/b"))],
+            }),
+            OscPacket::Message(OscMessage::from("/c")),
+        ],
+    });
+    assert_eq!(
+        "#bundle immediately\n  /a\n  #bundle 2000-02-29T12:34:56.500000Z\n    /b\n  /c",
+        bundle.to_string()
+    );
+
+    assert_eq!(
+        "1900-01-01T00:00:00.000000Z",
+        OscTime::from((0, 0)).to_string()
+    );
+    assert_eq!(
+        "2036-02-07T06:28:15.000000Z",
+        OscTime::from((u32::MAX, 0)).to_string()
+    );
+}
+
+#[test]
+fn test_midi_message_bytes() {
+    use rosc::OscMidiMessage;
+
+    let note_off = OscMidiMessage::note_off(1, 15, 64, 0);
+    assert_eq!(
+        OscMidiMessage {
+            port: 1,
+            status: 0x8f,
+            data1: 64,
+            data2: 0,
+        },
+        note_off
+    );
+    assert_eq!(Some(15), note_off.channel());
+    assert_eq!(vec![0x8f, 64, 0], note_off.to_bytes());
+
+    let cc = OscMidiMessage::control_change(0, 2, 7, 100);
+    assert_eq!(Some(cc), OscMidiMessage::from_bytes(0, &[0xb2, 7, 100]));
+
+    // out of range values are masked
+    assert_eq!(
+        OscMidiMessage::note_on(0, 0x12, 0x80 | 60, 0xff),
+        OscMidiMessage::note_on(0, 0x02, 60, 0x7f)
+    );
+
+    // system realtime messages have no data bytes
+    let clock = OscMidiMessage::from_bytes(3, &[0xf8]).unwrap();
+    assert_eq!(None, clock.channel());
+    assert_eq!(vec![0xf8], clock.to_bytes());
+
+    for invalid in &[
+        &[][..],
+        &[60, 127][..],
+        &[0x90, 60][..],
+        &[0x90, 60, 127, 0][..],
+        &[0x90, 0x80, 127][..],
+        &[0xf0, 1, 0xf7][..],
+    ] {
+        assert_eq!(
+            None,
+            OscMidiMessage::from_bytes(0, invalid),
+            "{:?}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_color_conversions() {
+    use rosc::OscColor;
+
+    let color = OscColor {
+        red: 0x12,
+        green: 0x34,
+        blue: 0x56,
+        alpha: 0x78,
+    };
+    assert_eq!(0x1234_5678, color.to_rgba_u32());
+    assert_eq!(color, OscColor::from_rgba_u32(0x1234_5678));
+    assert_eq!("#12345678", color.to_hex());
+    assert_eq!(Some(color), OscColor::from_hex("#12345678"));
+    assert_eq!(Some(color), OscColor::from_hex("12345678"));
+    assert_eq!(
+        Some(OscColor::from_rgba_u32(0xabcd_efff)),
+        OscColor::from_hex("#ABCDEF")
+    );
+
+    for invalid in &[
+        "",
+        "#",
+        "#1234567",
+        "#123456789",
+        "#12345g78",
+        "#+1234567",
+        "##123456",
+    ] {
+        assert_eq!(None, OscColor::from_hex(invalid), "{}", invalid);
+    }
+}
+
+#[test]
+fn test_parse_args() {
+    use rosc::OscMessage;
+
+    let msg = OscMessage {
+        addr: "/synth/voice".to_string(),
+        args: vec![
+            OscType::Float(440.0),
+            OscType::String("sine".to_string()),
+            OscType::Int(3),
+        ],
+    };
+    let (freq, name, voice): (f32, String, i32) = msg.parse_args().unwrap();
+    assert_eq!(440.0, freq);
+    assert_eq!("sine", name);
+    assert_eq!(3, voice);
+
+    let err = msg.parse_args::<(f32, String)>().unwrap_err();
+    assert_eq!(
+        "bad OSC argument: expected 2 arguments, found 3",
+        err.to_string()
+    );
+    let err = msg.parse_args::<(f32, String, f32)>().unwrap_err();
+    assert_eq!(
+        "bad OSC argument: argument 2: expected float argument, found Int(3)",
+        err.to_string()
+    );
+
+    let empty = OscMessage::from("/empty");
+    assert!(empty.parse_args::<()>().is_ok());
+    assert!(empty.parse_args::<(i32,)>().is_err());
+}
+
+#[test]
+fn test_osc_message_new() {
+    use rosc::{OscMessage, OscTime};
+
+    let msg = OscMessage::new("/synth/voice", (440.0f32, "sine", 3i32));
+    assert_eq!("/synth/voice", msg.addr);
+    assert_eq!((440.0f32, "sine".to_string(), 3), msg.parse_args().unwrap());
+
+    assert_eq!(OscMessage::from("/empty"), OscMessage::new("/empty", ()));
+    assert_eq!(
+        vec![OscType::Long(7)],
+        OscMessage::new("/single".to_string(), 7u32).args
+    );
+    assert_eq!(
+        vec![OscType::Time(OscTime::from((1, 2)))],
+        OscMessage::new("/time", (OscTime::from((1, 2)),)).args
+    );
+    // vectors are lists of arguments
+    assert_eq!(
+        vec![OscType::Int(1), OscType::Int(2)],
+        OscMessage::new("/bytes", vec![1u8, 2]).args
+    );
+    assert_eq!(
+        vec![OscType::Blob(vec![1, 2])],
+        OscMessage::new("/blob", (vec![1u8, 2],)).args
+    );
+}
+
+#[test]
+fn test_tagged_args() {
+    use rosc::{OscColor, OscMessage};
+
+    let msg = OscMessage::new(
+        "/mixer",
+        (
+            1i32,
+            OscType::Array(OscArray {
+                content: vec![
+                    0.5f64.into(),
+                    OscType::Array(OscArray { content: vec![] }),
+                    false.into(),
+                ],
+            }),
+            OscColor::from_rgba_u32(0),
+            OscType::Custom {
+                tag: 'x',
+                data: vec![],
+            },
+        ),
+    );
+    assert_eq!("i[d[]F]rx", msg.type_tags());
+    let tagged: Vec<(char, &OscType)> = msg.tagged_args().collect();
+    assert_eq!(4, tagged.len());
+    assert_eq!(('i', &msg.args[0]), tagged[0]);
+    assert_eq!(('[', &msg.args[1]), tagged[1]);
+    assert_eq!(('r', &msg.args[2]), tagged[2]);
+    assert_eq!(('x', &msg.args[3]), tagged[3]);
+    assert_eq!("", OscMessage::from("/empty").type_tags());
+}
+
+#[test]
+fn test_iter_messages() {
+    use rosc::{OscBundle, OscMessage, OscPacket, OscTime};
+
+    let msg = OscPacket::Message(OscMessage::from("/single"));
+    let messages: Vec<_> = msg.iter_messages().collect();
+    assert_eq!(1, messages.len());
+    assert_eq!("/single", messages[0].0.addr);
+    assert_eq!(None, messages[0].1);
+
+    let outer = OscTime::from((1, 0));
+    let inner = OscTime::from((2, 0));
+    let packet = OscPacket::Bundle(OscBundle {
+        timetag: outer,
+        content: vec![
+            OscPacket::Bundle(OscBundle {
+                timetag: inner,
+                content: vec![
+                    OscPacket::Message(OscMessage::from("/1")),
+                    OscPacket::Bundle(OscBundle {
+                        timetag: outer,
+                        content: vec![],
+                    }),
+                    OscPacket::Raw(vec![0; 4]),
+                    OscPacket::Message(OscMessage::from("/2")),
+                ],
+            }),
+            OscPacket::Message(OscMessage::from("/3")),
+        ],
+    });
+    let messages: Vec<_> = packet
+        .iter_messages()
+        .map(|(msg, time)| (msg.addr.as_str(), time.copied()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("/1", Some(inner)),
+            ("/2", Some(inner)),
+            ("/3", Some(outer))
+        ],
+        messages
+    );
+
+    assert_eq!(0, OscPacket::Raw(vec![]).iter_messages().count());
+}
+
+#[test]
+fn test_coercion() {
+    assert_eq!(Some(42), OscType::Int(42).coerce_int());
+    assert_eq!(Some(42), OscType::Long(42).coerce_int());
+    assert_eq!(Some(-3), OscType::Float(-2.5).coerce_int());
+    assert_eq!(Some(2), OscType::Double(1.5).coerce_int());
+    assert_eq!(Some(1), OscType::Bool(true).coerce_int());
+    assert_eq!(None, OscType::Long(i64::MAX).coerce_int());
+    assert_eq!(None, OscType::Float(f32::NAN).coerce_int());
+    assert_eq!(None, OscType::Float(3e9).coerce_int());
+    assert_eq!(None, OscType::Inf.coerce_int());
+    assert_eq!(None, OscType::Char('1').coerce_int());
+
+    assert_eq!(Some(3_000_000_000), OscType::Float(3e9).coerce_long());
+    assert_eq!(None, OscType::Double(1e19).coerce_long());
+    assert_eq!(None, OscType::Double(f64::INFINITY).coerce_long());
+
+    assert_eq!(Some(440.0), OscType::Int(440).coerce_float());
+    assert_eq!(Some(0.0), OscType::Bool(false).coerce_float());
+    assert_eq!(Some(0.5), OscType::Double(0.5).coerce_float());
+    assert_eq!(None, OscType::Double(1e300).coerce_float());
+    assert_eq!(
+        Some(f32::INFINITY),
+        OscType::Double(f64::INFINITY).coerce_float()
+    );
+    assert_eq!(None, OscType::String("1.0".to_string()).coerce_float());
+    assert_eq!(None, OscType::Float(f32::NAN).coerce_float());
+    assert_eq!(None, OscType::Double(f64::NAN).coerce_float());
+
+    assert_eq!(Some(1.0), OscType::Long(1).coerce_double());
+    assert_eq!(None, OscType::Nil.coerce_double());
+    assert_eq!(None, OscType::Float(f32::NAN).coerce_double());
+    assert_eq!(None, OscType::Double(f64::NAN).coerce_double());
+
+    assert_eq!(Some(true), OscType::Float(0.1).coerce_bool());
+    assert_eq!(Some(false), OscType::Int(0).coerce_bool());
+    assert_eq!(Some(true), OscType::Bool(true).coerce_bool());
+    assert_eq!(None, OscType::Double(f64::NAN).coerce_bool());
+    assert_eq!(None, OscType::Nil.coerce_bool());
+}
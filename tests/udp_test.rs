@@ -0,0 +1,427 @@
+#![cfg(feature = "std")]
+
+extern crate rosc;
+
+use rosc::udp::OscUdpClient;
+use rosc::{decoder, OscBundle, OscError, OscMessage, OscPacket, OscTime};
+use std::net::UdpSocket;
+use std::time::Duration;
+
+#[test]
+fn test_udp_client_send() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let target = receiver.local_addr().unwrap();
+
+    let mut client = OscUdpClient::connect(target).unwrap();
+    assert_eq!(target, client.target());
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/synth/freq".to_string(),
+        args: vec![440.0f32.into()],
+    });
+    client.send(&packet).unwrap();
+
+    let mut buf = [0u8; decoder::MTU];
+    let (len, from) = receiver.recv_from(&mut buf).unwrap();
+    assert_eq!(client.socket().local_addr().unwrap().port(), from.port());
+    assert_eq!(packet, decoder::decode(&buf[..len]).unwrap());
+}
+
+#[test]
+fn test_udp_client_errors() {
+    match OscUdpClient::connect("not an address") {
+        Err(OscError::SocketError(_)) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut client = OscUdpClient::connect(receiver.local_addr().unwrap()).unwrap();
+    let packet = OscPacket::Message(OscMessage::from("no slash"));
+    match client.send(&packet) {
+        Err(OscError::BadAddress(_)) => (),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_udp_client_connected_socket() {
+    use std::io;
+    use std::sync::mpsc;
+    use std::thread;
+
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let target = receiver.local_addr().unwrap();
+    let (errors, received_errors) = mpsc::channel();
+    let mut client = OscUdpClient::connect(target).unwrap();
+    client
+        .connect_socket()
+        .unwrap()
+        .on_error(move |err| errors.send(err.to_string()).unwrap());
+    let packet = OscPacket::Message(OscMessage::from("/ping"));
+    client.send(&packet).unwrap();
+    let mut buf = [0u8; 64];
+    let len = receiver.recv(&mut buf).unwrap();
+    assert_eq!(packet, decoder::decode(&buf[..len]).unwrap());
+
+    // once the receiver is gone, the ICMP error of its host is reported
+    drop(receiver);
+    let mut refused = None;
+    for _ in 0..10 {
+        if let Err(err) = client.send(&packet) {
+            refused = Some(err);
+            break;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    match refused {
+        Some(OscError::WriteError(ref err)) => {
+            assert_eq!(io::ErrorKind::ConnectionRefused, err.kind())
+        }
+        other => panic!("expected a refused connection, got {:?}", other),
+    }
+    assert!(received_errors.try_recv().is_ok());
+}
+
+#[test]
+fn test_multi_sender() {
+    use rosc::metrics::OscMetrics;
+    use rosc::udp::MultiSender;
+    use std::sync::Arc;
+
+    let receivers: Vec<_> = (0..3)
+        .map(|_| {
+            let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+            receiver
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            receiver
+        })
+        .collect();
+    let metrics = Arc::new(OscMetrics::new());
+    let mut sender = MultiSender::bind("127.0.0.1:0").unwrap();
+    sender.set_metrics(metrics.clone());
+    for receiver in &receivers {
+        sender.add_target(receiver.local_addr().unwrap()).unwrap();
+    }
+    // targets are only added once
+    sender
+        .add_target(receivers[0].local_addr().unwrap())
+        .unwrap();
+    assert_eq!(3, sender.targets().len());
+
+    let packet = OscPacket::Message(OscMessage::from("/mirror"));
+    assert!(sender.send(&packet).unwrap().is_empty());
+    let mut buf = [0u8; 64];
+    for receiver in &receivers {
+        let (len, from) = receiver.recv_from(&mut buf).unwrap();
+        assert_eq!(sender.socket().local_addr().unwrap(), from);
+        assert_eq!(packet, decoder::decode(&buf[..len]).unwrap());
+    }
+    assert_eq!(3, metrics.packets_sent());
+
+    assert!(sender.remove_target(receivers[1].local_addr().unwrap()));
+    assert!(!sender.remove_target(receivers[1].local_addr().unwrap()));
+    // the IPv4 socket can't send to IPv6 targets
+    let unreachable: std::net::SocketAddr = "[::1]:9".parse().unwrap();
+    sender.add_target(unreachable).unwrap();
+    let failed = sender.send(&packet).unwrap();
+    assert_eq!(1, failed.len());
+    assert_eq!(unreachable, failed[0].0);
+    match failed[0].1 {
+        OscError::WriteError(_) => (),
+        ref other => panic!("Expected a write error, got {:?}", other),
+    }
+    for receiver in [&receivers[0], &receivers[2]] {
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(packet, decoder::decode(&buf[..len]).unwrap());
+    }
+
+    let bad = OscPacket::Message(OscMessage::from("no slash"));
+    assert!(sender.send(&bad).is_err());
+}
+
+#[test]
+fn test_udp_server_recv() {
+    use rosc::udp::OscUdpServer;
+
+    let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    let mut client = OscUdpClient::connect(server.local_addr().unwrap()).unwrap();
+    let packet = OscPacket::Message(OscMessage::from("/ping"));
+    client.send(&packet).unwrap();
+
+    let (received, from) = server.recv().unwrap();
+    assert_eq!(packet, received);
+    assert_eq!(client.socket().local_addr().unwrap().port(), from.port());
+
+    // datagrams larger than the MTU aren't truncated
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/blob".to_string(),
+        args: vec![vec![7u8; 4096].into()],
+    });
+    client.send(&packet).unwrap();
+    assert_eq!(packet, server.recv().unwrap().0);
+}
+
+#[test]
+fn test_udp_server_try_recv() {
+    use rosc::udp::OscUdpServer;
+    use std::thread;
+
+    let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    server.set_nonblocking(true).unwrap();
+    assert!(server.try_recv().unwrap().is_none());
+
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let target = server.local_addr().unwrap();
+    let mut client = OscUdpClient::from_socket(sender.try_clone().unwrap(), target);
+    let first = OscPacket::Message(OscMessage::from("/first"));
+    let second = OscPacket::Message(OscMessage::from("/second"));
+    client.send(&first).unwrap();
+    sender.send_to(b"garbage", target).unwrap();
+    client.send(&second).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    let (packet, from) = server.try_recv().unwrap().unwrap();
+    assert_eq!(first, packet);
+    assert_eq!(sender.local_addr().unwrap(), from);
+    assert!(server.try_recv().is_err());
+    assert_eq!(second, server.try_recv().unwrap().unwrap().0);
+    assert!(server.try_recv().unwrap().is_none());
+    match server.recv() {
+        Err(OscError::ReadError(_)) => (),
+        other => panic!("Expected a read error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_udp_server_recv_timeout() {
+    use rosc::udp::OscUdpServer;
+    use std::io;
+    use std::time::Instant;
+
+    let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    let start = Instant::now();
+    match server.recv_timeout(Duration::from_millis(50)) {
+        Err(OscError::ReadError(ref err)) if err.kind() == io::ErrorKind::TimedOut => (),
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(None, server.socket().read_timeout().unwrap());
+
+    let mut client = OscUdpClient::connect(server.local_addr().unwrap()).unwrap();
+    let packet = OscPacket::Message(OscMessage::from("/watchdog"));
+    client.send(&packet).unwrap();
+    assert_eq!(
+        packet,
+        server.recv_timeout(Duration::from_secs(5)).unwrap().0
+    );
+
+    client.send(&packet).unwrap();
+    client.send(&packet).unwrap();
+    let mut received = 0;
+    let deadline = Instant::now() + Duration::from_millis(100);
+    server.run_until(deadline, |_, _| received += 1).unwrap();
+    assert!(Instant::now() >= deadline);
+    assert_eq!(2, received);
+    assert_eq!(None, server.socket().read_timeout().unwrap());
+}
+
+#[test]
+fn test_udp_server_run() {
+    use rosc::dispatcher::Dispatcher;
+    use rosc::udp::OscUdpServer;
+    use std::sync::mpsc;
+    use std::thread;
+
+    let (tx, rx) = mpsc::channel();
+    let server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    let target = server.local_addr().unwrap();
+    thread::spawn(move || {
+        let mut server = server;
+        let errors = tx.clone();
+        server.on_error(move |err, _| errors.send(Err(err.to_string())).unwrap());
+        let mut dispatcher = Dispatcher::new();
+        dispatcher
+            .on("/synth/*", move |msg| {
+                tx.send(Ok(msg.addr.clone())).unwrap()
+            })
+            .unwrap();
+        server.run_dispatcher(&mut dispatcher)
+    });
+
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    sender.send_to(b"garbage", target).unwrap();
+    let mut client = OscUdpClient::connect(target).unwrap();
+    client
+        .send(&OscPacket::Message(OscMessage::from("/synth/freq")))
+        .unwrap();
+
+    let timeout = Duration::from_secs(5);
+    assert!(rx.recv_timeout(timeout).unwrap().is_err());
+    assert_eq!(
+        Ok("/synth/freq".to_string()),
+        rx.recv_timeout(timeout).unwrap()
+    );
+}
+
+#[test]
+fn test_udp_multicast() {
+    use rosc::udp::OscUdpServer;
+    use std::net::{Ipv4Addr, SocketAddrV4};
+
+    let group = Ipv4Addr::new(239, 255, 77, 1);
+    let interface = Ipv4Addr::LOCALHOST;
+    let mut server = match OscUdpServer::bind_multicast_v4(group, 0, interface) {
+        Ok(server) => server,
+        // the test environment may not support multicast
+        Err(OscError::SocketError(err)) => {
+            eprintln!("skipping multicast test: {}", err);
+            return;
+        }
+        Err(err) => panic!("{}", err),
+    };
+    server
+        .socket()
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let port = server.local_addr().unwrap().port();
+
+    let mut client =
+        OscUdpClient::multicast_v4(SocketAddrV4::new(group, port), interface, 1).unwrap();
+    client.set_multicast_loop_v4(true).unwrap();
+    let packet = OscPacket::Message(OscMessage::from("/cue/go"));
+    client.send(&packet).unwrap();
+    assert_eq!(packet, server.recv().unwrap().0);
+
+    server.leave_multicast_v4(group, interface).unwrap();
+}
+
+#[test]
+fn test_udp_client_max_datagram_size() {
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    receiver
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut client = OscUdpClient::connect(receiver.local_addr().unwrap()).unwrap();
+    client.set_max_datagram_size(Some(100));
+
+    let bundle = OscBundle {
+        timetag: OscTime::IMMEDIATE,
+        content: (0..10)
+            .map(|i| {
+                OscPacket::Message(OscMessage {
+                    addr: "/fader".to_string(),
+                    args: vec![i.into()],
+                })
+            })
+            .collect(),
+    };
+    client.send(&OscPacket::Bundle(bundle.clone())).unwrap();
+
+    let mut content = vec![];
+    let mut buf = [0u8; decoder::MTU];
+    while content.len() < bundle.content.len() {
+        let (len, _) = receiver.recv_from(&mut buf).unwrap();
+        assert!(len <= 100);
+        match decoder::decode(&buf[..len]).unwrap() {
+            OscPacket::Bundle(part) => content.extend(part.content),
+            other => panic!("expected a bundle, got {:?}", other),
+        }
+    }
+    assert_eq!(bundle.content, content);
+
+    let msg = OscPacket::Message(OscMessage {
+        addr: "/waveform".to_string(),
+        args: vec![vec![0u8; 200].into()],
+    });
+    match client.send(&msg) {
+        Err(OscError::BadPacket(_)) => (),
+        other => panic!("expected BadPacket, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_udp_server_source_filter() {
+    use rosc::metrics::OscMetrics;
+    use rosc::udp::{OscUdpServer, SourceFilter};
+    use std::sync::{mpsc, Arc};
+
+    let metrics = Arc::new(OscMetrics::new());
+    let mut filter = SourceFilter::new();
+    filter.allow("10.0.0.0/8").unwrap();
+    let (rejected, rejections) = mpsc::channel();
+    let mut server = OscUdpServer::bind("127.0.0.1:0").unwrap();
+    server
+        .set_source_filter(filter.clone())
+        .set_metrics(metrics.clone())
+        .on_rejected(move |from| rejected.send(from).unwrap());
+    let target = server.local_addr().unwrap();
+
+    let mut client = OscUdpClient::connect(target).unwrap();
+    let packet = OscPacket::Message(OscMessage::from("/ping"));
+    client.send(&packet).unwrap();
+    // rejected datagrams are skipped without even decoding them
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    sender.send_to(b"garbage", target).unwrap();
+    server
+        .socket()
+        .set_read_timeout(Some(Duration::from_millis(200)))
+        .unwrap();
+    match server.recv() {
+        Err(OscError::ReadError(_)) => (),
+        other => panic!("Expected a read timeout, got {:?}", other),
+    }
+
+    filter.allow("127.0.0.1").unwrap();
+    server.set_source_filter(filter);
+    let allowed = OscPacket::Message(OscMessage::from("/allowed"));
+    client.send(&allowed).unwrap();
+
+    assert_eq!(allowed, server.recv().unwrap().0);
+    assert_eq!(
+        client.socket().local_addr().unwrap().port(),
+        rejections.try_recv().unwrap().port()
+    );
+    assert_eq!(sender.local_addr().unwrap(), rejections.try_recv().unwrap());
+    assert_eq!(2, metrics.packets_dropped());
+    assert_eq!(0, metrics.decode_errors());
+    assert_eq!(1, metrics.packets_received());
+}
+
+#[test]
+fn test_source_filter() {
+    use rosc::udp::SourceFilter;
+
+    let mut filter = SourceFilter::new();
+    assert!(!filter.is_allowed("127.0.0.1".parse().unwrap()));
+
+    filter
+        .allow("192.168.0.0/16")
+        .unwrap()
+        .allow("fd00::/8")
+        .unwrap()
+        .allow("10.1.2.3")
+        .unwrap();
+    for ip in ["192.168.4.2", "::ffff:192.168.0.1", "fd12::1", "10.1.2.3"] {
+        assert!(filter.is_allowed(ip.parse().unwrap()), "{}", ip);
+    }
+    for ip in ["192.169.0.1", "fe80::1", "10.1.2.4", "::1"] {
+        assert!(!filter.is_allowed(ip.parse().unwrap()), "{}", ip);
+    }
+
+    assert!(filter
+        .allow("0.0.0.0/0")
+        .unwrap()
+        .is_allowed("8.8.8.8".parse().unwrap()));
+    for subnet in ["", "10.0.0.0/", "10.0.0.0/33", "fd00::/129", "host/8"] {
+        match SourceFilter::new().allow(subnet) {
+            Err(rosc::OscError::BadAddress(_)) => (),
+            other => panic!("Expected a bad address for {:?}, got {:?}", subnet, other),
+        }
+    }
+}
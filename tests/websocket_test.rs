@@ -0,0 +1,142 @@
+#![cfg(feature = "websocket")]
+
+extern crate rosc;
+
+use rosc::dispatcher::Dispatcher;
+use rosc::websocket::{self, OscWsServer};
+use rosc::{OscMessage, OscPacket};
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_websocket_echo() {
+    let server = OscWsServer::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", server.local_addr().unwrap());
+    let echo = thread::spawn(move || {
+        let (mut socket, _) = server.accept().unwrap();
+        let packet = socket.recv().unwrap();
+        socket.send(&packet).unwrap();
+        socket.close().unwrap();
+    });
+
+    let mut socket = websocket::connect(&url).unwrap();
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/echo".to_string(),
+        args: vec![1.0f32.into()],
+    });
+    socket.send(&packet).unwrap();
+    assert_eq!(packet, socket.recv().unwrap());
+    assert!(socket.recv().is_err());
+    echo.join().unwrap();
+}
+
+#[test]
+fn test_websocket_server_run_dispatcher() {
+    let (tx, rx) = mpsc::channel();
+    let server = OscWsServer::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", server.local_addr().unwrap());
+    thread::spawn(move || {
+        let mut server = server;
+        let errors = tx.clone();
+        server.on_error(move |err, _| errors.send(Err(err.to_string())).unwrap());
+        let mut dispatcher = Dispatcher::new();
+        dispatcher
+            .on("/fader/*", move |msg| {
+                tx.send(Ok(msg.addr.clone())).unwrap()
+            })
+            .unwrap();
+        server.run_dispatcher(&mut dispatcher)
+    });
+
+    let mut socket = websocket::connect(&url).unwrap();
+    socket.send(&OscPacket::Raw(b"garbage".to_vec())).unwrap();
+    socket
+        .send(&OscPacket::Message(OscMessage::from("/fader/1")))
+        .unwrap();
+
+    let timeout = Duration::from_secs(5);
+    assert!(rx.recv_timeout(timeout).unwrap().is_err());
+    assert_eq!(
+        Ok("/fader/1".to_string()),
+        rx.recv_timeout(timeout).unwrap()
+    );
+}
+
+#[test]
+fn test_websocket_server_failed_handshake() {
+    let (tx, rx) = mpsc::channel();
+    let mut server = OscWsServer::bind("127.0.0.1:0").unwrap();
+    let target = server.local_addr().unwrap();
+    server.on_error(move |_, from| tx.send(from).unwrap());
+    thread::spawn(move || server.run(|_, _| ()));
+
+    let mut raw = TcpStream::connect(target).unwrap();
+    raw.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let from = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(raw.local_addr().unwrap(), from);
+}
+
+#[test]
+fn test_websocket_server_run_until() {
+    let mut server = OscWsServer::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", server.local_addr().unwrap());
+    let client = thread::spawn(move || {
+        let mut socket = websocket::connect(&url).unwrap();
+        socket
+            .send(&OscPacket::Message(OscMessage::from("/ping")))
+            .unwrap();
+        socket
+    });
+
+    let mut received = vec![];
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while received.is_empty() && Instant::now() < deadline {
+        let deadline = Instant::now() + Duration::from_millis(100);
+        server
+            .run_until(deadline, |packet, _| received.push(packet))
+            .unwrap();
+    }
+    assert_eq!(
+        vec![OscPacket::Message(OscMessage::from("/ping"))],
+        received
+    );
+    client.join().unwrap();
+}
+
+#[test]
+fn test_websocket_server_handshake_timeout() {
+    let (tx, rx) = mpsc::channel();
+    let mut server = OscWsServer::bind("127.0.0.1:0").unwrap();
+    let target = server.local_addr().unwrap();
+    server.set_handshake_timeout(Duration::from_millis(50));
+    server.on_error(move |err, _| tx.send(err.to_string()).unwrap());
+    thread::spawn(move || server.run(|_, _| ()));
+
+    // never sends the handshake
+    let _raw = TcpStream::connect(target).unwrap();
+    assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+}
+
+#[test]
+fn test_websocket_server_closes_oversized_messages() {
+    let (tx, rx) = mpsc::channel();
+    let mut server = OscWsServer::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", server.local_addr().unwrap());
+    server.on_error(move |err, _| tx.send(err.root().to_string()).unwrap());
+    thread::spawn(move || server.run(|_, _| ()));
+
+    let mut socket = websocket::connect(&url).unwrap();
+    let packet = OscPacket::Message(OscMessage {
+        addr: "/blob".to_string(),
+        args: vec![vec![0u8; 2 << 20].into()],
+    });
+    let _ = socket.send(&packet);
+    assert_eq!(
+        "exceeded maximum packet size",
+        rx.recv_timeout(Duration::from_secs(5)).unwrap()
+    );
+}